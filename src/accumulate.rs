@@ -0,0 +1,31 @@
+use crate::matrix_multiplication::algorithms::Algorithm;
+
+/// Kernel computing `C = alpha * A * B + beta * C` with a matrix multiplication algorithm,
+/// accumulating into an existing C as the updates of the trailing blocks of a blocked LU
+/// factorization do
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct AccumulateKernel {
+    /// Algorithm computing the product of A and B
+    pub algorithm: Algorithm,
+    /// Scalar multiplying the product of A and B
+    pub alpha: i32,
+    /// Scalar multiplying the previous value of C
+    pub beta: i32,
+}
+
+impl std::fmt::Display for AccumulateKernel {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(
+            f,
+            "{} (C = {} * A * B + {} * C)",
+            self.algorithm, self.alpha, self.beta
+        )
+    }
+}
+
+impl AccumulateKernel {
+    /// Returns the number of threads used by the kernel, or `None` if it is sequential
+    pub fn threads(&self) -> Option<usize> {
+        self.algorithm.threads()
+    }
+}
@@ -0,0 +1,96 @@
+use std::sync::OnceLock;
+
+use core_affinity::CoreId;
+use log::warn;
+
+/// Cores the benchmark is pinned to with `--pin-cores`, in the order the threads are pinned to
+static PINNED_CORES: OnceLock<Vec<usize>> = OnceLock::new();
+
+/// Pins the calling thread, the main thread of the benchmark, to the first of `cores`, and makes
+/// the workers of the thread pools spawned from now on pin themselves to `cores` in turn.
+/// Pinning again to the same cores only pins the calling thread.
+/// Fails if a core is not available to the process, if the platform does not support pinning, or
+/// if the benchmark is already pinned to other cores.
+pub fn pin(cores: &[usize]) -> Result<(), String> {
+    if let Some(pinned) = PINNED_CORES.get() {
+        return match pinned.as_slice() == cores {
+            true => pin_current(pinned[0]),
+            false => Err(format!(
+                "the benchmark is already pinned to the cores {}",
+                format_cores(pinned)
+            )),
+        };
+    }
+    // the available cores are read before pinning the calling thread, which restricts them
+    let available = core_affinity::get_core_ids()
+        .ok_or("the cores cannot be pinned on this platform")?
+        .into_iter()
+        .map(|core| core.id)
+        .collect::<Vec<_>>();
+    if let Some(core) = cores.iter().find(|core| !available.contains(core)) {
+        return Err(format!(
+            "core {} is not available, the available cores are {}",
+            core,
+            format_cores(&available)
+        ));
+    }
+    pin_current(*cores.first().ok_or("no core was given")?)?;
+    PINNED_CORES
+        .set(cores.to_vec())
+        .map_err(|_| "the benchmark is already pinned".to_string())
+}
+
+/// Pins the calling thread to `core`
+fn pin_current(core: usize) -> Result<(), String> {
+    match core_affinity::set_for_current(CoreId { id: core }) {
+        true => Ok(()),
+        false => Err(format!("cannot pin the main thread to core {}", core)),
+    }
+}
+
+/// Pins the calling worker thread to the core of its index among the pinned cores, cycling
+/// through them if there are more workers than cores. Does nothing if the benchmark is not pinned.
+pub fn pin_worker(index: usize) {
+    if let Some(cores) = PINNED_CORES.get() {
+        let id = cores[index % cores.len()];
+        if !core_affinity::set_for_current(CoreId { id }) {
+            warn!("Cannot pin worker {} to core {}", index, id);
+        }
+    }
+}
+
+/// Returns the cores the benchmark is pinned to, if it was pinned with `pin`
+pub fn pinned_cores() -> Option<&'static [usize]> {
+    PINNED_CORES.get().map(Vec::as_slice)
+}
+
+/// Formats a list of cores with the runs of consecutive cores as ranges, e.g. `0-3,8`
+pub fn format_cores(cores: &[usize]) -> String {
+    let mut ranges: Vec<(usize, usize)> = Vec::new();
+    for &core in cores {
+        match ranges.last_mut() {
+            Some((_, end)) if *end + 1 == core => *end = core,
+            _ => ranges.push((core, core)),
+        }
+    }
+    ranges
+        .iter()
+        .map(|(start, end)| match start == end {
+            true => start.to_string(),
+            false => format!("{}-{}", start, end),
+        })
+        .collect::<Vec<_>>()
+        .join(",")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_format_cores() {
+        assert_eq!(format_cores(&[0, 1, 2, 3, 8]), "0-3,8");
+        assert_eq!(format_cores(&[4, 2]), "4,2");
+        assert_eq!(format_cores(&[]), "");
+    }
+}
@@ -0,0 +1,171 @@
+use std::time::Duration;
+
+use clap::ValueEnum;
+
+/// Strategies of the search of the fastest tile size
+#[derive(Debug, Clone, Copy, PartialEq, Eq, ValueEnum)]
+pub enum Search {
+    /// Time every candidate tile size
+    Exhaustive,
+    /// Start from the tile size suggested by the L1 data cache and move to the faster neighbouring
+    /// candidate until neither neighbour is faster, timing a fraction of the candidates
+    HillClimbing,
+}
+
+impl std::fmt::Display for Search {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Search::Exhaustive => write!(f, "exhaustive"),
+            Search::HillClimbing => write!(f, "hill climbing"),
+        }
+    }
+}
+
+/// Returns the tile sizes worth trying on `n`x`n` matrices: the divisors of `n` from 4 up, as
+/// smaller tiles are dominated by the overhead of the loops, or every divisor of smaller matrices
+pub fn candidate_tile_sizes(n: usize) -> Vec<usize> {
+    let divisors = (1..=n).filter(|d| n.is_multiple_of(*d));
+    match n >= 4 {
+        true => divisors.filter(|d| *d >= 4).collect(),
+        false => divisors.collect(),
+    }
+}
+
+/// Searches the fastest of the sorted `candidates` with the given strategy, timing each candidate
+/// at most once with `measure`, which returns `None` if the candidate cannot run.
+/// Hill climbing starts from the candidate closest to `start`.
+/// Returns the timed candidates with their times, in the order of `candidates`.
+pub fn search<F>(
+    candidates: &[usize],
+    start: usize,
+    strategy: Search,
+    mut measure: F,
+) -> Vec<(usize, Option<Duration>)>
+where
+    F: FnMut(usize) -> Option<Duration>,
+{
+    let mut times: Vec<Option<Option<Duration>>> = vec![None; candidates.len()];
+    let mut time = |i: usize| *times[i].get_or_insert_with(|| measure(candidates[i]));
+
+    match strategy {
+        Search::Exhaustive => (0..candidates.len()).for_each(|i| {
+            time(i);
+        }),
+        Search::HillClimbing if !candidates.is_empty() => {
+            let mut current = (0..candidates.len())
+                .min_by_key(|i| candidates[*i].abs_diff(start))
+                .unwrap();
+            loop {
+                // the candidates that cannot run are never faster
+                let neighbours = [current.checked_sub(1), Some(current + 1)];
+                let best = neighbours
+                    .into_iter()
+                    .flatten()
+                    .filter(|i| *i < candidates.len())
+                    .chain([current])
+                    .filter_map(|i| time(i).map(|duration| (duration, i)))
+                    .min();
+                match best {
+                    Some((_, i)) if i != current => current = i,
+                    _ => break,
+                }
+            }
+        }
+        Search::HillClimbing => (),
+    }
+
+    candidates
+        .iter()
+        .zip(times)
+        .filter_map(|(candidate, time)| time.map(|time| (*candidate, time)))
+        .collect()
+}
+
+/// Returns the fastest of the timed candidates
+pub fn fastest(timed: &[(usize, Option<Duration>)]) -> Option<(usize, Duration)> {
+    timed
+        .iter()
+        .filter_map(|(candidate, time)| time.map(|time| (*candidate, time)))
+        .min_by_key(|(_, time)| *time)
+}
+
+/// Returns the knee of a scaling curve, the fewest threads whose time is within `tolerance`
+/// percent of the fastest time: adding threads beyond it stops helping.
+/// Returns `None` if no thread count was timed.
+pub fn knee(points: &[(usize, Duration)], tolerance: f64) -> Option<usize> {
+    let fastest = points.iter().map(|(_, time)| *time).min()?;
+    let limit = fastest.as_secs_f64() * (1.0 + tolerance / 100.0);
+    points
+        .iter()
+        .filter(|(_, time)| time.as_secs_f64() <= limit)
+        .map(|(threads, _)| *threads)
+        .min()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Times of a convex landscape with its minimum at 64
+    fn landscape(tile_size: usize) -> Option<Duration> {
+        Some(Duration::from_micros(tile_size.abs_diff(64) as u64 + 10))
+    }
+
+    #[test]
+    fn test_candidate_tile_sizes() {
+        assert_eq!(candidate_tile_sizes(48), [4, 6, 8, 12, 16, 24, 48]);
+        assert_eq!(candidate_tile_sizes(3), [1, 3]);
+        assert_eq!(candidate_tile_sizes(0), Vec::<usize>::new());
+    }
+
+    #[test]
+    fn test_exhaustive_search() {
+        let candidates = candidate_tile_sizes(256);
+        let timed = search(&candidates, 32, Search::Exhaustive, landscape);
+        assert_eq!(timed.len(), candidates.len());
+        assert_eq!(fastest(&timed), Some((64, Duration::from_micros(10))));
+    }
+
+    #[test]
+    fn test_hill_climbing_search() {
+        let candidates = candidate_tile_sizes(256);
+        let mut measured = Vec::new();
+        let timed = search(&candidates, 8, Search::HillClimbing, |tile_size| {
+            measured.push(tile_size);
+            landscape(tile_size)
+        });
+
+        assert_eq!(fastest(&timed), Some((64, Duration::from_micros(10))));
+        // every candidate is timed at most once, and the ones past the minimum are never timed
+        let mut distinct = measured.clone();
+        distinct.sort();
+        distinct.dedup();
+        assert_eq!(distinct.len(), measured.len());
+        assert!(!measured.contains(&256));
+    }
+
+    #[test]
+    fn test_hill_climbing_skips_failures() {
+        let candidates = [4, 8, 16, 32];
+        let timed = search(&candidates, 16, Search::HillClimbing, |tile_size| {
+            (tile_size != 8).then(|| Duration::from_micros(tile_size as u64))
+        });
+        // 8 cannot run, so the search stops at 16 although 4 is faster
+        assert_eq!(fastest(&timed), Some((16, Duration::from_micros(16))));
+        assert!(search(&[], 16, Search::HillClimbing, landscape).is_empty());
+    }
+
+    #[test]
+    fn test_knee() {
+        let curve = [100, 52, 36, 30, 29, 30, 31, 33]
+            .into_iter()
+            .enumerate()
+            .map(|(i, ms)| (i + 1, Duration::from_millis(ms)))
+            .collect::<Vec<_>>();
+        // 4 threads are within 5% of the fastest time, with 5 threads
+        assert_eq!(knee(&curve, 5.0), Some(4));
+        assert_eq!(knee(&curve, 0.0), Some(5));
+        assert_eq!(knee(&curve, 300.0), Some(1));
+        assert_eq!(knee(&[], 5.0), None);
+    }
+}
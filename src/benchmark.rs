@@ -1,145 +1,2745 @@
-use std::{thread, time::Instant, vec};
+use std::{
+    cell::RefCell, collections::HashMap, fs, num::Wrapping, sync::Arc, thread, time::Duration, vec,
+};
+
+use clap::ValueEnum;
+use cli_table::{Cell, CellStruct};
+use colored::Colorize;
+use log::{debug, error, info, warn};
+use num_complex::Complex;
+use rand::{rngs::StdRng, SeedableRng};
+
+use crate::{
+    accumulate::AccumulateKernel,
+    affinity::{self, format_cores},
+    autotune::{self, candidate_tile_sizes, Search},
+    cli::{
+        parse_cli_inner_tiles, parse_cli_tiles, parse_cli_tiles_per_job, Autotune, Cli,
+        Elementwise, Gemm, Gemv, Quantized, RunArgs, Scaling, Sparse, Sweep, Symmetric, Tiling,
+        Triangular,
+    },
+    cli_tables::{
+        print_args_table, print_benchmark_results_table, print_tables_to_stderr, print_title,
+    },
+    clock::{Clock, CpuUsage},
+    elementwise::{elementwise, ElementwiseKernel, ElementwiseOp},
+    estimate,
+    gemv::{gemv, GemvKernel},
+    matrix_multiplication::{
+        algorithms::{grid_side, Algorithm, TileScheduling},
+        checked::Checked,
+        element::{Element, TypedAlgorithm},
+        fixed::Fixed,
+        generate::{
+            generate_random_matrix_with_rng, generate_random_sparse_square_matrix_with_rng,
+            generate_random_square_matrix_with_rng,
+            generate_random_symmetric_square_matrix_with_rng,
+            generate_random_triangular_square_matrix_with_rng, seeded_rng, MAX_ABS_VALUE_DEFAULT,
+        },
+        initialize,
+        matrix::Matrix,
+        matrix_multiplication, multiply_into,
+        registry::{MatMulAlgorithm, Registry},
+        sanitize::SanitizeError,
+        saturating::Saturating,
+        simd,
+        types::{Arithmetic, ElementType, Shape},
+    },
+    order::ExecutionOrder,
+    output::{
+        csv, format_duration, format_optional, format_time, html, latex, markdown, OutputFormat,
+        ResultRow, Table,
+    },
+    quantized::{quantized_multiplication, to_i8, QuantizedKernel},
+    runner::{time_algorithm, BenchmarkRunner, Runs},
+    sparse::{spgemm, CsrMatrix, SparseKernel},
+    stats::{trimmed_mean, Summary},
+    symmetric::{symm, PackedSymmetricMatrix, SymmetricKernel},
+    system::{
+        format_kib, l1_data_cache_kib, tile_size_for_cache, FrequencyScaling, PeakMemory,
+        PeakMemoryTracker, SystemInfo,
+    },
+    thread_pool::{PoolMetrics, ThreadPool},
+    triangular::{trmm, Triangle, TriangularKernel},
+    tsc,
+};
+
+#[cfg(feature = "isolate")]
+use crate::isolate;
+#[cfg(feature = "numa")]
+use crate::numa;
+#[cfg(feature = "json")]
+use crate::output::json;
+#[cfg(feature = "plot")]
+use crate::output::plot;
+#[cfg(feature = "sqlite")]
+use crate::output::sqlite;
+#[cfg(feature = "perf")]
+use crate::perf::{self, Counters};
+#[cfg(feature = "plot")]
+use std::path::Path;
+
+/// A kernel that can be timed by the benchmark suite.
+pub trait Benchmarkable: Clone + std::fmt::Display {
+    /// Representation of the input matrices the kernel works on
+    type Inputs<'a>;
+    /// Result of the kernel
+    type Output;
+
+    /// Returns the number of threads used by the kernel, or `None` if it is sequential.
+    fn threads(&self) -> Option<usize>;
+
+    /// Returns the number of workers of the `ThreadPool` given to the kernel, or `None` if it is
+    /// given none, being sequential or running on threads of its own.
+    fn pool_threads(&self) -> Option<usize> {
+        self.threads()
+    }
+
+    /// Returns the number the size of the square matrices the kernel multiplies must be a
+    /// multiple of, e.g. its tile size.
+    fn size_multiple(&self) -> usize {
+        1
+    }
+
+    /// Returns the type of the elements the kernel multiplies.
+    fn element_type(&self) -> ElementType {
+        ElementType::I32
+    }
+
+    /// Returns the number of arithmetic operations performed by the kernel on matrices of the
+    /// given shape.
+    fn operations(&self, shape: Shape) -> f64;
+
+    /// Converts the dense matrices `a` and `b` to the representation the kernel works on, and
+    /// initializes the state the kernel keeps across its runs. Neither is part of the measurements.
+    fn prepare<'a>(&self, a: &'a Matrix<i32>, b: &'a Matrix<i32>) -> Self::Inputs<'a>;
+
+    /// Runs the kernel on the prepared inputs, returning the resulting matrix.
+    /// Parallel kernels submit their jobs to `pool`.
+    fn run(
+        &self,
+        inputs: &Self::Inputs<'_>,
+        pool: Option<&ThreadPool>,
+    ) -> Result<Self::Output, SanitizeError>;
+
+    /// Returns whether `output` agrees with `reference`, the product of the dense inputs computed
+    /// by the sequential ikj algorithm, or `None` if the result of the kernel cannot be compared
+    /// with it.
+    fn check(&self, _output: &Self::Output, _reference: &Matrix<i32>) -> Option<bool> {
+        None
+    }
+}
+
+impl Benchmarkable for Algorithm {
+    type Inputs<'a> = (&'a Matrix<i32>, &'a Matrix<i32>);
+    type Output = Matrix<i32>;
+
+    fn threads(&self) -> Option<usize> {
+        Algorithm::threads(self)
+    }
+
+    fn pool_threads(&self) -> Option<usize> {
+        Algorithm::pool_threads(self)
+    }
+
+    fn size_multiple(&self) -> usize {
+        Algorithm::size_multiple(self)
+    }
+
+    /// A multiplication and an addition for each of the `m` `k` `n` inner iterations.
+    fn operations(&self, shape: Shape) -> f64 {
+        2.0 * shape.m as f64 * shape.k as f64 * shape.n as f64
+    }
+
+    fn prepare<'a>(&self, a: &'a Matrix<i32>, b: &'a Matrix<i32>) -> Self::Inputs<'a> {
+        initialize(*self);
+        (a, b)
+    }
+
+    fn run(
+        &self,
+        (a, b): &Self::Inputs<'_>,
+        pool: Option<&ThreadPool>,
+    ) -> Result<Matrix<i32>, SanitizeError> {
+        matrix_multiplication(a, b, *self, pool)
+    }
+
+    fn check(&self, output: &Matrix<i32>, reference: &Matrix<i32>) -> Option<bool> {
+        Some(output == reference)
+    }
+}
+
+impl<T: Element> Benchmarkable for TypedAlgorithm<T> {
+    type Inputs<'a> = (Matrix<T>, Matrix<T>);
+    type Output = Matrix<T>;
+
+    fn threads(&self) -> Option<usize> {
+        self.algorithm.threads()
+    }
+
+    fn pool_threads(&self) -> Option<usize> {
+        self.algorithm.pool_threads()
+    }
+
+    fn size_multiple(&self) -> usize {
+        self.algorithm.size_multiple()
+    }
+
+    fn element_type(&self) -> ElementType {
+        T::ELEMENT_TYPE
+    }
+
+    /// A multiplication and an addition for each of the `m` `k` `n` inner iterations, which
+    /// take four real multiplications and four real additions on complex elements.
+    fn operations(&self, shape: Shape) -> f64 {
+        let operations = match T::ELEMENT_TYPE.is_complex() {
+            true => 8.0,
+            false => 2.0,
+        };
+        operations * shape.m as f64 * shape.k as f64 * shape.n as f64
+    }
+
+    fn prepare<'a>(&self, a: &'a Matrix<i32>, b: &'a Matrix<i32>) -> Self::Inputs<'a> {
+        initialize(self.algorithm);
+        let to_element = |m: &Matrix<i32>| m.map(|x| T::from_i32(*x));
+        (to_element(a), to_element(b))
+    }
+
+    fn run(
+        &self,
+        (a, b): &Self::Inputs<'_>,
+        pool: Option<&ThreadPool>,
+    ) -> Result<Matrix<T>, SanitizeError> {
+        self.multiply(a, b, pool)
+    }
+}
+
+impl Benchmarkable for Arc<dyn MatMulAlgorithm> {
+    type Inputs<'a> = (&'a Matrix<i32>, &'a Matrix<i32>);
+    type Output = Matrix<i32>;
+
+    fn threads(&self) -> Option<usize> {
+        MatMulAlgorithm::threads(self.as_ref())
+    }
+
+    fn pool_threads(&self) -> Option<usize> {
+        MatMulAlgorithm::pool_threads(self.as_ref())
+    }
+
+    fn size_multiple(&self) -> usize {
+        MatMulAlgorithm::size_multiple(self.as_ref())
+    }
+
+    /// A multiplication and an addition for each of the `m` `k` `n` inner iterations.
+    fn operations(&self, shape: Shape) -> f64 {
+        2.0 * shape.m as f64 * shape.k as f64 * shape.n as f64
+    }
+
+    fn prepare<'a>(&self, a: &'a Matrix<i32>, b: &'a Matrix<i32>) -> Self::Inputs<'a> {
+        MatMulAlgorithm::initialize(self.as_ref());
+        (a, b)
+    }
+
+    fn run(
+        &self,
+        (a, b): &Self::Inputs<'_>,
+        pool: Option<&ThreadPool>,
+    ) -> Result<Matrix<i32>, SanitizeError> {
+        self.multiply(a, b, pool)
+    }
+
+    fn check(&self, output: &Matrix<i32>, reference: &Matrix<i32>) -> Option<bool> {
+        Some(output == reference)
+    }
+}
+
+impl Benchmarkable for ElementwiseKernel {
+    type Inputs<'a> = (&'a Matrix<i32>, &'a Matrix<i32>);
+    type Output = Matrix<i32>;
+
+    fn threads(&self) -> Option<usize> {
+        ElementwiseKernel::threads(self)
+    }
+
+    /// A single operation per element.
+    fn operations(&self, shape: Shape) -> f64 {
+        shape.m as f64 * shape.n as f64
+    }
+
+    fn prepare<'a>(&self, a: &'a Matrix<i32>, b: &'a Matrix<i32>) -> Self::Inputs<'a> {
+        (a, b)
+    }
+
+    fn run(
+        &self,
+        (a, b): &Self::Inputs<'_>,
+        pool: Option<&ThreadPool>,
+    ) -> Result<Matrix<i32>, SanitizeError> {
+        elementwise(a, b, *self, pool)
+    }
+}
+
+impl Benchmarkable for GemvKernel {
+    /// The matrix A, and the first row of B as the vector x
+    type Inputs<'a> = (&'a Matrix<i32>, &'a [i32]);
+    type Output = Vec<i32>;
+
+    fn threads(&self) -> Option<usize> {
+        GemvKernel::threads(self)
+    }
+
+    /// A multiplication and an addition for each of the `m` `k` elements of A.
+    fn operations(&self, shape: Shape) -> f64 {
+        2.0 * shape.m as f64 * shape.k as f64
+    }
+
+    fn prepare<'a>(&self, a: &'a Matrix<i32>, b: &'a Matrix<i32>) -> Self::Inputs<'a> {
+        (a, b.iter_rows().next().unwrap_or(&[]))
+    }
+
+    fn run(
+        &self,
+        (a, x): &Self::Inputs<'_>,
+        pool: Option<&ThreadPool>,
+    ) -> Result<Vec<i32>, SanitizeError> {
+        gemv(a, x, *self, pool)
+    }
+}
+
+impl Benchmarkable for QuantizedKernel {
+    /// The inputs converted to i8, or the error of an element out of its range
+    type Inputs<'a> = Result<(Matrix<i8>, Matrix<i8>), SanitizeError>;
+    type Output = Matrix<i32>;
+
+    fn threads(&self) -> Option<usize> {
+        QuantizedKernel::threads(self)
+    }
+
+    fn element_type(&self) -> ElementType {
+        ElementType::I8
+    }
+
+    /// A multiplication and an addition for each of the `m` `k` `n` inner iterations.
+    fn operations(&self, shape: Shape) -> f64 {
+        2.0 * shape.m as f64 * shape.k as f64 * shape.n as f64
+    }
+
+    /// The generated elements are between -10 and 10, so they fit in i8, but loaded ones may not.
+    fn prepare<'a>(&self, a: &'a Matrix<i32>, b: &'a Matrix<i32>) -> Self::Inputs<'a> {
+        Ok((to_i8(a, "A")?, to_i8(b, "B")?))
+    }
+
+    fn run(
+        &self,
+        inputs: &Self::Inputs<'_>,
+        pool: Option<&ThreadPool>,
+    ) -> Result<Matrix<i32>, SanitizeError> {
+        let (a, b) = inputs.as_ref().map_err(Clone::clone)?;
+        quantized_multiplication(a, b, *self, pool)
+    }
+}
 
-use cli_table::Cell;
-use log::{error, info};
+impl Benchmarkable for SparseKernel {
+    type Inputs<'a> = (CsrMatrix, CsrMatrix);
+    type Output = CsrMatrix;
+
+    fn threads(&self) -> Option<usize> {
+        SparseKernel::threads(self)
+    }
+
+    /// A multiplication and an addition for every pair of nonzeros of A and B that meet, whose
+    /// expected number is `m` `k` `n` times the densities of A and B.
+    fn operations(&self, shape: Shape) -> f64 {
+        let (density_a, density_b) = self.densities();
+        2.0 * shape.m as f64 * shape.k as f64 * shape.n as f64 * density_a * density_b
+    }
+
+    fn prepare<'a>(&self, a: &'a Matrix<i32>, b: &'a Matrix<i32>) -> Self::Inputs<'a> {
+        let (a, b) = (CsrMatrix::from_dense(a), CsrMatrix::from_dense(b));
+        debug!("A has {} nonzeros, B has {} nonzeros", a.nnz(), b.nnz());
+        (a, b)
+    }
+
+    fn run(
+        &self,
+        (a, b): &Self::Inputs<'_>,
+        pool: Option<&ThreadPool>,
+    ) -> Result<CsrMatrix, SanitizeError> {
+        spgemm(a, b, *self, pool)
+    }
+}
+
+/// Execution times of a single run of a kernel.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct Timing {
+    /// Wall-clock time spent running the kernel
+    pub kernel: Duration,
+    /// Time spent running the kernel on the clock selected with `--clock`, if it is not the wall
+    /// clock and it is available on this platform
+    pub clock: Option<Duration>,
+    /// Time-stamp counter cycles spent running the kernel, if requested with `--tsc`
+    pub cycles: Option<u64>,
+    /// Time spent spawning and tearing down the thread pool, zero for sequential kernels
+    pub pool: Duration,
+    /// Scheduling metrics of the thread pool, `None` for sequential kernels
+    pub pool_metrics: Option<PoolMetrics>,
+    /// Whether the result agreed with the sequential ikj reference, `None` if it was not checked
+    /// with `--check` or the kernel cannot be checked
+    pub correct: Option<bool>,
+    /// Hardware events counted with `--perf-counters`, including the ones of the spawn and
+    /// teardown of the thread pool, if the counters are available
+    #[cfg(feature = "perf")]
+    pub counters: Option<Counters>,
+    /// Peak resident memory while the kernel ran, tracked with `--peak-memory` where the
+    /// platform allows it
+    pub peak_memory: Option<PeakMemory>,
+    /// CPU time consumed by all the threads of the process while the kernel ran, measured with
+    /// `--cpu-time` where the platform allows it
+    pub cpu_usage: Option<CpuUsage>,
+    /// Number of runs during which the CPUs throttled, 0 or 1 for a single run and summed over
+    /// the iterations by the averaged timings
+    #[cfg_attr(feature = "serde", serde(default))]
+    pub throttled: usize,
+    /// Position of the run among the runs of its iteration, 0 for the first, as set by
+    /// `--order`. Meaningless for averaged timings.
+    #[cfg_attr(feature = "serde", serde(default))]
+    pub position: usize,
+}
+
+/// Options controlling how the kernels are timed.
+#[derive(Debug, Clone, Copy)]
+pub struct TimingOptions {
+    /// Clock reported next to the wall clock
+    pub clock: Clock,
+    /// Whether to count the time-stamp counter cycles spent running the kernel
+    pub tsc: bool,
+    /// Whether to run the jobs of parallel kernels on a single worker, in submission order
+    pub serialize_parallel: bool,
+    /// Whether to count the hardware events of the kernel
+    #[cfg(feature = "perf")]
+    pub perf_counters: bool,
+    /// Whether to track the peak resident memory while the kernel runs
+    pub peak_memory: bool,
+    /// Whether to measure the user and system CPU time of the kernel
+    pub cpu_time: bool,
+}
+
+impl From<&RunArgs> for TimingOptions {
+    fn from(run: &RunArgs) -> Self {
+        TimingOptions {
+            clock: run.clock,
+            tsc: run.tsc && tsc::is_available(),
+            serialize_parallel: run.serialize_parallel,
+            #[cfg(feature = "perf")]
+            perf_counters: run.perf_counters,
+            peak_memory: run.peak_memory,
+            cpu_time: run.cpu_time,
+        }
+    }
+}
+
+/// Returns the throughput of `operations` arithmetic operations done in `elapsed`, in billions of
+/// operations per second.
+pub fn gops(operations: f64, elapsed: Duration) -> f64 {
+    operations / elapsed.as_secs_f64() / 1e9
+}
+
+/// Returns the aggregated results in the form used by the exported formats.
+/// The speedup of each algorithm is relative to the fastest sequential algorithm, if any was run
+/// long enough to be measured.
+fn result_rows<K: Benchmarkable>(results: &[(K, Timing)], shape: Shape) -> Vec<ResultRow> {
+    let fastest_sequential = results
+        .iter()
+        .filter(|(algorithm, _)| algorithm.threads().is_none())
+        .map(|(_, time)| time.kernel)
+        .filter(|kernel| !kernel.is_zero())
+        .min();
+
+    results
+        .iter()
+        .map(|(algorithm, time)| ResultRow {
+            algorithm: algorithm.to_string(),
+            time: time.kernel,
+            gops: (!time.kernel.is_zero()).then(|| gops(algorithm.operations(shape), time.kernel)),
+            speedup: fastest_sequential
+                .filter(|_| !time.kernel.is_zero())
+                .map(|fastest| fastest.as_secs_f64() / time.kernel.as_secs_f64()),
+        })
+        .collect()
+}
+
+/// Returns the number of workers of the pool of a kernel using `threads` threads.
+/// Serialized pools have a single worker, which runs the jobs in submission order. The kernels
+/// still split their work by their own number of threads, so that only the execution is
+/// serialized and the jobs are the same.
+pub fn pool_size(threads: usize, serialize_parallel: bool) -> usize {
+    match serialize_parallel {
+        true => 1,
+        false => threads,
+    }
+}
+
+/// The `A` and `B` input matrices of one iteration.
+pub(crate) type InputPair = (Matrix<i32>, Matrix<i32>);
+
+impl Benchmarkable for TriangularKernel {
+    type Inputs<'a> = (&'a Matrix<i32>, &'a Matrix<i32>);
+    type Output = Matrix<i32>;
+
+    fn threads(&self) -> Option<usize> {
+        TriangularKernel::threads(self)
+    }
+
+    /// A multiplication and an addition for each of the `m` (`m` + 1) / 2 elements of the
+    /// triangle of the square A times the `n` columns of B.
+    fn operations(&self, shape: Shape) -> f64 {
+        let (m, n) = (shape.m as f64, shape.n as f64);
+        m * (m + 1.0) * n
+    }
+
+    fn prepare<'a>(&self, a: &'a Matrix<i32>, b: &'a Matrix<i32>) -> Self::Inputs<'a> {
+        (a, b)
+    }
+
+    fn run(
+        &self,
+        (a, b): &Self::Inputs<'_>,
+        pool: Option<&ThreadPool>,
+    ) -> Result<Matrix<i32>, SanitizeError> {
+        trmm(a, b, *self, pool)
+    }
+}
+
+impl Benchmarkable for SymmetricKernel {
+    type Inputs<'a> = (PackedSymmetricMatrix, &'a Matrix<i32>);
+    type Output = Matrix<i32>;
+
+    fn threads(&self) -> Option<usize> {
+        SymmetricKernel::threads(self)
+    }
+
+    /// A multiplication and an addition for each of the `m` `k` `n` inner iterations, as the
+    /// symmetry saves memory traffic but no arithmetic.
+    fn operations(&self, shape: Shape) -> f64 {
+        2.0 * shape.m as f64 * shape.k as f64 * shape.n as f64
+    }
+
+    fn prepare<'a>(&self, a: &'a Matrix<i32>, b: &'a Matrix<i32>) -> Self::Inputs<'a> {
+        let a = PackedSymmetricMatrix::from_upper(a);
+        debug!("A packed in {} bytes", a.bytes());
+        (a, b)
+    }
+
+    fn run(
+        &self,
+        (a, b): &Self::Inputs<'_>,
+        pool: Option<&ThreadPool>,
+    ) -> Result<Matrix<i32>, SanitizeError> {
+        symm(a, b, *self, pool)
+    }
+}
+
+/// Returns the seed the initial C of the accumulating kernels is generated from, derived from the
+/// elements of A and B, so that the same inputs, e.g. generated with `--seed` or loaded with
+/// `--load-inputs`, give the same C on every machine
+fn accumulate_seed(a: &Matrix<i32>, b: &Matrix<i32>) -> u64 {
+    // FNV-1a, stable across platforms and toolchains unlike the hasher of the standard library
+    a.iter_rows()
+        .chain(b.iter_rows())
+        .flatten()
+        .fold(0xcbf2_9ce4_8422_2325, |hash, x| {
+            (hash ^ *x as u32 as u64).wrapping_mul(0x100_0000_01b3)
+        })
+}
+
+impl Benchmarkable for AccumulateKernel {
+    /// A and B, and the initial C, generated at random from A and B
+    type Inputs<'a> = (&'a Matrix<i32>, &'a Matrix<i32>, RefCell<Matrix<i32>>);
+    type Output = ();
+
+    fn threads(&self) -> Option<usize> {
+        AccumulateKernel::threads(self)
+    }
+
+    fn pool_threads(&self) -> Option<usize> {
+        self.algorithm.pool_threads()
+    }
+
+    fn size_multiple(&self) -> usize {
+        self.algorithm.size_multiple()
+    }
+
+    /// A multiplication and an addition for each of the `m` `k` `n` inner iterations, and two
+    /// multiplications and an addition for each of the `m` `n` elements of C.
+    fn operations(&self, shape: Shape) -> f64 {
+        let (m, k, n) = (shape.m as f64, shape.k as f64, shape.n as f64);
+        2.0 * m * k * n + 3.0 * m * n
+    }
+
+    fn prepare<'a>(&self, a: &'a Matrix<i32>, b: &'a Matrix<i32>) -> Self::Inputs<'a> {
+        initialize(self.algorithm);
+        let c = generate_random_matrix_with_rng(
+            a.rows(),
+            b.cols(),
+            MAX_ABS_VALUE_DEFAULT,
+            &mut StdRng::seed_from_u64(accumulate_seed(a, b)),
+        );
+        (a, b, RefCell::new(c))
+    }
+
+    fn run(
+        &self,
+        (a, b, c): &Self::Inputs<'_>,
+        pool: Option<&ThreadPool>,
+    ) -> Result<(), SanitizeError> {
+        multiply_into(
+            self.alpha,
+            a,
+            b,
+            self.beta,
+            &mut c.borrow_mut(),
+            self.algorithm,
+            pool,
+        )
+    }
+}
+
+/// Distribution of the generated input matrices
+#[derive(Debug, Clone, Copy, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "serde", serde(rename_all = "snake_case"))]
+pub enum InputDistribution {
+    /// Every element is a random value
+    Dense,
+    /// Every element of A and B is a nonzero random value with the given probability, and zero
+    /// otherwise
+    ///
+    /// # Arguments
+    ///
+    /// * `f64` - density of A
+    /// * `f64` - density of B
+    Sparse(f64, f64),
+    /// Every element of A in the given triangle, and every element of B, is a random value. The
+    /// other elements of A are zero.
+    Triangular(Triangle),
+    /// Every element of A on and above the diagonal, and every element of B, is a random value.
+    /// The elements of A below the diagonal mirror the ones above.
+    Symmetric,
+}
+
+impl std::fmt::Display for InputDistribution {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            InputDistribution::Dense => write!(f, "dense"),
+            InputDistribution::Sparse(density_a, density_b) => {
+                write!(f, "sparse ({} density of A, {} of B)", density_a, density_b)
+            }
+            InputDistribution::Triangular(triangle) => write!(f, "{} triangular", triangle),
+            InputDistribution::Symmetric => write!(f, "symmetric"),
+        }
+    }
+}
+
+impl InputDistribution {
+    /// Generates the `m` x `k` matrix `A` and the `k` x `n` matrix `B` from `rng`. The
+    /// distributions other than `Dense` only generate square matrices, of size `n`.
+    pub(crate) fn generate(&self, shape: Shape, rng: &mut StdRng) -> InputPair {
+        let size = shape.n;
+        match self {
+            InputDistribution::Dense => (
+                generate_random_matrix_with_rng(shape.m, shape.k, MAX_ABS_VALUE_DEFAULT, rng),
+                generate_random_matrix_with_rng(shape.k, shape.n, MAX_ABS_VALUE_DEFAULT, rng),
+            ),
+            InputDistribution::Symmetric => (
+                generate_random_symmetric_square_matrix_with_rng(size, MAX_ABS_VALUE_DEFAULT, rng),
+                generate_random_square_matrix_with_rng(size, MAX_ABS_VALUE_DEFAULT, rng),
+            ),
+            InputDistribution::Sparse(density_a, density_b) => (
+                generate_random_sparse_square_matrix_with_rng(
+                    size,
+                    *density_a,
+                    MAX_ABS_VALUE_DEFAULT,
+                    rng,
+                ),
+                generate_random_sparse_square_matrix_with_rng(
+                    size,
+                    *density_b,
+                    MAX_ABS_VALUE_DEFAULT,
+                    rng,
+                ),
+            ),
+            InputDistribution::Triangular(triangle) => (
+                generate_random_triangular_square_matrix_with_rng(
+                    size,
+                    *triangle,
+                    MAX_ABS_VALUE_DEFAULT,
+                    rng,
+                ),
+                generate_random_square_matrix_with_rng(size, MAX_ABS_VALUE_DEFAULT, rng),
+            ),
+        }
+    }
+}
+
+/// Returns whether the inputs are generated once and reused by every iteration, as described in
+/// the args table and in the reports.
+fn inputs_mode(run: &RunArgs) -> &'static str {
+    match run.fixed_inputs {
+        true => "fixed, reused by every iteration",
+        false => "new for every iteration",
+    }
+}
+
+/// Returns the rows of the args table describing the options shared by every benchmark subprogram.
+/// Rows describing the frequency scaling of the CPUs, with a warning row for every setting that
+/// makes the timings unreliable, are added when it can be read.
+fn run_args_rows(run: &RunArgs) -> Vec<Vec<CellStruct>> {
+    let mut rows = vec![
+        vec!["Clock".cell(), run.clock.to_string().cell()],
+        vec!["Inputs".cell(), inputs_mode(run).cell()],
+        vec!["Execution order".cell(), run.order.to_string().cell()],
+    ];
+    rows.extend(
+        SystemInfo::current()
+            .rows()
+            .into_iter()
+            .map(|(name, value)| vec![name.cell(), value.cell()]),
+    );
+    let scaling = FrequencyScaling::current();
+    if !scaling.governors.is_empty() {
+        rows.push(vec![
+            "CPU frequency governor".cell(),
+            scaling.governors.join(", ").cell(),
+        ]);
+    }
+    if let Some(turbo) = scaling.turbo {
+        let turbo = match turbo {
+            true => "enabled",
+            false => "disabled",
+        };
+        rows.push(vec!["Turbo boost".cell(), turbo.cell()]);
+    }
+    for warning in scaling.warnings() {
+        warn!("{}", warning);
+        rows.push(vec!["WARNING".red().bold().cell(), warning.red().cell()]);
+    }
+    if let Some(align) = run.align {
+        rows.push(vec!["Input alignment (bytes)".cell(), align.cell()]);
+    }
+    if let Some(pad) = run.pad {
+        rows.push(vec!["Row padding (elements)".cell(), pad.cell()]);
+    }
+    if run.serialize_parallel {
+        rows.push(vec!["Serialize parallel".cell(), "true".cell()]);
+    }
+    if let Some(min_time) = run.min_time {
+        rows.push(vec![
+            "Minimum time".cell(),
+            format!("{:?}", min_time).cell(),
+        ]);
+    }
+    if let Some(trim) = run.trim {
+        rows.push(vec!["Trimmed mean (%)".cell(), trim.cell()]);
+    }
+    // the pinning and the NUMA policy are applied once by `set_up`, and listed if they could be
+    if let Some(cores) = affinity::pinned_cores() {
+        rows.push(vec!["Pinned cores".cell(), format_cores(cores).cell()]);
+    }
+    #[cfg(feature = "numa")]
+    if let Some(policy) = numa::applied() {
+        rows.push(vec!["NUMA policy".cell(), policy.cell()]);
+    }
+    if let Some(pause) = run.throttle_pause {
+        rows.push(vec![
+            "Pause when throttled".cell(),
+            format!("{:?}", pause).cell(),
+        ]);
+    }
+    if run.warmup > 0 {
+        rows.push(vec!["Warm-up runs".cell(), run.warmup.cell()]);
+    }
+    if run.check {
+        rows.push(vec!["Check results".cell(), "yes".cell()]);
+    }
+    #[cfg(feature = "isolate")]
+    if run.isolate {
+        rows.push(vec![
+            "Isolated runs".cell(),
+            "a child process per run".cell(),
+        ]);
+    }
+    if run.cpu_time && CpuUsage::now().is_none() {
+        warn!("The CPU time cannot be measured on this platform");
+    }
+    if run.peak_memory {
+        match PeakMemoryTracker::start() {
+            Some(_) => rows.push(vec!["Peak memory".cell(), "tracked".cell()]),
+            None => warn!("The peak resident memory cannot be tracked on this platform"),
+        }
+    }
+    #[cfg(feature = "perf")]
+    if run.perf_counters {
+        match perf::is_available() {
+            true => rows.push(vec![
+                "Performance counters".cell(),
+                "cycles, instructions, cache misses".cell(),
+            ]),
+            false => warn!("The hardware performance counters are not available on this machine"),
+        }
+    }
+    if run.tsc {
+        match tsc::calibrate() {
+            Some(frequency) => {
+                if !tsc::is_invariant() {
+                    warn!(
+                        "The time-stamp counter is not invariant, cycle counts may be unreliable"
+                    );
+                }
+                rows.push(vec![
+                    "TSC frequency (GHz)".cell(),
+                    format!("{:.3}", frequency).cell(),
+                ]);
+            }
+            None => warn!("The time-stamp counter is not available on this platform"),
+        }
+    }
+    if let Some(seed) = run.seed {
+        rows.push(vec!["Seed".cell(), seed.cell()]);
+    }
+    if let Some(dir) = &run.save_inputs {
+        rows.push(vec!["Save inputs to".cell(), dir.display().cell()]);
+    }
+    if let Some(dir) = &run.load_inputs {
+        rows.push(vec!["Load inputs from".cell(), dir.display().cell()]);
+    }
+    rows
+}
+
+/// Sets up the process before a benchmark subprogram prints anything: when the results are printed
+/// as a document, the titles and the args table go to the standard error, so that the standard
+/// output only holds the document. The children of `--isolate` keep them on their standard
+/// output, read by the parent, instead of printing them along the ones of the parent.
+/// The placement of the threads is applied here, once, see [`apply_placement`].
+pub fn set_up(run: &RunArgs) {
+    #[cfg(feature = "isolate")]
+    let child = isolate::child_run().is_some();
+    #[cfg(not(feature = "isolate"))]
+    let child = false;
+    print_tables_to_stderr(run.output.is_document() && !child);
+    if let Err(err) = apply_placement(run) {
+        warn!("{}", err);
+    }
+}
+
+/// Pins the benchmark to the cores of `--pin-cores` and sets the NUMA policy of `--numa`, before
+/// any matrix is allocated and any thread is spawned. Applying the same placement again only
+/// applies it to the calling thread. Either is applied even if the other one fails.
+pub fn apply_placement(run: &RunArgs) -> Result<(), String> {
+    let mut errors = Vec::new();
+    if let Some(cores) = &run.pin_cores {
+        if let Err(err) = affinity::pin(cores) {
+            errors.push(format!("Cannot pin the benchmark to the cores: {}", err));
+        }
+    }
+    #[cfg(feature = "numa")]
+    if let Some(policy) = run.numa {
+        if let Err(err) = numa::apply(policy) {
+            errors.push(format!("Cannot set the NUMA policy {}: {}", policy, err));
+        }
+    }
+    match errors.is_empty() {
+        true => Ok(()),
+        false => Err(errors.join(". ")),
+    }
+}
+
+/// Runs the benchmark suite of `algorithms` on matrices of the given shape, with the options of
+/// the command line
+fn run_benchmark<K: Benchmarkable>(
+    algorithms: &[K],
+    iterations: usize,
+    shape: Shape,
+    distribution: InputDistribution,
+    run: &RunArgs,
+) -> Result<Runs<K>, String> {
+    BenchmarkRunner::new(algorithms.iter().cloned())
+        .iterations(iterations)
+        .distribution(distribution)
+        .options(run.clone())
+        .suite(shape)
+}
+
+/// Prints what running the algorithms would cost instead of running them, for `--dry-run`: the
+/// memory footprint of the matrices, and for every algorithm the number of jobs it submits and a
+/// rough estimate of its time. Both are extrapolated from quick multiplications of smaller
+/// matrices of the same proportions.
+fn dry_run<K: Benchmarkable>(
+    algorithms: &[K],
+    iterations: usize,
+    shape: Shape,
+    distribution: InputDistribution,
+    run: &RunArgs,
+) {
+    let element_type = algorithms
+        .first()
+        .map_or(ElementType::I32, Benchmarkable::element_type);
+    // the reference product of --check is always computed on i32 elements
+    let matrices = estimate::footprint(
+        shape,
+        element_type.size(),
+        run.pad.unwrap_or(0),
+        run.check.then(|| ElementType::I32.size()),
+    );
+    let total = matrices.iter().map(|(_, bytes)| bytes).sum::<u64>();
+    let mut rows = matrices
+        .into_iter()
+        .map(|(matrix, bytes)| vec![matrix, format_kib(bytes.div_ceil(1024))])
+        .collect::<Vec<_>>();
+    rows.push(vec!["Total".to_string(), format_kib(total.div_ceil(1024))]);
+    let table = Table {
+        title: format!("Memory Footprint ({})", shape),
+        columns: vec!["Matrix".to_string(), "Size".to_string()],
+        rows,
+    };
+    print_results_table(&table, run.output);
+
+    let largest = |shape: &Shape| shape.m.max(shape.k).max(shape.n) as f64;
+    let mut rng = seeded_rng(run.seed);
+    // the calibration inputs are shared by the algorithms working on multiples of the same size
+    let mut calibrations_by_multiple: HashMap<usize, (Vec<Shape>, Vec<InputPair>)> = HashMap::new();
+
+    let columns = ["Algorithm", "Jobs", "Time per iteration", "Total time"]
+        .map(String::from)
+        .to_vec();
+    let mut rows = Vec::with_capacity(algorithms.len());
+    let mut total = Duration::ZERO;
+    for algorithm in algorithms {
+        let (calibrations, inputs) = calibrations_by_multiple
+            .entry(algorithm.size_multiple())
+            .or_insert_with_key(|multiple| {
+                let calibrations = estimate::calibration_shapes(shape, *multiple);
+                info!(
+                    "Calibrating the estimates on {} matrices",
+                    calibrations
+                        .iter()
+                        .map(Shape::to_string)
+                        .collect::<Vec<_>>()
+                        .join(" and ")
+                );
+                let inputs = calibrations
+                    .iter()
+                    .map(|calibration| distribution.generate(*calibration, &mut rng))
+                    .collect();
+                (calibrations, inputs)
+            });
+        let timings = inputs
+            .iter()
+            .map(|(a, b)| time_algorithm(algorithm, a, b, None, run.into()))
+            .collect::<Vec<_>>();
+        let (Some(small), Some(large)) = (timings[0], timings[timings.len() - 1]) else {
+            rows.push(vec![
+                algorithm.to_string(),
+                "-".into(),
+                "-".into(),
+                "-".into(),
+            ]);
+            continue;
+        };
+        let calibration = calibrations[calibrations.len() - 1];
+        // sequential kernels submit no jobs
+        let jobs = match (small.pool_metrics, large.pool_metrics) {
+            (Some(small_metrics), Some(large_metrics)) => format!(
+                "{:.0}",
+                estimate::extrapolate(
+                    (largest(&calibrations[0]), small_metrics.jobs as f64),
+                    (largest(&calibration), large_metrics.jobs as f64),
+                    largest(&shape)
+                )
+            ),
+            _ => "-".to_string(),
+        };
+        let per_iteration = Duration::from_secs_f64(
+            large.kernel.as_secs_f64() * algorithm.operations(shape)
+                / algorithm.operations(calibration),
+        );
+        // with --min-time, the algorithm runs until it has spent the budget
+        let algorithm_total = ((per_iteration + large.pool) * iterations as u32)
+            .max(run.min_time.unwrap_or_default());
+        total += algorithm_total;
+        rows.push(vec![
+            algorithm.to_string(),
+            jobs,
+            format_duration(per_iteration),
+            format_duration(algorithm_total),
+        ]);
+    }
+    let table = Table {
+        title: format!("Estimates ({})", shape),
+        columns,
+        rows,
+    };
+    print_results_table(&table, run.output);
+    info!(
+        "The algorithms would run for about {} on {} matrices",
+        format_duration(total),
+        shape
+    );
+}
+
+/// Runs the benchmark on the specified algorithms for the specified number of iterations, and prints
+/// the results. Returns the average timing of every algorithm, or nothing if the benchmark could
+/// not run.
+///
+/// # Arguments
+///
+/// * `algorithms` - The algorithms to benchmark.
+/// * `iterations` - The number of iterations to run the benchmark for.
+/// * `shape` - The shape of the product of the matrices.
+/// * `distribution` - The distribution of the generated input matrices.
+/// * `run` - The options shared by every benchmark subprogram.
+fn benchmark_and_print_results<K: Benchmarkable>(
+    algorithms: &[K],
+    iterations: usize,
+    shape: Shape,
+    distribution: InputDistribution,
+    run: &RunArgs,
+) -> Vec<(K, Timing)> {
+    if run.dry_run {
+        dry_run(algorithms, iterations, shape, distribution, run);
+        return Vec::new();
+    }
+
+    print_title("Benchmarking!");
+
+    let runs = match run_benchmark(algorithms, iterations, shape, distribution, run) {
+        Ok(runs) => runs,
+        Err(err) => {
+            error!("{}", err);
+            return Vec::new();
+        }
+    };
+
+    let kernel_times = runs
+        .iter()
+        .map(|(_, times)| {
+            times
+                .iter()
+                .map(|time| time.kernel.as_secs_f64() * 1e3)
+                .collect::<Vec<_>>()
+        })
+        .collect::<Vec<_>>();
+    let summaries = kernel_times
+        .iter()
+        .map(|times| Summary::new(times))
+        .collect::<Vec<_>>();
+    let trimmed_means = kernel_times
+        .iter()
+        .map(|times| run.trim.map(|trim| trimmed_mean(times, trim / 100.0)))
+        .collect::<Vec<_>>();
+
+    let results = runs
+        .iter()
+        .map(|(algorithm, times)| {
+            let kernel_sum: Duration = times.iter().map(|time| time.kernel).sum();
+            let clock_sum: Option<Duration> = times.iter().map(|time| time.clock).sum();
+            let cycles_sum: Option<u64> = times.iter().map(|time| time.cycles).sum();
+            let pool_sum: Duration = times.iter().map(|time| time.pool).sum();
+            let user_sum: Option<Duration> = times
+                .iter()
+                .map(|time| time.cpu_usage.map(|usage| usage.user))
+                .sum();
+            let system_sum: Option<Duration> = times
+                .iter()
+                .map(|time| time.cpu_usage.map(|usage| usage.system))
+                .sum();
+            let pool_metrics = times
+                .iter()
+                .filter_map(|time| time.pool_metrics)
+                .reduce(|acc, metrics| acc.merge(&metrics));
+            // a single wrong result fails the algorithm
+            let correct = times
+                .iter()
+                .filter_map(|time| time.correct)
+                .reduce(|acc, correct| acc && correct);
+            #[cfg(feature = "perf")]
+            let counters = times
+                .iter()
+                .filter_map(|time| time.counters)
+                .collect::<Vec<_>>();
+            let avg = Timing {
+                kernel: kernel_sum / times.len() as u32,
+                clock: clock_sum.map(|sum| sum / times.len() as u32),
+                cycles: cycles_sum.map(|sum| sum / times.len() as u64),
+                pool: pool_sum / times.len() as u32,
+                // aggregated over all the iterations
+                pool_metrics,
+                correct,
+                #[cfg(feature = "perf")]
+                counters: Counters::mean(&counters),
+                // the highest over all the iterations
+                peak_memory: times
+                    .iter()
+                    .filter_map(|time| time.peak_memory)
+                    .max_by_key(|memory| memory.increase_kib),
+                cpu_usage: user_sum.zip(system_sum).map(|(user, system)| CpuUsage {
+                    user: user / times.len() as u32,
+                    system: system / times.len() as u32,
+                }),
+                throttled: times.iter().map(|time| time.throttled).sum(),
+                position: 0,
+            };
+            (algorithm.clone(), avg)
+        })
+        .collect::<Vec<_>>();
+
+    let mut tables = vec![results_table(
+        &results,
+        &summaries,
+        &trimmed_means,
+        shape,
+        run,
+    )];
+    if run.show_iterations {
+        tables.push(iterations_table(&runs));
+    }
+    if run.order != ExecutionOrder::Fixed {
+        tables.push(order_table(&runs));
+    }
+    if run.pool_metrics {
+        tables.push(pool_metrics_table(&runs));
+    }
+
+    if let Some(path) = &run.report {
+        let mut parameters = report_parameters(shape, iterations, distribution, run);
+        parameters.extend(SystemInfo::current().rows());
+        let report = html::Report {
+            parameters,
+            tables: tables.clone(),
+            series: runs
+                .iter()
+                .map(|(algorithm, times)| html::Series {
+                    algorithm: algorithm.to_string(),
+                    times: times
+                        .iter()
+                        .map(|time| time.kernel.as_secs_f64() * 1e3)
+                        .collect(),
+                })
+                .collect(),
+        };
+        match fs::write(path, html::to_html(&report)) {
+            Ok(()) => info!("Report written to {}", path.display()),
+            Err(err) => error!("Cannot write the report to {}: {}", path.display(), err),
+        }
+    }
+
+    #[cfg(feature = "sqlite")]
+    if let Some(path) = &run.db {
+        let history = sqlite::Run {
+            parameters: report_parameters(shape, iterations, distribution, run),
+            results: runs
+                .iter()
+                .zip(&results)
+                .map(|((algorithm, times), (_, avg))| sqlite::AlgorithmRun {
+                    algorithm: algorithm.to_string(),
+                    element_type: algorithm.element_type(),
+                    threads: algorithm.threads(),
+                    average: avg.kernel,
+                    iterations: times.clone(),
+                })
+                .collect(),
+        };
+        match sqlite::append(path, &history) {
+            Ok(id) => info!("Run {} appended to {}", id, path.display()),
+            Err(err) => error!("Cannot append the run to {}: {}", path.display(), err),
+        }
+    }
+
+    #[cfg(feature = "json")]
+    if run.output == OutputFormat::Json {
+        let report = json::Report {
+            parameters: json::Parameters {
+                shape,
+                iterations,
+                distribution,
+                clock: run.clock,
+                align: run.align,
+                pad: run.pad,
+                serialize_parallel: run.serialize_parallel,
+                seed: run.seed,
+                fixed_inputs: run.fixed_inputs,
+                order: run.order,
+                warmup: run.warmup,
+                #[cfg(feature = "numa")]
+                numa: numa::applied().map(|policy| policy.to_string()),
+                #[cfg(feature = "isolate")]
+                isolate: run.isolate,
+            },
+            system: SystemInfo::current(),
+            results: result_rows(&results, shape)
+                .into_iter()
+                .zip(summaries)
+                .zip(runs)
+                .map(
+                    |((result, summary), (algorithm, times))| json::AlgorithmReport {
+                        result,
+                        element_type: algorithm.element_type(),
+                        threads: algorithm.threads(),
+                        summary,
+                        iterations: times,
+                    },
+                )
+                .collect(),
+        };
+        println!("{}", json::to_json(&report));
+        return results;
+    }
+
+    if run.output == OutputFormat::Csv {
+        print!("{}", csv::results_table(&result_rows(&results, shape)));
+        return results;
+    }
+
+    if run.output == OutputFormat::Latex {
+        let caption = latex::caption(shape, iterations, run.min_time);
+        print!(
+            "{}",
+            latex::results_table(&result_rows(&results, shape), &caption)
+        );
+        return results;
+    }
+
+    for table in &tables {
+        print_results_table(table, run.output);
+    }
+    results
+}
+
+/// Returns the table of the aggregated results of every algorithm, with the columns selected by
+/// the options of the run.
+fn results_table<K: Benchmarkable>(
+    results: &[(K, Timing)],
+    summaries: &[Summary],
+    trimmed_means: &[Option<f64>],
+    shape: Shape,
+    run: &RunArgs,
+) -> Table {
+    // the selected clock is reported next to the wall clock, if it could be read
+    let clock_column = run.clock != Clock::Wall;
+    let mut columns = vec![
+        "Algorithm".to_string(),
+        "Element type".to_string(),
+        "Average time".to_string(),
+        "Min".to_string(),
+        "Median".to_string(),
+        "Max".to_string(),
+        "Stddev".to_string(),
+        "95% CI of the mean".to_string(),
+        "p95".to_string(),
+    ];
+    if let Some(trim) = run.trim {
+        columns.push(format!("{}% trimmed mean", trim));
+    }
+    columns.push("Outliers".to_string());
+    // with a time budget, the algorithms run a different number of iterations
+    let iterations_column = run.min_time.is_some();
+    if iterations_column {
+        columns.push("Iterations".to_string());
+    }
+    if clock_column {
+        columns.push(format!("Average {} time", run.clock));
+    }
+    let cycles_column = TimingOptions::from(run).tsc;
+    if cycles_column {
+        columns.push("Average cycles".to_string());
+        columns.push("Cycles per element".to_string());
+    }
+    // floating point and integer throughputs are told apart, as they are on spec sheets
+    let throughput_column = match results
+        .iter()
+        .all(|(algorithm, _)| algorithm.element_type().is_float())
+    {
+        true => "GFLOP/s",
+        false => "GOP/s",
+    };
+    columns.push(throughput_column.to_string());
+    // the speedup is relative to the fastest sequential algorithm, it is only meaningful next to
+    // parallel ones
+    let speedup_column = results
+        .iter()
+        .any(|(algorithm, _)| algorithm.threads().is_none())
+        && results
+            .iter()
+            .any(|(algorithm, _)| algorithm.threads().is_some());
+    if speedup_column {
+        columns.push("Speedup vs fastest sequential".to_string());
+        columns.push("Parallel efficiency".to_string());
+    }
+    columns.push("Average pool spawn + teardown".to_string());
+    if run.cpu_time {
+        columns.push("Average user CPU time".to_string());
+        columns.push("Average system CPU time".to_string());
+        columns.push("CPU time / wall time".to_string());
+    }
+    if run.peak_memory {
+        columns.push("Peak RSS (MiB)".to_string());
+        columns.push("Peak RSS increase (MiB)".to_string());
+    }
+    #[cfg(feature = "perf")]
+    if run.perf_counters {
+        columns.extend(
+            [
+                "Cycles",
+                "Instructions",
+                "IPC",
+                "Cache misses",
+                "Cache MPKI",
+            ]
+            .map(String::from),
+        );
+    }
+    if run.check {
+        columns.push("Check".to_string());
+    }
+    // the throttled runs are only annotated when there are some
+    let throttled_column = results.iter().any(|(_, time)| time.throttled > 0);
+    if throttled_column {
+        columns.push("Throttled iterations".to_string());
+    }
+
+    let result_rows = result_rows(results, shape);
+    let rows = results
+        .iter()
+        .zip(summaries)
+        .zip(trimmed_means)
+        .zip(&result_rows)
+        .map(
+            |((((algorithm, time), summary), trimmed_mean), result_row)| {
+                let mut row = vec![
+                    algorithm.to_string(),
+                    algorithm.element_type().to_string(),
+                    format_duration(time.kernel),
+                    format_time(summary.min),
+                    format_time(summary.median),
+                    format_time(summary.max),
+                    format_time(summary.stddev),
+                    match summary.ci95 {
+                        Some(ci95) => format!("±{}", format_time(ci95)),
+                        None => "-".to_string(),
+                    },
+                    format_time(summary.p95),
+                ];
+                if let Some(trimmed_mean) = trimmed_mean {
+                    row.push(format_time(*trimmed_mean));
+                }
+                row.push(summary.outliers.to_string());
+                if iterations_column {
+                    row.push(summary.count.to_string());
+                }
+                if clock_column {
+                    let clock = match time.clock {
+                        Some(clock) => format_duration(clock),
+                        None => "n/a".to_string(),
+                    };
+                    row.push(clock);
+                }
+                if cycles_column {
+                    let cycles = time.cycles.unwrap_or_default();
+                    row.push(cycles.to_string());
+                    row.push(format!("{:.2}", cycles as f64 / (shape.m * shape.n) as f64));
+                }
+                row.push(format_optional(result_row.gops, 3));
+                if speedup_column {
+                    let speedup = match result_row.speedup {
+                        Some(speedup) => format!("{:.2}x", speedup),
+                        None => "-".to_string(),
+                    };
+                    row.push(speedup);
+                    // speedup per thread, 100% when every thread is fully used
+                    let efficiency = match (result_row.speedup, algorithm.threads()) {
+                        (Some(speedup), Some(threads)) => {
+                            format!("{:.0}%", 100.0 * speedup / threads as f64)
+                        }
+                        _ => "-".to_string(),
+                    };
+                    row.push(efficiency);
+                }
+                let pool = match algorithm.pool_threads() {
+                    Some(_) => format_duration(time.pool),
+                    None => "-".to_string(),
+                };
+                row.push(pool);
+                if run.cpu_time {
+                    match time.cpu_usage {
+                        Some(usage) => {
+                            row.push(format_duration(usage.user));
+                            row.push(format_duration(usage.system));
+                            // the average number of busy cores, at most the number of threads
+                            // when the kernel does not wait
+                            let utilization = (!time.kernel.is_zero())
+                                .then(|| usage.total().as_secs_f64() / time.kernel.as_secs_f64());
+                            row.push(format_optional(utilization, 2));
+                        }
+                        None => row.extend(["n/a"; 3].map(String::from)),
+                    }
+                }
+                if run.peak_memory {
+                    let mib = |kib: u64| format!("{:.1}", kib as f64 / 1024.0);
+                    match time.peak_memory {
+                        Some(memory) => {
+                            row.push(mib(memory.peak_kib));
+                            row.push(mib(memory.increase_kib));
+                        }
+                        None => row.extend(["n/a"; 2].map(String::from)),
+                    }
+                }
+                #[cfg(feature = "perf")]
+                if run.perf_counters {
+                    match time.counters {
+                        Some(counters) => row.extend([
+                            counters.cycles.to_string(),
+                            counters.instructions.to_string(),
+                            format_optional(counters.ipc(), 2),
+                            counters.cache_misses.to_string(),
+                            format_optional(counters.mpki(), 2),
+                        ]),
+                        None => row.extend(["n/a"; 5].map(String::from)),
+                    }
+                }
+                if run.check {
+                    let check = match time.correct {
+                        Some(true) => "ok",
+                        Some(false) => "FAILED",
+                        None => "n/a",
+                    };
+                    row.push(check.to_string());
+                }
+                if throttled_column {
+                    row.push(format!("{}/{}", time.throttled, summary.count));
+                }
+                row
+            },
+        )
+        .collect::<Vec<_>>();
+    Table {
+        title: "Benchmark Results".to_string(),
+        columns,
+        rows,
+    }
+}
+
+/// Returns the table of the kernel time of every iteration of every algorithm. Algorithms that
+/// ran fewer iterations than the others, because of `--min-time`, have dashes in the last columns.
+fn iterations_table<K: Benchmarkable>(runs: &[(K, Vec<Timing>)]) -> Table {
+    let iterations = runs.iter().map(|(_, times)| times.len()).max();
+    let iterations = iterations.unwrap_or_default();
+
+    let mut columns = vec!["Algorithm".to_string()];
+    columns.extend((1..=iterations).map(|i| format!("#{}", i)));
+    let rows = runs
+        .iter()
+        .map(|(algorithm, times)| {
+            let mut row = vec![algorithm.to_string()];
+            // the runs during which the CPUs throttled are marked with an asterisk
+            row.extend(times.iter().map(|time| match time.throttled {
+                0 => format_duration(time.kernel),
+                _ => format!("{}*", format_duration(time.kernel)),
+            }));
+            row.resize(iterations + 1, "-".to_string());
+            row
+        })
+        .collect();
+    Table {
+        title: "Iterations".to_string(),
+        columns,
+        rows,
+    }
+}
+
+/// Returns the table of the position of every algorithm in the execution order of every
+/// iteration, set by `--order`, from 1 for the algorithm that ran first.
+fn order_table<K: Benchmarkable>(runs: &[(K, Vec<Timing>)]) -> Table {
+    let iterations = runs.iter().map(|(_, times)| times.len()).max();
+    let iterations = iterations.unwrap_or_default();
+
+    let mut columns = vec!["Algorithm".to_string()];
+    columns.extend((1..=iterations).map(|i| format!("#{}", i)));
+    let rows = runs
+        .iter()
+        .map(|(algorithm, times)| {
+            let mut row = vec![algorithm.to_string()];
+            row.extend(times.iter().map(|time| (time.position + 1).to_string()));
+            row.resize(iterations + 1, "-".to_string());
+            row
+        })
+        .collect();
+    Table {
+        title: "Execution Order".to_string(),
+        columns,
+        rows,
+    }
+}
+
+/// Returns the table of the scheduling metrics of the thread pool of the parallel kernels,
+/// aggregated over all their runs.
+fn pool_metrics_table<K: Benchmarkable>(runs: &[(K, Vec<Timing>)]) -> Table {
+    let columns = vec![
+        "Algorithm".to_string(),
+        "Jobs per run".to_string(),
+        "Mean job wait (µs)".to_string(),
+        "Max job wait (µs)".to_string(),
+        "Max queue depth".to_string(),
+    ];
+    let rows = runs
+        .iter()
+        .filter_map(|(algorithm, times)| {
+            // with --min-time, the number of runs differs from the number of iterations
+            let metrics = times.iter().filter_map(|time| time.pool_metrics);
+            let count = metrics.clone().count() as u64;
+            metrics
+                .reduce(|acc, metrics| acc.merge(&metrics))
+                .map(|metrics| (algorithm, metrics, count))
+        })
+        .map(|(algorithm, metrics, count)| {
+            vec![
+                algorithm.to_string(),
+                (metrics.jobs / count).to_string(),
+                metrics.mean_wait().as_micros().to_string(),
+                metrics.max_wait.as_micros().to_string(),
+                metrics.max_queue_depth.to_string(),
+            ]
+        })
+        .collect::<Vec<_>>();
+    Table {
+        title: "Thread Pool Metrics".to_string(),
+        columns,
+        rows,
+    }
+}
+
+/// Returns the tile size to run the tiling algorithms with, the given one unless `auto` selects
+/// the one suggested by the size of the L1 data cache for elements of `element_type`, and the row
+/// of the table of arguments with the suggested one.
+fn resolve_tile_size(
+    tile_size: usize,
+    auto: bool,
+    element_type: ElementType,
+) -> (usize, Vec<CellStruct>) {
+    let l1d = l1_data_cache_kib();
+    let suggested = l1d.map(|l1d| tile_size_for_cache(l1d, element_type.size()));
+    let row = match (l1d, suggested) {
+        (Some(l1d), Some(suggested)) => vec![
+            "Suggested tile size".cell(),
+            format!(
+                "{} (3 {} tiles fit in the {} KiB L1 data cache)",
+                suggested, element_type, l1d
+            )
+            .cell(),
+        ],
+        _ => vec!["Suggested tile size".cell(), "unknown L1 cache size".cell()],
+    };
+    let tile_size = match (auto, suggested) {
+        (true, Some(suggested)) => suggested,
+        (true, None) => {
+            warn!(
+                "The size of the L1 data cache cannot be read, using the tile size {}",
+                tile_size
+            );
+            tile_size
+        }
+        (false, _) => tile_size,
+    };
+    (tile_size, row)
+}
+
+/// Returns the parameters of the run listed at the top of the HTML report.
+fn report_parameters(
+    shape: Shape,
+    iterations: usize,
+    distribution: InputDistribution,
+    run: &RunArgs,
+) -> Vec<(String, String)> {
+    let mut parameters = vec![
+        ("Shape (MxKxN)".to_string(), shape.to_string()),
+        ("Number of iterations".to_string(), iterations.to_string()),
+        ("Input distribution".to_string(), distribution.to_string()),
+        ("Clock".to_string(), run.clock.to_string()),
+        ("Inputs".to_string(), inputs_mode(run).to_string()),
+        ("Execution order".to_string(), run.order.to_string()),
+    ];
+    if let Some(align) = run.align {
+        parameters.push(("Input alignment (bytes)".to_string(), align.to_string()));
+    }
+    if let Some(pad) = run.pad {
+        parameters.push(("Row padding (elements)".to_string(), pad.to_string()));
+    }
+    if run.serialize_parallel {
+        parameters.push(("Serialize parallel".to_string(), "true".to_string()));
+    }
+    if let Some(seed) = run.seed {
+        parameters.push(("Seed".to_string(), seed.to_string()));
+    }
+    if run.warmup > 0 {
+        parameters.push(("Warm-up runs".to_string(), run.warmup.to_string()));
+    }
+    // the pinning and the NUMA policy are recorded if they could be set
+    if let Some(cores) = affinity::pinned_cores() {
+        parameters.push(("Pinned cores".to_string(), format_cores(cores)));
+    }
+    #[cfg(feature = "numa")]
+    if let Some(policy) = numa::applied() {
+        parameters.push(("NUMA policy".to_string(), policy.to_string()));
+    }
+    #[cfg(feature = "isolate")]
+    if run.isolate {
+        parameters.push((
+            "Isolated runs".to_string(),
+            "a child process per run".to_string(),
+        ));
+    }
+    parameters
+}
+
+/// Prints a table of results under its title, drawn on the console or as a markdown table
+/// depending on `output`.
+fn print_results_table(table: &Table, output: OutputFormat) {
+    if output == OutputFormat::Markdown {
+        println!(
+            "### {}\n\n{}",
+            table.title,
+            markdown::table(&table.columns, &table.rows)
+        );
+        return;
+    }
+
+    print_title(&table.title);
+    let rows = table
+        .rows
+        .iter()
+        .map(|row| row.iter().map(|cell| cell.cell()).collect())
+        .collect();
+    print_benchmark_results_table(&table.columns, rows);
+}
+
+pub fn matrix_multiplication_benchmark(cli: &Cli) {
+    let n = cli.size;
+    let shape = cli.shape.unwrap_or(Shape::square(n));
+    let iterations = cli.iterations;
+    let available_threads = thread::available_parallelism().unwrap().get();
+    let threads = cli.threads.unwrap_or(available_threads);
+    let parallel_only: bool = cli.parallel_only;
+    let (tile_size, suggested_tile_size) =
+        resolve_tile_size(cli.tile_size, cli.auto_tile_size, cli.dtype);
+    let tiles_per_job = cli.tiles_per_job;
+    let tile_scheduling = cli.tile_scheduling;
+    let skip_ijk = cli.skip_sequential_ijk;
+    let strassen_cutoff = cli.strassen_cutoff;
+    let grid_size = cli.grid_size.unwrap_or(grid_side(threads));
+
+    print_title("Welcome to Matrix Multiplication Benchmark!");
+
+    let size_row = match cli.shape {
+        Some(shape) => vec!["Shape (MxKxN)".cell(), shape.to_string().cell()],
+        None => vec!["Matrix size".cell(), n.to_string().cell()],
+    };
+    let mut table = vec![
+        size_row,
+        vec!["Element type".cell(), cli.dtype.to_string().cell()],
+        vec!["Number of threads".cell(), threads.to_string().cell()],
+        vec!["Number of iterations".cell(), iterations.to_string().cell()],
+        vec!["Parallel only".cell(), parallel_only.to_string().cell()],
+        vec!["Tile size".cell(), tile_size.to_string().cell()],
+        suggested_tile_size,
+        vec!["Tiles per job".cell(), tiles_per_job.to_string().cell()],
+        vec!["Tile scheduling".cell(), tile_scheduling.to_string().cell()],
+        vec![
+            "Morton leaf size".cell(),
+            cli.morton_leaf_size.to_string().cell(),
+        ],
+        vec!["Unrolling factor".cell(), cli.unroll.to_string().cell()],
+        vec![
+            "Row chunk size".cell(),
+            cli.row_chunk_size.to_string().cell(),
+        ],
+        vec!["Grid size".cell(), format!("{0}x{0}", grid_size).cell()],
+        vec!["Strassen cutoff".cell(), strassen_cutoff.to_string().cell()],
+        vec![
+            "Arithmetic".cell(),
+            cli.arithmetic
+                .map_or("native".to_string(), |arithmetic| arithmetic.to_string())
+                .cell(),
+        ],
+    ];
+    table.extend(run_args_rows(&cli.run));
+    print_args_table(table);
+
+    let mut algorithms = Vec::with_capacity(22);
+    if !parallel_only {
+        if !skip_ijk {
+            algorithms.push(Algorithm::SequentialIjk);
+        }
+        algorithms.push(Algorithm::SequentialIkj);
+        if cli.all_loop_orders {
+            algorithms.extend([
+                Algorithm::SequentialJik,
+                Algorithm::SequentialJki,
+                Algorithm::SequentialKij,
+                Algorithm::SequentialKji,
+            ]);
+        }
+        algorithms.push(Algorithm::SequentialTransposeB);
+        if simd::neon_detected() {
+            algorithms.push(Algorithm::SequentialNeon);
+        } else {
+            if !simd::avx2_detected() {
+                warn!("AVX2 is not available, the AVX2 algorithm falls back to scalar code");
+            }
+            algorithms.push(Algorithm::SequentialAvx2);
+            if simd::avx512_detected() {
+                algorithms.push(Algorithm::SequentialAvx512);
+            }
+        }
+        algorithms.push(Algorithm::SequentialPacked);
+        if cli.dtype.is_complex() {
+            algorithms.push(Algorithm::SequentialSplitComplex);
+        }
+        if cli.dtype == ElementType::F32 {
+            algorithms.push(Algorithm::SequentialMixedPrecision);
+        }
+        #[cfg(feature = "matrixmultiply")]
+        algorithms.push(Algorithm::SequentialMatrixmultiply);
+        #[cfg(feature = "ndarray")]
+        algorithms.push(Algorithm::SequentialNdarray);
+        #[cfg(feature = "faer")]
+        algorithms.push(Algorithm::SequentialFaer);
+        #[cfg(feature = "blas")]
+        algorithms.push(Algorithm::SequentialBlas);
+        algorithms.push(Algorithm::SequentialTiling(tile_size));
+        algorithms.push(Algorithm::SequentialMorton(cli.morton_leaf_size));
+        if !cli.skip_strassen {
+            algorithms.push(Algorithm::Strassen(strassen_cutoff));
+            algorithms.push(Algorithm::StrassenWinograd(strassen_cutoff));
+        }
+    }
+    #[cfg(feature = "opencl")]
+    algorithms.push(Algorithm::OpenCl);
+    algorithms.push(Algorithm::ParallelILoop(threads));
+    algorithms.push(Algorithm::ParallelJLoop(threads));
+    algorithms.push(Algorithm::ParallelRowChunks(threads, cli.row_chunk_size));
+    algorithms.push(Algorithm::ParallelPrivateBuffers(threads));
+    algorithms.push(Algorithm::ParallelRayon(threads));
+    algorithms.push(Algorithm::ParallelTiling(
+        threads,
+        tile_size,
+        tiles_per_job,
+        tile_scheduling,
+    ));
+    algorithms.push(Algorithm::ParallelTilingUnrolled(
+        threads, tile_size, cli.unroll,
+    ));
+    algorithms.push(Algorithm::ParallelTilingPrefetch(threads, tile_size));
+    if !cli.skip_strassen {
+        algorithms.push(Algorithm::ParallelStrassen(threads, strassen_cutoff));
+        algorithms.push(Algorithm::StrassenTiled(
+            threads,
+            strassen_cutoff,
+            tile_size,
+        ));
+    }
+    algorithms.push(Algorithm::ParallelBlocks(threads));
+    if n.is_multiple_of(grid_size) {
+        algorithms.push(Algorithm::ParallelCannon(grid_size));
+        algorithms.push(Algorithm::ParallelSumma(grid_size));
+    } else {
+        warn!(
+            "Skipping Cannon's and SUMMA algorithms, matrix size {} is not a multiple of grid size {}",
+            n, grid_size
+        );
+    }
+
+    if !shape.is_square() {
+        warn!("Skipping the algorithms that only multiply square matrices");
+        algorithms.retain(|algorithm| algorithm.supports_rectangular());
+    }
+
+    if cli.arithmetic.is_some() && cli.dtype != ElementType::I32 {
+        warn!(
+            "--arithmetic only applies to i32 elements, ignoring it for {} elements",
+            cli.dtype
+        );
+    }
+
+    match cli.dtype {
+        ElementType::I8 => unreachable!("i8 is not a value of --dtype"),
+        ElementType::I32 => match cli.arithmetic {
+            Some(Arithmetic::Wrapping) => {
+                return typed_benchmark::<Wrapping<i32>>(&algorithms, shape, cli)
+            }
+            Some(Arithmetic::Saturating) => {
+                return typed_benchmark::<Saturating>(&algorithms, shape, cli)
+            }
+            Some(Arithmetic::Checked) | None => (),
+        },
+        ElementType::I64 => return typed_benchmark::<i64>(&algorithms, shape, cli),
+        ElementType::U64 => return typed_benchmark::<u64>(&algorithms, shape, cli),
+        ElementType::F32 => return typed_benchmark::<f32>(&algorithms, shape, cli),
+        ElementType::F64 => return typed_benchmark::<f64>(&algorithms, shape, cli),
+        ElementType::C32 => return typed_benchmark::<Complex<f32>>(&algorithms, shape, cli),
+        ElementType::C64 => return typed_benchmark::<Complex<f64>>(&algorithms, shape, cli),
+        ElementType::Fixed => return typed_benchmark::<Fixed>(&algorithms, shape, cli),
+        ElementType::WrappingI32 | ElementType::SaturatingI32 => {
+            unreachable!("the i32 arithmetic is selected with --arithmetic, not --dtype")
+        }
+    }
+
+    let mut registry = Registry::default();
+    for algorithm in algorithms {
+        match cli.arithmetic {
+            Some(Arithmetic::Checked) => registry.register(Checked(algorithm)),
+            _ => registry.register(algorithm),
+        }
+    }
+
+    let algorithms = registry
+        .algorithms()
+        .iter()
+        .filter(|algorithm| shape.is_square() || algorithm.supports_rectangular())
+        .cloned()
+        .collect::<Vec<_>>();
+
+    benchmark_and_print_results(
+        &algorithms,
+        iterations,
+        shape,
+        InputDistribution::Dense,
+        &cli.run,
+    );
+}
+
+/// Runs the main benchmark on matrices of `T` elements, skipping the algorithms tied to another
+/// element type.
+fn typed_benchmark<T: Element>(algorithms: &[Algorithm], shape: Shape, cli: &Cli) {
+    let (supported, skipped): (Vec<&Algorithm>, Vec<&Algorithm>) = algorithms
+        .iter()
+        .partition(|algorithm| algorithm.supports_element_type(T::ELEMENT_TYPE));
+    for algorithm in skipped {
+        warn!(
+            "Skipping {}, not available for {} elements",
+            algorithm,
+            T::ELEMENT_TYPE
+        );
+    }
+
+    let algorithms = supported
+        .into_iter()
+        .map(|algorithm| TypedAlgorithm::<T>::new(*algorithm))
+        .collect::<Vec<_>>();
+    benchmark_and_print_results(
+        &algorithms,
+        cli.iterations,
+        shape,
+        InputDistribution::Dense,
+        &cli.run,
+    );
+}
 
-use crate::{
-    cli::{parse_cli_tiles, Cli, Tiling},
-    cli_tables::{print_args_table, print_benchmark_results_table, print_title},
-    matrix_multiplication::{algorithms::Algorithm, matrix_multiplication},
-    random_filled_square_matrix_of_size,
-};
+/// Subprogram benchmarking the performance of different tiling strategies.
+pub fn tiling_benchmark(cli: &Tiling) {
+    let n = cli.size;
+    let iterations = cli.iterations;
+    let available_threads = thread::available_parallelism().unwrap().get();
+    let threads_list = match &cli.threads_list {
+        Some(threads_list) => threads_list.clone(),
+        None => vec![cli.threads.unwrap_or(available_threads)],
+    };
+    if threads_list.contains(&0) {
+        error!("The thread counts must be positive");
+        return;
+    }
+    let tiles = match parse_cli_tiles(&cli.tiles) {
+        Ok(tiles) => tiles,
+        Err(err) => {
+            error!("{}", err);
+            return;
+        }
+    };
+    let tiles_per_job = match parse_cli_tiles_per_job(&cli.tiles_per_job) {
+        Ok(tiles_per_job) => tiles_per_job,
+        Err(err) => {
+            error!("{}", err);
+            return;
+        }
+    };
 
-/// Benchmarks the execution time of a given matrix multiplication algorithm.
-/// Returns the execution time in milliseconds, or `None` if an error occurred.
-/// If an error occurs, the error is logged and printed to the console.
-pub fn time_algorithm(algorithm: &Algorithm, a: &Vec<Vec<i32>>, b: &Vec<Vec<i32>>) -> Option<u128> {
-    let start = Instant::now();
-    let res = matrix_multiplication(&a, &b, *algorithm);
-    let end = Instant::now();
-    match res {
-        Ok(_) => Some(end.duration_since(start).as_millis()),
+    let inner_tiles = match parse_cli_inner_tiles(&cli.inner_tiles) {
+        Ok(inner_tiles) => inner_tiles,
         Err(err) => {
-            error!("In algorithm: {}. {}", algorithm, err);
-            None
+            error!("{}", err);
+            return;
+        }
+    };
+
+    print_title("Welcome to Tiling Benchmark!");
+
+    let mut table = vec![
+        vec!["Matrix size".cell(), n.to_string().cell()],
+        vec!["Threads".cell(), format!("{:?}", threads_list).cell()],
+        vec!["Number of iterations".cell(), iterations.to_string().cell()],
+        vec!["Tiles".cell(), format!("{:?}", tiles).cell()],
+        vec![
+            "Tiles per job".cell(),
+            format!("{:?}", tiles_per_job).cell(),
+        ],
+        vec!["Inner tiles".cell(), format!("{:?}", inner_tiles).cell()],
+    ];
+    table.extend(run_args_rows(&cli.run));
+    print_args_table(table);
+
+    let mut algorithms = Vec::new();
+    for &threads in &threads_list {
+        algorithms.extend(tiles.iter().flat_map(|tile| {
+            tiles_per_job.iter().flat_map(move |tiles_per_job| {
+                cli.tile_scheduling.iter().map(move |scheduling| {
+                    Algorithm::ParallelTiling(threads, *tile, *tiles_per_job, *scheduling)
+                })
+            })
+        }));
+        // every outer tile is paired with the inner tiles that split it into smaller ones
+        for tile in &tiles {
+            for inner_tile in &inner_tiles {
+                if inner_tile < tile && tile.is_multiple_of(*inner_tile) {
+                    algorithms.push(Algorithm::ParallelTilingTwoLevel(
+                        threads,
+                        *tile,
+                        *inner_tile,
+                    ));
+                }
+            }
         }
     }
-}
 
-/// Runs the benchmark suite for a given number of iterations.
-fn run_benchmark(
-    algorithms: &[Algorithm],
-    iterations: usize,
-    size: usize,
-) -> Vec<(Algorithm, Vec<u128>)> {
-    let mut results: Vec<(Algorithm, Vec<u128>)> = Vec::with_capacity(algorithms.len());
+    let results = benchmark_and_print_results(
+        &algorithms,
+        iterations,
+        Shape::square(n),
+        InputDistribution::Dense,
+        &cli.run,
+    );
+    if cli.run.dry_run {
+        return;
+    }
 
-    for algorithm in algorithms {
-        results.push((*algorithm, Vec::with_capacity(iterations)));
+    #[cfg(feature = "plot")]
+    if let Some(path) = &cli.plot {
+        // a line per combination of the parameters other than the tile size
+        let mut lines: Vec<plot::Line> = Vec::new();
+        for (algorithm, time) in &results {
+            if let Algorithm::ParallelTiling(threads, tile, tiles_per_job, scheduling) = algorithm {
+                let label = format!(
+                    "{} threads, {} tiles per job, {} scheduling",
+                    threads, tiles_per_job, scheduling
+                );
+                let point = (*tile as f64, time.kernel.as_secs_f64() * 1e3);
+                match lines.iter_mut().find(|line| line.label == label) {
+                    Some(line) => line.points.push(point),
+                    None => lines.push(plot::Line {
+                        label,
+                        points: vec![point],
+                    }),
+                }
+            }
+        }
+        let chart = plot::Chart {
+            title: format!("Parallel Tiling on {n}x{n} matrices", n = n),
+            x_label: "Tile size".to_string(),
+            y_label: "Average time (ms)".to_string(),
+            lines,
+        };
+        draw_plot(path, &chart);
     }
 
-    for i in 0..iterations {
-        let a = random_filled_square_matrix_of_size!(size);
-        let b = random_filled_square_matrix_of_size!(size);
-        info!("Running iteration {}/{}", i + 1, iterations);
-        for (algorithm, times) in &mut results {
-            let time = time_algorithm(algorithm, &a, &b).unwrap_or_default();
-            times.push(time);
-            info!("Finished {} in {} ms", algorithm, time);
+    if !matches!(cli.run.output, OutputFormat::Table | OutputFormat::Markdown) {
+        return;
+    }
+    // kernels too fast to be measured cannot be ranked
+    let best = results
+        .iter()
+        .filter(|(_, time)| !time.kernel.is_zero())
+        .min_by_key(|(_, time)| time.kernel);
+    match best {
+        Some((algorithm, time)) => {
+            let table = Table {
+                title: "Best Combination".to_string(),
+                columns: vec!["Algorithm".to_string(), "Average time".to_string()],
+                rows: vec![vec![algorithm.to_string(), format_duration(time.kernel)]],
+            };
+            print_results_table(&table, cli.run.output);
         }
+        None if !results.is_empty() => {
+            warn!("Every combination ran too fast to be measured, try larger matrices")
+        }
+        None => (),
     }
-    results
 }
 
-/// Runs the benchmark on the specified algorithms for the specified number of iterations, and prints
-/// the results.
-///
-/// # Arguments
-///
-/// * `algorithms` - The algorithms to benchmark.
-/// * `iterations` - The number of iterations to run the benchmark for.
-fn benchmark_and_print_results(algorithms: &[Algorithm], iterations: usize, size: usize) {
-    print_title("Benchmarking!");
+/// Subprogram benchmarking memory-bound element-wise kernels, to be contrasted with the
+/// compute-bound matrix multiplication algorithms.
+pub fn elementwise_benchmark(cli: &Elementwise) {
+    let n = cli.size;
+    let iterations = cli.iterations;
+    let available_threads = thread::available_parallelism().unwrap().get();
+    let threads = cli.threads.unwrap_or(available_threads);
+    let parallel_only = cli.parallel_only;
+    let scalar = cli.scalar;
 
-    let results = run_benchmark(&algorithms, iterations, size)
-        .into_iter()
-        .map(|(algorithm, times)| {
-            let sum: u128 = times.iter().sum();
-            let avg = sum / times.len() as u128;
-            (algorithm, avg)
-        })
-        .collect::<Vec<_>>();
+    print_title("Welcome to Element-wise Benchmark!");
+
+    let mut table = vec![
+        vec!["Matrix size".cell(), n.to_string().cell()],
+        vec!["Number of threads".cell(), threads.to_string().cell()],
+        vec!["Number of iterations".cell(), iterations.to_string().cell()],
+        vec!["Parallel only".cell(), parallel_only.to_string().cell()],
+        vec!["Scalar".cell(), scalar.to_string().cell()],
+    ];
+    table.extend(run_args_rows(&cli.run));
+    print_args_table(table);
 
-    print_title("Benchmark Results");
+    let ops = [
+        ElementwiseOp::Add,
+        ElementwiseOp::Scale(scalar),
+        ElementwiseOp::Hadamard,
+    ];
 
-    let benchmark_results_table = results
-        .iter()
-        .map(|(algorithm, time)| vec![algorithm.to_string().cell(), time.to_string().cell()])
-        .collect::<Vec<_>>();
-    print_benchmark_results_table(benchmark_results_table);
+    let mut kernels = Vec::with_capacity(ops.len() * 2);
+    for op in ops {
+        if !parallel_only {
+            kernels.push(ElementwiseKernel::Sequential(op));
+        }
+        kernels.push(ElementwiseKernel::Parallel(op, threads));
+    }
+
+    benchmark_and_print_results(
+        &kernels,
+        iterations,
+        Shape::square(n),
+        InputDistribution::Dense,
+        &cli.run,
+    );
 }
 
-pub fn matrix_multiplication_benchmark(cli: &Cli) {
+/// Subprogram benchmarking sparse x sparse matrix multiplication on CSR matrices, whose cost
+/// depends on the number of nonzeros rather than on the size of the matrices.
+pub fn sparse_benchmark(cli: &Sparse) {
     let n = cli.size;
     let iterations = cli.iterations;
     let available_threads = thread::available_parallelism().unwrap().get();
     let threads = cli.threads.unwrap_or(available_threads);
-    let parallel_only: bool = cli.parallel_only;
-    let tile_size = cli.tile_size;
-    let skip_ijk = cli.skip_sequential_ijk;
+    let parallel_only = cli.parallel_only;
+    let (density_a, density_b) = (cli.density_a, cli.density_b);
 
-    print_title("Welcome to Matrix Multiplication Benchmark!");
+    print_title("Welcome to Sparse Benchmark!");
+
+    let mut table = vec![
+        vec!["Matrix size".cell(), n.to_string().cell()],
+        vec!["Number of threads".cell(), threads.to_string().cell()],
+        vec!["Number of iterations".cell(), iterations.to_string().cell()],
+        vec!["Parallel only".cell(), parallel_only.to_string().cell()],
+        vec!["Density of A".cell(), density_a.to_string().cell()],
+        vec!["Density of B".cell(), density_b.to_string().cell()],
+    ];
+    table.extend(run_args_rows(&cli.run));
+    print_args_table(table);
+
+    let mut kernels = Vec::with_capacity(2);
+    if !parallel_only {
+        kernels.push(SparseKernel::Sequential(density_a, density_b));
+    }
+    kernels.push(SparseKernel::Parallel(density_a, density_b, threads));
+
+    benchmark_and_print_results(
+        &kernels,
+        iterations,
+        Shape::square(n),
+        InputDistribution::Sparse(density_a, density_b),
+        &cli.run,
+    );
+}
+
+/// Subprogram benchmarking the multiplication of a triangular matrix by a dense one with kernels
+/// skipping the zero half of the triangular matrix.
+pub fn triangular_benchmark(cli: &Triangular) {
+    let n = cli.size;
+    let iterations = cli.iterations;
+    let available_threads = thread::available_parallelism().unwrap().get();
+    let threads = cli.threads.unwrap_or(available_threads);
+    let parallel_only = cli.parallel_only;
+    let triangle = cli.triangle;
+
+    print_title("Welcome to Triangular Benchmark!");
+
+    let mut table = vec![
+        vec!["Matrix size".cell(), n.to_string().cell()],
+        vec!["Number of threads".cell(), threads.to_string().cell()],
+        vec!["Number of iterations".cell(), iterations.to_string().cell()],
+        vec!["Parallel only".cell(), parallel_only.to_string().cell()],
+        vec!["Triangle of A".cell(), triangle.to_string().cell()],
+    ];
+    table.extend(run_args_rows(&cli.run));
+    print_args_table(table);
+
+    let mut kernels = Vec::with_capacity(2);
+    if !parallel_only {
+        kernels.push(TriangularKernel::Sequential(triangle));
+    }
+    kernels.push(TriangularKernel::Parallel(triangle, threads));
+
+    benchmark_and_print_results(
+        &kernels,
+        iterations,
+        Shape::square(n),
+        InputDistribution::Triangular(triangle),
+        &cli.run,
+    );
+}
 
-    let table = vec![
+/// Subprogram benchmarking the multiplication of a symmetric matrix by a dense one with kernels
+/// reading only the upper triangle of the symmetric matrix, packed to about half its dense size.
+pub fn symmetric_benchmark(cli: &Symmetric) {
+    let n = cli.size;
+    let iterations = cli.iterations;
+    let available_threads = thread::available_parallelism().unwrap().get();
+    let threads = cli.threads.unwrap_or(available_threads);
+    let parallel_only = cli.parallel_only;
+    let packed_bytes = n * (n + 1) / 2 * std::mem::size_of::<i32>();
+    let dense_bytes = n * n * std::mem::size_of::<i32>();
+
+    print_title("Welcome to Symmetric Benchmark!");
+
+    let mut table = vec![
         vec!["Matrix size".cell(), n.to_string().cell()],
         vec!["Number of threads".cell(), threads.to_string().cell()],
         vec!["Number of iterations".cell(), iterations.to_string().cell()],
         vec!["Parallel only".cell(), parallel_only.to_string().cell()],
+        vec![
+            "Bytes of A, packed / dense".cell(),
+            format!("{} / {}", packed_bytes, dense_bytes).cell(),
+        ],
+    ];
+    table.extend(run_args_rows(&cli.run));
+    print_args_table(table);
+
+    let mut kernels = Vec::with_capacity(2);
+    if !parallel_only {
+        kernels.push(SymmetricKernel::Sequential);
+    }
+    kernels.push(SymmetricKernel::Parallel(threads));
+
+    benchmark_and_print_results(
+        &kernels,
+        iterations,
+        Shape::square(n),
+        InputDistribution::Symmetric,
+        &cli.run,
+    );
+}
+
+/// Subprogram benchmarking the multiplication algorithms accumulating their product into an
+/// existing matrix, `C = alpha * A * B + beta * C`, as the updates of the trailing blocks of a
+/// blocked LU factorization do.
+pub fn accumulate_benchmark(cli: &Gemm) {
+    let n = cli.size;
+    let iterations = cli.iterations;
+    let available_threads = thread::available_parallelism().unwrap().get();
+    let threads = cli.threads.unwrap_or(available_threads);
+    let (tile_size, suggested_tile_size) =
+        resolve_tile_size(cli.tile_size, cli.auto_tile_size, ElementType::I32);
+
+    print_title("Welcome to GEMM Benchmark!");
+
+    let mut table = vec![
+        vec!["Matrix size".cell(), n.to_string().cell()],
+        vec!["Number of threads".cell(), threads.to_string().cell()],
+        vec!["Number of iterations".cell(), iterations.to_string().cell()],
         vec!["Tile size".cell(), tile_size.to_string().cell()],
+        suggested_tile_size,
+        vec!["Alpha".cell(), cli.alpha.to_string().cell()],
+        vec!["Beta".cell(), cli.beta.to_string().cell()],
     ];
+    table.extend(run_args_rows(&cli.run));
     print_args_table(table);
 
-    let mut algorithms = Vec::with_capacity(4);
-    if parallel_only == false {
-        if skip_ijk == false {
-            algorithms.push(Algorithm::SequentialIjk);
+    let kernels = cli
+        .algorithms
+        .iter()
+        .map(|name| AccumulateKernel {
+            algorithm: name.to_algorithm(threads, tile_size),
+            alpha: cli.alpha,
+            beta: cli.beta,
+        })
+        .collect::<Vec<_>>();
+
+    benchmark_and_print_results(
+        &kernels,
+        iterations,
+        Shape::square(n),
+        InputDistribution::Dense,
+        &cli.run,
+    );
+}
+
+/// Subprogram benchmarking the selected algorithms on matrices of several sizes, and printing
+/// their average times as a table with a row per algorithm and a column per size.
+pub fn sweep_benchmark(cli: &Sweep) {
+    let iterations = cli.iterations;
+    let available_threads = thread::available_parallelism().unwrap().get();
+    let threads = cli.threads.unwrap_or(available_threads);
+
+    if cli.run.save_inputs.is_some() || cli.run.load_inputs.is_some() {
+        error!(
+            "Saving and loading the inputs is not supported by the sweep, whose inputs change size"
+        );
+        return;
+    }
+
+    let (tile_size, suggested_tile_size) =
+        resolve_tile_size(cli.tile_size, cli.auto_tile_size, ElementType::I32);
+
+    print_title("Welcome to Size Sweep Benchmark!");
+
+    let mut table = vec![
+        vec!["Matrix sizes".cell(), format!("{:?}", cli.sizes).cell()],
+        vec!["Number of threads".cell(), threads.to_string().cell()],
+        vec!["Number of iterations".cell(), iterations.to_string().cell()],
+        vec!["Tile size".cell(), tile_size.to_string().cell()],
+        suggested_tile_size,
+    ];
+    table.extend(run_args_rows(&cli.run));
+    print_args_table(table);
+
+    let algorithms = cli
+        .algorithms
+        .iter()
+        .map(|name| name.to_algorithm(threads, tile_size))
+        .collect::<Vec<_>>();
+
+    print_title("Benchmarking!");
+
+    // average time of every algorithm on every size
+    let mut averages = vec![Vec::with_capacity(cli.sizes.len()); algorithms.len()];
+    for &n in &cli.sizes {
+        if cli.run.dry_run {
+            dry_run(
+                &algorithms,
+                iterations,
+                Shape::square(n),
+                InputDistribution::Dense,
+                &cli.run,
+            );
+            continue;
+        }
+        info!("Running size {}", n);
+        let runs = match run_benchmark(
+            &algorithms,
+            iterations,
+            Shape::square(n),
+            InputDistribution::Dense,
+            &cli.run,
+        ) {
+            Ok(runs) => runs,
+            Err(err) => {
+                error!("{}", err);
+                return;
+            }
+        };
+        for (average, (_, times)) in averages.iter_mut().zip(runs) {
+            let sum = times.iter().map(|time| time.kernel).sum::<Duration>();
+            average.push(sum / times.len() as u32);
         }
-        algorithms.push(Algorithm::SequentialIkj);
     }
-    algorithms.push(Algorithm::ParallelILoop(threads));
-    algorithms.push(Algorithm::ParallelTiling(threads, tile_size));
+    if cli.run.dry_run {
+        return;
+    }
+
+    let mut columns = vec!["Algorithm".to_string()];
+    columns.extend(cli.sizes.iter().map(|n| format!("{n}x{n}", n = n)));
+    let rows = algorithms
+        .iter()
+        .zip(&averages)
+        .map(|(algorithm, averages)| {
+            let mut row = vec![algorithm.to_string()];
+            row.extend(averages.iter().map(|average| format_duration(*average)));
+            row
+        })
+        .collect();
+    let table = Table {
+        title: "Benchmark Results".to_string(),
+        columns,
+        rows,
+    };
+    print_results_table(&table, cli.run.output);
 
-    benchmark_and_print_results(&algorithms, iterations, n);
+    #[cfg(feature = "plot")]
+    if let Some(path) = &cli.plot {
+        let chart = plot::Chart {
+            title: "Average time by matrix size".to_string(),
+            x_label: "Matrix size".to_string(),
+            y_label: "Average time (ms)".to_string(),
+            lines: algorithms
+                .iter()
+                .zip(&averages)
+                .map(|(algorithm, averages)| plot::Line {
+                    label: algorithm.to_string(),
+                    points: cli
+                        .sizes
+                        .iter()
+                        .zip(averages)
+                        .map(|(n, average)| (*n as f64, average.as_secs_f64() * 1e3))
+                        .collect(),
+                })
+                .collect(),
+        };
+        draw_plot(path, &chart);
+    }
 }
 
-/// Subprogram benchmarking the performance of different tiling strategies.
-pub fn tiling_benchmark(cli: &Tiling) {
+/// Subprogram benchmarking the selected parallel algorithms with every thread count, and printing a
+/// strong-scaling table: the speedup and the parallel efficiency of every algorithm are relative to
+/// the same algorithm running with the first thread count.
+pub fn scaling_benchmark(cli: &Scaling) {
+    let n = cli.size;
+    let iterations = cli.iterations;
+
+    let threads_list = match cli.all_threads {
+        true => (1..=thread::available_parallelism().unwrap().get()).collect(),
+        false => cli.threads_list.clone(),
+    };
+    if threads_list.contains(&0) {
+        error!("The thread counts must be positive");
+        return;
+    }
+
+    let (tile_size, suggested_tile_size) =
+        resolve_tile_size(cli.tile_size, cli.auto_tile_size, ElementType::I32);
+
+    print_title("Welcome to Thread Scaling Benchmark!");
+
+    let mut table = vec![
+        vec!["Matrix size".cell(), n.to_string().cell()],
+        vec!["Thread counts".cell(), format!("{:?}", threads_list).cell()],
+        vec!["Number of iterations".cell(), iterations.to_string().cell()],
+        vec!["Tile size".cell(), tile_size.to_string().cell()],
+        suggested_tile_size,
+    ];
+    table.extend(run_args_rows(&cli.run));
+    print_args_table(table);
+
+    let names = cli
+        .algorithms
+        .iter()
+        .filter(|name| {
+            let parallel = name.to_algorithm(1, tile_size).threads().is_some();
+            if !parallel {
+                warn!(
+                    "Skipping {}, a sequential algorithm does not scale",
+                    name.to_possible_value().unwrap().get_name()
+                );
+            }
+            parallel
+        })
+        .collect::<Vec<_>>();
+
+    print_title("Benchmarking!");
+
+    // average time of every algorithm with every thread count
+    let mut averages = vec![Vec::with_capacity(threads_list.len()); names.len()];
+    for &threads in &threads_list {
+        let algorithms = names
+            .iter()
+            .map(|name| name.to_algorithm(threads, tile_size))
+            .collect::<Vec<_>>();
+        if cli.run.dry_run {
+            dry_run(
+                &algorithms,
+                iterations,
+                Shape::square(n),
+                InputDistribution::Dense,
+                &cli.run,
+            );
+            continue;
+        }
+        info!("Running with {} threads", threads);
+        let runs = match run_benchmark(
+            &algorithms,
+            iterations,
+            Shape::square(n),
+            InputDistribution::Dense,
+            &cli.run,
+        ) {
+            Ok(runs) => runs,
+            Err(err) => {
+                error!("{}", err);
+                return;
+            }
+        };
+        for (average, (_, times)) in averages.iter_mut().zip(runs) {
+            let sum = times.iter().map(|time| time.kernel).sum::<Duration>();
+            average.push(sum / times.len() as u32);
+        }
+    }
+    if cli.run.dry_run {
+        return;
+    }
+
+    let columns = [
+        "Algorithm",
+        "Threads",
+        "Average time",
+        "Speedup",
+        "Parallel efficiency",
+    ]
+    .map(String::from)
+    .to_vec();
+    let mut rows = Vec::with_capacity(names.len() * threads_list.len());
+    for (name, averages) in names.iter().zip(&averages) {
+        let (base_threads, base_time) = (threads_list[0], averages[0]);
+        for (&threads, &time) in threads_list.iter().zip(averages) {
+            let speedup = (!base_time.is_zero() && !time.is_zero())
+                .then(|| base_time.as_secs_f64() / time.as_secs_f64());
+            let efficiency = match speedup {
+                Some(speedup) => {
+                    format!(
+                        "{:.0}%",
+                        100.0 * speedup * base_threads as f64 / threads as f64
+                    )
+                }
+                None => "-".to_string(),
+            };
+            rows.push(vec![
+                name.to_possible_value().unwrap().get_name().to_string(),
+                threads.to_string(),
+                format_duration(time),
+                format_optional(speedup, 2),
+                efficiency,
+            ]);
+        }
+    }
+    let table = Table {
+        title: "Strong Scaling".to_string(),
+        columns,
+        rows,
+    };
+    print_results_table(&table, cli.run.output);
+
+    // the knee of the scaling curve of every algorithm
+    let columns = [
+        "Algorithm",
+        "Recommended threads",
+        "Average time",
+        "Fastest threads",
+        "Fastest time",
+    ]
+    .map(String::from)
+    .to_vec();
+    let mut rows = Vec::with_capacity(names.len());
+    for (name, averages) in names.iter().zip(&averages) {
+        let points = threads_list
+            .iter()
+            .copied()
+            .zip(averages.iter().copied())
+            .collect::<Vec<_>>();
+        let time = |threads: usize| points.iter().find(|point| point.0 == threads).unwrap().1;
+        let (Some(recommended), Some(&(fastest, _))) = (
+            autotune::knee(&points, cli.knee_tolerance),
+            points.iter().min_by_key(|(_, time)| *time),
+        ) else {
+            continue;
+        };
+        let name = name.to_possible_value().unwrap().get_name().to_string();
+        info!(
+            "{} stops scaling beyond {} threads on this machine",
+            name, recommended
+        );
+        rows.push(vec![
+            name,
+            recommended.to_string(),
+            format_duration(time(recommended)),
+            fastest.to_string(),
+            format_duration(time(fastest)),
+        ]);
+    }
+    let table = Table {
+        title: "Recommended Thread Counts".to_string(),
+        columns,
+        rows,
+    };
+    print_results_table(&table, cli.run.output);
+
+    #[cfg(feature = "plot")]
+    if let Some(path) = &cli.plot {
+        let base_threads = threads_list[0] as f64;
+        let mut lines = names
+            .iter()
+            .zip(&averages)
+            .map(|(name, averages)| plot::Line {
+                label: name.to_possible_value().unwrap().get_name().to_string(),
+                // kernels too fast to be measured have no speedup
+                points: threads_list
+                    .iter()
+                    .zip(averages)
+                    .filter(|(_, time)| !averages[0].is_zero() && !time.is_zero())
+                    .map(|(threads, time)| {
+                        (
+                            *threads as f64,
+                            averages[0].as_secs_f64() / time.as_secs_f64(),
+                        )
+                    })
+                    .collect(),
+            })
+            .collect::<Vec<_>>();
+        lines.push(plot::Line {
+            label: "Ideal".to_string(),
+            points: threads_list
+                .iter()
+                .map(|threads| (*threads as f64, *threads as f64 / base_threads))
+                .collect(),
+        });
+        let chart = plot::Chart {
+            title: format!("Strong scaling on {n}x{n} matrices", n = n),
+            x_label: "Threads".to_string(),
+            y_label: "Speedup".to_string(),
+            lines,
+        };
+        draw_plot(path, &chart);
+    }
+}
+
+/// Subprogram searching the fastest tile size of the parallel tiling algorithm for the given matrix
+/// size and thread count, timing the candidate tile sizes in the order of the search and printing
+/// the time of every candidate it timed.
+pub fn autotune_benchmark(cli: &Autotune) {
     let n = cli.size;
     let iterations = cli.iterations;
     let available_threads = thread::available_parallelism().unwrap().get();
     let threads = cli.threads.unwrap_or(available_threads);
-    let tiles = match parse_cli_tiles(&cli.tiles) {
-        Ok(tiles) => tiles,
-        Err(err) => {
-            error!("{}", err);
-            return;
-        }
+    let mut candidates = match &cli.tile_sizes {
+        Some(tile_sizes) => tile_sizes.clone(),
+        None => candidate_tile_sizes(n),
     };
+    candidates.sort();
+    candidates.dedup();
+    // the search starts from the tile size suggested by the cache, or from the default one
+    let (start, suggested_tile_size) = resolve_tile_size(32, true, ElementType::I32);
 
-    print_title("Welcome to Tiling Benchmark!");
+    print_title("Welcome to Autotune!");
 
-    let table = vec![
+    let mut table = vec![
         vec!["Matrix size".cell(), n.to_string().cell()],
         vec!["Number of threads".cell(), threads.to_string().cell()],
         vec!["Number of iterations".cell(), iterations.to_string().cell()],
-        vec!["Tiles".cell(), format!("{:?}", tiles).cell()],
+        vec![
+            "Candidate tile sizes".cell(),
+            format!("{:?}", candidates).cell(),
+        ],
+        vec!["Search".cell(), cli.search.to_string().cell()],
+        suggested_tile_size,
     ];
+    table.extend(run_args_rows(&cli.run));
     print_args_table(table);
 
-    let algorithms = tiles
+    // the candidates timed by hill climbing are only known once they are timed, so every
+    // candidate is estimated
+    if cli.run.dry_run {
+        let algorithms = candidates
+            .iter()
+            .map(|tile_size| {
+                Algorithm::ParallelTiling(threads, *tile_size, 1, TileScheduling::Dynamic)
+            })
+            .collect::<Vec<_>>();
+        dry_run(
+            &algorithms,
+            iterations,
+            Shape::square(n),
+            InputDistribution::Dense,
+            &cli.run,
+        );
+        return;
+    }
+
+    // the children of --isolate replay the search with empty timings, which would lead hill
+    // climbing to other candidates than the parent
+    #[cfg(feature = "isolate")]
+    if cli.run.isolate && cli.search == Search::HillClimbing {
+        error!(
+            "--isolate cannot be combined with the hill climbing search, use --search exhaustive"
+        );
+        return;
+    }
+
+    print_title("Benchmarking!");
+
+    let timed = autotune::search(&candidates, start, cli.search, |tile_size| {
+        info!("Timing tile size {}", tile_size);
+        let algorithm = Algorithm::ParallelTiling(threads, tile_size, 1, TileScheduling::Dynamic);
+        let runs = run_benchmark(
+            &[algorithm],
+            iterations,
+            Shape::square(n),
+            InputDistribution::Dense,
+            &cli.run,
+        );
+        let times = match runs {
+            Ok(mut runs) => runs.pop()?.1,
+            Err(err) => {
+                error!("{}", err);
+                return None;
+            }
+        };
+        // an algorithm rejecting the tile size returns no timings
+        (!times.is_empty())
+            .then(|| times.iter().map(|time| time.kernel).sum::<Duration>() / times.len() as u32)
+    });
+    if cli.search == Search::HillClimbing {
+        info!(
+            "Hill climbing timed {} of {} candidates",
+            timed.len(),
+            candidates.len()
+        );
+    }
+
+    let Some((best, best_time)) = autotune::fastest(&timed) else {
+        error!("No candidate tile size could be timed");
+        return;
+    };
+    let columns = ["Tile size", "Average time", "Slowdown vs fastest"]
+        .map(String::from)
+        .to_vec();
+    let rows = timed
         .iter()
-        .map(|tile| Algorithm::ParallelTiling(threads, *tile))
-        .collect::<Vec<_>>();
+        .map(|(tile_size, time)| {
+            let slowdown = time
+                .filter(|_| !best_time.is_zero())
+                .map(|time| time.as_secs_f64() / best_time.as_secs_f64());
+            vec![
+                tile_size.to_string(),
+                time.map_or("-".to_string(), format_duration),
+                slowdown.map_or("-".to_string(), |slowdown| format!("{:.2}x", slowdown)),
+            ]
+        })
+        .collect();
+    print_results_table(
+        &Table {
+            title: "Autotune Results".to_string(),
+            columns,
+            rows,
+        },
+        cli.run.output,
+    );
+    info!(
+        "The fastest tile size is {}, in {}",
+        best,
+        format_duration(best_time)
+    );
+
+    if let Some(path) = &cli.save {
+        let winner = format!(
+            "size = {}\nthreads = {}\ntile_size = {}\naverage_time_ns = {}\n",
+            n,
+            threads,
+            best,
+            best_time.as_nanos()
+        );
+        match fs::write(path, winner) {
+            Ok(()) => info!("Fastest tile size written to {}", path.display()),
+            Err(err) => error!("Cannot write the tile size to {}: {}", path.display(), err),
+        }
+    }
+}
+
+/// Draws the chart of a subprogram to the file given with `--plot`, logging the outcome
+#[cfg(feature = "plot")]
+fn draw_plot(path: &Path, chart: &plot::Chart) {
+    match plot::draw(path, chart) {
+        Ok(()) => info!("Chart written to {}", path.display()),
+        Err(err) => error!("Cannot draw the chart to {}: {}", path.display(), err),
+    }
+}
+
+/// Subprogram benchmarking the multiplication of `i8` matrices accumulating into `i32`, the
+/// pattern of quantized inference.
+pub fn quantized_benchmark(cli: &Quantized) {
+    let n = cli.size;
+    let iterations = cli.iterations;
+    let available_threads = thread::available_parallelism().unwrap().get();
+    let threads = cli.threads.unwrap_or(available_threads);
+    let parallel_only = cli.parallel_only;
+
+    print_title("Welcome to Quantized Benchmark!");
+
+    let mut table = vec![
+        vec!["Matrix size".cell(), n.to_string().cell()],
+        vec!["Number of threads".cell(), threads.to_string().cell()],
+        vec!["Number of iterations".cell(), iterations.to_string().cell()],
+        vec!["Parallel only".cell(), parallel_only.to_string().cell()],
+        vec![
+            "Dot product instructions".cell(),
+            match (simd::avx2_detected(), simd::neon_detected()) {
+                (true, _) => "AVX2",
+                (_, true) => "NEON",
+                _ => "scalar",
+            }
+            .cell(),
+        ],
+    ];
+    table.extend(run_args_rows(&cli.run));
+    print_args_table(table);
+
+    let mut kernels = Vec::with_capacity(3);
+    if !parallel_only {
+        kernels.push(QuantizedKernel::Sequential);
+        kernels.push(QuantizedKernel::SequentialDot);
+    }
+    kernels.push(QuantizedKernel::ParallelDot(threads));
+
+    benchmark_and_print_results(
+        &kernels,
+        iterations,
+        Shape::square(n),
+        InputDistribution::Dense,
+        &cli.run,
+    );
+}
+
+/// Subprogram benchmarking matrix-vector multiplication, which reads every element of the matrix
+/// once and is bound by memory bandwidth rather than by arithmetic.
+pub fn gemv_benchmark(cli: &Gemv) {
+    let n = cli.size;
+    let iterations = cli.iterations;
+    let available_threads = thread::available_parallelism().unwrap().get();
+    let threads = cli.threads.unwrap_or(available_threads);
+    let parallel_only = cli.parallel_only;
+
+    print_title("Welcome to GEMV Benchmark!");
+
+    let mut table = vec![
+        vec!["Matrix size".cell(), n.to_string().cell()],
+        vec!["Number of threads".cell(), threads.to_string().cell()],
+        vec!["Number of iterations".cell(), iterations.to_string().cell()],
+        vec!["Parallel only".cell(), parallel_only.to_string().cell()],
+    ];
+    table.extend(run_args_rows(&cli.run));
+    print_args_table(table);
+
+    let mut kernels = Vec::with_capacity(2);
+    if !parallel_only {
+        kernels.push(GemvKernel::Sequential);
+    }
+    kernels.push(GemvKernel::Parallel(threads));
 
-    benchmark_and_print_results(&algorithms, iterations, n);
+    benchmark_and_print_results(
+        &kernels,
+        iterations,
+        Shape::square(n),
+        InputDistribution::Dense,
+        &cli.run,
+    );
 }
@@ -1,4 +1,24 @@
-use clap::{Args, Parser, Subcommand};
+use std::{path::PathBuf, time::Duration};
+
+use clap::{Args, Parser, Subcommand, ValueEnum};
+
+#[cfg(feature = "numa")]
+use crate::numa::NumaPolicy;
+use crate::{
+    autotune::Search,
+    clock::Clock,
+    matrix_multiplication::{
+        algorithms::{
+            grid_side, Algorithm, TileScheduling, INNER_TILE_SIZE_DEFAULT,
+            MORTON_LEAF_SIZE_DEFAULT, ROW_CHUNK_SIZE_DEFAULT, STRASSEN_CUTOFF_DEFAULT,
+            UNROLL_DEFAULT,
+        },
+        types::{Arithmetic, ElementType, Shape},
+    },
+    order::ExecutionOrder,
+    output::OutputFormat,
+    triangular::Triangle,
+};
 
 const ABOUT: &str = "Matrix Multiplication Benchmark \n
 A benchmark suite for evaluating the performance of different matrix multiplication algorithms. \n
@@ -16,6 +36,18 @@ pub struct Cli {
     /// Size of the matrix
     pub size: usize,
 
+    #[arg(long, value_parser = parse_shape)]
+    /// Multiply an M x K matrix by a K x N one instead of square matrices of the given size,
+    /// given as MxKxN. Only the algorithms supporting rectangular matrices are run.
+    pub shape: Option<Shape>,
+
+    #[arg(long, value_enum, default_value_t = ElementType::I32)]
+    /// Type of the elements of the matrices. The SIMD kernels, the baselines of other crates,
+    /// OpenCL and the custom registered kernels only multiply i32 elements and are skipped for
+    /// the other types. Strassen's algorithms are skipped for u64, whose elements are the
+    /// absolute values of the generated ones.
+    pub dtype: ElementType,
+
     #[arg(short, long, default_value_t = 5)]
     /// Number of iterations to run the benchmark
     pub iterations: usize,
@@ -32,10 +64,61 @@ pub struct Cli {
     /// Skip sequential ijk algorithm
     pub skip_sequential_ijk: bool,
 
+    #[arg(long, action = clap::ArgAction::SetTrue)]
+    /// Also run the jik, jki, kij and kji loop orders of the sequential algorithm
+    pub all_loop_orders: bool,
+
     #[arg(long, default_value_t = 32)]
-    /// Tile size for parallel tiling algorithm
+    /// Tile size for the sequential and parallel tiling algorithms
     pub tile_size: usize,
 
+    #[arg(long, action = clap::ArgAction::SetTrue, conflicts_with = "tile_size")]
+    /// Use the tile size suggested by the size of the L1 data cache instead of --tile-size, the
+    /// largest power of two whose tiles of A, B and C fit in it together
+    pub auto_tile_size: bool,
+
+    #[arg(long, default_value_t = 1)]
+    /// Number of tiles grouped into a single job submitted to the thread pool
+    pub tiles_per_job: usize,
+
+    #[arg(long, value_enum, default_value_t = TileScheduling::Dynamic)]
+    /// Policy for assigning the tiles to the threads in the parallel tiling algorithm
+    pub tile_scheduling: TileScheduling,
+
+    #[arg(long, default_value_t = MORTON_LEAF_SIZE_DEFAULT)]
+    /// Largest side of the leaf blocks of the Morton layout algorithm
+    pub morton_leaf_size: usize,
+
+    #[arg(long, default_value_t = UNROLL_DEFAULT)]
+    /// Unrolling factor of the innermost loop of the unrolled parallel tiling algorithm: 1, 2, 4 or 8
+    pub unroll: usize,
+
+    #[arg(long, default_value_t = ROW_CHUNK_SIZE_DEFAULT)]
+    /// Number of rows claimed at a time by the threads of the row chunks algorithm
+    pub row_chunk_size: usize,
+
+    #[arg(long)]
+    /// Side of the square grid of threads of Cannon's and SUMMA algorithms [default: square root of the number of threads]
+    pub grid_size: Option<usize>,
+
+    #[arg(long, default_value_t = STRASSEN_CUTOFF_DEFAULT)]
+    /// Block size at or below which Strassen's algorithms fall back to the classical kernel
+    pub strassen_cutoff: usize,
+
+    #[arg(long, action = clap::ArgAction::SetTrue)]
+    /// Skip Strassen's algorithm and its Winograd, parallel and parallel tiling variants
+    pub skip_strassen: bool,
+
+    #[arg(long, value_enum)]
+    /// Policy applied to the i32 sums of products that overflow, instead of the native
+    /// arithmetic, which wraps in release builds and panics in debug ones. The checked mode
+    /// reports the overflows as errors, computing the products in i64 when the inputs are large
+    /// enough to overflow. Only applies to i32 elements.
+    pub arithmetic: Option<Arithmetic>,
+
+    #[command(flatten)]
+    pub run: RunArgs,
+
     #[command(subcommand)]
     pub subcommands: Option<Commands>,
 }
@@ -48,9 +131,90 @@ pub enum Commands {
     #[command(name = "tiling")]
     /// Run benchmark suite for parallel tiling algorithm
     Tiling(Tiling),
+    #[command(name = "elementwise")]
+    /// Run benchmark suite for memory-bound element-wise kernels (add, scale, Hadamard)
+    Elementwise(Elementwise),
+    #[command(name = "gemv")]
+    /// Run benchmark suite for memory-bound matrix-vector multiplication (GEMV)
+    Gemv(Gemv),
+    #[command(name = "quantized")]
+    /// Run benchmark suite for quantized i8 x i8 matrix multiplication accumulating into i32
+    Quantized(Quantized),
+    #[command(name = "sparse")]
+    /// Run benchmark suite for sparse x sparse matrix multiplication (SpGEMM) on CSR matrices
+    Sparse(Sparse),
+    #[command(name = "triangular")]
+    /// Run benchmark suite for triangular x dense matrix multiplication (TRMM)
+    Triangular(Triangular),
+    #[command(name = "symmetric")]
+    /// Run benchmark suite for symmetric x dense matrix multiplication (SYMM)
+    Symmetric(Symmetric),
+    #[command(name = "gemm")]
+    /// Run benchmark suite for matrix multiplication accumulating into an existing matrix,
+    /// C = alpha * A * B + beta * C
+    Gemm(Gemm),
+    #[command(name = "sweep")]
+    /// Run benchmark suite for the selected algorithms across several matrix sizes
+    Sweep(Sweep),
+    #[command(name = "scaling")]
+    /// Run benchmark suite for the selected parallel algorithms at several thread counts
+    Scaling(Scaling),
+    #[command(name = "stress")]
+    /// Continuously run an algorithm for a given duration, verifying every result
+    Stress(Stress),
+    #[command(name = "fuzz-verify")]
+    /// Verify every algorithm against the sequential reference on random configurations
+    FuzzVerify(FuzzVerify),
+    #[command(name = "selftest")]
+    /// Verify every algorithm against the sequential reference across a grid of small sizes,
+    /// tile sizes and thread counts, printing a pass/fail matrix
+    SelfTest(SelfTest),
+    #[command(name = "autotune")]
+    /// Search the fastest tile size of the parallel tiling algorithm for a matrix size and a
+    /// thread count
+    Autotune(Autotune),
+    #[command(name = "compare")]
+    /// Compare the results of two runs exported with --format json or --format csv, printing
+    /// the difference and the relative change of the time of every algorithm
+    Compare(Compare),
+    #[cfg(feature = "criterion")]
+    #[command(name = "criterion")]
+    /// Measure algorithms with Criterion.rs, for statistically rigorous comparisons
+    Criterion(CriterionBench),
+}
+
+impl Cli {
+    /// Returns the options shared by the benchmark subprograms, if the selected subprogram is one
+    pub fn run_args(&self) -> Option<&RunArgs> {
+        match &self.subcommands {
+            None => Some(&self.run),
+            Some(Commands::Tiling(args)) => Some(&args.run),
+            Some(Commands::Elementwise(args)) => Some(&args.run),
+            Some(Commands::Gemv(args)) => Some(&args.run),
+            Some(Commands::Quantized(args)) => Some(&args.run),
+            Some(Commands::Sparse(args)) => Some(&args.run),
+            Some(Commands::Triangular(args)) => Some(&args.run),
+            Some(Commands::Symmetric(args)) => Some(&args.run),
+            Some(Commands::Gemm(args)) => Some(&args.run),
+            Some(Commands::Sweep(args)) => Some(&args.run),
+            Some(Commands::Scaling(args)) => Some(&args.run),
+            Some(Commands::Autotune(args)) => Some(&args.run),
+            Some(
+                Commands::OsThreads
+                | Commands::Stress(_)
+                | Commands::FuzzVerify(_)
+                | Commands::SelfTest(_)
+                | Commands::Compare(_),
+            ) => None,
+            #[cfg(feature = "criterion")]
+            Some(Commands::Criterion(_)) => None,
+        }
+    }
 }
 
 const TILES_DEFAULTS: &str = "16,32,64";
+const TILES_PER_JOB_DEFAULTS: &str = "1";
+const INNER_TILES_DEFAULTS: &str = "8";
 
 #[derive(Args)]
 pub struct Tiling {
@@ -62,25 +226,975 @@ pub struct Tiling {
     /// Number of iterations to run the benchmark
     pub iterations: usize,
 
-    #[arg(long)]
+    #[arg(long, conflicts_with = "threads_list")]
     /// Number of threads to use for parallel matrix multiplication [default: number of logical cores]
     pub threads: Option<usize>,
 
+    #[arg(long, value_delimiter = ',')]
+    /// Thread counts to test, every one paired with every tiling configuration. Separate multiple
+    /// values with commas.
+    pub threads_list: Option<Vec<usize>>,
+
     #[arg(short, long, default_value_t = String::from(TILES_DEFAULTS))]
     /// Tile sizes to test. Separate multiple values with commas.
     pub tiles: String,
+
+    #[arg(long, default_value_t = String::from(TILES_PER_JOB_DEFAULTS))]
+    /// Number of tiles grouped into a single job to test. Separate multiple values with commas.
+    pub tiles_per_job: String,
+
+    #[arg(long, value_enum, value_delimiter = ',', default_value = "dynamic")]
+    /// Tile scheduling policies to test. Separate multiple values with commas.
+    pub tile_scheduling: Vec<TileScheduling>,
+
+    #[arg(long, default_value_t = String::from(INNER_TILES_DEFAULTS))]
+    /// Inner tile sizes of the two-level tiling algorithm to test, each paired with every tile
+    /// size it divides. Separate multiple values with commas, or leave empty to skip it.
+    pub inner_tiles: String,
+
+    #[cfg(feature = "plot")]
+    #[arg(long, value_name = "FILE")]
+    /// Also draw the average time of the tiling algorithm against the tile size to FILE, a .png or
+    /// .svg image, with a line per combination of the other parameters
+    pub plot: Option<PathBuf>,
+
+    #[command(flatten)]
+    pub run: RunArgs,
+}
+
+#[derive(Args)]
+pub struct Elementwise {
+    #[arg(default_value_t = 128)]
+    /// Size of the matrix
+    pub size: usize,
+
+    #[arg(short, long, default_value_t = 5)]
+    /// Number of iterations to run the benchmark
+    pub iterations: usize,
+
+    #[arg(long)]
+    /// Number of threads to use for parallel kernels [default: number of logical cores]
+    pub threads: Option<usize>,
+
+    #[arg(short, long, action = clap::ArgAction::SetTrue)]
+    /// Only run parallel kernels
+    pub parallel_only: bool,
+
+    #[arg(long, default_value_t = 3, allow_negative_numbers = true)]
+    /// Scalar used by the scale kernel
+    pub scalar: i32,
+
+    #[command(flatten)]
+    pub run: RunArgs,
+}
+
+#[derive(Args)]
+pub struct Gemv {
+    #[arg(default_value_t = 4096)]
+    /// Size of the matrix
+    pub size: usize,
+
+    #[arg(short, long, default_value_t = 5)]
+    /// Number of iterations to run the benchmark
+    pub iterations: usize,
+
+    #[arg(long)]
+    /// Number of threads to use for parallel kernels [default: number of logical cores]
+    pub threads: Option<usize>,
+
+    #[arg(short, long, action = clap::ArgAction::SetTrue)]
+    /// Only run parallel kernels
+    pub parallel_only: bool,
+
+    #[command(flatten)]
+    pub run: RunArgs,
+}
+
+#[derive(Args)]
+pub struct Quantized {
+    #[arg(default_value_t = 512)]
+    /// Size of the matrix
+    pub size: usize,
+
+    #[arg(short, long, default_value_t = 5)]
+    /// Number of iterations to run the benchmark
+    pub iterations: usize,
+
+    #[arg(long)]
+    /// Number of threads to use for parallel kernels [default: number of logical cores]
+    pub threads: Option<usize>,
+
+    #[arg(short, long, action = clap::ArgAction::SetTrue)]
+    /// Only run parallel kernels
+    pub parallel_only: bool,
+
+    #[command(flatten)]
+    pub run: RunArgs,
+}
+
+#[derive(Args)]
+pub struct Sparse {
+    #[arg(default_value_t = 1024)]
+    /// Size of the matrix
+    pub size: usize,
+
+    #[arg(short, long, default_value_t = 5)]
+    /// Number of iterations to run the benchmark
+    pub iterations: usize,
+
+    #[arg(long)]
+    /// Number of threads to use for parallel kernels [default: number of logical cores]
+    pub threads: Option<usize>,
+
+    #[arg(short, long, action = clap::ArgAction::SetTrue)]
+    /// Only run parallel kernels
+    pub parallel_only: bool,
+
+    #[arg(long, value_parser = parse_density, default_value_t = 0.01)]
+    /// Fraction of nonzero elements of A, between 0 and 1
+    pub density_a: f64,
+
+    #[arg(long, value_parser = parse_density, default_value_t = 0.01)]
+    /// Fraction of nonzero elements of B, between 0 and 1
+    pub density_b: f64,
+
+    #[command(flatten)]
+    pub run: RunArgs,
+}
+
+#[derive(Args)]
+pub struct Triangular {
+    #[arg(default_value_t = 512)]
+    /// Size of the matrix
+    pub size: usize,
+
+    #[arg(short, long, default_value_t = 5)]
+    /// Number of iterations to run the benchmark
+    pub iterations: usize,
+
+    #[arg(long)]
+    /// Number of threads to use for parallel kernels [default: number of logical cores]
+    pub threads: Option<usize>,
+
+    #[arg(short, long, action = clap::ArgAction::SetTrue)]
+    /// Only run parallel kernels
+    pub parallel_only: bool,
+
+    #[arg(long, value_enum, default_value_t = Triangle::Upper)]
+    /// Triangle of A holding its nonzeros
+    pub triangle: Triangle,
+
+    #[command(flatten)]
+    pub run: RunArgs,
+}
+
+#[derive(Args)]
+pub struct Symmetric {
+    #[arg(default_value_t = 512)]
+    /// Size of the matrix
+    pub size: usize,
+
+    #[arg(short, long, default_value_t = 5)]
+    /// Number of iterations to run the benchmark
+    pub iterations: usize,
+
+    #[arg(long)]
+    /// Number of threads to use for parallel kernels [default: number of logical cores]
+    pub threads: Option<usize>,
+
+    #[arg(short, long, action = clap::ArgAction::SetTrue)]
+    /// Only run parallel kernels
+    pub parallel_only: bool,
+
+    #[command(flatten)]
+    pub run: RunArgs,
 }
 
-pub fn parse_cli_tiles(tiles_string: &String) -> Result<Vec<usize>, String> {
-    let spit_tiles: Vec<_> = tiles_string.split(",").collect();
+#[derive(Args)]
+pub struct Gemm {
+    #[arg(default_value_t = 512)]
+    /// Size of the matrix
+    pub size: usize,
+
+    #[arg(short, long, default_value_t = 5)]
+    /// Number of iterations to run the benchmark
+    pub iterations: usize,
+
+    #[arg(long)]
+    /// Number of threads to use for parallel matrix multiplication [default: number of logical cores]
+    pub threads: Option<usize>,
+
+    #[arg(long, default_value_t = 32)]
+    /// Tile size for the tiling algorithms
+    pub tile_size: usize,
+
+    #[arg(long, action = clap::ArgAction::SetTrue, conflicts_with = "tile_size")]
+    /// Use the tile size suggested by the size of the L1 data cache instead of --tile-size
+    pub auto_tile_size: bool,
+
+    #[arg(
+        short,
+        long,
+        value_enum,
+        value_delimiter = ',',
+        default_value = "sequential-ikj,parallel-tiling"
+    )]
+    /// Algorithms computing the product. Separate multiple values with commas.
+    pub algorithms: Vec<AlgorithmName>,
+
+    #[arg(long, default_value_t = 1, allow_negative_numbers = true)]
+    /// Scalar multiplying the product of A and B
+    pub alpha: i32,
+
+    #[arg(long, default_value_t = 1, allow_negative_numbers = true)]
+    /// Scalar multiplying the previous value of C
+    pub beta: i32,
+
+    #[command(flatten)]
+    pub run: RunArgs,
+}
+
+#[derive(Args)]
+pub struct Sweep {
+    #[arg(short, long, value_delimiter = ',', default_value = "128,256,512,1024")]
+    /// Sizes of the matrices. Separate multiple values with commas.
+    pub sizes: Vec<usize>,
 
-    let tiles = spit_tiles
+    #[arg(short, long, default_value_t = 5)]
+    /// Number of iterations to run the benchmark for every size
+    pub iterations: usize,
+
+    #[arg(long)]
+    /// Number of threads to use for parallel matrix multiplication [default: number of logical cores]
+    pub threads: Option<usize>,
+
+    #[arg(long, default_value_t = 32)]
+    /// Tile size for the tiling algorithms
+    pub tile_size: usize,
+
+    #[arg(long, action = clap::ArgAction::SetTrue, conflicts_with = "tile_size")]
+    /// Use the tile size suggested by the size of the L1 data cache instead of --tile-size
+    pub auto_tile_size: bool,
+
+    #[arg(
+        short,
+        long,
+        value_enum,
+        value_delimiter = ',',
+        default_value = "sequential-ikj,sequential-transpose-b,parallel-i-loop,parallel-tiling,strassen"
+    )]
+    /// Algorithms to benchmark. Separate multiple values with commas.
+    pub algorithms: Vec<AlgorithmName>,
+
+    #[cfg(feature = "plot")]
+    #[arg(long, value_name = "FILE")]
+    /// Also draw the average time of every algorithm against the matrix size to FILE, a .png or
+    /// .svg image
+    pub plot: Option<PathBuf>,
+
+    #[command(flatten)]
+    pub run: RunArgs,
+}
+
+#[derive(Args)]
+pub struct Scaling {
+    #[arg(default_value_t = 512)]
+    /// Size of the matrix
+    pub size: usize,
+
+    #[arg(short, long, default_value_t = 5)]
+    /// Number of iterations to run the benchmark for every thread count
+    pub iterations: usize,
+
+    #[arg(short, long, value_delimiter = ',', default_value = "1,2,4,8")]
+    /// Thread counts to run the algorithms with. Separate multiple values with commas.
+    pub threads_list: Vec<usize>,
+
+    #[arg(long, action = clap::ArgAction::SetTrue, conflicts_with = "threads_list")]
+    /// Run the algorithms with every thread count from 1 to the number of available threads
+    /// instead of --threads-list, to find the knee of the scaling curve
+    pub all_threads: bool,
+
+    #[arg(long, default_value_t = 5.0)]
+    /// Recommend the fewest threads whose time is within this percentage of the fastest time
+    pub knee_tolerance: f64,
+
+    #[arg(long, default_value_t = 32)]
+    /// Tile size for the tiling algorithms
+    pub tile_size: usize,
+
+    #[arg(long, action = clap::ArgAction::SetTrue, conflicts_with = "tile_size")]
+    /// Use the tile size suggested by the size of the L1 data cache instead of --tile-size
+    pub auto_tile_size: bool,
+
+    #[arg(
+        short,
+        long,
+        value_enum,
+        value_delimiter = ',',
+        default_value = "parallel-i-loop,parallel-rayon,parallel-tiling,parallel-strassen"
+    )]
+    /// Parallel algorithms to benchmark. Separate multiple values with commas.
+    pub algorithms: Vec<AlgorithmName>,
+
+    #[cfg(feature = "plot")]
+    #[arg(long, value_name = "FILE")]
+    /// Also draw the speedup of every algorithm against the thread count to FILE, a .png or .svg
+    /// image, next to the ideal linear speedup
+    pub plot: Option<PathBuf>,
+
+    #[command(flatten)]
+    pub run: RunArgs,
+}
+
+#[derive(Args)]
+pub struct Stress {
+    #[arg(default_value_t = 512)]
+    /// Size of the matrix
+    pub size: usize,
+
+    #[arg(short, long, value_enum, default_value_t = AlgorithmName::ParallelTiling)]
+    /// Algorithm to stress
+    pub algorithm: AlgorithmName,
+
+    #[arg(long)]
+    /// Number of threads to use for parallel matrix multiplication [default: number of logical cores]
+    pub threads: Option<usize>,
+
+    #[arg(long, default_value_t = 32)]
+    /// Tile size for parallel tiling algorithm
+    pub tile_size: usize,
+
+    #[arg(short, long, value_parser = parse_duration, default_value = "1m")]
+    /// How long to run the stress test for, e.g. 90s, 30m or 2h
+    pub duration: Duration,
+
+    #[arg(short, long, value_parser = parse_duration, default_value = "10s")]
+    /// How often to report the progress of the stress test
+    pub report_interval: Duration,
+
+    #[arg(long, action = clap::ArgAction::SetTrue)]
+    /// Run the jobs of parallel algorithms on a single worker, in submission order
+    pub serialize_parallel: bool,
+
+    #[arg(long)]
+    /// Seed of the input matrices [default: derived from the OS]
+    pub seed: Option<u64>,
+}
+
+#[cfg(feature = "criterion")]
+#[derive(Args)]
+pub struct CriterionBench {
+    #[arg(default_value_t = 256)]
+    /// Size of the matrix
+    pub size: usize,
+
+    #[arg(
+        short,
+        long,
+        value_enum,
+        value_delimiter = ',',
+        default_value = "sequential-ikj,parallel-tiling"
+    )]
+    /// Algorithms to measure. Separate multiple values with commas.
+    pub algorithms: Vec<AlgorithmName>,
+
+    #[arg(long)]
+    /// Number of threads to use for parallel matrix multiplication [default: number of logical cores]
+    pub threads: Option<usize>,
+
+    #[arg(long, default_value_t = 32)]
+    /// Tile size for the tiling algorithms
+    pub tile_size: usize,
+
+    #[arg(long, default_value_t = 20, value_parser = clap::value_parser!(u64).range(10..))]
+    /// Number of samples Criterion collects per algorithm, at least 10
+    pub sample_size: u64,
+
+    #[arg(long, value_parser = parse_duration, default_value = "1s")]
+    /// How long to warm every algorithm up before measuring it
+    pub warm_up_time: Duration,
+
+    #[arg(long, value_parser = parse_duration, default_value = "5s")]
+    /// How long to measure every algorithm for
+    pub measurement_time: Duration,
+}
+
+#[derive(Args)]
+pub struct FuzzVerify {
+    #[arg(short, long, default_value_t = 100)]
+    /// Number of random configurations to verify
+    pub cases: usize,
+
+    #[arg(short, long, default_value_t = 64, value_parser = parse_positive)]
+    /// Largest matrix size to generate, at least 1
+    pub max_size: usize,
+
+    #[arg(long)]
+    /// Seed of the random configurations [default: derived from the current time]
+    pub seed: Option<u64>,
+}
+
+#[derive(Args)]
+pub struct SelfTest {
+    #[arg(long, value_delimiter = ',', default_value = "1,2,3,5,8,16,31,64")]
+    /// Matrix sizes to test. Separate multiple values with commas.
+    pub sizes: Vec<usize>,
+
+    #[arg(long, value_delimiter = ',', default_value = "1,2,4,8,16")]
+    /// Tile sizes to test, the ones not dividing a matrix size are skipped by the tiling
+    /// algorithms. Separate multiple values with commas.
+    pub tile_sizes: Vec<usize>,
+
+    #[arg(long, value_delimiter = ',', default_value = "1,2,3,4")]
+    /// Thread counts to test. Separate multiple values with commas.
+    pub threads: Vec<usize>,
+
+    #[arg(long, default_value_t = 0)]
+    /// Seed of the input matrices
+    pub seed: u64,
+}
+
+#[derive(Args)]
+pub struct Autotune {
+    #[arg(default_value_t = 512)]
+    /// Size of the matrix
+    pub size: usize,
+
+    #[arg(short, long, default_value_t = 3)]
+    /// Number of iterations to time every tile size
+    pub iterations: usize,
+
+    #[arg(long)]
+    /// Number of threads to use for parallel matrix multiplication [default: number of logical cores]
+    pub threads: Option<usize>,
+
+    #[arg(long, value_delimiter = ',')]
+    /// Tile sizes to search. Separate multiple values with commas. [default: the divisors of the
+    /// matrix size from 4 up]
+    pub tile_sizes: Option<Vec<usize>>,
+
+    #[arg(long, value_enum, default_value_t = Search::HillClimbing)]
+    /// Strategy of the search
+    pub search: Search,
+
+    #[arg(long, value_name = "FILE")]
+    /// Also write the matrix size, the thread count and the fastest tile size with its time to
+    /// FILE, as `key = value` lines
+    pub save: Option<PathBuf>,
+
+    #[command(flatten)]
+    pub run: RunArgs,
+}
+
+#[derive(Args)]
+pub struct Compare {
+    /// Results of the reference run, a .json file exported with --format json or a CSV file
+    /// exported with --format csv
+    pub baseline: PathBuf,
+
+    /// Results of the run to compare with the baseline, in either format
+    pub candidate: PathBuf,
+
+    #[arg(long, action = clap::ArgAction::SetTrue)]
+    /// Exit with a non-zero code if any algorithm is slower in the candidate than in the baseline
+    /// by more than --max-regression, to use the comparison as a performance gate in pipelines
+    pub ci: bool,
+
+    #[arg(long, value_name = "PERCENT", default_value = "10%", value_parser = parse_percentage, requires = "ci")]
+    /// Largest increase of the time of an algorithm over the baseline tolerated with --ci
+    pub max_regression: f64,
+}
+
+/// Names of the algorithms that can be selected from the command line
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, ValueEnum)]
+pub enum AlgorithmName {
+    SequentialIjk,
+    SequentialIkj,
+    SequentialJik,
+    SequentialJki,
+    SequentialKij,
+    SequentialKji,
+    SequentialTransposeB,
+    SequentialAvx2,
+    SequentialAvx512,
+    SequentialNeon,
+    SequentialPacked,
+    SequentialTiling,
+    SequentialMorton,
+    ParallelILoop,
+    ParallelJLoop,
+    ParallelRowChunks,
+    ParallelPrivateBuffers,
+    ParallelRayon,
+    ParallelTiling,
+    ParallelTilingUnrolled,
+    ParallelTilingPrefetch,
+    ParallelTilingTwoLevel,
+    ParallelBlocks,
+    ParallelCannon,
+    ParallelSumma,
+    Strassen,
+    StrassenWinograd,
+    ParallelStrassen,
+    StrassenTiled,
+    #[cfg(feature = "matrixmultiply")]
+    SequentialMatrixmultiply,
+    #[cfg(feature = "ndarray")]
+    SequentialNdarray,
+    #[cfg(feature = "faer")]
+    SequentialFaer,
+    #[cfg(feature = "blas")]
+    SequentialBlas,
+    #[cfg(feature = "opencl")]
+    OpenCl,
+}
+
+impl AlgorithmName {
+    /// Returns the algorithm with the given parameters. Parameters not used by the algorithm are
+    /// ignored.
+    pub fn to_algorithm(self, threads: usize, tile_size: usize) -> Algorithm {
+        match self {
+            AlgorithmName::SequentialIjk => Algorithm::SequentialIjk,
+            AlgorithmName::SequentialIkj => Algorithm::SequentialIkj,
+            AlgorithmName::SequentialJik => Algorithm::SequentialJik,
+            AlgorithmName::SequentialJki => Algorithm::SequentialJki,
+            AlgorithmName::SequentialKij => Algorithm::SequentialKij,
+            AlgorithmName::SequentialKji => Algorithm::SequentialKji,
+            AlgorithmName::SequentialTransposeB => Algorithm::SequentialTransposeB,
+            AlgorithmName::SequentialAvx2 => Algorithm::SequentialAvx2,
+            AlgorithmName::SequentialAvx512 => Algorithm::SequentialAvx512,
+            AlgorithmName::SequentialNeon => Algorithm::SequentialNeon,
+            AlgorithmName::SequentialPacked => Algorithm::SequentialPacked,
+            AlgorithmName::SequentialTiling => Algorithm::SequentialTiling(tile_size),
+            AlgorithmName::SequentialMorton => {
+                Algorithm::SequentialMorton(MORTON_LEAF_SIZE_DEFAULT)
+            }
+            AlgorithmName::ParallelILoop => Algorithm::ParallelILoop(threads),
+            AlgorithmName::ParallelJLoop => Algorithm::ParallelJLoop(threads),
+            AlgorithmName::ParallelPrivateBuffers => Algorithm::ParallelPrivateBuffers(threads),
+            AlgorithmName::ParallelRowChunks => {
+                Algorithm::ParallelRowChunks(threads, ROW_CHUNK_SIZE_DEFAULT)
+            }
+            AlgorithmName::ParallelRayon => Algorithm::ParallelRayon(threads),
+            AlgorithmName::ParallelTiling => {
+                Algorithm::ParallelTiling(threads, tile_size, 1, TileScheduling::Dynamic)
+            }
+            AlgorithmName::ParallelTilingUnrolled => {
+                Algorithm::ParallelTilingUnrolled(threads, tile_size, UNROLL_DEFAULT)
+            }
+            AlgorithmName::ParallelTilingPrefetch => {
+                Algorithm::ParallelTilingPrefetch(threads, tile_size)
+            }
+            AlgorithmName::ParallelTilingTwoLevel => {
+                Algorithm::ParallelTilingTwoLevel(threads, tile_size, INNER_TILE_SIZE_DEFAULT)
+            }
+            AlgorithmName::ParallelBlocks => Algorithm::ParallelBlocks(threads),
+            AlgorithmName::ParallelCannon => Algorithm::ParallelCannon(grid_side(threads)),
+            AlgorithmName::ParallelSumma => Algorithm::ParallelSumma(grid_side(threads)),
+            AlgorithmName::Strassen => Algorithm::Strassen(STRASSEN_CUTOFF_DEFAULT),
+            AlgorithmName::StrassenWinograd => Algorithm::StrassenWinograd(STRASSEN_CUTOFF_DEFAULT),
+            AlgorithmName::ParallelStrassen => {
+                Algorithm::ParallelStrassen(threads, STRASSEN_CUTOFF_DEFAULT)
+            }
+            AlgorithmName::StrassenTiled => {
+                Algorithm::StrassenTiled(threads, STRASSEN_CUTOFF_DEFAULT, tile_size)
+            }
+            #[cfg(feature = "matrixmultiply")]
+            AlgorithmName::SequentialMatrixmultiply => Algorithm::SequentialMatrixmultiply,
+            #[cfg(feature = "ndarray")]
+            AlgorithmName::SequentialNdarray => Algorithm::SequentialNdarray,
+            #[cfg(feature = "faer")]
+            AlgorithmName::SequentialFaer => Algorithm::SequentialFaer,
+            #[cfg(feature = "blas")]
+            AlgorithmName::SequentialBlas => Algorithm::SequentialBlas,
+            #[cfg(feature = "opencl")]
+            AlgorithmName::OpenCl => Algorithm::OpenCl,
+        }
+    }
+}
+
+#[derive(Args, Clone, Default)]
+/// Options shared by every benchmark subprogram, with the defaults of the command line as
+/// `Default`
+pub struct RunArgs {
+    #[arg(long, value_name = "DIR", conflicts_with = "load_inputs")]
+    /// Save the input matrices of every iteration to DIR, so that the run can be replayed
+    pub save_inputs: Option<PathBuf>,
+
+    #[arg(long, value_name = "DIR")]
+    /// Load the input matrices of every iteration from DIR instead of generating them
+    pub load_inputs: Option<PathBuf>,
+
+    #[arg(long, conflicts_with = "load_inputs")]
+    /// Seed of the generated input matrices, so that two runs or two machines benchmark on
+    /// identical inputs [default: derived from the OS]
+    pub seed: Option<u64>,
+
+    #[arg(long, action = clap::ArgAction::SetTrue)]
+    /// Generate the input matrices once and reuse them in every iteration, instead of generating
+    /// new ones between the timed regions of every iteration
+    pub fixed_inputs: bool,
+
+    #[arg(long, value_name = "RUNS", default_value_t = 0)]
+    /// Run every algorithm RUNS times before its first timed run, discarding the timings, to leave
+    /// the cold caches and the lazy initializations of the first runs out of the statistics
+    pub warmup: usize,
+
+    #[arg(long, value_enum, default_value_t = ExecutionOrder::Fixed)]
+    /// Order in which the algorithms run within every iteration. Running them in the same order
+    /// every time favors the later ones with warm caches, or penalizes them with a hot CPU
+    pub order: ExecutionOrder,
+
+    #[arg(long, value_name = "CORES", value_parser = parse_cores)]
+    /// Pin the main thread and the workers of the thread pools to CORES, e.g. 0-7 or 0,2,4,6, to
+    /// avoid the noise of the threads migrating between cores. The workers are pinned to the
+    /// cores in turn, in the given order
+    pub pin_cores: Option<Cores>,
+
+    #[cfg(feature = "isolate")]
+    #[arg(long, action = clap::ArgAction::SetTrue)]
+    /// Time every run of every algorithm in a freshly spawned child process, receiving the inputs
+    /// and sending back its timing through pipes, so that the memory kept by the allocator, the
+    /// warm caches and the lazily initialized state of the previous runs cannot affect it
+    pub isolate: bool,
+
+    #[cfg(feature = "numa")]
+    #[arg(long, value_name = "POLICY")]
+    /// Place the memory of the matrices on the NUMA nodes with POLICY: interleave, spreading it
+    /// over every node, or bind:NODE, allocating it on NODE only (Linux only)
+    pub numa: Option<NumaPolicy>,
+
+    #[arg(long, value_name = "BYTES", value_parser = parse_alignment)]
+    /// Align the input matrices to BYTES bytes, a power of two such as 64 for a cache line, to
+    /// compare aligned and unaligned storage. By default they have the alignment of their elements
+    pub align: Option<usize>,
+
+    #[arg(long, value_name = "ELEMENTS")]
+    /// Pad every row of the input matrices with ELEMENTS elements, e.g. 16 to keep the rows of the
+    /// tiles of power-of-two-sized matrices from mapping to the same cache sets
+    pub pad: Option<usize>,
+
+    #[arg(long, value_enum, default_value_t = Clock::Wall)]
+    /// Clock used to time the kernels. CPU clocks are reported next to the wall clock
+    pub clock: Clock,
+
+    #[arg(long, action = clap::ArgAction::SetTrue)]
+    /// Also count the time-stamp counter cycles spent by each kernel (x86_64 only)
+    pub tsc: bool,
+
+    #[arg(long, action = clap::ArgAction::SetTrue)]
+    /// Also report the user and system CPU time consumed by all the threads while each kernel
+    /// runs, and its ratio to the wall-clock time, to spot parallel kernels burning far more CPU
+    /// than they save
+    pub cpu_time: bool,
+
+    #[arg(long, action = clap::ArgAction::SetTrue)]
+    /// Also report the peak resident memory of the process while each kernel runs, and its
+    /// increase over the memory in use before (Linux only)
+    pub peak_memory: bool,
+
+    #[cfg(feature = "perf")]
+    #[arg(long, action = clap::ArgAction::SetTrue)]
+    /// Also count the cycles, instructions and cache misses of each kernel with the hardware
+    /// performance counters (Linux only)
+    pub perf_counters: bool,
+
+    #[arg(long, action = clap::ArgAction::SetTrue)]
+    /// Print the job wait latency and queue depth of the thread pool of the parallel kernels
+    pub pool_metrics: bool,
+
+    #[arg(long, action = clap::ArgAction::SetTrue)]
+    /// Also print the time of every iteration of every algorithm, to spot warm-up effects and
+    /// noise
+    pub show_iterations: bool,
+
+    #[arg(long, value_name = "PERCENT", value_parser = parse_trim)]
+    /// Also report the mean of the iterations without the PERCENT% fastest and the PERCENT%
+    /// slowest ones, e.g. 20 to drop one of five iterations at each end
+    pub trim: Option<f64>,
+
+    #[arg(long, action = clap::ArgAction::SetTrue)]
+    /// Compare the result of every algorithm with the one of the sequential ikj algorithm on the
+    /// same inputs, marking the algorithms that disagree as FAILED
+    pub check: bool,
+
+    #[arg(long, action = clap::ArgAction::SetTrue)]
+    /// Run the jobs of parallel kernels on a single worker, in submission order, to make failures
+    /// reproducible. The jobs are the same as with the requested number of threads
+    pub serialize_parallel: bool,
+
+    #[arg(long, value_name = "DURATION", value_parser = parse_duration)]
+    /// Keep running every algorithm after the fixed iterations until it has run for at least
+    /// DURATION, e.g. 10s, to get stable statistics on small matrices
+    pub min_time: Option<Duration>,
+
+    #[arg(long, value_name = "DURATION", value_parser = parse_duration)]
+    /// Pause for DURATION, e.g. 30s, after every run during which the CPUs throttled, to let them
+    /// cool down before the next run
+    pub throttle_pause: Option<Duration>,
+
+    #[arg(long = "format", visible_alias = "output", value_enum, default_value_t = OutputFormat::Table)]
+    /// Format of the benchmark results
+    pub output: OutputFormat,
+
+    #[arg(long, value_name = "FILE")]
+    /// Also write the parameters, the results and charts of the timings to FILE, a self-contained
+    /// HTML page to share the results with people not using the command line
+    pub report: Option<PathBuf>,
+
+    #[cfg(feature = "sqlite")]
+    #[arg(long, value_name = "FILE")]
+    /// Also append the parameters, the timings of every iteration, the machine and the time of
+    /// the run to the SQLite database FILE, created if missing, to track the performance across
+    /// commits and machines
+    pub db: Option<PathBuf>,
+
+    #[arg(long, action = clap::ArgAction::SetTrue)]
+    /// Print the memory footprint of the matrices, the number of jobs every algorithm submits and
+    /// a rough estimate of its time, from a quick multiplication of smaller matrices, and exit
+    /// without running the benchmark
+    pub dry_run: bool,
+}
+
+/// Parses a comma-separated list of positive integers, using `name` in the error message.
+fn parse_cli_usize_list(list_string: &str, name: &str) -> Result<Vec<usize>, String> {
+    let split_list: Vec<_> = list_string.split(',').collect();
+
+    let list = split_list
         .iter()
-        .map(|tile| tile.parse::<usize>())
+        .map(|value| value.parse::<usize>())
         .collect::<Result<Vec<_>, _>>();
 
-    match tiles {
-        Ok(tiles) => Ok(tiles),
-        Err(_) => Err("tiles must be a positive integer".to_string()),
+    match list {
+        Ok(list) => Ok(list),
+        Err(_) => Err(format!("{} must be a positive integer", name)),
+    }
+}
+
+/// Parses the shape of a product given as MxKxN, the dimensions of an M x K matrix and a K x N one.
+pub fn parse_shape(shape_string: &str) -> Result<Shape, String> {
+    let dimensions = shape_string
+        .split('x')
+        .map(|dimension| dimension.trim().parse::<usize>().ok().filter(|d| *d > 0))
+        .collect::<Option<Vec<_>>>();
+
+    match dimensions.as_deref() {
+        Some(&[m, k, n]) => Ok(Shape { m, k, n }),
+        _ => Err(format!(
+            "shape must be given as MxKxN with positive dimensions: {}",
+            shape_string
+        )),
+    }
+}
+
+pub fn parse_cli_tiles(tiles_string: &str) -> Result<Vec<usize>, String> {
+    parse_cli_usize_list(tiles_string, "tiles")
+}
+
+pub fn parse_cli_inner_tiles(inner_tiles_string: &str) -> Result<Vec<usize>, String> {
+    if inner_tiles_string.trim().is_empty() {
+        return Ok(Vec::new());
+    }
+    parse_cli_usize_list(inner_tiles_string, "inner tiles")
+}
+
+pub fn parse_cli_tiles_per_job(tiles_per_job_string: &str) -> Result<Vec<usize>, String> {
+    parse_cli_usize_list(tiles_per_job_string, "tiles per job")
+}
+
+/// Parses an alignment in bytes, a power of two.
+pub fn parse_alignment(alignment_string: &str) -> Result<usize, String> {
+    match alignment_string.trim().parse::<usize>() {
+        Ok(alignment) if alignment.is_power_of_two() => Ok(alignment),
+        _ => Err(format!(
+            "alignment must be a power of two: {}",
+            alignment_string
+        )),
+    }
+}
+
+/// Parses a positive integer.
+pub fn parse_positive(value_string: &str) -> Result<usize, String> {
+    match value_string.trim().parse::<usize>() {
+        Ok(value) if value > 0 => Ok(value),
+        _ => Err(format!("must be a positive integer: {}", value_string)),
+    }
+}
+
+/// Parses the percentage of the lowest and of the highest timings dropped from a trimmed mean,
+/// at least 0 and less than 50.
+pub fn parse_trim(trim_string: &str) -> Result<f64, String> {
+    match trim_string.trim().trim_end_matches('%').parse::<f64>() {
+        Ok(trim) if (0.0..50.0).contains(&trim) => Ok(trim),
+        _ => Err(format!(
+            "trim must be a percentage of at least 0 and less than 50: {}",
+            trim_string
+        )),
+    }
+}
+
+/// Parses a non-negative percentage, with or without the percent sign.
+pub fn parse_percentage(percentage_string: &str) -> Result<f64, String> {
+    match percentage_string
+        .trim()
+        .trim_end_matches('%')
+        .parse::<f64>()
+    {
+        Ok(percentage) if percentage >= 0.0 => Ok(percentage),
+        _ => Err(format!(
+            "must be a non-negative percentage: {}",
+            percentage_string
+        )),
+    }
+}
+
+/// Ids of CPU cores
+pub type Cores = Vec<usize>;
+
+/// Parses a comma-separated list of cores and ranges of cores, e.g. `0-3,8`, without duplicates.
+pub fn parse_cores(cores_string: &str) -> Result<Cores, String> {
+    let mut cores = Vec::new();
+    for part in cores_string.split(',').map(str::trim) {
+        let (start, end) = part.split_once('-').unwrap_or((part, part));
+        let parse = |core: &str| {
+            core.trim()
+                .parse::<usize>()
+                .map_err(|_| format!("invalid core {:?} in {}", core, cores_string))
+        };
+        let (start, end) = (parse(start)?, parse(end)?);
+        if start > end {
+            return Err(format!("empty range of cores {} in {}", part, cores_string));
+        }
+        cores.extend(start..=end);
+    }
+    let mut distinct = cores.clone();
+    distinct.sort();
+    distinct.dedup();
+    match distinct.len() == cores.len() {
+        true => Ok(cores),
+        false => Err(format!("cores given more than once: {}", cores_string)),
+    }
+}
+
+/// Parses the density of a sparse matrix, a fraction between 0 and 1.
+pub fn parse_density(density_string: &str) -> Result<f64, String> {
+    match density_string.trim().parse::<f64>() {
+        Ok(density) if (0.0..=1.0).contains(&density) => Ok(density),
+        _ => Err(format!(
+            "density must be a number between 0 and 1: {}",
+            density_string
+        )),
+    }
+}
+
+/// Parses a duration made of a number followed by a unit among `ms`, `s`, `m` and `h`,
+/// e.g. `500ms` or `30m`. A number without unit is interpreted as seconds.
+pub fn parse_duration(duration_string: &str) -> Result<Duration, String> {
+    let duration_string = duration_string.trim();
+    let split_at = duration_string
+        .find(|c: char| !c.is_ascii_digit() && c != '.')
+        .unwrap_or(duration_string.len());
+    let (value, unit) = duration_string.split_at(split_at);
+
+    let value = value
+        .parse::<f64>()
+        .map_err(|_| format!("invalid duration: {}", duration_string))?;
+    let seconds = match unit {
+        "ms" => value / 1000.0,
+        "" | "s" => value,
+        "m" => value * 60.0,
+        "h" => value * 3600.0,
+        _ => return Err(format!("invalid duration unit: {}", unit)),
+    };
+
+    Ok(Duration::from_secs_f64(seconds))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_duration() {
+        assert_eq!(parse_duration("500ms"), Ok(Duration::from_millis(500)));
+        assert_eq!(parse_duration("90"), Ok(Duration::from_secs(90)));
+        assert_eq!(parse_duration("90s"), Ok(Duration::from_secs(90)));
+        assert_eq!(parse_duration("30m"), Ok(Duration::from_secs(30 * 60)));
+        assert_eq!(parse_duration("1.5h"), Ok(Duration::from_secs(90 * 60)));
+        assert!(parse_duration("10d").is_err());
+        assert!(parse_duration("m").is_err());
+    }
+
+    #[test]
+    fn test_parse_shape() {
+        assert_eq!(parse_shape("2x3x4"), Ok(Shape { m: 2, k: 3, n: 4 }));
+        assert!(parse_shape("2x3").is_err());
+        assert!(parse_shape("2x0x4").is_err());
+        assert!(parse_shape("2x3x4x5").is_err());
+        assert!(parse_shape("axbxc").is_err());
+    }
+
+    #[test]
+    fn test_parse_alignment() {
+        assert_eq!(parse_alignment("64"), Ok(64));
+        assert_eq!(parse_alignment("1"), Ok(1));
+        assert!(parse_alignment("0").is_err());
+        assert!(parse_alignment("48").is_err());
+        assert!(parse_alignment("-64").is_err());
+    }
+
+    #[test]
+    fn test_parse_positive() {
+        assert_eq!(parse_positive("64"), Ok(64));
+        assert_eq!(parse_positive("1"), Ok(1));
+        assert!(parse_positive("0").is_err());
+        assert!(parse_positive("-1").is_err());
+    }
+
+    #[test]
+    fn test_parse_density() {
+        assert_eq!(parse_density("0.01"), Ok(0.01));
+        assert_eq!(parse_density("1"), Ok(1.0));
+        assert!(parse_density("1.5").is_err());
+        assert!(parse_density("-0.1").is_err());
+        assert!(parse_density("dense").is_err());
+    }
+
+    #[test]
+    fn test_parse_trim() {
+        assert_eq!(parse_trim("10"), Ok(10.0));
+        assert_eq!(parse_trim("12.5%"), Ok(12.5));
+        assert!(parse_trim("50").is_err());
+        assert!(parse_trim("-1").is_err());
+    }
+
+    #[test]
+    fn test_parse_cores() {
+        assert_eq!(parse_cores("0-3,8"), Ok(vec![0, 1, 2, 3, 8]));
+        assert_eq!(parse_cores("6, 4"), Ok(vec![6, 4]));
+        assert!(parse_cores("3-1").is_err());
+        assert!(parse_cores("0-3,2").is_err());
+        assert!(parse_cores("a").is_err());
+    }
+
+    #[test]
+    fn test_parse_percentage() {
+        assert_eq!(parse_percentage("10%"), Ok(10.0));
+        assert_eq!(parse_percentage("150"), Ok(150.0));
+        assert!(parse_percentage("-5%").is_err());
+        assert!(parse_percentage("ten").is_err());
+    }
+
+    #[test]
+    fn test_parse_cli_tiles() {
+        assert_eq!(parse_cli_tiles("16,32"), Ok(vec![16, 32]));
+        assert!(parse_cli_tiles("16,a").is_err());
+    }
+
+    #[test]
+    fn test_parse_cli_inner_tiles() {
+        assert_eq!(parse_cli_inner_tiles(""), Ok(vec![]));
+        assert_eq!(parse_cli_inner_tiles("4,8"), Ok(vec![4, 8]));
     }
 }
@@ -1,9 +1,25 @@
-use cli_table::{format::Justify, print_stdout, Cell, CellStruct, Style, Table, TableStruct};
+use std::sync::atomic::{AtomicBool, Ordering};
+
+use cli_table::{
+    format::Justify, print_stderr, print_stdout, Cell, CellStruct, Style, Table, TableStruct,
+};
 use colored::Colorize;
 
+/// Whether the tables are printed to the standard error, set with `print_tables_to_stderr`
+static TABLES_TO_STDERR: AtomicBool = AtomicBool::new(false);
+
+/// Prints the tables to the standard error instead of the standard output from now on, so that
+/// the standard output only holds the document of the results, e.g. with `--format json`
+pub fn print_tables_to_stderr(to_stderr: bool) {
+    TABLES_TO_STDERR.store(to_stderr, Ordering::Relaxed);
+}
+
 /// Prints a table to the console.
 fn print_table(table: TableStruct) {
-    print_stdout(table).unwrap();
+    match TABLES_TO_STDERR.load(Ordering::Relaxed) {
+        true => print_stderr(table).unwrap(),
+        false => print_stdout(table).unwrap(),
+    }
 }
 
 /// Prints a title to the console.
@@ -27,10 +43,13 @@ pub fn print_args_table(elements: Vec<Vec<CellStruct>>) {
 }
 
 /// Prints the table with the benchmark results to the console.
-pub fn print_benchmark_results_table(elements: Vec<Vec<CellStruct>>) {
-    let table = elements.table().title(vec![
-        "Algorithm".cell().bold(true),
-        "Average time (ms)".cell().bold(true),
-    ]);
+/// The table has one column per entry of `columns`, whose values are used as titles.
+pub fn print_benchmark_results_table(columns: &[String], elements: Vec<Vec<CellStruct>>) {
+    let table = elements.table().title(
+        columns
+            .iter()
+            .map(|column| column.cell().bold(true))
+            .collect::<Vec<_>>(),
+    );
     print_table(table);
 }
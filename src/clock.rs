@@ -0,0 +1,188 @@
+use std::time::{Duration, Instant};
+
+use clap::ValueEnum;
+
+/// Enum representing the clocks that can be used to time the kernels
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Hash, ValueEnum)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "serde", serde(rename_all = "snake_case"))]
+pub enum Clock {
+    /// Wall-clock time
+    #[default]
+    Wall,
+    /// CPU time consumed by all the threads of the process
+    Cpu,
+    /// CPU time consumed by the calling thread only. Work done by the thread pool workers is not
+    /// accounted for
+    ThreadCpu,
+}
+
+impl std::fmt::Display for Clock {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Clock::Wall => write!(f, "wall"),
+            Clock::Cpu => write!(f, "cpu"),
+            Clock::ThreadCpu => write!(f, "thread-cpu"),
+        }
+    }
+}
+
+impl Clock {
+    /// Returns the current CPU time for CPU clocks, or `None` for the wall clock and on platforms
+    /// where the CPU clocks are not available.
+    fn cpu_time(&self) -> Option<Duration> {
+        match self {
+            Clock::Wall => None,
+            Clock::Cpu => cpu_time(CpuClock::Process),
+            Clock::ThreadCpu => cpu_time(CpuClock::Thread),
+        }
+    }
+}
+
+/// Measures the wall-clock time elapsed since its creation, together with the time elapsed on the
+/// selected clock when it is not the wall clock.
+pub struct Stopwatch {
+    clock: Clock,
+    wall_start: Instant,
+    clock_start: Option<Duration>,
+}
+
+impl Stopwatch {
+    /// Starts a new stopwatch on the given clock
+    pub fn start(clock: Clock) -> Stopwatch {
+        Stopwatch {
+            clock,
+            clock_start: clock.cpu_time(),
+            wall_start: Instant::now(),
+        }
+    }
+
+    /// Stops the stopwatch, returning the elapsed wall-clock time and the time elapsed on the
+    /// selected clock, if it is not the wall clock and it is available on this platform
+    pub fn stop(self) -> (Duration, Option<Duration>) {
+        let wall = self.wall_start.elapsed();
+        let clock = self
+            .clock_start
+            .zip(self.clock.cpu_time())
+            .map(|(start, end)| end.saturating_sub(start));
+        (wall, clock)
+    }
+}
+
+enum CpuClock {
+    Process,
+    Thread,
+}
+
+#[cfg(unix)]
+fn cpu_time(clock: CpuClock) -> Option<Duration> {
+    let clock_id = match clock {
+        CpuClock::Process => libc::CLOCK_PROCESS_CPUTIME_ID,
+        CpuClock::Thread => libc::CLOCK_THREAD_CPUTIME_ID,
+    };
+
+    let mut time = libc::timespec {
+        tv_sec: 0,
+        tv_nsec: 0,
+    };
+    // SAFETY: `time` is a valid, writable timespec
+    let res = unsafe { libc::clock_gettime(clock_id, &mut time) };
+    if res != 0 {
+        return None;
+    }
+
+    Some(Duration::new(time.tv_sec as u64, time.tv_nsec as u32))
+}
+
+#[cfg(not(unix))]
+fn cpu_time(_clock: CpuClock) -> Option<Duration> {
+    None
+}
+
+/// CPU time consumed by all the threads of the process, split between user and kernel space
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct CpuUsage {
+    pub user: Duration,
+    pub system: Duration,
+}
+
+impl CpuUsage {
+    /// Returns the CPU time consumed by the process so far, or `None` on platforms where it is
+    /// not available
+    #[cfg(unix)]
+    pub fn now() -> Option<CpuUsage> {
+        // SAFETY: an all-zero rusage is a valid value for getrusage to overwrite
+        let mut usage: libc::rusage = unsafe { std::mem::zeroed() };
+        // SAFETY: `usage` is a valid, writable rusage
+        let res = unsafe { libc::getrusage(libc::RUSAGE_SELF, &mut usage) };
+        if res != 0 {
+            return None;
+        }
+
+        let duration =
+            |time: libc::timeval| Duration::new(time.tv_sec as u64, time.tv_usec as u32 * 1000);
+        Some(CpuUsage {
+            user: duration(usage.ru_utime),
+            system: duration(usage.ru_stime),
+        })
+    }
+
+    #[cfg(not(unix))]
+    pub fn now() -> Option<CpuUsage> {
+        None
+    }
+
+    /// Returns the CPU time consumed between `start` and `self`
+    pub fn since(&self, start: &CpuUsage) -> CpuUsage {
+        CpuUsage {
+            user: self.user.saturating_sub(start.user),
+            system: self.system.saturating_sub(start.system),
+        }
+    }
+
+    /// Returns the total CPU time, in user and kernel space
+    pub fn total(&self) -> Duration {
+        self.user + self.system
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn busy_loop() -> u64 {
+        (0..1_000_000u64).fold(0, |acc, x| acc.wrapping_add(x * x))
+    }
+
+    #[test]
+    fn test_wall_clock_reports_no_cpu_time() {
+        let stopwatch = Stopwatch::start(Clock::Wall);
+        std::hint::black_box(busy_loop());
+        let (_, clock) = stopwatch.stop();
+        assert_eq!(clock, None);
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn test_cpu_usage() {
+        let start = CpuUsage::now().unwrap();
+        for _ in 0..100 {
+            std::hint::black_box(busy_loop());
+        }
+        let usage = CpuUsage::now().unwrap().since(&start);
+        assert!(!usage.total().is_zero());
+        assert_eq!(usage.total(), usage.user + usage.system);
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn test_cpu_clocks_report_time() {
+        for clock in [Clock::Cpu, Clock::ThreadCpu] {
+            let stopwatch = Stopwatch::start(clock);
+            std::hint::black_box(busy_loop());
+            let (_, clock_time) = stopwatch.stop();
+            assert!(clock_time.is_some());
+        }
+    }
+}
@@ -0,0 +1,270 @@
+use std::{fs, path::Path, time::Duration};
+
+use cli_table::Cell;
+use log::{error, info, warn};
+
+#[cfg(feature = "json")]
+use crate::output::json;
+use crate::{
+    cli::Compare,
+    cli_tables::{print_args_table, print_benchmark_results_table, print_title},
+    output::{csv, format_duration, ResultRow},
+};
+
+/// Times of an algorithm in the two compared runs, `None` if it was not run
+#[derive(Debug, Clone, PartialEq)]
+struct Comparison {
+    algorithm: String,
+    baseline: Option<Duration>,
+    candidate: Option<Duration>,
+}
+
+impl Comparison {
+    /// Returns the difference between the candidate and the baseline time, in milliseconds,
+    /// positive if the candidate is slower
+    fn delta(&self) -> Option<f64> {
+        let (baseline, candidate) = self.baseline.zip(self.candidate)?;
+        Some((candidate.as_secs_f64() - baseline.as_secs_f64()) * 1e3)
+    }
+
+    /// Returns the change of the time relative to the baseline, in percent
+    fn change(&self) -> Option<f64> {
+        let baseline = self.baseline.filter(|baseline| !baseline.is_zero())?;
+        Some(100.0 * self.delta()? / (baseline.as_secs_f64() * 1e3))
+    }
+}
+
+/// Reads the aggregated results of a run exported with `--format json` or `--format csv`,
+/// depending on the extension of the file
+fn load_results(path: &Path) -> Result<Vec<ResultRow>, String> {
+    let text = fs::read_to_string(path).map_err(|err| err.to_string())?;
+    match path.extension().and_then(|extension| extension.to_str()) {
+        #[cfg(feature = "json")]
+        Some("json") => json::from_json(&text)
+            .map(|report| {
+                report
+                    .results
+                    .into_iter()
+                    .map(|result| result.result)
+                    .collect()
+            })
+            .map_err(|err| err.to_string()),
+        #[cfg(not(feature = "json"))]
+        Some("json") => Err("reading JSON results requires building with --features json".into()),
+        _ => csv::parse_results(&text),
+    }
+}
+
+/// Pairs the results of the two runs by algorithm, in the order of the baseline followed by the
+/// algorithms only in the candidate
+fn align(baseline: &[ResultRow], candidate: &[ResultRow]) -> Vec<Comparison> {
+    let time = |rows: &[ResultRow], algorithm: &str| {
+        rows.iter()
+            .find(|row| row.algorithm == algorithm)
+            .map(|row| row.time)
+    };
+    let mut comparisons = baseline
+        .iter()
+        .map(|row| Comparison {
+            algorithm: row.algorithm.clone(),
+            baseline: Some(row.time),
+            candidate: time(candidate, &row.algorithm),
+        })
+        .collect::<Vec<_>>();
+    comparisons.extend(
+        candidate
+            .iter()
+            .filter(|row| time(baseline, &row.algorithm).is_none())
+            .map(|row| Comparison {
+                algorithm: row.algorithm.clone(),
+                baseline: None,
+                candidate: Some(row.time),
+            }),
+    );
+    comparisons
+}
+
+/// Returns the comparisons whose time increased over the baseline by more than `max_regression`
+/// percent
+fn regressions(comparisons: &[Comparison], max_regression: f64) -> Vec<&Comparison> {
+    comparisons
+        .iter()
+        .filter(|comparison| {
+            comparison
+                .change()
+                .is_some_and(|change| change > max_regression)
+        })
+        .collect()
+}
+
+/// Formats a signed difference of times in milliseconds, with the unit of `format_duration`
+fn format_delta(ms: f64) -> String {
+    let sign = if ms < 0.0 { "-" } else { "+" };
+    format!(
+        "{}{}",
+        sign,
+        format_duration(Duration::from_secs_f64(ms.abs() / 1e3))
+    )
+}
+
+/// Subprogram comparing the results of two runs exported with `--format json` or
+/// `--format csv`, e.g. on two machines or before and after a change, printing the time of every
+/// algorithm in both runs with the difference and the relative change.
+/// Returns `false` if a file cannot be read or, with `--ci`, if an algorithm regressed past
+/// `--max-regression`.
+pub fn compare(cli: &Compare) -> bool {
+    print_title("Welcome to Compare!");
+
+    let mut table = vec![
+        vec!["Baseline".cell(), cli.baseline.display().cell()],
+        vec!["Candidate".cell(), cli.candidate.display().cell()],
+    ];
+    if cli.ci {
+        table.push(vec![
+            "Max regression".cell(),
+            format!("{}%", cli.max_regression).cell(),
+        ]);
+    }
+    print_args_table(table);
+
+    let mut runs = Vec::new();
+    for path in [&cli.baseline, &cli.candidate] {
+        match load_results(path) {
+            Ok(results) => runs.push(results),
+            Err(err) => {
+                error!("Cannot read the results of {}: {}", path.display(), err);
+                return false;
+            }
+        }
+    }
+    let comparisons = align(&runs[0], &runs[1]);
+
+    let optional = |time: Option<Duration>| time.map_or("-".to_string(), format_duration);
+    let columns = ["Algorithm", "Baseline", "Candidate", "Delta", "Change"]
+        .map(String::from)
+        .to_vec();
+    let rows = comparisons
+        .iter()
+        .map(|comparison| {
+            vec![
+                comparison.algorithm.clone().cell(),
+                optional(comparison.baseline).cell(),
+                optional(comparison.candidate).cell(),
+                comparison
+                    .delta()
+                    .map_or("-".to_string(), format_delta)
+                    .cell(),
+                comparison
+                    .change()
+                    .map_or("-".to_string(), |change| format!("{:+.1}%", change))
+                    .cell(),
+            ]
+        })
+        .collect();
+
+    print_title("Comparison");
+    print_benchmark_results_table(&columns, rows);
+
+    let changes = comparisons.iter().filter_map(Comparison::change);
+    let (faster, slower) = changes.fold((0, 0), |(faster, slower), change| {
+        (
+            faster + usize::from(change < 0.0),
+            slower + usize::from(change > 0.0),
+        )
+    });
+    info!(
+        "{} algorithms faster and {} slower in the candidate",
+        faster, slower
+    );
+
+    if !cli.ci {
+        return true;
+    }
+    for comparison in comparisons
+        .iter()
+        .filter(|comparison| comparison.candidate.is_none())
+    {
+        warn!(
+            "{} is missing from the candidate and is not checked",
+            comparison.algorithm
+        );
+    }
+    let regressions = regressions(&comparisons, cli.max_regression);
+    for comparison in &regressions {
+        error!(
+            "{} regressed by {:+.1}%, past the {}% threshold",
+            comparison.algorithm,
+            comparison.change().unwrap(),
+            cli.max_regression
+        );
+    }
+    match regressions.is_empty() {
+        true => info!(
+            "No algorithm regressed by more than {}%",
+            cli.max_regression
+        ),
+        false => error!(
+            "{} of {} algorithms regressed",
+            regressions.len(),
+            comparisons.len()
+        ),
+    }
+    regressions.is_empty()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn row(algorithm: &str, ms: u64) -> ResultRow {
+        ResultRow {
+            algorithm: algorithm.to_string(),
+            time: Duration::from_millis(ms),
+            gops: None,
+            speedup: None,
+        }
+    }
+
+    #[test]
+    fn test_align() {
+        let baseline = [row("A", 10), row("B", 20)];
+        let candidate = [row("C", 5), row("A", 12)];
+
+        let comparisons = align(&baseline, &candidate);
+
+        let algorithms = comparisons
+            .iter()
+            .map(|comparison| comparison.algorithm.as_str())
+            .collect::<Vec<_>>();
+        assert_eq!(algorithms, ["A", "B", "C"]);
+        assert_eq!(comparisons[1].candidate, None);
+        assert_eq!(comparisons[2].baseline, None);
+
+        let delta = comparisons[0].delta().unwrap();
+        assert!((delta - 2.0).abs() < 1e-9);
+        assert!((comparisons[0].change().unwrap() - 20.0).abs() < 1e-9);
+        assert_eq!(comparisons[1].change(), None);
+    }
+
+    #[test]
+    fn test_regressions() {
+        let baseline = [row("A", 100), row("B", 100), row("C", 100), row("D", 100)];
+        let candidate = [row("A", 105), row("B", 120), row("C", 50)];
+
+        let comparisons = align(&baseline, &candidate);
+
+        let regressed = regressions(&comparisons, 10.0)
+            .iter()
+            .map(|comparison| comparison.algorithm.as_str())
+            .collect::<Vec<_>>();
+        assert_eq!(regressed, ["B"]);
+        assert_eq!(regressions(&comparisons, 4.0).len(), 2);
+        assert!(regressions(&comparisons, 25.0).is_empty());
+    }
+
+    #[test]
+    fn test_format_delta() {
+        assert_eq!(format_delta(2.0), "+2.00 ms");
+        assert_eq!(format_delta(-0.25), "-250 µs");
+    }
+}
@@ -0,0 +1,71 @@
+use std::{hint::black_box, thread};
+
+use cli_table::Cell;
+use criterion::{BenchmarkId, Criterion, Throughput};
+
+use crate::{
+    benchmark::Benchmarkable,
+    cli::CriterionBench,
+    cli_tables::{print_args_table, print_title},
+    matrix_multiplication::{initialize, matrix_multiplication, types::Shape},
+    random_filled_square_matrix_of_size,
+    thread_pool::ThreadPool,
+};
+
+/// Subprogram measuring the selected algorithms with Criterion.rs, which estimates their
+/// execution time from many samples, with confidence intervals and outlier classification, and
+/// compares it with the previous measurement saved in `target/criterion`.
+pub fn criterion_benchmark(cli: &CriterionBench) {
+    let n = cli.size;
+    let available_threads = thread::available_parallelism().unwrap().get();
+    let threads = cli.threads.unwrap_or(available_threads);
+
+    print_title("Welcome to Criterion Benchmark!");
+
+    let table = vec![
+        vec!["Matrix size".cell(), n.to_string().cell()],
+        vec!["Number of threads".cell(), threads.to_string().cell()],
+        vec!["Tile size".cell(), cli.tile_size.to_string().cell()],
+        vec!["Sample size".cell(), cli.sample_size.to_string().cell()],
+        vec![
+            "Warm-up time".cell(),
+            format!("{:?}", cli.warm_up_time).cell(),
+        ],
+        vec![
+            "Measurement time".cell(),
+            format!("{:?}", cli.measurement_time).cell(),
+        ],
+    ];
+    print_args_table(table);
+
+    let a = random_filled_square_matrix_of_size!(n);
+    let b = random_filled_square_matrix_of_size!(n);
+
+    let mut criterion = Criterion::default()
+        .sample_size(cli.sample_size as usize)
+        .warm_up_time(cli.warm_up_time)
+        .measurement_time(cli.measurement_time);
+    let mut group = criterion.benchmark_group(format!("matrix_multiplication/{}", n));
+
+    for name in &cli.algorithms {
+        let algorithm = name.to_algorithm(threads, cli.tile_size);
+        group.throughput(Throughput::Elements(
+            algorithm.operations(Shape::square(n)) as u64
+        ));
+        // the pool is spawned once, so that its cost is not part of the samples
+        initialize(algorithm);
+        let pool = algorithm.pool_threads().map(ThreadPool::new);
+        group.bench_function(BenchmarkId::from_parameter(algorithm), |bencher| {
+            bencher.iter(|| {
+                matrix_multiplication(black_box(&a), black_box(&b), algorithm, pool.as_ref())
+                    .unwrap()
+            })
+        });
+        if let Some(pool) = pool {
+            ThreadPool::terminate(pool);
+        }
+    }
+
+    group.finish();
+    criterion.final_summary();
+}
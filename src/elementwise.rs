@@ -0,0 +1,208 @@
+use crate::{
+    matrix_multiplication::{
+        in_pool,
+        matrix::Matrix,
+        sanitize::{sanitize_matrices, SanitizeError},
+        types::MatrixRowPtr,
+    },
+    thread_pool::ThreadPool,
+    zero_filled_square_matrix_of_size,
+};
+
+/// Enum representing the available element-wise operations
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum ElementwiseOp {
+    /// Element-wise sum, `C = A + B`
+    Add,
+    /// Multiplication by a scalar, `C = alpha * A`
+    ///
+    /// # Arguments
+    ///
+    /// * `i32` - the scalar `alpha`
+    Scale(i32),
+    /// Element-wise (Hadamard) product, `C = A ∘ B`
+    Hadamard,
+}
+
+impl ElementwiseOp {
+    /// Applies the operation to a single pair of elements
+    fn apply(&self, a: i32, b: i32) -> i32 {
+        match self {
+            ElementwiseOp::Add => a + b,
+            ElementwiseOp::Scale(alpha) => alpha * a,
+            ElementwiseOp::Hadamard => a * b,
+        }
+    }
+}
+
+impl std::fmt::Display for ElementwiseOp {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            ElementwiseOp::Add => write!(f, "Add"),
+            ElementwiseOp::Scale(alpha) => write!(f, "Scale (alpha = {})", alpha),
+            ElementwiseOp::Hadamard => write!(f, "Hadamard"),
+        }
+    }
+}
+
+/// Enum representing available element-wise kernels
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum ElementwiseKernel {
+    /// Sequential kernel iterating over the rows of the matrices
+    ///
+    /// # Arguments
+    ///
+    /// * `ElementwiseOp` - the operation to apply
+    Sequential(ElementwiseOp),
+    /// Parallel kernel submitting one job per row to the thread pool
+    ///
+    /// # Arguments
+    ///
+    /// * `ElementwiseOp` - the operation to apply
+    /// * `usize` - number of threads to use
+    Parallel(ElementwiseOp, usize),
+}
+
+impl std::fmt::Display for ElementwiseKernel {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            ElementwiseKernel::Sequential(op) => write!(f, "Sequential {}", op),
+            ElementwiseKernel::Parallel(op, threads) => {
+                write!(f, "Parallel {} ({} threads)", op, threads)
+            }
+        }
+    }
+}
+
+impl ElementwiseKernel {
+    /// Returns the number of threads used by the kernel, or `None` if it is sequential
+    pub fn threads(&self) -> Option<usize> {
+        match self {
+            ElementwiseKernel::Sequential(_) => None,
+            ElementwiseKernel::Parallel(_, threads) => Some(*threads),
+        }
+    }
+}
+
+/// Applies an element-wise kernel to the matrices `a` and `b`.
+/// The `b` matrix is ignored by the operations that only read `a`, but it is
+/// still sanitized so that every kernel runs on the same inputs.
+/// Parallel kernels run on `pool`, see `in_pool`.
+pub fn elementwise(
+    a: &Matrix<i32>,
+    b: &Matrix<i32>,
+    kernel: ElementwiseKernel,
+    pool: Option<&ThreadPool>,
+) -> Result<Matrix<i32>, SanitizeError> {
+    sanitize_matrices(a, b)?;
+
+    let size = a.rows();
+
+    match kernel {
+        ElementwiseKernel::Sequential(op) => elementwise_sequential(a, b, size, op),
+        ElementwiseKernel::Parallel(op, threads) => in_pool(pool, threads, |pool| {
+            elementwise_parallel(a, b, size, op, pool)
+        }),
+    }
+}
+
+fn elementwise_sequential(
+    a: &Matrix<i32>,
+    b: &Matrix<i32>,
+    size: usize,
+    op: ElementwiseOp,
+) -> Result<Matrix<i32>, SanitizeError> {
+    let mut c = zero_filled_square_matrix_of_size!(size);
+
+    for ((c_i, a_i), b_i) in c.iter_rows_mut().zip(a.iter_rows()).zip(b.iter_rows()) {
+        for ((c_ij, a_ij), b_ij) in c_i.iter_mut().zip(a_i).zip(b_i) {
+            *c_ij = op.apply(*a_ij, *b_ij);
+        }
+    }
+
+    Ok(c)
+}
+
+fn elementwise_parallel(
+    a: &Matrix<i32>,
+    b: &Matrix<i32>,
+    size: usize,
+    op: ElementwiseOp,
+    pool: &ThreadPool,
+) -> Result<Matrix<i32>, SanitizeError> {
+    let mut c = zero_filled_square_matrix_of_size!(size);
+
+    for i in 0..size {
+        let a_i = MatrixRowPtr(a[i].as_ptr());
+        let b_i = MatrixRowPtr(b[i].as_ptr());
+        let mut c_i = MatrixRowPtr(c[i].as_mut_ptr());
+
+        pool.execute(move || {
+            for j in 0..size {
+                unsafe {
+                    *c_i.add_mut(j) = op.apply(*a_i.add(j), *b_i.add(j));
+                }
+            }
+        });
+    }
+
+    pool.wait();
+
+    Ok(c)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn get_a() -> Matrix<i32> {
+        Matrix::from(vec![vec![1, 2], vec![3, 4]])
+    }
+
+    fn get_b() -> Matrix<i32> {
+        Matrix::from(vec![vec![5, 6], vec![7, 8]])
+    }
+
+    fn expected(op: ElementwiseOp) -> Matrix<i32> {
+        Matrix::from(match op {
+            ElementwiseOp::Add => vec![vec![6, 8], vec![10, 12]],
+            ElementwiseOp::Scale(_) => vec![vec![3, 6], vec![9, 12]],
+            ElementwiseOp::Hadamard => vec![vec![5, 12], vec![21, 32]],
+        })
+    }
+
+    const OPS: [ElementwiseOp; 3] = [
+        ElementwiseOp::Add,
+        ElementwiseOp::Scale(3),
+        ElementwiseOp::Hadamard,
+    ];
+
+    #[test]
+    fn test_elementwise_sequential() {
+        for op in OPS {
+            let c =
+                elementwise(&get_a(), &get_b(), ElementwiseKernel::Sequential(op), None).unwrap();
+            assert_eq!(c, expected(op));
+        }
+    }
+
+    #[test]
+    fn test_elementwise_parallel() {
+        for op in OPS {
+            let c =
+                elementwise(&get_a(), &get_b(), ElementwiseKernel::Parallel(op, 2), None).unwrap();
+            assert_eq!(c, expected(op));
+        }
+    }
+
+    #[test]
+    fn test_elementwise_sanitizes_inputs() {
+        let c = elementwise(
+            &get_a(),
+            &Matrix::default(),
+            ElementwiseKernel::Sequential(ElementwiseOp::Add),
+            None,
+        );
+        assert_eq!(c, Err(SanitizeError::EmptyMatrix("B".to_string())));
+    }
+}
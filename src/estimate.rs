@@ -0,0 +1,146 @@
+use crate::matrix_multiplication::types::Shape;
+
+/// Largest dimension of the matrices multiplied to calibrate the estimates of `--dry-run`
+pub const CALIBRATION_SIZE: usize = 128;
+
+/// Returns the shapes of the calibration multiplications, the shape scaled down so that its
+/// largest dimension is half of `CALIBRATION_SIZE` and then `CALIBRATION_SIZE`, in this order,
+/// or the shape itself if it is not larger.
+/// The dimensions are rounded up to a multiple of `multiple`, the size the kernel works on
+/// multiples of, e.g. its tile size, the larger calibration staying twice the smaller one.
+pub fn calibration_shapes(shape: Shape, multiple: usize) -> Vec<Shape> {
+    let largest = shape.m.max(shape.k).max(shape.n);
+    let small = (CALIBRATION_SIZE / 2).next_multiple_of(multiple);
+    if largest <= 2 * small {
+        return vec![shape];
+    }
+    let scale = |dimension: usize, target: usize| {
+        (dimension * target / largest)
+            .max(1)
+            .next_multiple_of(multiple)
+    };
+    [small, 2 * small]
+        .into_iter()
+        .map(|target| Shape {
+            m: scale(shape.m, target),
+            k: scale(shape.k, target),
+            n: scale(shape.n, target),
+        })
+        .collect()
+}
+
+/// Extrapolates to `x` the power law `y = c·x^p` through the two points measured at `small` and
+/// `large`, e.g. the number of jobs of a kernel, constant for a fixed grid of blocks, linear in
+/// the size with a job per row and quadratic with a job per tile.
+/// A single point, or a quantity that is zero at the smaller point, is extrapolated as constant.
+pub fn extrapolate(small: (f64, f64), large: (f64, f64), x: f64) -> f64 {
+    if small.0 == large.0 || small.1 <= 0.0 || large.1 <= 0.0 {
+        return large.1;
+    }
+    let exponent = (large.1 / small.1).ln() / (large.0 / small.0).ln();
+    large.1 * (x / large.0).powf(exponent)
+}
+
+/// Returns the size in bytes of every matrix held while a kernel runs: the inputs A and B, with
+/// the padding of their rows, the result C and, when the results are checked, the reference
+/// product of `reference_size` bytes per element
+pub fn footprint(
+    shape: Shape,
+    element_size: usize,
+    pad: usize,
+    reference_size: Option<usize>,
+) -> Vec<(String, u64)> {
+    let bytes = |rows: usize, cols: usize, size: usize| (rows * cols * size) as u64;
+    let mut matrices = vec![
+        (
+            format!("A ({}x{})", shape.m, shape.k),
+            bytes(shape.m, shape.k + pad, element_size),
+        ),
+        (
+            format!("B ({}x{})", shape.k, shape.n),
+            bytes(shape.k, shape.n + pad, element_size),
+        ),
+        (
+            format!("C ({}x{})", shape.m, shape.n),
+            bytes(shape.m, shape.n, element_size),
+        ),
+    ];
+    if let Some(size) = reference_size {
+        matrices.push((
+            format!("Reference ({}x{})", shape.m, shape.n),
+            bytes(shape.m, shape.n, size),
+        ));
+    }
+    matrices
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_calibration_shapes() {
+        assert_eq!(
+            calibration_shapes(Shape::square(96), 1),
+            [Shape::square(96)]
+        );
+        assert_eq!(
+            calibration_shapes(Shape::square(4096), 1),
+            [Shape::square(64), Shape::square(128)]
+        );
+        let shape = Shape {
+            m: 4096,
+            k: 1024,
+            n: 1,
+        };
+        assert_eq!(
+            calibration_shapes(shape, 1)[1],
+            Shape {
+                m: 128,
+                k: 32,
+                n: 1
+            }
+        );
+        // a tile size of 48 or a grid of 3 x 3 blocks
+        assert_eq!(
+            calibration_shapes(Shape::square(4800), 48),
+            [Shape::square(96), Shape::square(192)]
+        );
+        assert_eq!(
+            calibration_shapes(Shape::square(4800), 3),
+            [Shape::square(66), Shape::square(132)]
+        );
+        assert_eq!(
+            calibration_shapes(Shape::square(1024), 512),
+            [Shape::square(1024)]
+        );
+    }
+
+    #[test]
+    fn test_extrapolate() {
+        let close = |value: f64, expected: f64| (value - expected).abs() < 1e-6 * expected;
+        // a job per tile of 32x32 elements
+        assert!(close(
+            extrapolate((64.0, 4.0), (128.0, 16.0), 4096.0),
+            16384.0
+        ));
+        // a job per row
+        assert!(close(
+            extrapolate((64.0, 64.0), (128.0, 128.0), 4096.0),
+            4096.0
+        ));
+        // a fixed grid of blocks
+        assert_eq!(extrapolate((64.0, 4.0), (128.0, 4.0), 4096.0), 4.0);
+        assert_eq!(extrapolate((96.0, 9.0), (96.0, 9.0), 96.0), 9.0);
+    }
+
+    #[test]
+    fn test_footprint() {
+        let matrices = footprint(Shape::square(1024), 4, 0, Some(4));
+        assert_eq!(matrices.len(), 4);
+        assert!(matrices.iter().all(|(_, bytes)| *bytes == 4 << 20));
+        let matrices = footprint(Shape::square(1024), 8, 16, None);
+        assert_eq!(matrices[0], ("A (1024x1024)".to_string(), 1024 * 1040 * 8));
+        assert_eq!(matrices[2].1, 8 << 20);
+    }
+}
@@ -0,0 +1,249 @@
+use std::time::{SystemTime, UNIX_EPOCH};
+
+use cli_table::Cell;
+use log::{debug, error, info};
+use rand::{rngs::StdRng, Rng, SeedableRng};
+
+use crate::{
+    cli::FuzzVerify,
+    cli_tables::{print_args_table, print_title},
+    matrix_multiplication::{
+        algorithms::{Algorithm, TileScheduling, UNROLL_FACTORS},
+        generate::{generate_random_square_matrix_with_rng, MAX_ABS_VALUE_DEFAULT},
+        matrix_multiplication,
+    },
+};
+
+/// Largest number of threads a fuzz case can request
+const MAX_FUZZ_THREADS: usize = 8;
+/// Largest side of the thread grid a fuzz case can request
+const MAX_FUZZ_GRID_SIDE: usize = 4;
+/// Largest number of tiles per job a fuzz case can request
+const MAX_FUZZ_TILES_PER_JOB: usize = 4;
+
+/// A randomly generated configuration to verify
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+struct FuzzCase {
+    /// Seed the input matrices are generated from
+    seed: u64,
+    size: usize,
+    threads: usize,
+    tile_size: usize,
+    /// Inner tile size of the two-level tiling algorithm, a divisor of the tile size
+    inner_tile_size: usize,
+    tiles_per_job: usize,
+    unroll: usize,
+    grid_size: usize,
+    strassen_cutoff: usize,
+}
+
+impl FuzzCase {
+    /// Draws a random case with matrices of size up to `max_size`.
+    /// The tile and grid sizes are always divisors of the matrix size, and the inner tile size a
+    /// divisor of the tile size.
+    fn random<R: Rng>(rng: &mut R, max_size: usize) -> FuzzCase {
+        let size = rng.gen_range(1..=max_size);
+        let divisors = (1..=size).filter(|d| size % d == 0).collect::<Vec<_>>();
+        let grid_sizes = divisors
+            .iter()
+            .copied()
+            .take_while(|d| *d <= MAX_FUZZ_GRID_SIDE)
+            .collect::<Vec<_>>();
+        let tile_size = divisors[rng.gen_range(0..divisors.len())];
+        let inner_tile_sizes = (1..=tile_size)
+            .filter(|d| tile_size % d == 0)
+            .collect::<Vec<_>>();
+        FuzzCase {
+            seed: rng.gen(),
+            size,
+            threads: rng.gen_range(1..=MAX_FUZZ_THREADS),
+            tile_size,
+            inner_tile_size: inner_tile_sizes[rng.gen_range(0..inner_tile_sizes.len())],
+            tiles_per_job: rng.gen_range(1..=MAX_FUZZ_TILES_PER_JOB),
+            unroll: UNROLL_FACTORS[rng.gen_range(0..UNROLL_FACTORS.len())],
+            grid_size: grid_sizes[rng.gen_range(0..grid_sizes.len())],
+            strassen_cutoff: rng.gen_range(1..=size),
+        }
+    }
+
+    /// Returns every algorithm to compare against the sequential reference
+    fn algorithms(&self) -> Vec<Algorithm> {
+        #[allow(unused_mut)]
+        let mut algorithms = vec![
+            Algorithm::SequentialIjk,
+            Algorithm::SequentialJik,
+            Algorithm::SequentialJki,
+            Algorithm::SequentialKij,
+            Algorithm::SequentialKji,
+            Algorithm::SequentialTransposeB,
+            Algorithm::SequentialAvx2,
+            Algorithm::SequentialAvx512,
+            Algorithm::SequentialNeon,
+            Algorithm::SequentialPacked,
+            Algorithm::SequentialTiling(self.tile_size),
+            Algorithm::SequentialMorton(self.strassen_cutoff),
+            Algorithm::ParallelILoop(self.threads),
+            Algorithm::ParallelJLoop(self.threads),
+            Algorithm::ParallelPrivateBuffers(self.threads),
+            Algorithm::ParallelRowChunks(self.threads, self.tiles_per_job),
+            Algorithm::ParallelRayon(self.threads),
+            Algorithm::ParallelTiling(
+                self.threads,
+                self.tile_size,
+                self.tiles_per_job,
+                TileScheduling::Static,
+            ),
+            Algorithm::ParallelTiling(
+                self.threads,
+                self.tile_size,
+                self.tiles_per_job,
+                TileScheduling::Dynamic,
+            ),
+            Algorithm::ParallelTilingUnrolled(self.threads, self.tile_size, self.unroll),
+            Algorithm::ParallelTilingPrefetch(self.threads, self.tile_size),
+            Algorithm::ParallelTilingTwoLevel(self.threads, self.tile_size, self.inner_tile_size),
+            Algorithm::ParallelBlocks(self.threads),
+            Algorithm::ParallelCannon(self.grid_size),
+            Algorithm::ParallelSumma(self.grid_size),
+            Algorithm::Strassen(self.strassen_cutoff),
+            Algorithm::StrassenWinograd(self.strassen_cutoff),
+            Algorithm::ParallelStrassen(self.threads, self.strassen_cutoff),
+            Algorithm::StrassenTiled(self.threads, self.strassen_cutoff, self.tile_size),
+        ];
+        #[cfg(feature = "matrixmultiply")]
+        algorithms.push(Algorithm::SequentialMatrixmultiply);
+        #[cfg(feature = "ndarray")]
+        algorithms.push(Algorithm::SequentialNdarray);
+        #[cfg(feature = "faer")]
+        algorithms.push(Algorithm::SequentialFaer);
+        #[cfg(feature = "blas")]
+        algorithms.push(Algorithm::SequentialBlas);
+        #[cfg(feature = "opencl")]
+        algorithms.push(Algorithm::OpenCl);
+        algorithms
+    }
+}
+
+impl std::fmt::Display for FuzzCase {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(
+            f,
+            "size {}, seed {}, {} threads, {}/{} tile sizes, {} tiles per job, unrolled {}x, {}x{} grid, {} Strassen cutoff",
+            self.size,
+            self.seed,
+            self.threads,
+            self.tile_size,
+            self.inner_tile_size,
+            self.tiles_per_job,
+            self.unroll,
+            self.grid_size,
+            self.grid_size,
+            self.strassen_cutoff
+        )
+    }
+}
+
+/// Runs every algorithm of `case` and returns the first one whose output diverges from the
+/// sequential reference, together with a description of the divergence.
+fn verify_case(case: &FuzzCase) -> Option<(Algorithm, String)> {
+    let mut rng = StdRng::seed_from_u64(case.seed);
+    let a = generate_random_square_matrix_with_rng(case.size, MAX_ABS_VALUE_DEFAULT, &mut rng);
+    let b = generate_random_square_matrix_with_rng(case.size, MAX_ABS_VALUE_DEFAULT, &mut rng);
+
+    let reference = matrix_multiplication(&a, &b, Algorithm::SequentialIkj, None)
+        .expect("fuzz cases generate valid square matrices");
+
+    for algorithm in case.algorithms() {
+        let c = match matrix_multiplication(&a, &b, algorithm, None) {
+            Ok(c) => c,
+            Err(err) => return Some((algorithm, err.to_string())),
+        };
+        let mismatch = reference
+            .iter_rows()
+            .flatten()
+            .zip(c.iter_rows().flatten())
+            .position(|(expected, actual)| expected != actual);
+        if let Some(index) = mismatch {
+            let (i, j) = (index / case.size, index % case.size);
+            return Some((
+                algorithm,
+                format!(
+                    "C[{}][{}] is {}, expected {}",
+                    i, j, c[i][j], reference[i][j]
+                ),
+            ));
+        }
+    }
+
+    None
+}
+
+/// Subprogram verifying that every algorithm agrees with the sequential reference on randomly
+/// generated configurations. Returns `false` if a divergence was found.
+pub fn fuzz_verify(cli: &FuzzVerify) -> bool {
+    let seed = cli.seed.unwrap_or_else(|| {
+        SystemTime::now()
+            .duration_since(UNIX_EPOCH)
+            .map(|time| time.as_nanos() as u64)
+            .unwrap_or_default()
+    });
+
+    print_title("Welcome to Fuzz Verify!");
+
+    let table = vec![
+        vec!["Number of cases".cell(), cli.cases.to_string().cell()],
+        vec!["Max matrix size".cell(), cli.max_size.to_string().cell()],
+        vec!["Seed".cell(), seed.to_string().cell()],
+    ];
+    print_args_table(table);
+
+    let mut rng = StdRng::seed_from_u64(seed);
+    for i in 0..cli.cases {
+        let case = FuzzCase::random(&mut rng, cli.max_size);
+        debug!("Case {}/{}: {}", i + 1, cli.cases, case);
+
+        if let Some((algorithm, divergence)) = verify_case(&case) {
+            error!(
+                "Case {}/{} diverged in {}: {}",
+                i + 1,
+                cli.cases,
+                algorithm,
+                divergence
+            );
+            error!("Failing configuration: {}", case);
+            return false;
+        }
+    }
+
+    info!(
+        "All {} cases agree with the sequential reference",
+        cli.cases
+    );
+    true
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_random_case_tile_divides_size() {
+        let mut rng = StdRng::seed_from_u64(0);
+        for _ in 0..100 {
+            let case = FuzzCase::random(&mut rng, 32);
+            assert!((1..=32).contains(&case.size));
+            assert_eq!(case.size % case.tile_size, 0);
+            assert_eq!(case.size % case.grid_size, 0);
+            assert!((1..=MAX_FUZZ_THREADS).contains(&case.threads));
+        }
+    }
+
+    #[test]
+    fn test_verify_case() {
+        let mut rng = StdRng::seed_from_u64(1);
+        for _ in 0..5 {
+            let case = FuzzCase::random(&mut rng, 16);
+            assert_eq!(verify_case(&case), None);
+        }
+    }
+}
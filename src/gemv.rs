@@ -0,0 +1,165 @@
+use crate::{
+    matrix_multiplication::{
+        in_pool,
+        matrix::Matrix,
+        sanitize::{sanitize_matrices, SanitizeError},
+        types::{MatrixRowPtr, SquareMatrixPtr},
+    },
+    thread_pool::ThreadPool,
+};
+
+/// Enum representing available matrix-vector multiplication (GEMV) kernels, computing `y = A * x`
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum GemvKernel {
+    /// Sequential kernel computing the dot product of every row of A with x
+    Sequential,
+    /// Parallel kernel splitting the rows of A into one stripe per thread
+    ///
+    /// # Arguments
+    ///
+    /// * `usize` - number of threads to use
+    Parallel(usize),
+}
+
+impl std::fmt::Display for GemvKernel {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            GemvKernel::Sequential => write!(f, "Sequential GEMV"),
+            GemvKernel::Parallel(threads) => write!(f, "Parallel GEMV ({} threads)", threads),
+        }
+    }
+}
+
+impl GemvKernel {
+    /// Returns the number of threads used by the kernel, or `None` if it is sequential
+    pub fn threads(&self) -> Option<usize> {
+        match self {
+            GemvKernel::Sequential => None,
+            GemvKernel::Parallel(threads) => Some(*threads),
+        }
+    }
+}
+
+/// Multiplies the square matrix `a` by the vector `x` with the given kernel.
+/// The parallel kernel runs on `pool`, see `in_pool`.
+pub fn gemv(
+    a: &Matrix<i32>,
+    x: &[i32],
+    kernel: GemvKernel,
+    pool: Option<&ThreadPool>,
+) -> Result<Vec<i32>, SanitizeError> {
+    sanitize_matrices(a, a)?;
+    if x.len() != a.rows() {
+        return Err(SanitizeError::NotSameSize);
+    }
+
+    match kernel {
+        GemvKernel::Sequential => Ok(gemv_sequential(a, x)),
+        GemvKernel::Parallel(threads) => {
+            in_pool(pool, threads, |pool| Ok(gemv_parallel(a, x, threads, pool)))
+        }
+    }
+}
+
+fn dot(a_i: &[i32], x: &[i32]) -> i32 {
+    a_i.iter().zip(x).map(|(a_ij, x_j)| a_ij * x_j).sum()
+}
+
+fn gemv_sequential(a: &Matrix<i32>, x: &[i32]) -> Vec<i32> {
+    a.iter_rows().map(|a_i| dot(a_i, x)).collect()
+}
+
+fn gemv_parallel(a: &Matrix<i32>, x: &[i32], threads: usize, pool: &ThreadPool) -> Vec<i32> {
+    let size = a.rows();
+    let threads = threads.max(1);
+    let mut y = vec![0; size];
+
+    let a = SquareMatrixPtr::new(a);
+    let x = MatrixRowPtr(x.as_ptr());
+    for n in 0..threads {
+        let rows = n * size / threads..(n + 1) * size / threads;
+        let a = a.clone();
+        let mut y = MatrixRowPtr(y.as_mut_ptr());
+
+        pool.execute(move || {
+            // SAFETY: the inputs outlive the jobs, which are waited for below, and every job
+            // writes to its own stripe of y
+            for i in rows {
+                let a_i = a.get_row(i);
+                let mut y_i = 0;
+                for j in 0..size {
+                    unsafe {
+                        y_i += *a_i.add(j) * *x.add(j);
+                    }
+                }
+                unsafe {
+                    *y.add_mut(i) = y_i;
+                }
+            }
+        });
+    }
+
+    pool.wait();
+
+    y
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::matrix_multiplication::generate::{
+        generate_random_square_matrix_with_rng, seeded_rng, MAX_ABS_VALUE_DEFAULT,
+    };
+
+    fn get_a(size: usize) -> Matrix<i32> {
+        generate_random_square_matrix_with_rng(
+            size,
+            MAX_ABS_VALUE_DEFAULT,
+            &mut seeded_rng(Some(7)),
+        )
+    }
+
+    fn get_x(size: usize) -> Vec<i32> {
+        (0..size).map(|j| j as i32 % 5 - 2).collect()
+    }
+
+    #[test]
+    fn test_gemv_sequential() {
+        let a = Matrix::from_rows(vec![vec![1, 2], vec![-3, 4]], "A").unwrap();
+
+        assert_eq!(
+            gemv(&a, &[5, -1], GemvKernel::Sequential, None).unwrap(),
+            vec![3, -19]
+        );
+    }
+
+    #[test]
+    fn test_gemv_parallel_uneven_stripes() {
+        // 7 rows among 3 threads leave stripes of 2, 2 and 3 rows
+        let (a, x) = (get_a(7), get_x(7));
+
+        assert_eq!(
+            gemv(&a, &x, GemvKernel::Parallel(3), None).unwrap(),
+            gemv_sequential(&a, &x)
+        );
+    }
+
+    #[test]
+    fn test_gemv_more_threads_than_rows() {
+        // the stripes of 6 of the 8 threads are empty
+        let (a, x) = (get_a(2), get_x(2));
+
+        assert_eq!(
+            gemv(&a, &x, GemvKernel::Parallel(8), None).unwrap(),
+            gemv_sequential(&a, &x)
+        );
+    }
+
+    #[test]
+    fn test_gemv_size_mismatch() {
+        assert_eq!(
+            gemv(&get_a(3), &[1, 2], GemvKernel::Sequential, None),
+            Err(SanitizeError::NotSameSize)
+        );
+    }
+}
@@ -0,0 +1,129 @@
+use std::{
+    env,
+    io::{self, Write},
+    process::{Command, Stdio},
+    sync::atomic::{AtomicUsize, Ordering},
+    thread,
+};
+
+use crate::{benchmark::Timing, matrix_multiplication::matrix::Matrix};
+
+/// Environment variable selecting the run a child process of `--isolate` times, as `SUITE:NAME`
+const CHILD_RUN: &str = "MATRIX_MULTIPLICATION_ISOLATED_RUN";
+
+/// Inputs sent to a child process: the matrices `A` and `B` and the reference product, if the
+/// results are checked
+type ChildInputs = (Matrix<i32>, Matrix<i32>, Option<Matrix<i32>>);
+
+/// Number of benchmark suites started so far by this process, which identifies a suite among the
+/// ones of the subprograms running several, e.g. one per size of `sweep`
+static SUITES: AtomicUsize = AtomicUsize::new(0);
+
+/// Run a child process is spawned to time: the algorithm `algorithm` of the suite `suite`
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ChildRun {
+    pub suite: usize,
+    pub algorithm: String,
+}
+
+impl ChildRun {
+    /// Parses the value of the environment variable of the child processes
+    fn parse(value: &str) -> Option<ChildRun> {
+        let (suite, algorithm) = value.split_once(':')?;
+        Some(ChildRun {
+            suite: suite.parse().ok()?,
+            algorithm: algorithm.to_string(),
+        })
+    }
+}
+
+/// Returns the index of the benchmark suite starting, counting from 0
+pub fn next_suite() -> usize {
+    SUITES.fetch_add(1, Ordering::Relaxed)
+}
+
+/// Returns the run to time if this process is a child spawned by `--isolate`
+pub fn child_run() -> Option<ChildRun> {
+    env::var(CHILD_RUN)
+        .ok()
+        .and_then(|value| ChildRun::parse(&value))
+}
+
+/// Times a run in a freshly spawned child process, so that the allocator, the caches and the
+/// lazily initialized state left by the previous runs cannot affect it.
+/// The child is this program run again with the same arguments: it replays the subprogram up to
+/// the suite `run.suite`, reads the inputs from its standard input, times `run.algorithm` and
+/// writes the timing as JSON on the last line of its standard output, `null` if the run failed.
+/// Its logs are limited to the errors, which are printed along the ones of the parent.
+pub fn time_in_child(
+    run: &ChildRun,
+    a: &Matrix<i32>,
+    b: &Matrix<i32>,
+    reference: Option<&Matrix<i32>>,
+) -> Result<Option<Timing>, String> {
+    let program = env::current_exe().map_err(|err| format!("Cannot find the program: {}", err))?;
+    let mut child = Command::new(program)
+        .args(env::args_os().skip(1))
+        .env(CHILD_RUN, format!("{}:{}", run.suite, run.algorithm))
+        .env("RUST_LOG", "error")
+        .stdin(Stdio::piped())
+        .stdout(Stdio::piped())
+        .stderr(Stdio::inherit())
+        .spawn()
+        .map_err(|err| format!("Cannot spawn the child process: {}", err))?;
+
+    // the inputs are written while the output of the child is read, which neither blocks the
+    // other when they outgrow the buffers of the pipes; the pipe is closed once they are written
+    let stdin = child.stdin.take().expect("the standard input is piped");
+    let (written, output) = thread::scope(|scope| {
+        let writer =
+            scope.spawn(|| serde_json::to_writer(io::BufWriter::new(stdin), &(a, b, reference)));
+        let output = child.wait_with_output();
+        (writer.join().expect("the writer does not panic"), output)
+    });
+    let output = output.map_err(|err| format!("Cannot wait for the child process: {}", err))?;
+    if !output.status.success() {
+        return Err(format!("The child process failed: {}", output.status));
+    }
+    written.map_err(|err| format!("Cannot send the inputs to the child process: {}", err))?;
+
+    let stdout = String::from_utf8_lossy(&output.stdout);
+    let timing = stdout.lines().last().unwrap_or_default();
+    serde_json::from_str(timing)
+        .map_err(|err| format!("Cannot read the timing of the child process: {}", err))
+}
+
+/// Reads the inputs sent by the parent process
+pub fn read_inputs() -> Result<ChildInputs, String> {
+    serde_json::from_reader(io::BufReader::new(io::stdin().lock()))
+        .map_err(|err| format!("Cannot read the inputs sent by the parent process: {}", err))
+}
+
+/// Writes the timing of the run for the parent process, `None` if it failed, on a line of its own
+/// after the tables printed before, which can leave styling codes on their last line
+pub fn write_timing(timing: Option<Timing>) -> Result<(), String> {
+    let mut stdout = io::stdout().lock();
+    writeln!(stdout)
+        .and_then(|_| serde_json::to_writer(&mut stdout, &timing).map_err(io::Error::from))
+        .and_then(|_| writeln!(stdout))
+        .and_then(|_| stdout.flush())
+        .map_err(|err| format!("Cannot send the timing to the parent process: {}", err))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_child_run() {
+        assert_eq!(
+            ChildRun::parse("2:Parallel tiling (4: 32)"),
+            Some(ChildRun {
+                suite: 2,
+                algorithm: "Parallel tiling (4: 32)".to_string()
+            })
+        );
+        assert_eq!(ChildRun::parse("Sequential ikj"), None);
+        assert_eq!(ChildRun::parse("a:Sequential ikj"), None);
+    }
+}
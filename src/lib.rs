@@ -0,0 +1,51 @@
+//! Benchmark of matrix multiplication algorithms, the library behind the `matrix_multiplication`
+//! binary. Other crates can run the benchmark suite programmatically with [`BenchmarkRunner`]
+//! instead of running the binary.
+
+// links the BLAS backend selected through the features of blas-src
+#[cfg(feature = "blas")]
+extern crate blas_src;
+
+mod accumulate;
+mod affinity;
+mod autotune;
+pub mod benchmark;
+pub mod cli;
+mod cli_tables;
+pub mod clock;
+pub mod compare;
+#[cfg(feature = "criterion")]
+pub mod criterion_bench;
+mod elementwise;
+mod estimate;
+pub mod fuzz;
+mod gemv;
+#[cfg(feature = "isolate")]
+mod isolate;
+pub mod matrix_multiplication;
+#[cfg(feature = "numa")]
+mod numa;
+pub mod order;
+mod output;
+#[cfg(feature = "perf")]
+mod perf;
+mod quantized;
+pub mod runner;
+pub mod selftest;
+mod sparse;
+mod stats;
+pub mod stress;
+mod symmetric;
+mod system;
+pub mod thread_pool;
+mod triangular;
+mod tsc;
+
+pub use benchmark::{Benchmarkable, InputDistribution, Timing};
+pub use matrix_multiplication::{
+    algorithms::Algorithm,
+    matrix::Matrix,
+    registry::{MatMulAlgorithm, Registry},
+    types::Shape,
+};
+pub use runner::BenchmarkRunner;
@@ -1,22 +1,29 @@
 extern crate core;
 
-use std::thread;
+use std::{process, thread};
 
-use benchmark::{matrix_multiplication_benchmark, tiling_benchmark};
 use clap::Parser;
-
-use crate::cli::Cli;
-
-mod benchmark;
-mod cli;
-mod cli_tables;
-mod matrix_multiplication;
-mod thread_pool;
+use matrix_multiplication::{
+    benchmark::{
+        accumulate_benchmark, autotune_benchmark, elementwise_benchmark, gemv_benchmark,
+        matrix_multiplication_benchmark, quantized_benchmark, scaling_benchmark, set_up,
+        sparse_benchmark, sweep_benchmark, symmetric_benchmark, tiling_benchmark,
+        triangular_benchmark,
+    },
+    cli::{self, Cli},
+    compare::compare,
+    fuzz::fuzz_verify,
+    selftest::selftest,
+    stress::stress_test,
+};
 
 fn main() {
     env_logger::init_from_env(env_logger::Env::default().default_filter_or("info"));
 
     let cli = Cli::parse();
+    if let Some(run) = cli.run_args() {
+        set_up(run);
+    }
 
     match &cli.subcommands {
         Some(cli::Commands::OsThreads) => {
@@ -26,7 +33,61 @@ fn main() {
             );
         }
         Some(cli::Commands::Tiling(args)) => {
-            tiling_benchmark(&args);
+            tiling_benchmark(args);
+        }
+        Some(cli::Commands::Elementwise(args)) => {
+            elementwise_benchmark(args);
+        }
+        Some(cli::Commands::Gemv(args)) => {
+            gemv_benchmark(args);
+        }
+        Some(cli::Commands::Quantized(args)) => {
+            quantized_benchmark(args);
+        }
+        Some(cli::Commands::Sparse(args)) => {
+            sparse_benchmark(args);
+        }
+        Some(cli::Commands::Triangular(args)) => {
+            triangular_benchmark(args);
+        }
+        Some(cli::Commands::Symmetric(args)) => {
+            symmetric_benchmark(args);
+        }
+        Some(cli::Commands::Gemm(args)) => {
+            accumulate_benchmark(args);
+        }
+        Some(cli::Commands::Sweep(args)) => {
+            sweep_benchmark(args);
+        }
+        Some(cli::Commands::Scaling(args)) => {
+            scaling_benchmark(args);
+        }
+        Some(cli::Commands::Stress(args)) => {
+            if !stress_test(args) {
+                process::exit(1);
+            }
+        }
+        Some(cli::Commands::FuzzVerify(args)) => {
+            if !fuzz_verify(args) {
+                process::exit(1);
+            }
+        }
+        Some(cli::Commands::SelfTest(args)) => {
+            if !selftest(args) {
+                process::exit(1);
+            }
+        }
+        Some(cli::Commands::Autotune(args)) => {
+            autotune_benchmark(args);
+        }
+        Some(cli::Commands::Compare(args)) => {
+            if !compare(args) {
+                process::exit(1);
+            }
+        }
+        #[cfg(feature = "criterion")]
+        Some(cli::Commands::Criterion(args)) => {
+            matrix_multiplication::criterion_bench::criterion_benchmark(args);
         }
         None => {
             matrix_multiplication_benchmark(&cli);
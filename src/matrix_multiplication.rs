@@ -1,64 +1,333 @@
-use std::mem;
+use std::{
+    collections::HashMap,
+    mem,
+    sync::{
+        atomic::{AtomicUsize, Ordering},
+        Arc, Mutex, OnceLock,
+    },
+};
+
+use rayon::prelude::*;
 
 use thread_pool::ThreadPool;
 use types::MatrixRowPtr;
 
-use crate::{thread_pool, zero_filled_square_matrix_of_size};
+use crate::{affinity, thread_pool};
 
 use self::{
-    algorithms::Algorithm,
-    sanitize::{extra_sanitization_steps_for_tiling_algorithm, sanitize_matrices, SanitizeError},
-    types::SquareMatrixPtr,
+    algorithms::{block_grid, Algorithm, TileScheduling},
+    element::Element,
+    matrix::Matrix,
+    sanitize::{
+        extra_sanitization_steps_for_grid_algorithm, extra_sanitization_steps_for_morton_algorithm,
+        extra_sanitization_steps_for_row_chunks_algorithm,
+        extra_sanitization_steps_for_strassen_algorithm,
+        extra_sanitization_steps_for_tiling_algorithm,
+        extra_sanitization_steps_for_two_level_tiling_algorithm,
+        extra_sanitization_steps_for_unrolled_algorithm, sanitize_matrices,
+        sanitize_rectangular_matrices, SanitizeError,
+    },
+    types::{Shape, SquareMatrixPtr},
+    view::{MatrixView, MatrixViewMut},
 };
 
 pub mod algorithms;
+pub mod checked;
+mod complex;
+pub mod element;
+pub mod fixed;
+#[cfg(any(
+    feature = "matrixmultiply",
+    feature = "ndarray",
+    feature = "faer",
+    feature = "blas"
+))]
+mod gemm;
 pub mod generate;
-mod sanitize;
-mod types;
+pub mod matrix;
+mod mixed;
+mod morton;
+#[cfg(feature = "opencl")]
+mod opencl;
+mod packed;
+pub mod persist;
+pub mod registry;
+pub(crate) mod sanitize;
+pub mod saturating;
+pub mod simd;
+mod strassen;
+pub(crate) mod types;
+pub mod view;
 
-pub fn matrix_multiplication(
-    a: &Vec<Vec<i32>>,
-    b: &Vec<Vec<i32>>,
+/// Multiplies `a` and `b`, whose elements can be of any `Element` type, with the given algorithm.
+/// The algorithms tied to an element type return `SanitizeError::UnsupportedElementType` on the
+/// other ones.
+/// Parallel algorithms submit their jobs to `pool` if one is given, so that the cost of spawning
+/// the threads can be kept out of the measurements; otherwise a pool is spawned for the occasion.
+pub fn matrix_multiplication<T: Element>(
+    a: &Matrix<T>,
+    b: &Matrix<T>,
     algorithm: Algorithm,
-) -> Result<Vec<Vec<i32>>, SanitizeError> {
-    sanitize_matrices(a, b)?;
+    pool: Option<&ThreadPool>,
+) -> Result<Matrix<T>, SanitizeError> {
+    if !algorithm.supports_element_type(T::ELEMENT_TYPE) {
+        return Err(SanitizeError::UnsupportedElementType(T::ELEMENT_TYPE));
+    }
+    // the algorithms limited to square matrices keep reporting why the inputs are not square
+    let shape = match algorithm.supports_rectangular() {
+        true => sanitize_rectangular_matrices(a, b)?,
+        false => {
+            sanitize_matrices(a, b)?;
+            Shape::square(a.rows())
+        }
+    };
     match algorithm {
-        Algorithm::ParallelTiling(_, tile_size) => {
-            extra_sanitization_steps_for_tiling_algorithm(a.len(), tile_size)?
+        Algorithm::SequentialTiling(tile_size) => {
+            extra_sanitization_steps_for_tiling_algorithm(a.rows(), tile_size, 1)?
+        }
+        Algorithm::SequentialMorton(leaf_size) => {
+            extra_sanitization_steps_for_morton_algorithm(leaf_size)?
+        }
+        Algorithm::ParallelTiling(_, tile_size, tiles_per_job, _) => {
+            extra_sanitization_steps_for_tiling_algorithm(a.rows(), tile_size, tiles_per_job)?
+        }
+        Algorithm::ParallelTilingUnrolled(_, tile_size, unroll) => {
+            extra_sanitization_steps_for_tiling_algorithm(a.rows(), tile_size, 1)?;
+            extra_sanitization_steps_for_unrolled_algorithm(unroll)?
+        }
+        Algorithm::ParallelTilingPrefetch(_, tile_size) => {
+            extra_sanitization_steps_for_tiling_algorithm(a.rows(), tile_size, 1)?
+        }
+        Algorithm::ParallelTilingTwoLevel(_, outer_tile_size, inner_tile_size) => {
+            extra_sanitization_steps_for_two_level_tiling_algorithm(
+                a.rows(),
+                outer_tile_size,
+                inner_tile_size,
+            )?
+        }
+        Algorithm::ParallelRowChunks(_, chunk_size) => {
+            extra_sanitization_steps_for_row_chunks_algorithm(chunk_size)?
+        }
+        Algorithm::ParallelCannon(grid) | Algorithm::ParallelSumma(grid) => {
+            extra_sanitization_steps_for_grid_algorithm(a.rows(), grid)?
+        }
+        Algorithm::Strassen(cutoff)
+        | Algorithm::StrassenWinograd(cutoff)
+        | Algorithm::ParallelStrassen(_, cutoff) => {
+            extra_sanitization_steps_for_strassen_algorithm(cutoff)?
+        }
+        Algorithm::StrassenTiled(_, cutoff, tile_size) => {
+            extra_sanitization_steps_for_strassen_algorithm(cutoff)?;
+            // the blocks below the cutoff are padded to a multiple of the tile size
+            extra_sanitization_steps_for_tiling_algorithm(tile_size, tile_size, 1)?
         }
         _ => (),
-    };
+    }
 
-    let size = a.len();
+    let size = a.rows();
+    // the tiled kernels read A and B with their own strides, and lay out C with the one of A
+    let c_stride = a.stride();
 
     match algorithm {
-        Algorithm::SequentialIjk => matrix_multiplication_sequential_ijk(a, b, size),
-        Algorithm::SequentialIkj => matrix_multiplication_sequential_ikj(a, b, size),
-        Algorithm::ParallelILoop(threads) => {
-            matrix_multiplication_parallel_i_loop(a, b, size, threads)
+        Algorithm::SequentialIjk => matrix_multiplication_sequential_ijk(a, b, shape),
+        Algorithm::SequentialIkj => matrix_multiplication_sequential_ikj(a, b, shape),
+        Algorithm::SequentialJik => matrix_multiplication_sequential_jik(a, b, shape),
+        Algorithm::SequentialJki => matrix_multiplication_sequential_jki(a, b, shape),
+        Algorithm::SequentialKij => matrix_multiplication_sequential_kij(a, b, shape),
+        Algorithm::SequentialKji => matrix_multiplication_sequential_kji(a, b, shape),
+        Algorithm::SequentialTransposeB => {
+            matrix_multiplication_sequential_transpose_b(a, b, shape)
+        }
+        Algorithm::SequentialPacked => Ok(packed::multiply_packed(a, b, size)),
+        Algorithm::SequentialMorton(leaf_size) => {
+            Ok(morton::multiply_morton(a, b, size, leaf_size))
+        }
+        Algorithm::SequentialTiling(tile_size) => {
+            let res = matrix_multiplication_sequential_tiling(a, b, size, c_stride, tile_size);
+            Ok(Matrix::from_vec_strided(size, size, c_stride, res))
+        }
+        Algorithm::ParallelILoop(threads) => in_pool(pool, threads, |pool| {
+            matrix_multiplication_parallel_i_loop(a, b, shape, pool)
+        }),
+        Algorithm::ParallelJLoop(threads) => in_pool(pool, threads, |pool| {
+            matrix_multiplication_parallel_j_loop(a, b, shape, pool)
+        }),
+        Algorithm::ParallelRowChunks(threads, chunk_size) => in_pool(pool, threads, |pool| {
+            matrix_multiplication_parallel_row_chunks(a, b, shape, chunk_size, threads, pool)
+        }),
+        Algorithm::ParallelPrivateBuffers(threads) => {
+            let res = in_pool(pool, threads, |pool| {
+                matrix_multiplication_parallel_private_buffers(a, b, shape, threads, pool)
+            });
+            Ok(Matrix::from_vec(shape.m, shape.n, res))
         }
-        Algorithm::ParallelTiling(threads, tile_size) => {
-            let res = matrix_multiplication_parallel_tiling(a, b, size, tile_size, threads)?;
-            let c: Vec<Vec<i32>> = res.chunks(size).map(|row| row.to_vec()).collect();
-            Ok(c)
+        Algorithm::ParallelRayon(threads) => {
+            Ok(matrix_multiplication_parallel_rayon(a, b, shape, threads))
+        }
+        Algorithm::ParallelTiling(threads, tile_size, tiles_per_job, scheduling) => {
+            let res = in_pool(pool, threads, |pool| {
+                matrix_multiplication_parallel_tiling(
+                    a.view(),
+                    b.view(),
+                    size,
+                    c_stride,
+                    tile_size,
+                    tiles_per_job,
+                    scheduling,
+                    threads,
+                    pool,
+                )
+            });
+            Ok(Matrix::from_vec_strided(size, size, c_stride, res))
+        }
+        Algorithm::ParallelTilingUnrolled(threads, tile_size, unroll) => {
+            let res = in_pool(pool, threads, |pool| {
+                matrix_multiplication_parallel_tiling_unrolled(
+                    a, b, size, c_stride, tile_size, unroll, pool,
+                )
+            });
+            Ok(Matrix::from_vec_strided(size, size, c_stride, res))
+        }
+        Algorithm::ParallelTilingPrefetch(threads, tile_size) => {
+            let res = in_pool(pool, threads, |pool| {
+                matrix_multiplication_parallel_tiling_prefetch(
+                    a, b, size, c_stride, tile_size, pool,
+                )
+            });
+            Ok(Matrix::from_vec_strided(size, size, c_stride, res))
         }
+        Algorithm::ParallelTilingTwoLevel(threads, outer_tile_size, inner_tile_size) => {
+            let res = in_pool(pool, threads, |pool| {
+                matrix_multiplication_parallel_tiling_two_level(
+                    a,
+                    b,
+                    size,
+                    c_stride,
+                    outer_tile_size,
+                    inner_tile_size,
+                    pool,
+                )
+            });
+            Ok(Matrix::from_vec_strided(size, size, c_stride, res))
+        }
+        Algorithm::ParallelBlocks(threads) => {
+            let res = in_pool(pool, threads, |pool| {
+                matrix_multiplication_parallel_blocks(
+                    a,
+                    b,
+                    size,
+                    c_stride,
+                    block_grid(threads),
+                    pool,
+                )
+            });
+            Ok(Matrix::from_vec_strided(size, size, c_stride, res))
+        }
+        Algorithm::ParallelCannon(grid) => {
+            let res = in_pool(pool, grid * grid, |pool| {
+                matrix_multiplication_parallel_cannon(a, b, size, c_stride, grid, pool)
+            });
+            Ok(Matrix::from_vec_strided(size, size, c_stride, res))
+        }
+        Algorithm::ParallelSumma(grid) => {
+            let res = in_pool(pool, grid * grid, |pool| {
+                matrix_multiplication_parallel_summa(a, b, size, c_stride, grid, pool)
+            });
+            Ok(Matrix::from_vec_strided(size, size, c_stride, res))
+        }
+        Algorithm::Strassen(cutoff) => Ok(strassen::strassen(a, b, size, cutoff)),
+        Algorithm::StrassenWinograd(cutoff) => Ok(strassen::strassen_winograd(a, b, size, cutoff)),
+        Algorithm::ParallelStrassen(threads, cutoff) => Ok(in_pool(pool, threads, |pool| {
+            strassen::strassen_parallel(a, b, size, cutoff, pool)
+        })),
+        Algorithm::StrassenTiled(threads, cutoff, tile_size) => {
+            Ok(in_pool(pool, threads, |pool| {
+                strassen::strassen_tiled(a, b, size, cutoff, tile_size, pool)
+            }))
+        }
+        _ => T::multiply_specific(a, b, shape, algorithm),
     }
 }
 
-fn matrix_multiplication_sequential_ijk(
-    a: &Vec<Vec<i32>>,
-    b: &Vec<Vec<i32>>,
-    size: usize,
-) -> Result<Vec<Vec<i32>>, SanitizeError> {
-    let mut c = zero_filled_square_matrix_of_size!(size);
+/// Initializes the state `algorithm` keeps across its runs, the rayon pool of the rayon algorithm
+/// and the device of the OpenCL one, so that it is not part of the measurements of the first run.
+/// Other algorithms keep no state.
+pub fn initialize(algorithm: Algorithm) {
+    match algorithm {
+        Algorithm::ParallelRayon(threads) => {
+            rayon_pool(threads);
+        }
+        // a device that cannot be set up is reported by the multiplication, which tries again
+        #[cfg(feature = "opencl")]
+        Algorithm::OpenCl => {
+            let _ = opencl::initialize();
+        }
+        _ => (),
+    }
+}
+
+/// Computes `C = alpha * A * B + beta * C`, accumulating the product of `a` and `b`, computed with
+/// the given algorithm, into `c`. As in BLAS, `c` is not read when `beta` is zero.
+/// Returns `SanitizeError::OutputShapeMismatch` if `c` does not have the shape of the product.
+pub fn multiply_into<T: Element>(
+    alpha: T,
+    a: &Matrix<T>,
+    b: &Matrix<T>,
+    beta: T,
+    c: &mut Matrix<T>,
+    algorithm: Algorithm,
+    pool: Option<&ThreadPool>,
+) -> Result<(), SanitizeError> {
+    if (c.rows(), c.cols()) != (a.rows(), b.cols()) {
+        return Err(SanitizeError::OutputShapeMismatch);
+    }
+    let product = matrix_multiplication(a, b, algorithm, pool)?;
+
+    for (c_i, p_i) in c.iter_rows_mut().zip(product.iter_rows()) {
+        for (c_ij, p_ij) in c_i.iter_mut().zip(p_i) {
+            *c_ij = match beta.is_zero() {
+                true => alpha * *p_ij,
+                false => alpha * *p_ij + beta * *c_ij,
+            };
+        }
+    }
+
+    Ok(())
+}
+
+/// Runs `f` on `pool`, or on a pool of `threads` threads spawned for the occasion if `pool` is
+/// `None`. The parallel kernels of every subprogram go through it, so that they submit their jobs
+/// to the pool the benchmark spawned outside of the measurements when there is one.
+pub(crate) fn in_pool<R>(
+    pool: Option<&ThreadPool>,
+    threads: usize,
+    f: impl FnOnce(&ThreadPool) -> R,
+) -> R {
+    match pool {
+        Some(pool) => f(pool),
+        None => {
+            let pool = ThreadPool::new(threads);
+            let res = f(&pool);
+            ThreadPool::terminate(pool);
+            res
+        }
+    }
+}
+
+fn matrix_multiplication_sequential_ijk<T: Element>(
+    a: &Matrix<T>,
+    b: &Matrix<T>,
+    shape: Shape,
+) -> Result<Matrix<T>, SanitizeError> {
+    let mut c = Matrix::zeros(shape.m, shape.n);
 
     let b = SquareMatrixPtr::new(b);
 
-    for i in 0..size {
+    for i in 0..shape.m {
         let a_i = MatrixRowPtr(a[i].as_ptr());
         let mut c_i = MatrixRowPtr(c[i].as_mut_ptr());
-        for j in 0..size {
-            for k in 0..size {
+        for j in 0..shape.n {
+            for k in 0..shape.k {
                 let b_k = b.get_row(k);
                 unsafe {
                     *c_i.add_mut(j) += *a_i.add(k) * *b_k.add(j);
@@ -70,21 +339,80 @@ fn matrix_multiplication_sequential_ijk(
     Ok(c)
 }
 
-fn matrix_multiplication_sequential_ikj(
-    a: &Vec<Vec<i32>>,
-    b: &Vec<Vec<i32>>,
-    size: usize,
-) -> Result<Vec<Vec<i32>>, SanitizeError> {
-    let mut c = zero_filled_square_matrix_of_size!(size);
+fn matrix_multiplication_sequential_ikj<T: Element>(
+    a: &Matrix<T>,
+    b: &Matrix<T>,
+    shape: Shape,
+) -> Result<Matrix<T>, SanitizeError> {
+    let mut c = Matrix::zeros(shape.m, shape.n);
 
     let b = SquareMatrixPtr::new(b);
 
-    for i in 0..size {
+    for i in 0..shape.m {
         let a_i = MatrixRowPtr(a[i].as_ptr());
         let mut c_i = MatrixRowPtr(c[i].as_mut_ptr());
-        for k in 0..size {
+        for k in 0..shape.k {
+            let b_k = b.get_row(k);
+            for j in 0..shape.n {
+                unsafe {
+                    *c_i.add_mut(j) += *a_i.add(k) * *b_k.add(j);
+                }
+            }
+        }
+    }
+
+    Ok(c)
+}
+
+/// Returns mutable pointers to the rows of `c`, for the loop orders that do not walk C row by row
+fn row_ptrs_mut<T>(c: &mut Matrix<T>) -> Vec<MatrixRowPtr<*mut T>> {
+    c.iter_rows_mut()
+        .map(|row| MatrixRowPtr(row.as_mut_ptr()))
+        .collect()
+}
+
+fn matrix_multiplication_sequential_jik<T: Element>(
+    a: &Matrix<T>,
+    b: &Matrix<T>,
+    shape: Shape,
+) -> Result<Matrix<T>, SanitizeError> {
+    let mut c = Matrix::zeros(shape.m, shape.n);
+
+    let a = SquareMatrixPtr::new(a);
+    let b = SquareMatrixPtr::new(b);
+    let mut c_rows = row_ptrs_mut(&mut c);
+
+    for j in 0..shape.n {
+        for (i, c_i) in c_rows.iter_mut().enumerate() {
+            let a_i = a.get_row(i);
+            for k in 0..shape.k {
+                let b_k = b.get_row(k);
+                unsafe {
+                    *c_i.add_mut(j) += *a_i.add(k) * *b_k.add(j);
+                }
+            }
+        }
+    }
+
+    Ok(c)
+}
+
+fn matrix_multiplication_sequential_jki<T: Element>(
+    a: &Matrix<T>,
+    b: &Matrix<T>,
+    shape: Shape,
+) -> Result<Matrix<T>, SanitizeError> {
+    let mut c = Matrix::zeros(shape.m, shape.n);
+
+    let a = SquareMatrixPtr::new(a);
+    let b = SquareMatrixPtr::new(b);
+    let mut c_rows = row_ptrs_mut(&mut c);
+
+    for j in 0..shape.n {
+        for k in 0..shape.k {
             let b_k = b.get_row(k);
-            for j in 0..size {
+            for (i, c_i) in c_rows.iter_mut().enumerate() {
+                let a_i = a.get_row(i);
                 unsafe {
                     *c_i.add_mut(j) += *a_i.add(k) * *b_k.add(j);
                 }
@@ -95,26 +423,131 @@ fn matrix_multiplication_sequential_ikj(
     Ok(c)
 }
 
-fn matrix_multiplication_parallel_i_loop(
-    a: &Vec<Vec<i32>>,
-    b: &Vec<Vec<i32>>,
+fn matrix_multiplication_sequential_kij<T: Element>(
+    a: &Matrix<T>,
+    b: &Matrix<T>,
+    shape: Shape,
+) -> Result<Matrix<T>, SanitizeError> {
+    let mut c = Matrix::zeros(shape.m, shape.n);
+
+    let a = SquareMatrixPtr::new(a);
+    let b = SquareMatrixPtr::new(b);
+    let mut c_rows = row_ptrs_mut(&mut c);
+
+    for k in 0..shape.k {
+        let b_k = b.get_row(k);
+        for (i, c_i) in c_rows.iter_mut().enumerate() {
+            let a_i = a.get_row(i);
+            for j in 0..shape.n {
+                unsafe {
+                    *c_i.add_mut(j) += *a_i.add(k) * *b_k.add(j);
+                }
+            }
+        }
+    }
+
+    Ok(c)
+}
+
+fn matrix_multiplication_sequential_kji<T: Element>(
+    a: &Matrix<T>,
+    b: &Matrix<T>,
+    shape: Shape,
+) -> Result<Matrix<T>, SanitizeError> {
+    let mut c = Matrix::zeros(shape.m, shape.n);
+
+    let a = SquareMatrixPtr::new(a);
+    let b = SquareMatrixPtr::new(b);
+    let mut c_rows = row_ptrs_mut(&mut c);
+
+    for k in 0..shape.k {
+        let b_k = b.get_row(k);
+        for j in 0..shape.n {
+            for (i, c_i) in c_rows.iter_mut().enumerate() {
+                let a_i = a.get_row(i);
+                unsafe {
+                    *c_i.add_mut(j) += *a_i.add(k) * *b_k.add(j);
+                }
+            }
+        }
+    }
+
+    Ok(c)
+}
+
+fn matrix_multiplication_sequential_transpose_b<T: Element>(
+    a: &Matrix<T>,
+    b: &Matrix<T>,
+    shape: Shape,
+) -> Result<Matrix<T>, SanitizeError> {
+    let mut c = Matrix::zeros(shape.m, shape.n);
+
+    // B^T flattened, so that row j of the scratch buffer is column j of B
+    let mut b_t: Vec<T> = vec![T::zero(); shape.n * shape.k];
+    for (k, b_k) in b.iter_rows().enumerate() {
+        for (j, b_kj) in b_k.iter().enumerate() {
+            b_t[j * shape.k + k] = *b_kj;
+        }
+    }
+    let b_t = MatrixRowPtr(b_t.as_ptr());
+
+    for i in 0..shape.m {
+        let a_i = MatrixRowPtr(a[i].as_ptr());
+        let mut c_i = MatrixRowPtr(c[i].as_mut_ptr());
+        for j in 0..shape.n {
+            let mut sum = T::zero();
+            for k in 0..shape.k {
+                unsafe {
+                    sum += *a_i.add(k) * *b_t.add(j * shape.k + k);
+                }
+            }
+            unsafe {
+                *c_i.add_mut(j) = sum;
+            }
+        }
+    }
+
+    Ok(c)
+}
+
+/// Tiling kernel, giving C rows `c_stride` elements apart
+fn matrix_multiplication_sequential_tiling<T: Element>(
+    a: &Matrix<T>,
+    b: &Matrix<T>,
     size: usize,
-    preferred_number_of_threads: usize,
-) -> Result<Vec<Vec<i32>>, SanitizeError> {
-    let mut c = zero_filled_square_matrix_of_size!(size);
+    c_stride: usize,
+    tile_size: usize,
+) -> Vec<T> {
+    let mut c: Vec<T> = vec![T::zero(); size * c_stride];
+    let mut c_view = MatrixViewMut::from_slice(&mut c, size, size, c_stride);
 
-    let pool = ThreadPool::new(preferred_number_of_threads);
+    for l in (0..size).step_by(tile_size) {
+        for w in (0..size).step_by(tile_size) {
+            multiply_tile(&a.view(), &b.view(), &mut c_view, tile_size, l, w);
+        }
+    }
+
+    c
+}
 
-    for i in 0..size {
+fn matrix_multiplication_parallel_i_loop<T: Element>(
+    a: &Matrix<T>,
+    b: &Matrix<T>,
+    shape: Shape,
+    pool: &ThreadPool,
+) -> Result<Matrix<T>, SanitizeError> {
+    let mut c = Matrix::zeros(shape.m, shape.n);
+
+    for i in 0..shape.m {
         let a_i = MatrixRowPtr(a[i].as_ptr());
         let mut c_i = MatrixRowPtr(c[i].as_mut_ptr());
         let b = SquareMatrixPtr::new(b);
 
         unsafe {
             pool.execute(move || {
-                for k in 0..size {
+                for k in 0..shape.k {
                     let b_k = b.get_row(k);
-                    for j in 0..size {
+                    for j in 0..shape.n {
                         *c_i.add_mut(j) += *a_i.add(k) * *b_k.add(j);
                     }
                 }
@@ -122,47 +555,458 @@ fn matrix_multiplication_parallel_i_loop(
         }
     }
 
-    ThreadPool::terminate(pool);
+    pool.wait();
+
+    Ok(c)
+}
+
+fn matrix_multiplication_parallel_j_loop<T: Element>(
+    a: &Matrix<T>,
+    b: &Matrix<T>,
+    shape: Shape,
+    pool: &ThreadPool,
+) -> Result<Matrix<T>, SanitizeError> {
+    let mut c = Matrix::zeros(shape.m, shape.n);
+    let c_rows = row_ptrs_mut(&mut c);
+
+    for j in 0..shape.n {
+        let a = SquareMatrixPtr::new(a);
+        let b = SquareMatrixPtr::new(b);
+        let mut c_rows = c_rows.clone();
+
+        unsafe {
+            pool.execute(move || {
+                for k in 0..shape.k {
+                    let b_kj = *b.get_row(k).add(j);
+                    for (i, c_i) in c_rows.iter_mut().enumerate() {
+                        *c_i.add_mut(j) += *a.get_row(i).add(k) * b_kj;
+                    }
+                }
+            });
+        }
+    }
+
+    pool.wait();
 
     Ok(c)
 }
 
-fn matrix_multiplication_parallel_tiling(
-    a: &Vec<Vec<i32>>,
-    b: &Vec<Vec<i32>>,
+/// Submits one job per thread, each repeatedly claiming the next `chunk_size` rows of C from a
+/// shared counter and computing them with the ikj loop order, until every row is claimed.
+fn matrix_multiplication_parallel_row_chunks<T: Element>(
+    a: &Matrix<T>,
+    b: &Matrix<T>,
+    shape: Shape,
+    chunk_size: usize,
+    threads: usize,
+    pool: &ThreadPool,
+) -> Result<Matrix<T>, SanitizeError> {
+    let mut c = Matrix::zeros(shape.m, shape.n);
+    let c_rows = row_ptrs_mut(&mut c);
+    let next_row = Arc::new(AtomicUsize::new(0));
+
+    for _ in 0..threads {
+        let a = SquareMatrixPtr::new(a);
+        let b = SquareMatrixPtr::new(b);
+        let mut c_rows = c_rows.clone();
+        let next_row = Arc::clone(&next_row);
+
+        pool.execute(move || loop {
+            let start = next_row.fetch_add(chunk_size, Ordering::Relaxed);
+            if start >= shape.m {
+                break;
+            }
+            let end = (start + chunk_size).min(shape.m);
+            for (i, c_i) in c_rows.iter_mut().enumerate().take(end).skip(start) {
+                let a_i = a.get_row(i);
+                for k in 0..shape.k {
+                    let b_k = b.get_row(k);
+                    for j in 0..shape.n {
+                        unsafe {
+                            *c_i.add_mut(j) += *a_i.add(k) * *b_k.add(j);
+                        }
+                    }
+                }
+            }
+        });
+    }
+
+    pool.wait();
+
+    Ok(c)
+}
+
+/// Splits C into `stripes` stripes of contiguous rows, one job each. Every job accumulates its
+/// stripe into a buffer allocated by the worker, and copies it into C only once complete, so that
+/// the writes of different threads to adjacent rows never share a cache line while computing.
+fn matrix_multiplication_parallel_private_buffers<T: Element>(
+    a: &Matrix<T>,
+    b: &Matrix<T>,
+    shape: Shape,
+    stripes: usize,
+    pool: &ThreadPool,
+) -> Vec<T> {
+    let Shape { m, k: inner, n } = shape;
+    let mut c: Vec<T> = vec![T::zero(); m * n];
+
+    let (a_stride, b_stride) = (a.stride(), b.stride());
+    let a_ptr = MatrixRowPtr(a.as_slice().as_ptr());
+    let b_ptr = MatrixRowPtr(b.as_slice().as_ptr());
+    let c_ptr = MatrixRowPtr(c.as_mut_ptr());
+
+    for stripe in 0..stripes {
+        let rows = stripe * m / stripes..(stripe + 1) * m / stripes;
+        pool.execute(move || {
+            // SAFETY: the matrices outlive the jobs, which are waited for below, and every job
+            // writes to its own stripe of C
+            let a = unsafe { MatrixView::from_raw_parts(a_ptr.get(), m, inner, a_stride) };
+            let b = unsafe { MatrixView::from_raw_parts(b_ptr.get(), inner, n, b_stride) };
+            let mut c = unsafe { MatrixViewMut::from_raw_parts(c_ptr.get(), m, n, n) };
+
+            let mut local = Matrix::zeros(rows.len(), n);
+            let a_stripe = a.block(rows.start, 0, rows.len(), inner);
+            multiply_add(&a_stripe, &b, &mut local.view_mut());
+            c.block_mut(rows.start, 0, rows.len(), n)
+                .copy_from(local.view());
+        });
+    }
+
+    // a, b and c must outlive the jobs
+    pool.wait();
+
+    c
+}
+
+/// Returns a rayon pool of `threads` threads. The pools are kept alive for the whole run and spawned
+/// by [`initialize`] before the first one is timed, so that, as with `ThreadPool`, spawning the
+/// threads is not part of the measurements.
+fn rayon_pool(threads: usize) -> Arc<rayon::ThreadPool> {
+    static POOLS: OnceLock<Mutex<HashMap<usize, Arc<rayon::ThreadPool>>>> = OnceLock::new();
+
+    let mut pools = POOLS.get_or_init(Default::default).lock().unwrap();
+    let pool = pools.entry(threads).or_insert_with(|| {
+        Arc::new(
+            rayon::ThreadPoolBuilder::new()
+                .num_threads(threads)
+                .start_handler(affinity::pin_worker)
+                .build()
+                .unwrap(),
+        )
+    });
+    Arc::clone(pool)
+}
+
+fn matrix_multiplication_parallel_rayon<T: Element>(
+    a: &Matrix<T>,
+    b: &Matrix<T>,
+    shape: Shape,
+    threads: usize,
+) -> Matrix<T> {
+    let mut c = Matrix::zeros(shape.m, shape.n);
+
+    rayon_pool(threads).install(|| {
+        c.as_mut_slice()
+            .par_chunks_exact_mut(shape.n)
+            .enumerate()
+            .for_each(|(i, c_i)| {
+                for (a_ik, b_k) in a[i].iter().zip(b.iter_rows()) {
+                    for (c_ij, b_kj) in c_i.iter_mut().zip(b_k) {
+                        *c_ij += *a_ik * *b_kj;
+                    }
+                }
+            })
+    });
+
+    c
+}
+
+/// Parallel tiling kernel on `size` x `size` matrices, giving C rows `c_stride` elements apart.
+/// With static scheduling the tiles are split among `threads` jobs, whatever the number of workers
+/// of the pool, e.g. a single one with `--serialize-parallel`.
+#[allow(clippy::too_many_arguments)]
+fn matrix_multiplication_parallel_tiling<T: Element>(
+    a: MatrixView<T>,
+    b: MatrixView<T>,
     size: usize,
+    c_stride: usize,
     tile_size: usize,
+    tiles_per_job: usize,
+    scheduling: TileScheduling,
     threads: usize,
-) -> Result<Vec<i32>, SanitizeError> {
-    let out_vec_len = size * size;
-    let mut c: Vec<i32> = vec![0; out_vec_len];
+    pool: &ThreadPool,
+) -> Vec<T> {
+    let mut c: Vec<T> = vec![T::zero(); size * c_stride];
+
+    let (a_stride, b_stride) = (a.stride(), b.stride());
+    let a_ptr = MatrixRowPtr(a.as_ptr());
+    let b_ptr = MatrixRowPtr(b.as_ptr());
+    let c_ptr = MatrixRowPtr(c.as_mut_ptr());
+
+    // (row, column) origin of every tile of C, grouped into jobs of `tiles_per_job` tiles
+    let tiles: Vec<(usize, usize)> = (0..size)
+        .step_by(tile_size)
+        .flat_map(|l| (0..size).step_by(tile_size).map(move |w| (l, w)))
+        .collect();
+
+    let groups: Arc<Vec<Vec<(usize, usize)>>> = Arc::new(
+        tiles
+            .chunks(tiles_per_job)
+            .map(|group| group.to_vec())
+            .collect(),
+    );
+
+    // every job claims its id w in turn and computes the groups w, w + J, w + 2J, ... of the J
+    // jobs: with static scheduling there is a job per worker, which computes its own partition
+    // with no group left to be balanced; with dynamic scheduling every group is a job of its own,
+    // pulled by the first idle worker from the shared queue of the pool
+    let jobs = match scheduling {
+        TileScheduling::Static => threads,
+        TileScheduling::Dynamic => groups.len(),
+    };
+    let next_id = Arc::new(AtomicUsize::new(0));
+
+    for _ in 0..jobs {
+        let groups = Arc::clone(&groups);
+        let next_id = Arc::clone(&next_id);
+        pool.execute(move || {
+            // SAFETY: the matrices outlive the jobs, which are waited for below, and every tile of
+            // C is written by a single job
+            let a = unsafe { MatrixView::from_raw_parts(a_ptr.get(), size, size, a_stride) };
+            let b = unsafe { MatrixView::from_raw_parts(b_ptr.get(), size, size, b_stride) };
+            let mut c = unsafe { MatrixViewMut::from_raw_parts(c_ptr.get(), size, size, c_stride) };
+            let id = next_id.fetch_add(1, Ordering::Relaxed);
+            for (l, w) in groups.iter().skip(id).step_by(jobs).flatten() {
+                multiply_tile(&a, &b, &mut c, tile_size, *l, *w);
+            }
+        });
+    }
+
+    pool.wait();
+
+    c
+}
+
+/// Computes the `tile_size` x `tile_size` tile of C whose origin is at row `l` and column `w`
+fn multiply_tile<T: Element>(
+    a: &MatrixView<T>,
+    b: &MatrixView<T>,
+    c: &mut MatrixViewMut<T>,
+    tile_size: usize,
+    l: usize,
+    w: usize,
+) {
+    let mut c_tile = c.block_mut(l, w, tile_size, tile_size);
+    for kh in (0..a.cols()).step_by(tile_size) {
+        multiply_add(
+            &a.block(l, kh, tile_size, tile_size),
+            &b.block(kh, w, tile_size, tile_size),
+            &mut c_tile,
+        );
+    }
+}
 
-    let a: Vec<i32> = a.into_iter().flatten().map(|x| *x).collect::<Vec<_>>();
-    let b: Vec<i32> = b.into_iter().flatten().map(|x| *x).collect::<Vec<_>>();
+/// Adds the product of `a` and `b` to `c`, with the ikj loop order
+#[inline(always)]
+fn multiply_add<T: Element>(a: &MatrixView<T>, b: &MatrixView<T>, c: &mut MatrixViewMut<T>) {
+    for (i, a_i) in a.iter_rows().enumerate() {
+        let c_i = c.row_mut(i);
+        for (a_ik, b_k) in a_i.iter().zip(b.iter_rows()) {
+            for (c_ij, b_kj) in c_i.iter_mut().zip(b_k) {
+                *c_ij += *a_ik * *b_kj;
+            }
+        }
+    }
+}
 
-    let a = MatrixRowPtr(a.as_ptr());
-    let b = MatrixRowPtr(b.as_ptr());
+/// Parallel tiling algorithm whose innermost j loop is unrolled `unroll` times, one job per tile
+fn matrix_multiplication_parallel_tiling_unrolled<T: Element>(
+    a: &Matrix<T>,
+    b: &Matrix<T>,
+    size: usize,
+    c_stride: usize,
+    tile_size: usize,
+    unroll: usize,
+    pool: &ThreadPool,
+) -> Vec<T> {
+    let mut c: Vec<T> = vec![T::zero(); size * c_stride];
+
+    let (a_stride, b_stride) = (a.stride(), b.stride());
+    let a_ptr = MatrixRowPtr(a.as_slice().as_ptr());
+    let b_ptr = MatrixRowPtr(b.as_slice().as_ptr());
     let c_ptr = MatrixRowPtr(c.as_mut_ptr());
 
-    mem::forget(c); // forgets c so that it is not dropped, avoiding double free
+    // the unrolling factor is a const generic, so the kernel is picked once outside the jobs
+    let multiply_tile_unrolled = match unroll {
+        1 => multiply_tile_unrolled::<T, 1>,
+        2 => multiply_tile_unrolled::<T, 2>,
+        4 => multiply_tile_unrolled::<T, 4>,
+        8 => multiply_tile_unrolled::<T, 8>,
+        _ => unreachable!("unsupported unrolling factors are rejected by the sanitization"),
+    };
+
+    for l in (0..size).step_by(tile_size) {
+        for w in (0..size).step_by(tile_size) {
+            pool.execute(move || {
+                // SAFETY: the matrices outlive the jobs, which are waited for below, and every
+                // job writes to its own tile of C
+                let a = unsafe { MatrixView::from_raw_parts(a_ptr.get(), size, size, a_stride) };
+                let b = unsafe { MatrixView::from_raw_parts(b_ptr.get(), size, size, b_stride) };
+                let mut c =
+                    unsafe { MatrixViewMut::from_raw_parts(c_ptr.get(), size, size, c_stride) };
+                multiply_tile_unrolled(&a, &b, &mut c, tile_size, l, w)
+            });
+        }
+    }
+
+    pool.wait();
 
-    let pool = ThreadPool::new(threads);
+    c
+}
+
+/// Computes the `tile_size` x `tile_size` tile of C whose origin is at row `l` and column `w`
+/// like [`multiply_tile`], with the innermost j loop unrolled `UNROLL` times. The columns left
+/// over when `tile_size` is not a multiple of `UNROLL` are computed one at a time.
+fn multiply_tile_unrolled<T: Element, const UNROLL: usize>(
+    a: &MatrixView<T>,
+    b: &MatrixView<T>,
+    c: &mut MatrixViewMut<T>,
+    tile_size: usize,
+    l: usize,
+    w: usize,
+) {
+    let unrolled = tile_size - tile_size % UNROLL;
+    let mut c_tile = c.block_mut(l, w, tile_size, tile_size);
+    for kh in (0..a.cols()).step_by(tile_size) {
+        for i in l..l + tile_size {
+            let c_i = c_tile.row_mut(i - l);
+            for k in kh..kh + tile_size {
+                let a_ik = a[i][k];
+                let b_k = &b[k][w..w + tile_size];
+                for (c_j, b_j) in c_i.chunks_exact_mut(UNROLL).zip(b_k.chunks_exact(UNROLL)) {
+                    // constant trip count, so that the body is repeated UNROLL times
+                    for u in 0..UNROLL {
+                        c_j[u] += a_ik * b_j[u];
+                    }
+                }
+                for (c_ij, b_kj) in c_i[unrolled..].iter_mut().zip(&b_k[unrolled..]) {
+                    *c_ij += a_ik * *b_kj;
+                }
+            }
+        }
+    }
+}
+
+/// Parallel tiling algorithm issuing software prefetches for the next tile of B, one job per tile
+fn matrix_multiplication_parallel_tiling_prefetch<T: Element>(
+    a: &Matrix<T>,
+    b: &Matrix<T>,
+    size: usize,
+    c_stride: usize,
+    tile_size: usize,
+    pool: &ThreadPool,
+) -> Vec<T> {
+    let mut c: Vec<T> = vec![T::zero(); size * c_stride];
+
+    let (a_stride, b_stride) = (a.stride(), b.stride());
+    let a_ptr = MatrixRowPtr(a.as_slice().as_ptr());
+    let b_ptr = MatrixRowPtr(b.as_slice().as_ptr());
+    let c_ptr = MatrixRowPtr(c.as_mut_ptr());
 
     for l in (0..size).step_by(tile_size) {
         for w in (0..size).step_by(tile_size) {
             pool.execute(move || {
-                let a = a;
-                let b = b;
-                let mut c_ptr = c_ptr;
-                for kh in (0..size).step_by(tile_size) {
-                    for i in 0..tile_size {
-                        for k in 0..tile_size {
-                            for j in 0..tile_size {
-                                unsafe {
-                                    *c_ptr.add_mut((l + i) * size + w + j) += *a
-                                        .add((l + i) * size + kh + k)
-                                        * *b.add((kh + k) * size + w + j);
-                                }
+                // SAFETY: the matrices outlive the jobs, which are waited for below, and every
+                // job writes to its own tile of C
+                let a = unsafe { MatrixView::from_raw_parts(a_ptr.get(), size, size, a_stride) };
+                let b = unsafe { MatrixView::from_raw_parts(b_ptr.get(), size, size, b_stride) };
+                let mut c =
+                    unsafe { MatrixViewMut::from_raw_parts(c_ptr.get(), size, size, c_stride) };
+                multiply_tile_prefetch(&a, &b, &mut c, tile_size, l, w)
+            });
+        }
+    }
+
+    pool.wait();
+
+    c
+}
+
+/// Size of a cache line in bytes, the granularity of the prefetches
+const CACHE_LINE_BYTES: usize = 64;
+
+/// Computes the `tile_size` x `tile_size` tile of C whose origin is at row `l` and column `w`
+/// like [`multiply_tile`], prefetching one row of the next tile of B for every row of the current
+/// tile of A, so that the whole next tile is in cache when the current one is done.
+fn multiply_tile_prefetch<T: Element>(
+    a: &MatrixView<T>,
+    b: &MatrixView<T>,
+    c: &mut MatrixViewMut<T>,
+    tile_size: usize,
+    l: usize,
+    w: usize,
+) {
+    let size = a.cols();
+    let mut c_tile = c.block_mut(l, w, tile_size, tile_size);
+    for kh in (0..size).step_by(tile_size) {
+        let next_kh = kh + tile_size;
+        for i in 0..tile_size {
+            if next_kh < size {
+                let next_b_row = &b[next_kh + i][w..w + tile_size];
+                for j in (0..tile_size).step_by(CACHE_LINE_BYTES / mem::size_of::<T>()) {
+                    simd::prefetch_read(&next_b_row[j]);
+                }
+            }
+
+            let a_i = a.row(l + i);
+            let c_i = c_tile.row_mut(i);
+            for k in kh..next_kh {
+                let a_ik = a_i[k];
+                for (c_ij, b_kj) in c_i.iter_mut().zip(&b[k][w..w + tile_size]) {
+                    *c_ij += a_ik * *b_kj;
+                }
+            }
+        }
+    }
+}
+
+/// Parallel tiling algorithm with one job per `outer_tile_size` x `outer_tile_size` tile of C,
+/// which is computed as a grid of `inner_tile_size` x `inner_tile_size` tiles, so that the outer
+/// tiles of A and B stay in the L2 cache and the inner ones in the L1 cache.
+fn matrix_multiplication_parallel_tiling_two_level<T: Element>(
+    a: &Matrix<T>,
+    b: &Matrix<T>,
+    size: usize,
+    c_stride: usize,
+    outer_tile_size: usize,
+    inner_tile_size: usize,
+    pool: &ThreadPool,
+) -> Vec<T> {
+    let mut c: Vec<T> = vec![T::zero(); size * c_stride];
+
+    let (a_stride, b_stride) = (a.stride(), b.stride());
+    let a_ptr = MatrixRowPtr(a.as_slice().as_ptr());
+    let b_ptr = MatrixRowPtr(b.as_slice().as_ptr());
+    let c_ptr = MatrixRowPtr(c.as_mut_ptr());
+
+    for l in (0..size).step_by(outer_tile_size) {
+        for w in (0..size).step_by(outer_tile_size) {
+            pool.execute(move || {
+                // SAFETY: the matrices outlive the jobs, which are waited for below, and every
+                // job writes to its own outer tile of C
+                let a = unsafe { MatrixView::from_raw_parts(a_ptr.get(), size, size, a_stride) };
+                let b = unsafe { MatrixView::from_raw_parts(b_ptr.get(), size, size, b_stride) };
+                let mut c =
+                    unsafe { MatrixViewMut::from_raw_parts(c_ptr.get(), size, size, c_stride) };
+                let inner = inner_tile_size;
+                for kh in (0..size).step_by(outer_tile_size) {
+                    for row in (l..l + outer_tile_size).step_by(inner) {
+                        for mid in (kh..kh + outer_tile_size).step_by(inner) {
+                            for col in (w..w + outer_tile_size).step_by(inner) {
+                                multiply_add(
+                                    &a.block(row, mid, inner, inner),
+                                    &b.block(mid, col, inner, inner),
+                                    &mut c.block_mut(row, col, inner, inner),
+                                );
                             }
                         }
                     }
@@ -171,13 +1015,160 @@ fn matrix_multiplication_parallel_tiling(
         }
     }
 
-    ThreadPool::terminate(pool);
+    pool.wait();
+
+    c
+}
 
-    let c: Vec<i32>;
-    unsafe {
-        c = Vec::from_raw_parts(c_ptr.0, out_vec_len, out_vec_len);
+/// Splits C into a `rows` x `columns` grid of rectangular blocks, as even as possible, and
+/// computes each block with the ikj loop order in a single job.
+fn matrix_multiplication_parallel_blocks<T: Element>(
+    a: &Matrix<T>,
+    b: &Matrix<T>,
+    size: usize,
+    c_stride: usize,
+    (rows, columns): (usize, usize),
+    pool: &ThreadPool,
+) -> Vec<T> {
+    let mut c: Vec<T> = vec![T::zero(); size * c_stride];
+
+    let (a_stride, b_stride) = (a.stride(), b.stride());
+    let a_ptr = MatrixRowPtr(a.as_slice().as_ptr());
+    let b_ptr = MatrixRowPtr(b.as_slice().as_ptr());
+    let c_ptr = MatrixRowPtr(c.as_mut_ptr());
+
+    // boundaries of the n-th of `parts` even slices of 0..size
+    let bounds = |n: usize, parts: usize| n * size / parts..(n + 1) * size / parts;
+
+    for block_row in 0..rows {
+        for block_column in 0..columns {
+            let (row_range, column_range) =
+                (bounds(block_row, rows), bounds(block_column, columns));
+            pool.execute(move || {
+                // SAFETY: the matrices outlive the jobs, which are waited for below, and every
+                // job writes to its own block of C
+                let a = unsafe { MatrixView::from_raw_parts(a_ptr.get(), size, size, a_stride) };
+                let b = unsafe { MatrixView::from_raw_parts(b_ptr.get(), size, size, b_stride) };
+                let mut c =
+                    unsafe { MatrixViewMut::from_raw_parts(c_ptr.get(), size, size, c_stride) };
+                let (row, height) = (row_range.start, row_range.len());
+                let (col, width) = (column_range.start, column_range.len());
+                multiply_add(
+                    &a.block(row, 0, height, size),
+                    &b.block(0, col, size, width),
+                    &mut c.block_mut(row, col, height, width),
+                );
+            });
+        }
     }
-    Ok(c)
+
+    // a, b and c must outlive the jobs
+    pool.wait();
+
+    c
+}
+
+/// Cannon's algorithm on a `grid` x `grid` grid of jobs, job (i, j) owning block (i, j) of C.
+/// After the initial skew, job (i, j) multiplies block (i, i + j + s) of A by block (i + j + s, j)
+/// of B at step `s`, the indices taken modulo `grid`. As the blocks live in shared memory, the
+/// shifts of the blocks between the steps become index rotations instead of messages, so no job
+/// ever waits for another and the grid can be larger than the pool.
+fn matrix_multiplication_parallel_cannon<T: Element>(
+    a: &Matrix<T>,
+    b: &Matrix<T>,
+    size: usize,
+    c_stride: usize,
+    grid: usize,
+    pool: &ThreadPool,
+) -> Vec<T> {
+    let block_size = size / grid;
+    let mut c: Vec<T> = vec![T::zero(); size * c_stride];
+
+    let (a_stride, b_stride) = (a.stride(), b.stride());
+    let a_ptr = MatrixRowPtr(a.as_slice().as_ptr());
+    let b_ptr = MatrixRowPtr(b.as_slice().as_ptr());
+    let c_ptr = MatrixRowPtr(c.as_mut_ptr());
+
+    for i in 0..grid {
+        for j in 0..grid {
+            pool.execute(move || {
+                // SAFETY: the matrices outlive the jobs, which are waited for below, and every
+                // job writes to its own block of C
+                let a = unsafe { MatrixView::from_raw_parts(a_ptr.get(), size, size, a_stride) };
+                let b = unsafe { MatrixView::from_raw_parts(b_ptr.get(), size, size, b_stride) };
+                let mut c =
+                    unsafe { MatrixViewMut::from_raw_parts(c_ptr.get(), size, size, c_stride) };
+                let mut c_ij = c.block_mut(i * block_size, j * block_size, block_size, block_size);
+                for s in 0..grid {
+                    let k = (i + j + s) % grid;
+                    multiply_add(
+                        &a.block(i * block_size, k * block_size, block_size, block_size),
+                        &b.block(k * block_size, j * block_size, block_size, block_size),
+                        &mut c_ij,
+                    );
+                }
+            });
+        }
+    }
+
+    // a, b and c must outlive the jobs
+    pool.wait();
+
+    c
+}
+
+/// SUMMA on a `grid` x `grid` grid of jobs, job (i, j) owning block (i, j) of C.
+/// At step `k`, the k-th panel of columns of A and the k-th panel of rows of B are broadcast, that
+/// is copied into contiguous buffers read by every job, and each job adds the product of its
+/// slices of the panels to its block of C. Unlike Cannon's algorithm, the steps are separated by
+/// a barrier, as in the distributed algorithm.
+fn matrix_multiplication_parallel_summa<T: Element>(
+    a: &Matrix<T>,
+    b: &Matrix<T>,
+    size: usize,
+    c_stride: usize,
+    grid: usize,
+    pool: &ThreadPool,
+) -> Vec<T> {
+    let block_size = size / grid;
+    let mut c: Vec<T> = vec![T::zero(); size * c_stride];
+    let c_ptr = MatrixRowPtr(c.as_mut_ptr());
+
+    for k in 0..grid {
+        let panel = k * block_size;
+        // size x block_size panel of A and block_size x size panel of B
+        let a_panel = a.view().block(0, panel, size, block_size).to_matrix();
+        let b_panel = b.view().block(panel, 0, block_size, size).to_matrix();
+
+        let a_ptr = MatrixRowPtr(a_panel.as_slice().as_ptr());
+        let b_ptr = MatrixRowPtr(b_panel.as_slice().as_ptr());
+
+        for i in 0..grid {
+            for j in 0..grid {
+                pool.execute(move || {
+                    // SAFETY: the panels and C outlive the jobs, which are waited for below, and
+                    // every job writes to its own block of C
+                    let a_panel = unsafe {
+                        MatrixView::from_raw_parts(a_ptr.get(), size, block_size, block_size)
+                    };
+                    let b_panel =
+                        unsafe { MatrixView::from_raw_parts(b_ptr.get(), block_size, size, size) };
+                    let mut c =
+                        unsafe { MatrixViewMut::from_raw_parts(c_ptr.get(), size, size, c_stride) };
+                    multiply_add(
+                        &a_panel.block(i * block_size, 0, block_size, block_size),
+                        &b_panel.block(0, j * block_size, block_size, block_size),
+                        &mut c.block_mut(i * block_size, j * block_size, block_size, block_size),
+                    );
+                });
+            }
+        }
+
+        // the panels must outlive the jobs, and the next step starts when every job is done
+        pool.wait();
+    }
+
+    c
 }
 
 #[cfg(test)]
@@ -185,16 +1176,16 @@ mod tests {
     use super::*;
     use std::{num::NonZeroUsize, thread};
 
-    fn get_a() -> Vec<Vec<i32>> {
-        vec![vec![1, 2], vec![3, 4]]
+    fn get_a() -> Matrix<i32> {
+        Matrix::from(vec![vec![1, 2], vec![3, 4]])
     }
 
-    fn get_b() -> Vec<Vec<i32>> {
-        vec![vec![5, 6], vec![7, 8]]
+    fn get_b() -> Matrix<i32> {
+        Matrix::from(vec![vec![5, 6], vec![7, 8]])
     }
 
-    fn get_c() -> Vec<Vec<i32>> {
-        vec![vec![19, 22], vec![43, 50]]
+    fn get_c() -> Matrix<i32> {
+        Matrix::from(vec![vec![19, 22], vec![43, 50]])
     }
 
     #[test]
@@ -202,7 +1193,7 @@ mod tests {
         let a = get_a();
         let b = get_b();
 
-        let c = matrix_multiplication_sequential_ijk(&a, &b, a.len()).unwrap();
+        let c = matrix_multiplication_sequential_ijk(&a, &b, Shape::square(a.rows())).unwrap();
 
         assert_eq!(c, get_c());
     }
@@ -212,11 +1203,126 @@ mod tests {
         let a = get_a();
         let b = get_b();
 
-        let c = matrix_multiplication_sequential_ikj(&a, &b, a.len()).unwrap();
+        let c = matrix_multiplication_sequential_ikj(&a, &b, Shape::square(a.rows())).unwrap();
 
         assert_eq!(c, get_c());
     }
 
+    #[test]
+    fn test_matrix_multiplication_rectangular() {
+        // 3x4 by 4x2
+        let a = Matrix::from(vec![
+            vec![1, 2, 3, 4],
+            vec![5, 6, 7, 8],
+            vec![9, 10, 11, 12],
+        ]);
+        let b = Matrix::from(vec![vec![1, -1], vec![2, 0], vec![0, 3], vec![-2, 1]]);
+        let c = Matrix::from(vec![vec![-3, 12], vec![1, 24], vec![5, 36]]);
+
+        #[allow(unused_mut)]
+        let mut algorithms = vec![
+            Algorithm::SequentialIjk,
+            Algorithm::SequentialIkj,
+            Algorithm::SequentialJik,
+            Algorithm::SequentialJki,
+            Algorithm::SequentialKij,
+            Algorithm::SequentialKji,
+            Algorithm::SequentialTransposeB,
+            Algorithm::ParallelILoop(2),
+            Algorithm::ParallelJLoop(2),
+            Algorithm::ParallelRowChunks(2, 2),
+            Algorithm::ParallelPrivateBuffers(2),
+            Algorithm::ParallelRayon(2),
+        ];
+        #[cfg(feature = "matrixmultiply")]
+        algorithms.push(Algorithm::SequentialMatrixmultiply);
+        #[cfg(feature = "ndarray")]
+        algorithms.push(Algorithm::SequentialNdarray);
+        #[cfg(feature = "faer")]
+        algorithms.push(Algorithm::SequentialFaer);
+        #[cfg(feature = "blas")]
+        algorithms.push(Algorithm::SequentialBlas);
+
+        for algorithm in algorithms {
+            assert!(algorithm.supports_rectangular());
+            assert_eq!(
+                matrix_multiplication(&a, &b, algorithm, None),
+                Ok(c.clone()),
+                "{}",
+                algorithm
+            );
+        }
+
+        // the inner dimensions must match
+        assert_eq!(
+            matrix_multiplication(&b, &a, Algorithm::SequentialIkj, None),
+            Err(SanitizeError::InnerDimensionMismatch)
+        );
+        // the other algorithms keep requiring square matrices
+        assert_eq!(
+            matrix_multiplication(&a, &b, Algorithm::SequentialTiling(2), None),
+            Err(SanitizeError::NotSquareMatrix("A".to_string()))
+        );
+    }
+
+    #[test]
+    fn test_multiply_into() {
+        let (a, b) = (get_a(), get_b());
+
+        // C = 2 * A * B - C = 2 * [[19, 22], [43, 50]] - [[1, 1], [1, 1]]
+        let mut c = Matrix::filled(2, 2, 1);
+        multiply_into(
+            2,
+            &a,
+            &b,
+            -1,
+            &mut c,
+            Algorithm::ParallelTiling(2, 1, 1, TileScheduling::Dynamic),
+            None,
+        )
+        .unwrap();
+        assert_eq!(c, Matrix::from(vec![vec![37, 43], vec![85, 99]]));
+
+        // C is overwritten when beta is zero, even with NaNs
+        let mut c = Matrix::filled(2, 2, f64::NAN);
+        let (a, b) = (a.map(|x| *x as f64), b.map(|x| *x as f64));
+        multiply_into(1.0, &a, &b, 0.0, &mut c, Algorithm::SequentialIkj, None).unwrap();
+        assert_eq!(c, Matrix::from(vec![vec![19.0, 22.0], vec![43.0, 50.0]]));
+
+        let mut c = Matrix::zeros(2, 3);
+        assert_eq!(
+            multiply_into(1.0, &a, &b, 1.0, &mut c, Algorithm::SequentialIkj, None),
+            Err(SanitizeError::OutputShapeMismatch)
+        );
+    }
+
+    #[test]
+    fn test_matrix_multiplication_sequential_kernels() {
+        let a = get_a();
+        let b = get_b();
+
+        for kernel in [
+            matrix_multiplication_sequential_jik,
+            matrix_multiplication_sequential_jki,
+            matrix_multiplication_sequential_kij,
+            matrix_multiplication_sequential_kji,
+            matrix_multiplication_sequential_transpose_b,
+        ] {
+            assert_eq!(kernel(&a, &b, Shape::square(a.rows())).unwrap(), get_c());
+        }
+    }
+
+    #[test]
+    fn test_matrix_multiplication_sequential_tiling() {
+        let a = get_a();
+        let b = get_b();
+
+        for tile_size in [1, 2] {
+            let c = matrix_multiplication_sequential_tiling(&a, &b, a.rows(), a.rows(), tile_size);
+            assert_eq!(c, get_c().as_slice())
+        }
+    }
+
     #[test]
     fn test_matrix_multiplication_parallel_i_loop() {
         let a = get_a();
@@ -226,7 +1332,74 @@ mod tests {
             .unwrap_or(NonZeroUsize::new(1).unwrap())
             .into();
 
-        let c = matrix_multiplication_parallel_i_loop(&a, &b, a.len(), threads).unwrap();
+        let pool = ThreadPool::new(threads);
+        let c =
+            matrix_multiplication_parallel_i_loop(&a, &b, Shape::square(a.rows()), &pool).unwrap();
+        ThreadPool::terminate(pool);
+
+        assert_eq!(c, get_c());
+    }
+
+    #[test]
+    fn test_matrix_multiplication_parallel_j_loop() {
+        let a = get_a();
+        let b = get_b();
+
+        let pool = ThreadPool::new(2);
+        let c =
+            matrix_multiplication_parallel_j_loop(&a, &b, Shape::square(a.rows()), &pool).unwrap();
+        ThreadPool::terminate(pool);
+
+        assert_eq!(c, get_c());
+    }
+
+    #[test]
+    fn test_matrix_multiplication_parallel_row_chunks() {
+        let a = get_a();
+        let b = get_b();
+
+        let pool = ThreadPool::new(2);
+        for chunk_size in [1, 2, 3] {
+            let c = matrix_multiplication_parallel_row_chunks(
+                &a,
+                &b,
+                Shape::square(a.rows()),
+                chunk_size,
+                2,
+                &pool,
+            )
+            .unwrap();
+            assert_eq!(c, get_c(), "{} rows per chunk", chunk_size);
+        }
+        ThreadPool::terminate(pool);
+    }
+
+    #[test]
+    fn test_matrix_multiplication_parallel_private_buffers() {
+        let a = get_a();
+        let b = get_b();
+
+        let pool = ThreadPool::new(2);
+        // more stripes than rows leaves some stripes empty
+        for stripes in [1, 2, 3] {
+            let c = matrix_multiplication_parallel_private_buffers(
+                &a,
+                &b,
+                Shape::square(a.rows()),
+                stripes,
+                &pool,
+            );
+            assert_eq!(c, get_c().as_slice(), "{} stripes", stripes);
+        }
+        ThreadPool::terminate(pool);
+    }
+
+    #[test]
+    fn test_matrix_multiplication_parallel_rayon() {
+        let a = get_a();
+        let b = get_b();
+
+        let c = matrix_multiplication_parallel_rayon(&a, &b, Shape::square(a.rows()), 2);
 
         assert_eq!(c, get_c());
     }
@@ -240,8 +1413,201 @@ mod tests {
             .unwrap_or(NonZeroUsize::new(1).unwrap())
             .into();
 
-        let c = matrix_multiplication_parallel_tiling(&a, &b, a.len(), 1, threads).unwrap();
+        let pool = ThreadPool::new(threads);
+        let c = matrix_multiplication_parallel_tiling(
+            a.view(),
+            b.view(),
+            a.rows(),
+            a.rows(),
+            1,
+            1,
+            TileScheduling::Dynamic,
+            threads,
+            &pool,
+        );
+        ThreadPool::terminate(pool);
 
-        assert_eq!(c, get_c().into_iter().flatten().collect::<Vec<_>>())
+        assert_eq!(c, get_c().as_slice())
+    }
+
+    #[test]
+    fn test_matrix_multiplication_parallel_tiling_tiles_per_job() {
+        let a = get_a();
+        let b = get_b();
+
+        // 4 tiles of size 1, grouped in jobs of 3 so that the last job is partial
+        let pool = ThreadPool::new(2);
+        for scheduling in [TileScheduling::Static, TileScheduling::Dynamic] {
+            let c = matrix_multiplication_parallel_tiling(
+                a.view(),
+                b.view(),
+                2,
+                2,
+                1,
+                3,
+                scheduling,
+                2,
+                &pool,
+            );
+            assert_eq!(c, get_c().as_slice())
+        }
+        ThreadPool::terminate(pool);
+    }
+
+    #[test]
+    fn test_serialized_pool_keeps_the_partition() {
+        let a = get_a();
+        let b = get_b();
+        let shape = Shape::square(a.rows());
+
+        // the work of 3 threads runs on a single worker, as with --serialize-parallel
+        let pool = ThreadPool::new(1);
+        let c = matrix_multiplication_parallel_row_chunks(&a, &b, shape, 1, 3, &pool).unwrap();
+        assert_eq!(c, get_c());
+        assert_eq!(pool.metrics().jobs, 3);
+        let c = matrix_multiplication_parallel_tiling(
+            a.view(),
+            b.view(),
+            2,
+            2,
+            1,
+            1,
+            TileScheduling::Static,
+            3,
+            &pool,
+        );
+        assert_eq!(c, get_c().as_slice());
+        assert_eq!(pool.metrics().jobs, 6);
+        ThreadPool::terminate(pool);
+    }
+
+    #[test]
+    fn test_matrix_multiplication_parallel_tiling_unrolled() {
+        let pool = ThreadPool::new(2);
+        // a tile size of 6 leaves a remainder for every factor but 1 and 2
+        let size = 12;
+        let a = Matrix::from_fn(size, size, |i, j| (i * 3 + j) as i32 % 7 - 3);
+        let b = Matrix::from_fn(size, size, |i, j| (i + j * 5) as i32 % 9 - 4);
+        let expected = matrix_multiplication_sequential_ikj(&a, &b, Shape::square(size)).unwrap();
+        for unroll in algorithms::UNROLL_FACTORS {
+            let c = matrix_multiplication_parallel_tiling_unrolled(
+                &a, &b, size, size, 6, unroll, &pool,
+            );
+            assert_eq!(c, expected.as_slice(), "unroll {}", unroll);
+        }
+        ThreadPool::terminate(pool);
+    }
+
+    #[test]
+    fn test_matrix_multiplication_parallel_tiling_prefetch() {
+        let pool = ThreadPool::new(2);
+        let size = 12;
+        let a = Matrix::from_fn(size, size, |i, j| (i * 3 + j) as i32 % 7 - 3);
+        let b = Matrix::from_fn(size, size, |i, j| (i + j * 5) as i32 % 9 - 4);
+        let expected = matrix_multiplication_sequential_ikj(&a, &b, Shape::square(size)).unwrap();
+        // a single tile has no next tile to prefetch
+        for tile_size in [1, 3, 12] {
+            let c = matrix_multiplication_parallel_tiling_prefetch(
+                &a, &b, size, size, tile_size, &pool,
+            );
+            assert_eq!(c, expected.as_slice(), "tile size {}", tile_size);
+        }
+        ThreadPool::terminate(pool);
+    }
+
+    #[test]
+    fn test_matrix_multiplication_parallel_tiling_two_level() {
+        let pool = ThreadPool::new(2);
+        let size = 12;
+        let a = Matrix::from_fn(size, size, |i, j| (i * 3 + j) as i32 % 7 - 3);
+        let b = Matrix::from_fn(size, size, |i, j| (i + j * 5) as i32 % 9 - 4);
+        let expected = matrix_multiplication_sequential_ikj(&a, &b, Shape::square(size)).unwrap();
+        for (outer, inner) in [(1, 1), (4, 2), (6, 3), (12, 4), (12, 12)] {
+            let c = matrix_multiplication_parallel_tiling_two_level(
+                &a, &b, size, size, outer, inner, &pool,
+            );
+            assert_eq!(c, expected.as_slice(), "tiles {}/{}", outer, inner);
+        }
+        ThreadPool::terminate(pool);
+    }
+
+    #[test]
+    fn test_matrix_multiplication_parallel_blocks() {
+        let a = get_a();
+        let b = get_b();
+
+        let pool = ThreadPool::new(2);
+        // more blocks than rows and columns leaves some blocks empty
+        for grid in [(1, 1), (1, 2), (2, 2), (3, 1)] {
+            let c = matrix_multiplication_parallel_blocks(&a, &b, 2, 2, grid, &pool);
+            assert_eq!(c, get_c().as_slice(), "{:?} blocks", grid);
+        }
+        ThreadPool::terminate(pool);
+    }
+
+    #[test]
+    fn test_matrix_multiplication_parallel_grid_algorithms() {
+        let a = Matrix::from_fn(6, 6, |i, j| (i * 6 + j) as i32);
+        let b = Matrix::from_fn(6, 6, |i, j| (i + j) as i32 % 4 - 2);
+        let expected = matrix_multiplication_sequential_ikj(&a, &b, Shape::square(6)).unwrap();
+
+        // more jobs than workers, as on machines with fewer cores than blocks
+        let pool = ThreadPool::new(2);
+        for grid in [1, 2, 3, 6] {
+            let c = matrix_multiplication_parallel_cannon(&a, &b, 6, 6, grid, &pool);
+            assert_eq!(c, expected.as_slice(), "Cannon, grid {}", grid);
+            let c = matrix_multiplication_parallel_summa(&a, &b, 6, 6, grid, &pool);
+            assert_eq!(c, expected.as_slice(), "SUMMA, grid {}", grid);
+        }
+        ThreadPool::terminate(pool);
+    }
+
+    #[test]
+    fn test_matrix_multiplication_padded_inputs() {
+        let size = 8;
+        let a = Matrix::from_fn(size, size, |i, j| (i * 5 + j * 3) as i32 % 9 - 4);
+        let b = Matrix::from_fn(size, size, |i, j| (i + j * 7) as i32 % 5 - 2);
+        let expected = matrix_multiplication_sequential_ikj(&a, &b, Shape::square(size)).unwrap();
+        // different strides, so that the tiled kernels read A and B with strides of their own
+        let (a, b) = (a.padded(11), b.padded(13));
+
+        for algorithm in [
+            Algorithm::SequentialIkj,
+            Algorithm::SequentialPacked,
+            Algorithm::SequentialMorton(2),
+            Algorithm::SequentialTiling(4),
+            Algorithm::ParallelJLoop(2),
+            Algorithm::ParallelPrivateBuffers(3),
+            Algorithm::ParallelRayon(2),
+            Algorithm::ParallelTiling(2, 4, 2, TileScheduling::Static),
+            Algorithm::ParallelTilingUnrolled(2, 4, 4),
+            Algorithm::ParallelTilingPrefetch(2, 4),
+            Algorithm::ParallelTilingTwoLevel(2, 4, 2),
+            Algorithm::ParallelBlocks(3),
+            Algorithm::ParallelCannon(2),
+            Algorithm::ParallelSumma(2),
+            Algorithm::Strassen(2),
+            Algorithm::StrassenTiled(2, 2, 4),
+            Algorithm::SequentialAvx2,
+        ] {
+            let c = matrix_multiplication(&a, &b, algorithm, None).unwrap();
+            assert_eq!(c, expected, "{}", algorithm);
+        }
+    }
+
+    #[test]
+    fn test_matrix_multiplication_reusing_pool() {
+        let a = get_a();
+        let b = get_b();
+
+        let pool = ThreadPool::new(2);
+        for algorithm in [
+            Algorithm::ParallelILoop(2),
+            Algorithm::ParallelTiling(2, 1, 1, TileScheduling::Static),
+        ] {
+            let c = matrix_multiplication(&a, &b, algorithm, Some(&pool)).unwrap();
+            assert_eq!(c, get_c());
+        }
+        ThreadPool::terminate(pool);
     }
 }
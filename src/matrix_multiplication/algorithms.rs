@@ -1,21 +1,377 @@
+use clap::ValueEnum;
+
+use super::types::ElementType;
+
+/// Default block size below which Strassen's algorithm falls back to the classical kernel
+pub const STRASSEN_CUTOFF_DEFAULT: usize = 64;
+/// Default number of rows claimed at a time by the threads of the row chunks algorithm
+pub const ROW_CHUNK_SIZE_DEFAULT: usize = 4;
+/// Default largest side of the leaf blocks of the Morton layout algorithm
+pub const MORTON_LEAF_SIZE_DEFAULT: usize = 32;
+/// Default size of the inner tiles of the two-level tiling algorithm, sized for the L1 cache
+pub const INNER_TILE_SIZE_DEFAULT: usize = 8;
+/// Unrolling factors supported by the unrolled parallel tiling algorithm
+pub const UNROLL_FACTORS: [usize; 4] = [1, 2, 4, 8];
+/// Default unrolling factor of the unrolled parallel tiling algorithm
+pub const UNROLL_DEFAULT: usize = 4;
+
+/// Returns the side of the largest square grid of at most `threads` threads, at least 1
+pub fn grid_side(threads: usize) -> usize {
+    (1..)
+        .take_while(|side| side * side <= threads)
+        .last()
+        .unwrap_or(1)
+}
+
+/// Returns the `(rows, columns)` of the grid of `threads` blocks closest to a square, at least 1x1
+pub fn block_grid(threads: usize) -> (usize, usize) {
+    let threads = threads.max(1);
+    let rows = (1..=grid_side(threads))
+        .rev()
+        .find(|rows| threads.is_multiple_of(*rows))
+        .unwrap_or(1);
+    (rows, threads / rows)
+}
+
+/// Enum representing the policies for assigning the tiles of C to the threads
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, ValueEnum)]
+pub enum TileScheduling {
+    /// Every thread computes its own partition of the groups of tiles, the thread w of P taking
+    /// the groups w, w + P, w + 2P, ..., with no balancing between the threads
+    Static,
+    /// Every group of tiles is a separate job, pulled by the first idle thread from the shared
+    /// queue of the thread pool
+    Dynamic,
+}
+
+impl std::fmt::Display for TileScheduling {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            TileScheduling::Static => write!(f, "static"),
+            TileScheduling::Dynamic => write!(f, "dynamic"),
+        }
+    }
+}
+
 /// Enum representing available matrix multiplication algorithms
 #[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
 pub enum Algorithm {
     SequentialIjk,
     SequentialIkj,
+    SequentialJik,
+    SequentialJki,
+    SequentialKij,
+    SequentialKji,
+    /// Sequential algorithm transposing B into a scratch buffer first, so that both operands of
+    /// the inner product are traversed row by row
+    SequentialTransposeB,
+    /// Sequential ikj algorithm vectorized with AVX2 intrinsics, falling back to scalar code if
+    /// the CPU does not support them
+    SequentialAvx2,
+    /// Sequential ikj algorithm vectorized with AVX-512 intrinsics, falling back to scalar code
+    /// if the CPU does not support them
+    SequentialAvx512,
+    /// Sequential ikj algorithm vectorized with aarch64 NEON intrinsics, falling back to scalar
+    /// code on other architectures
+    SequentialNeon,
+    /// Sequential BLIS-style algorithm, packing panels of A and B into contiguous buffers and
+    /// computing blocks of C with a register-blocked micro-kernel
+    SequentialPacked,
+    /// Sequential algorithm for complex elements, storing the real and imaginary parts of B and
+    /// C in separate planes so that the four real products of every complex product vectorize
+    /// like a real GEMM
+    SequentialSplitComplex,
+    /// Sequential ikj algorithm for `f32` elements, accumulating the rows of C in `f64` to
+    /// measure the cost of the safer accumulation
+    SequentialMixedPrecision,
+    /// Sequential algorithm using tiling, to separate the benefit of cache blocking from the
+    /// benefit of threads
+    ///
+    /// # Arguments
+    ///
+    /// * `usize` - tile size
+    SequentialTiling(usize),
+    /// Sequential recursive algorithm on matrices converted to Morton (Z-order) layout, so that
+    /// every quadrant is contiguous in memory
+    ///
+    /// # Arguments
+    ///
+    /// * `usize` - largest side of the leaf blocks, multiplied with the ikj loop order
+    SequentialMorton(usize),
     /// Parallel algorithm using a loop over i
     ///
     /// # Arguments
     ///
     /// * `usize` - number of threads to use
     ParallelILoop(usize),
+    /// Parallel algorithm using a loop over j, partitioning the work by columns of C instead of
+    /// rows
+    ///
+    /// # Arguments
+    ///
+    /// * `usize` - number of threads to use
+    ParallelJLoop(usize),
+    /// Parallel algorithm using a loop over i, where every thread pulls the next chunk of rows
+    /// from a shared atomic counter, so that faster threads do more work
+    ///
+    /// # Arguments
+    ///
+    /// * `usize` - number of threads to use
+    /// * `usize` - number of rows pulled at a time
+    ParallelRowChunks(usize, usize),
+    /// Parallel algorithm where every thread accumulates a stripe of rows of C into a private
+    /// buffer, copied into C once complete, so that threads never write to shared cache lines
+    /// while computing
+    ///
+    /// # Arguments
+    ///
+    /// * `usize` - number of threads to use
+    ParallelPrivateBuffers(usize),
+    /// Parallel algorithm distributing the rows of C with rayon's work-stealing scheduler
+    ///
+    /// # Arguments
+    ///
+    /// * `usize` - number of threads to use
+    ParallelRayon(usize),
     /// Parallel algorithm using tiling
     ///
     /// # Arguments
     ///
     /// * `usize` - number of threads to use
     /// * `usize` - tile size
-    ParallelTiling(usize, usize),
+    /// * `usize` - number of tiles grouped into a single job submitted to the thread pool
+    /// * `TileScheduling` - policy for assigning the groups of tiles to the threads
+    ParallelTiling(usize, usize, usize, TileScheduling),
+    /// Parallel tiling algorithm with the innermost j loop manually unrolled, one job per tile
+    ///
+    /// # Arguments
+    ///
+    /// * `usize` - number of threads to use
+    /// * `usize` - tile size
+    /// * `usize` - unrolling factor, one of [`UNROLL_FACTORS`]
+    ParallelTilingUnrolled(usize, usize, usize),
+    /// Parallel tiling algorithm prefetching the next tile of B while multiplying the current
+    /// one, one job per tile
+    ///
+    /// # Arguments
+    ///
+    /// * `usize` - number of threads to use
+    /// * `usize` - tile size
+    ParallelTilingPrefetch(usize, usize),
+    /// Parallel tiling algorithm blocking for two cache levels, one job per outer tile of C,
+    /// each multiplied as a grid of inner tiles
+    ///
+    /// # Arguments
+    ///
+    /// * `usize` - number of threads to use
+    /// * `usize` - outer tile size, sized for the L2 cache
+    /// * `usize` - inner tile size, sized for the L1 cache, dividing the outer tile size
+    ParallelTilingTwoLevel(usize, usize, usize),
+    /// Cannon's block-shifting algorithm on a grid of threads, one per block of C
+    ///
+    /// # Arguments
+    ///
+    /// * `usize` - side of the square grid, which uses its square as number of threads
+    ParallelCannon(usize),
+    /// Parallel algorithm splitting C into a grid of rectangular blocks, one job per thread
+    ///
+    /// # Arguments
+    ///
+    /// * `usize` - number of threads to use, whose factorization closest to a square gives the
+    ///   grid of blocks
+    ParallelBlocks(usize),
+    /// SUMMA algorithm on a grid of threads, one per block of C, broadcasting a panel of columns
+    /// of A and a panel of rows of B at every step
+    ///
+    /// # Arguments
+    ///
+    /// * `usize` - side of the square grid, which uses its square as number of threads
+    ParallelSumma(usize),
+    /// Strassen's recursive sub-cubic algorithm
+    ///
+    /// # Arguments
+    ///
+    /// * `usize` - block size at or below which the recursion falls back to the ikj kernel
+    Strassen(usize),
+    /// Winograd form of Strassen's algorithm, with 15 block additions per step instead of 18
+    ///
+    /// # Arguments
+    ///
+    /// * `usize` - block size at or below which the recursion falls back to the ikj kernel
+    StrassenWinograd(usize),
+    /// Strassen's algorithm running the seven subproducts of the first recursion step as
+    /// parallel jobs, each recursing sequentially
+    ///
+    /// # Arguments
+    ///
+    /// * `usize` - number of threads to use
+    /// * `usize` - block size at or below which the recursion falls back to the ikj kernel
+    ParallelStrassen(usize, usize),
+    /// Strassen's algorithm down to the cutoff, switching to the parallel tiling algorithm below
+    /// it
+    ///
+    /// # Arguments
+    ///
+    /// * `usize` - number of threads to use
+    /// * `usize` - block size at or below which the recursion switches to parallel tiling
+    /// * `usize` - tile size
+    StrassenTiled(usize, usize, usize),
+    /// Baseline calling the f64 GEMM of the `matrixmultiply` crate
+    #[cfg(feature = "matrixmultiply")]
+    SequentialMatrixmultiply,
+    /// Baseline calling the `dot` of `ndarray` on i32 arrays
+    #[cfg(feature = "ndarray")]
+    SequentialNdarray,
+    /// Baseline calling the sequential f64 `matmul` of `faer`
+    #[cfg(feature = "faer")]
+    SequentialFaer,
+    /// Baseline calling the `cblas_dgemm` of the BLAS library the binary is linked against
+    #[cfg(feature = "blas")]
+    SequentialBlas,
+    /// Naive algorithm running on the first OpenCL device, one work item per element of C
+    #[cfg(feature = "opencl")]
+    OpenCl,
+}
+
+impl Algorithm {
+    /// Returns the number of threads used by the algorithm, or `None` if it is sequential
+    pub fn threads(&self) -> Option<usize> {
+        match self {
+            Algorithm::SequentialIjk
+            | Algorithm::SequentialIkj
+            | Algorithm::SequentialJik
+            | Algorithm::SequentialJki
+            | Algorithm::SequentialKij
+            | Algorithm::SequentialKji
+            | Algorithm::SequentialTransposeB
+            | Algorithm::SequentialAvx2
+            | Algorithm::SequentialAvx512
+            | Algorithm::SequentialNeon
+            | Algorithm::SequentialPacked
+            | Algorithm::SequentialSplitComplex
+            | Algorithm::SequentialMixedPrecision
+            | Algorithm::SequentialTiling(_)
+            | Algorithm::SequentialMorton(_)
+            | Algorithm::Strassen(_)
+            | Algorithm::StrassenWinograd(_) => None,
+            #[cfg(feature = "matrixmultiply")]
+            Algorithm::SequentialMatrixmultiply => None,
+            #[cfg(feature = "ndarray")]
+            Algorithm::SequentialNdarray => None,
+            #[cfg(feature = "faer")]
+            Algorithm::SequentialFaer => None,
+            #[cfg(feature = "blas")]
+            Algorithm::SequentialBlas => None,
+            #[cfg(feature = "opencl")]
+            Algorithm::OpenCl => None,
+            Algorithm::ParallelILoop(threads)
+            | Algorithm::ParallelJLoop(threads)
+            | Algorithm::ParallelRowChunks(threads, _)
+            | Algorithm::ParallelPrivateBuffers(threads)
+            | Algorithm::ParallelRayon(threads)
+            | Algorithm::ParallelBlocks(threads)
+            | Algorithm::ParallelTiling(threads, _, _, _)
+            | Algorithm::ParallelTilingUnrolled(threads, _, _)
+            | Algorithm::ParallelTilingPrefetch(threads, _)
+            | Algorithm::ParallelTilingTwoLevel(threads, _, _) => Some(*threads),
+            Algorithm::ParallelCannon(grid) | Algorithm::ParallelSumma(grid) => Some(grid * grid),
+            Algorithm::ParallelStrassen(threads, _) | Algorithm::StrassenTiled(threads, _, _) => {
+                Some(*threads)
+            }
+        }
+    }
+
+    /// Returns the number of workers of the `ThreadPool` the algorithm submits its jobs to, or
+    /// `None` if it does not use one: the sequential algorithms and the rayon one, which runs on
+    /// its own pool
+    pub fn pool_threads(&self) -> Option<usize> {
+        match self {
+            Algorithm::ParallelRayon(_) => None,
+            _ => self.threads(),
+        }
+    }
+
+    /// Returns the number the size of the matrices must be a multiple of, the tile size of the
+    /// tiling algorithms and the side of the grid of Cannon's and SUMMA, 1 for the other ones
+    pub fn size_multiple(&self) -> usize {
+        let multiple = match self {
+            Algorithm::SequentialTiling(tile_size)
+            | Algorithm::ParallelTiling(_, tile_size, _, _)
+            | Algorithm::ParallelTilingUnrolled(_, tile_size, _)
+            | Algorithm::ParallelTilingPrefetch(_, tile_size)
+            | Algorithm::ParallelTilingTwoLevel(_, tile_size, _) => *tile_size,
+            Algorithm::ParallelCannon(grid) | Algorithm::ParallelSumma(grid) => *grid,
+            _ => 1,
+        };
+        multiple.max(1)
+    }
+
+    /// Returns whether the algorithm can multiply an `m` x `k` matrix by a `k` x `n` one, rather
+    /// than only square matrices of the same size
+    pub fn supports_rectangular(&self) -> bool {
+        match self {
+            Algorithm::SequentialIjk
+            | Algorithm::SequentialIkj
+            | Algorithm::SequentialJik
+            | Algorithm::SequentialJki
+            | Algorithm::SequentialKij
+            | Algorithm::SequentialKji
+            | Algorithm::SequentialTransposeB
+            | Algorithm::SequentialSplitComplex
+            | Algorithm::SequentialMixedPrecision
+            | Algorithm::ParallelILoop(_)
+            | Algorithm::ParallelJLoop(_)
+            | Algorithm::ParallelRowChunks(_, _)
+            | Algorithm::ParallelPrivateBuffers(_)
+            | Algorithm::ParallelRayon(_) => true,
+            #[cfg(feature = "matrixmultiply")]
+            Algorithm::SequentialMatrixmultiply => true,
+            #[cfg(feature = "ndarray")]
+            Algorithm::SequentialNdarray => true,
+            #[cfg(feature = "faer")]
+            Algorithm::SequentialFaer => true,
+            #[cfg(feature = "blas")]
+            Algorithm::SequentialBlas => true,
+            _ => false,
+        }
+    }
+
+    /// Returns whether the algorithm can multiply matrices of the given element type. The SIMD
+    /// kernels and the baselines of other crates are only implemented for `i32`, the split
+    /// complex algorithm only for complex types, the mixed-precision one only for `f32`, and
+    /// Strassen's algorithms subtract blocks, so they need a signed type.
+    pub fn supports_element_type(&self, element_type: ElementType) -> bool {
+        if let Algorithm::SequentialSplitComplex = self {
+            return element_type.is_complex();
+        }
+        if let Algorithm::SequentialMixedPrecision = self {
+            return element_type == ElementType::F32;
+        }
+        if let Algorithm::Strassen(_)
+        | Algorithm::StrassenWinograd(_)
+        | Algorithm::ParallelStrassen(_, _)
+        | Algorithm::StrassenTiled(_, _, _) = self
+        {
+            return element_type.is_signed();
+        }
+        let i32_only = match self {
+            Algorithm::SequentialAvx2 | Algorithm::SequentialAvx512 | Algorithm::SequentialNeon => {
+                true
+            }
+            #[cfg(feature = "matrixmultiply")]
+            Algorithm::SequentialMatrixmultiply => true,
+            #[cfg(feature = "ndarray")]
+            Algorithm::SequentialNdarray => true,
+            #[cfg(feature = "faer")]
+            Algorithm::SequentialFaer => true,
+            #[cfg(feature = "blas")]
+            Algorithm::SequentialBlas => true,
+            #[cfg(feature = "opencl")]
+            Algorithm::OpenCl => true,
+            _ => false,
+        };
+        !i32_only || element_type == ElementType::I32
+    }
 }
 
 impl std::fmt::Display for Algorithm {
@@ -23,14 +379,131 @@ impl std::fmt::Display for Algorithm {
         match self {
             Algorithm::SequentialIjk => write!(f, "Sequential IJK"),
             Algorithm::SequentialIkj => write!(f, "Sequential IKJ"),
+            Algorithm::SequentialJik => write!(f, "Sequential JIK"),
+            Algorithm::SequentialJki => write!(f, "Sequential JKI"),
+            Algorithm::SequentialKij => write!(f, "Sequential KIJ"),
+            Algorithm::SequentialKji => write!(f, "Sequential KJI"),
+            Algorithm::SequentialTransposeB => write!(f, "Sequential Transpose B"),
+            Algorithm::SequentialAvx2 => write!(f, "Sequential AVX2"),
+            Algorithm::SequentialAvx512 => write!(f, "Sequential AVX-512"),
+            Algorithm::SequentialNeon => write!(f, "Sequential NEON"),
+            Algorithm::SequentialPacked => write!(f, "Sequential Packed"),
+            Algorithm::SequentialSplitComplex => write!(f, "Sequential Split Complex"),
+            Algorithm::SequentialMixedPrecision => {
+                write!(f, "Sequential Mixed Precision (f64 accumulation)")
+            }
+            Algorithm::SequentialTiling(tile_size) => {
+                write!(f, "Sequential Tiling ({} tile size)", tile_size)
+            }
+            Algorithm::SequentialMorton(leaf_size) => {
+                write!(f, "Sequential Morton ({} leaf size)", leaf_size)
+            }
             Algorithm::ParallelILoop(threads) => write!(f, "Parallel I Loop ({} threads)", threads),
-            Algorithm::ParallelTiling(threads, tile_size) => {
+            Algorithm::ParallelJLoop(threads) => write!(f, "Parallel J Loop ({} threads)", threads),
+            Algorithm::ParallelPrivateBuffers(threads) => {
+                write!(f, "Parallel Private Buffers ({} threads)", threads)
+            }
+            Algorithm::ParallelRowChunks(threads, chunk_size) => write!(
+                f,
+                "Parallel Row Chunks ({} threads, {} rows per chunk)",
+                threads, chunk_size
+            ),
+            Algorithm::ParallelRayon(threads) => write!(f, "Parallel Rayon ({} threads)", threads),
+            Algorithm::ParallelTiling(threads, tile_size, tiles_per_job, scheduling) => {
                 write!(
                     f,
-                    "Parallel Tiling ({} threads, {} tile size)",
-                    threads, tile_size
+                    "Parallel Tiling ({} threads, {} tile size, {} tiles per job, {} scheduling)",
+                    threads, tile_size, tiles_per_job, scheduling
                 )
             }
+            Algorithm::ParallelTilingUnrolled(threads, tile_size, unroll) => write!(
+                f,
+                "Parallel Tiling Unrolled ({} threads, {} tile size, unrolled {}x)",
+                threads, tile_size, unroll
+            ),
+            Algorithm::ParallelTilingPrefetch(threads, tile_size) => write!(
+                f,
+                "Parallel Tiling Prefetch ({} threads, {} tile size)",
+                threads, tile_size
+            ),
+            Algorithm::ParallelTilingTwoLevel(threads, outer_tile_size, inner_tile_size) => write!(
+                f,
+                "Parallel Tiling Two-Level ({} threads, {} outer tile size, {} inner tile size)",
+                threads, outer_tile_size, inner_tile_size
+            ),
+            Algorithm::ParallelCannon(grid) => write!(f, "Parallel Cannon ({0}x{0} grid)", grid),
+            Algorithm::ParallelBlocks(threads) => {
+                let (rows, cols) = block_grid(*threads);
+                write!(
+                    f,
+                    "Parallel Blocks ({} threads, {}x{} blocks)",
+                    threads, rows, cols
+                )
+            }
+            Algorithm::ParallelSumma(grid) => write!(f, "Parallel SUMMA ({0}x{0} grid)", grid),
+            Algorithm::Strassen(cutoff) => write!(f, "Strassen ({} cutoff)", cutoff),
+            Algorithm::StrassenWinograd(cutoff) => {
+                write!(f, "Strassen-Winograd ({} cutoff)", cutoff)
+            }
+            Algorithm::ParallelStrassen(threads, cutoff) => write!(
+                f,
+                "Parallel Strassen ({} threads, {} cutoff)",
+                threads, cutoff
+            ),
+            Algorithm::StrassenTiled(threads, cutoff, tile_size) => write!(
+                f,
+                "Strassen + Parallel Tiling ({} threads, {} cutoff, {} tile size)",
+                threads, cutoff, tile_size
+            ),
+            #[cfg(feature = "matrixmultiply")]
+            Algorithm::SequentialMatrixmultiply => write!(f, "Sequential matrixmultiply DGEMM"),
+            #[cfg(feature = "ndarray")]
+            Algorithm::SequentialNdarray => write!(f, "Sequential ndarray dot"),
+            #[cfg(feature = "faer")]
+            Algorithm::SequentialFaer => write!(f, "Sequential faer matmul"),
+            #[cfg(feature = "blas")]
+            Algorithm::SequentialBlas => write!(f, "Sequential BLAS DGEMM"),
+            #[cfg(feature = "opencl")]
+            Algorithm::OpenCl => write!(f, "OpenCL"),
         }
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_grid_side() {
+        assert_eq!(grid_side(0), 1);
+        assert_eq!(grid_side(1), 1);
+        assert_eq!(grid_side(8), 2);
+        assert_eq!(grid_side(9), 3);
+    }
+
+    #[test]
+    fn test_block_grid() {
+        assert_eq!(block_grid(0), (1, 1));
+        assert_eq!(block_grid(4), (2, 2));
+        assert_eq!(block_grid(6), (2, 3));
+        assert_eq!(block_grid(7), (1, 7));
+    }
+
+    #[test]
+    fn test_pool_threads() {
+        assert_eq!(Algorithm::SequentialIkj.pool_threads(), None);
+        assert_eq!(Algorithm::ParallelILoop(4).pool_threads(), Some(4));
+        assert_eq!(Algorithm::ParallelCannon(2).pool_threads(), Some(4));
+        // rayon runs on its own pool
+        assert_eq!(Algorithm::ParallelRayon(4).threads(), Some(4));
+        assert_eq!(Algorithm::ParallelRayon(4).pool_threads(), None);
+    }
+
+    #[test]
+    fn test_size_multiple() {
+        assert_eq!(Algorithm::SequentialIkj.size_multiple(), 1);
+        assert_eq!(Algorithm::ParallelTilingPrefetch(4, 48).size_multiple(), 48);
+        assert_eq!(Algorithm::ParallelSumma(3).size_multiple(), 3);
+        assert_eq!(Algorithm::SequentialTiling(0).size_multiple(), 1);
+    }
+}
@@ -0,0 +1,133 @@
+//! Overflow-checked multiplication of i32 matrices, reporting the elements of C that do not fit in
+//! i32 instead of letting them wrap
+
+use crate::thread_pool::ThreadPool;
+
+use super::{
+    algorithms::Algorithm, initialize, matrix::Matrix, matrix_multiplication,
+    registry::MatMulAlgorithm, sanitize::SanitizeError, types::ElementType,
+};
+
+/// Wrapper multiplying with the given algorithm in checked mode, see
+/// [`checked_matrix_multiplication`]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct Checked(pub Algorithm);
+
+impl std::fmt::Display for Checked {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{} (checked)", self.0)
+    }
+}
+
+impl MatMulAlgorithm for Checked {
+    fn threads(&self) -> Option<usize> {
+        self.0.threads()
+    }
+
+    fn pool_threads(&self) -> Option<usize> {
+        self.0.pool_threads()
+    }
+
+    fn initialize(&self) {
+        initialize(self.0)
+    }
+
+    fn size_multiple(&self) -> usize {
+        self.0.size_multiple()
+    }
+
+    fn supports_rectangular(&self) -> bool {
+        self.0.supports_rectangular()
+    }
+
+    fn multiply(
+        &self,
+        a: &Matrix<i32>,
+        b: &Matrix<i32>,
+        pool: Option<&ThreadPool>,
+    ) -> Result<Matrix<i32>, SanitizeError> {
+        checked_matrix_multiplication(a, b, self.0, pool)
+    }
+}
+
+/// Multiplies `a` and `b` with the given algorithm, returning `SanitizeError::Overflow` with the
+/// first element of C that does not fit in i32.
+/// Inputs whose elements are small enough for no sum of products to overflow are multiplied in
+/// i32 as usual. The other ones are widened to i64 and multiplied with the i64 version of the
+/// algorithm, which is exact as long as the sums of products fit in i64; the algorithms only
+/// implemented for i32, and the inputs too large even for i64, return `SanitizeError::MayOverflow`.
+pub fn checked_matrix_multiplication(
+    a: &Matrix<i32>,
+    b: &Matrix<i32>,
+    algorithm: Algorithm,
+    pool: Option<&ThreadPool>,
+) -> Result<Matrix<i32>, SanitizeError> {
+    let bound = product_bound(a, b);
+    if bound <= i32::MAX as u128 {
+        return matrix_multiplication(a, b, algorithm, pool);
+    }
+    if bound > i64::MAX as u128 || !algorithm.supports_element_type(ElementType::I64) {
+        return Err(SanitizeError::MayOverflow);
+    }
+
+    let widen = |m: &Matrix<i32>| m.map(|x| *x as i64);
+    let c = matrix_multiplication(&widen(a), &widen(b), algorithm, pool)?;
+    for (i, row) in c.iter_rows().enumerate() {
+        if let Some(j) = row.iter().position(|x| i32::try_from(*x).is_err()) {
+            return Err(SanitizeError::Overflow(i, j));
+        }
+    }
+    Ok(c.map(|x| *x as i32))
+}
+
+/// Returns an upper bound of the absolute values of the elements of the product of `a` and `b`,
+/// and of the partial sums computing them
+fn product_bound(a: &Matrix<i32>, b: &Matrix<i32>) -> u128 {
+    let max_abs = |m: &Matrix<i32>| {
+        m.iter_rows()
+            .flatten()
+            .map(|x| x.unsigned_abs())
+            .max()
+            .unwrap_or(0) as u128
+    };
+    max_abs(a) * max_abs(b) * a.cols() as u128
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_checked_matrix_multiplication() {
+        let a = Matrix::from(vec![vec![1, 2], vec![3, 4]]);
+        let b = Matrix::from(vec![vec![5, 6], vec![7, 8]]);
+        let c = Matrix::from(vec![vec![19, 22], vec![43, 50]]);
+        assert_eq!(
+            checked_matrix_multiplication(&a, &b, Algorithm::ParallelRayon(2), None),
+            Ok(c)
+        );
+
+        // only C[1][1] = 2 * 2^30 + -1 * -1 overflows
+        let a = Matrix::from(vec![vec![1, 1], vec![2, -1]]);
+        let b = Matrix::from(vec![vec![0, 1 << 30], vec![0, -1]]);
+        assert_eq!(
+            checked_matrix_multiplication(&a, &b, Algorithm::SequentialTiling(1), None),
+            Err(SanitizeError::Overflow(1, 1))
+        );
+
+        // large elements whose product fits in i32 are widened and narrowed back
+        let big = 1 << 16;
+        let a = Matrix::from(vec![vec![big, -big], vec![0, 1]]);
+        let b = Matrix::from(vec![vec![big, 0], vec![big, 1]]);
+        assert_eq!(
+            checked_matrix_multiplication(&a, &b, Algorithm::SequentialIkj, None),
+            Ok(Matrix::from(vec![vec![0, -big], vec![big, 1]]))
+        );
+
+        // the SIMD kernels cannot be widened
+        assert_eq!(
+            checked_matrix_multiplication(&a, &b, Algorithm::SequentialAvx2, None),
+            Err(SanitizeError::MayOverflow)
+        );
+    }
+}
@@ -0,0 +1,327 @@
+//! Element types the algorithms are generic over. Every algorithm is implemented once for any
+//! `Element`, except the ones tied to a type, such as the SIMD kernels and the baselines of other
+//! crates, which are dispatched by `Element::multiply_specific`.
+
+use std::{fmt::Debug, marker::PhantomData, num::Wrapping};
+
+use num_complex::Complex;
+use num_traits::NumAssign;
+
+use crate::thread_pool::ThreadPool;
+
+#[cfg(any(
+    feature = "matrixmultiply",
+    feature = "ndarray",
+    feature = "faer",
+    feature = "blas"
+))]
+use super::gemm;
+#[cfg(feature = "opencl")]
+use super::opencl;
+use super::{
+    algorithms::Algorithm,
+    complex,
+    fixed::{Fixed, FRACTIONAL_BITS},
+    matrix::Matrix,
+    matrix_multiplication, mixed,
+    sanitize::SanitizeError,
+    saturating::Saturating,
+    simd,
+    types::{ElementType, Shape},
+};
+
+/// Type of the elements of the matrices multiplied by the algorithms
+pub trait Element: Copy + Debug + PartialEq + NumAssign + Send + Sync + 'static {
+    /// Element type reported in the results
+    const ELEMENT_TYPE: ElementType;
+
+    /// Converts an element of the generated integer matrices
+    fn from_i32(x: i32) -> Self;
+
+    /// Multiplies the sanitized matrices `a` and `b`, of the given shape, with an algorithm that
+    /// is only implemented for some element types. Returns
+    /// `SanitizeError::UnsupportedElementType` if it is not implemented for this one.
+    fn multiply_specific(
+        _a: &Matrix<Self>,
+        _b: &Matrix<Self>,
+        _shape: Shape,
+        _algorithm: Algorithm,
+    ) -> Result<Matrix<Self>, SanitizeError> {
+        Err(SanitizeError::UnsupportedElementType(Self::ELEMENT_TYPE))
+    }
+}
+
+impl Element for i32 {
+    const ELEMENT_TYPE: ElementType = ElementType::I32;
+
+    fn from_i32(x: i32) -> i32 {
+        x
+    }
+
+    fn multiply_specific(
+        a: &Matrix<i32>,
+        b: &Matrix<i32>,
+        shape: Shape,
+        algorithm: Algorithm,
+    ) -> Result<Matrix<i32>, SanitizeError> {
+        #[cfg(not(any(
+            feature = "matrixmultiply",
+            feature = "ndarray",
+            feature = "faer",
+            feature = "blas"
+        )))]
+        let _ = shape;
+        match algorithm {
+            Algorithm::SequentialAvx2 => Ok(simd::multiply_avx2(a, b, a.rows())),
+            Algorithm::SequentialAvx512 => Ok(simd::multiply_avx512(a, b, a.rows())),
+            Algorithm::SequentialNeon => Ok(simd::multiply_neon(a, b, a.rows())),
+            #[cfg(feature = "matrixmultiply")]
+            Algorithm::SequentialMatrixmultiply => Ok(gemm::multiply_matrixmultiply(a, b, shape)),
+            #[cfg(feature = "ndarray")]
+            Algorithm::SequentialNdarray => Ok(gemm::multiply_ndarray(a, b, shape)),
+            #[cfg(feature = "faer")]
+            Algorithm::SequentialFaer => Ok(gemm::multiply_faer(a, b, shape)),
+            #[cfg(feature = "blas")]
+            Algorithm::SequentialBlas => Ok(gemm::multiply_blas(a, b, shape)),
+            #[cfg(feature = "opencl")]
+            Algorithm::OpenCl => opencl::multiply(a, b, a.rows())
+                .map_err(|err| SanitizeError::OpenCl(err.to_string())),
+            _ => unreachable!("{} is implemented for every element type", algorithm),
+        }
+    }
+}
+
+impl Element for i64 {
+    const ELEMENT_TYPE: ElementType = ElementType::I64;
+
+    fn from_i32(x: i32) -> i64 {
+        x as i64
+    }
+}
+
+impl Element for u64 {
+    const ELEMENT_TYPE: ElementType = ElementType::U64;
+
+    /// Takes the absolute value, so that the generated matrices have no negative elements
+    fn from_i32(x: i32) -> u64 {
+        x.unsigned_abs() as u64
+    }
+}
+
+impl Element for f32 {
+    const ELEMENT_TYPE: ElementType = ElementType::F32;
+
+    fn from_i32(x: i32) -> f32 {
+        x as f32
+    }
+
+    fn multiply_specific(
+        a: &Matrix<f32>,
+        b: &Matrix<f32>,
+        shape: Shape,
+        algorithm: Algorithm,
+    ) -> Result<Matrix<f32>, SanitizeError> {
+        match algorithm {
+            Algorithm::SequentialMixedPrecision => {
+                Ok(mixed::multiply_f64_accumulation(a, b, shape))
+            }
+            _ => unreachable!("{} is implemented for every element type", algorithm),
+        }
+    }
+}
+
+impl Element for f64 {
+    const ELEMENT_TYPE: ElementType = ElementType::F64;
+
+    fn from_i32(x: i32) -> f64 {
+        x as f64
+    }
+}
+
+macro_rules! impl_complex_element {
+    ($part:ty, $element_type:expr) => {
+        impl Element for Complex<$part> {
+            const ELEMENT_TYPE: ElementType = $element_type;
+
+            /// The imaginary part is half the real part, so that both are exact and nonzero.
+            fn from_i32(x: i32) -> Complex<$part> {
+                Complex::new(x as $part, x as $part / 2.0)
+            }
+
+            fn multiply_specific(
+                a: &Matrix<Complex<$part>>,
+                b: &Matrix<Complex<$part>>,
+                shape: Shape,
+                algorithm: Algorithm,
+            ) -> Result<Matrix<Complex<$part>>, SanitizeError> {
+                match algorithm {
+                    Algorithm::SequentialSplitComplex => Ok(complex::multiply_split(a, b, shape)),
+                    _ => unreachable!("{} is implemented for every element type", algorithm),
+                }
+            }
+        }
+    };
+}
+
+impl_complex_element!(f32, ElementType::C32);
+impl_complex_element!(f64, ElementType::C64);
+
+impl Element for Fixed {
+    const ELEMENT_TYPE: ElementType = ElementType::Fixed;
+
+    /// Divides the generated elements by 16, so that their products have fractional bits and
+    /// the sums of the products stay far from overflowing the 15 integer bits.
+    fn from_i32(x: i32) -> Fixed {
+        Fixed(x << (FRACTIONAL_BITS - 4))
+    }
+}
+
+impl Element for Wrapping<i32> {
+    const ELEMENT_TYPE: ElementType = ElementType::WrappingI32;
+
+    fn from_i32(x: i32) -> Wrapping<i32> {
+        Wrapping(x)
+    }
+}
+
+impl Element for Saturating {
+    const ELEMENT_TYPE: ElementType = ElementType::SaturatingI32;
+
+    fn from_i32(x: i32) -> Saturating {
+        Saturating(x)
+    }
+}
+
+/// An algorithm multiplying matrices of `T` elements
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct TypedAlgorithm<T> {
+    pub algorithm: Algorithm,
+    element: PhantomData<T>,
+}
+
+impl<T: Element> TypedAlgorithm<T> {
+    pub fn new(algorithm: Algorithm) -> TypedAlgorithm<T> {
+        TypedAlgorithm {
+            algorithm,
+            element: PhantomData,
+        }
+    }
+
+    /// Multiplies `a` and `b`, see [`matrix_multiplication`]
+    pub fn multiply(
+        &self,
+        a: &Matrix<T>,
+        b: &Matrix<T>,
+        pool: Option<&ThreadPool>,
+    ) -> Result<Matrix<T>, SanitizeError> {
+        matrix_multiplication(a, b, self.algorithm, pool)
+    }
+}
+
+impl<T> std::fmt::Display for TypedAlgorithm<T> {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}", self.algorithm)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::matrix_multiplication::algorithms::TileScheduling;
+
+    fn to_element<T: Element>(m: &Matrix<i32>) -> Matrix<T> {
+        m.map(|x| T::from_i32(*x))
+    }
+
+    fn check_generic_algorithms<T: Element>() {
+        let size = 8;
+        let a = Matrix::from_fn(size, size, |i, j| (i * 5 + j * 3) as i32 % 9 - 4);
+        let b = Matrix::from_fn(size, size, |i, j| (i + j * 7) as i32 % 5 - 2);
+        let (a, b) = (to_element::<T>(&a), to_element::<T>(&b));
+        let expected = matrix_multiplication(&a, &b, Algorithm::SequentialIkj, None).unwrap();
+
+        // small integers are multiplied exactly, so every algorithm gives the same result
+        for algorithm in [
+            Algorithm::SequentialIjk,
+            Algorithm::SequentialKji,
+            Algorithm::SequentialTransposeB,
+            Algorithm::SequentialPacked,
+            Algorithm::SequentialSplitComplex,
+            Algorithm::SequentialMixedPrecision,
+            Algorithm::SequentialTiling(4),
+            Algorithm::SequentialMorton(2),
+            Algorithm::ParallelILoop(2),
+            Algorithm::ParallelPrivateBuffers(3),
+            Algorithm::ParallelRayon(2),
+            Algorithm::ParallelTiling(2, 4, 1, TileScheduling::Dynamic),
+            Algorithm::ParallelTilingUnrolled(2, 4, 4),
+            Algorithm::ParallelTilingPrefetch(2, 4),
+            Algorithm::ParallelCannon(2),
+            Algorithm::Strassen(2),
+            Algorithm::ParallelStrassen(2, 2),
+        ] {
+            if !algorithm.supports_element_type(T::ELEMENT_TYPE) {
+                continue;
+            }
+            assert_eq!(
+                TypedAlgorithm::<T>::new(algorithm).multiply(&a, &b, None),
+                Ok(expected.clone()),
+                "{} on {}",
+                algorithm,
+                T::ELEMENT_TYPE
+            );
+        }
+    }
+
+    #[test]
+    fn test_generic_algorithms() {
+        check_generic_algorithms::<i32>();
+        check_generic_algorithms::<i64>();
+        check_generic_algorithms::<u64>();
+        check_generic_algorithms::<f32>();
+        check_generic_algorithms::<f64>();
+        check_generic_algorithms::<Complex<f32>>();
+        check_generic_algorithms::<Complex<f64>>();
+        check_generic_algorithms::<Fixed>();
+        check_generic_algorithms::<Wrapping<i32>>();
+        check_generic_algorithms::<Saturating>();
+    }
+
+    #[test]
+    fn test_overflow_policies() {
+        // 2^16 * 2^16 + 2^16 * 2^16 = 2^33 does not fit in i32
+        let a = Matrix::filled(2, 2, 1 << 16);
+        let algorithm = Algorithm::ParallelTiling(2, 1, 1, TileScheduling::Dynamic);
+        let c = TypedAlgorithm::new(algorithm).multiply(
+            &to_element::<Wrapping<i32>>(&a),
+            &to_element(&a),
+            None,
+        );
+        assert_eq!(c, Ok(Matrix::filled(2, 2, Wrapping(0))));
+        let c = TypedAlgorithm::new(algorithm).multiply(
+            &to_element::<Saturating>(&a),
+            &to_element(&a),
+            None,
+        );
+        assert_eq!(c, Ok(Matrix::filled(2, 2, Saturating(i32::MAX))));
+    }
+
+    #[test]
+    fn test_unsupported_element_type() {
+        let a = Matrix::from(vec![vec![1.0f32]]);
+        assert!(!Algorithm::SequentialAvx2.supports_element_type(ElementType::F32));
+        assert!(Algorithm::SequentialAvx2.supports_element_type(ElementType::I32));
+        assert_eq!(
+            matrix_multiplication(&a, &a, Algorithm::SequentialAvx2, None),
+            Err(SanitizeError::UnsupportedElementType(ElementType::F32))
+        );
+
+        // Strassen's algorithm subtracts blocks, which underflows unsigned elements
+        let a = Matrix::from(vec![vec![1u64]]);
+        assert!(!Algorithm::Strassen(1).supports_element_type(ElementType::U64));
+        assert_eq!(
+            matrix_multiplication(&a, &a, Algorithm::Strassen(1), None),
+            Err(SanitizeError::UnsupportedElementType(ElementType::U64))
+        );
+    }
+}
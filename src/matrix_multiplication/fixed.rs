@@ -0,0 +1,137 @@
+//! Q16.16 fixed-point numbers, multiplied by the generic kernels like any other element type
+
+use std::ops::{Add, AddAssign, Div, DivAssign, Mul, MulAssign, Rem, RemAssign, Sub, SubAssign};
+
+use num_traits::{Num, One, ParseFloatError, Zero};
+
+/// Number of fractional bits of `Fixed`
+pub const FRACTIONAL_BITS: u32 = 16;
+
+/// Signed Q16.16 fixed-point number, stored as an `i32` holding the value times 2^16. The
+/// product of two numbers is computed on 64 bits and rescaled, rounding to the nearest.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash, Default)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct Fixed(pub i32);
+
+impl Fixed {
+    /// Returns the fixed-point number closest to `x`
+    pub fn from_f64(x: f64) -> Fixed {
+        Fixed((x * (1 << FRACTIONAL_BITS) as f64).round() as i32)
+    }
+
+    pub fn to_f64(self) -> f64 {
+        self.0 as f64 / (1 << FRACTIONAL_BITS) as f64
+    }
+}
+
+impl std::fmt::Display for Fixed {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}", self.to_f64())
+    }
+}
+
+impl Add for Fixed {
+    type Output = Fixed;
+
+    fn add(self, rhs: Fixed) -> Fixed {
+        Fixed(self.0 + rhs.0)
+    }
+}
+
+impl Sub for Fixed {
+    type Output = Fixed;
+
+    fn sub(self, rhs: Fixed) -> Fixed {
+        Fixed(self.0 - rhs.0)
+    }
+}
+
+impl Mul for Fixed {
+    type Output = Fixed;
+
+    fn mul(self, rhs: Fixed) -> Fixed {
+        let product = self.0 as i64 * rhs.0 as i64;
+        Fixed(((product + (1 << (FRACTIONAL_BITS - 1))) >> FRACTIONAL_BITS) as i32)
+    }
+}
+
+impl Div for Fixed {
+    type Output = Fixed;
+
+    fn div(self, rhs: Fixed) -> Fixed {
+        Fixed((((self.0 as i64) << FRACTIONAL_BITS) / rhs.0 as i64) as i32)
+    }
+}
+
+impl Rem for Fixed {
+    type Output = Fixed;
+
+    fn rem(self, rhs: Fixed) -> Fixed {
+        Fixed(self.0 % rhs.0)
+    }
+}
+
+macro_rules! impl_assign_op {
+    ($trait:ident, $method:ident, $op:tt) => {
+        impl $trait for Fixed {
+            fn $method(&mut self, rhs: Fixed) {
+                *self = *self $op rhs;
+            }
+        }
+    };
+}
+
+impl_assign_op!(AddAssign, add_assign, +);
+impl_assign_op!(SubAssign, sub_assign, -);
+impl_assign_op!(MulAssign, mul_assign, *);
+impl_assign_op!(DivAssign, div_assign, /);
+impl_assign_op!(RemAssign, rem_assign, %);
+
+impl Zero for Fixed {
+    fn zero() -> Fixed {
+        Fixed(0)
+    }
+
+    fn is_zero(&self) -> bool {
+        self.0 == 0
+    }
+}
+
+impl One for Fixed {
+    fn one() -> Fixed {
+        Fixed(1 << FRACTIONAL_BITS)
+    }
+}
+
+impl Num for Fixed {
+    type FromStrRadixErr = ParseFloatError;
+
+    fn from_str_radix(s: &str, radix: u32) -> Result<Fixed, ParseFloatError> {
+        f64::from_str_radix(s, radix).map(Fixed::from_f64)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_fixed_arithmetic() {
+        let (x, y) = (Fixed::from_f64(1.5), Fixed::from_f64(-2.25));
+        assert_eq!((x + y).to_f64(), -0.75);
+        assert_eq!((x - y).to_f64(), 3.75);
+        assert_eq!((x * y).to_f64(), -3.375);
+        assert_eq!((y / x).to_f64(), -1.5);
+        assert_eq!(Fixed::one() * x, x);
+        assert_eq!(Fixed::from_str_radix("0.5", 10).unwrap(), Fixed(1 << 15));
+    }
+
+    #[test]
+    fn test_fixed_rescaling_rounds() {
+        // 2^-16 * 0.5 is half of the smallest step, rounded up
+        let epsilon = Fixed(1);
+        assert_eq!(epsilon * Fixed::from_f64(0.5), Fixed(1));
+        assert_eq!(epsilon * Fixed::from_f64(0.25), Fixed(0));
+        assert_eq!(epsilon * epsilon, Fixed::zero());
+    }
+}
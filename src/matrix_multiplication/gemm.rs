@@ -0,0 +1,174 @@
+//! Baselines calling the matrix multiplication of other crates. Those working on floating point
+//! numbers represent the products and sums of the i32 inputs exactly as long as they do not
+//! overflow.
+
+use super::{matrix::Matrix, types::Shape};
+
+/// Converts `m` into a row-major buffer of f64
+#[cfg(any(feature = "matrixmultiply", feature = "blas"))]
+fn to_f64(m: &Matrix<i32>) -> Vec<f64> {
+    m.iter_rows().flatten().map(|x| *x as f64).collect()
+}
+
+/// Converts the row-major `rows` x `cols` buffer `m` back to i32
+#[cfg(any(feature = "matrixmultiply", feature = "blas"))]
+fn from_f64(m: &[f64], rows: usize, cols: usize) -> Matrix<i32> {
+    Matrix::from_vec(rows, cols, m.iter().map(|x| *x as i32).collect())
+}
+
+/// Multiplies `a` and `b` with the `dgemm` of the `matrixmultiply` crate
+#[cfg(feature = "matrixmultiply")]
+pub(super) fn multiply_matrixmultiply(
+    a: &Matrix<i32>,
+    b: &Matrix<i32>,
+    shape: Shape,
+) -> Matrix<i32> {
+    let Shape { m, k, n } = shape;
+    let a = to_f64(a);
+    let b = to_f64(b);
+    let mut c = vec![0.0; m * n];
+
+    // SAFETY: the buffers hold `m * k`, `k * n` and `m * n` elements, laid out row-major with a
+    // row stride of their number of columns and a column stride of 1
+    unsafe {
+        matrixmultiply::dgemm(
+            m,
+            k,
+            n,
+            1.0,
+            a.as_ptr(),
+            k as isize,
+            1,
+            b.as_ptr(),
+            n as isize,
+            1,
+            0.0,
+            c.as_mut_ptr(),
+            n as isize,
+            1,
+        );
+    }
+
+    from_f64(&c, m, n)
+}
+
+/// Multiplies `a` and `b` with the sequential f64 `matmul` of `faer`
+#[cfg(feature = "faer")]
+pub(super) fn multiply_faer(a: &Matrix<i32>, b: &Matrix<i32>, shape: Shape) -> Matrix<i32> {
+    use faer::{linalg::matmul::matmul, Accum, Mat, Par};
+
+    let Shape { m, k, n } = shape;
+    let a = Mat::from_fn(m, k, |i, j| a[i][j] as f64);
+    let b = Mat::from_fn(k, n, |i, j| b[i][j] as f64);
+    let mut c = Mat::<f64>::zeros(m, n);
+
+    matmul(&mut c, Accum::Replace, &a, &b, 1.0, Par::Seq);
+
+    Matrix::from_fn(m, n, |i, j| c[(i, j)] as i32)
+}
+
+/// Multiplies `a` and `b` with the `cblas_dgemm` of the BLAS library the binary is linked against
+#[cfg(feature = "blas")]
+pub(super) fn multiply_blas(a: &Matrix<i32>, b: &Matrix<i32>, shape: Shape) -> Matrix<i32> {
+    use cblas::{Layout, Transpose};
+
+    let a = to_f64(a);
+    let b = to_f64(b);
+    let mut c = vec![0.0; shape.m * shape.n];
+
+    let (m, k, n) = (shape.m as i32, shape.k as i32, shape.n as i32);
+    // SAFETY: the buffers hold `m * k`, `k * n` and `m * n` elements, laid out row-major with a
+    // leading dimension of their number of columns
+    unsafe {
+        cblas::dgemm(
+            Layout::RowMajor,
+            Transpose::None,
+            Transpose::None,
+            m,
+            n,
+            k,
+            1.0,
+            &a,
+            k,
+            &b,
+            n,
+            0.0,
+            &mut c,
+            n,
+        );
+    }
+
+    from_f64(&c, shape.m, shape.n)
+}
+
+/// Multiplies `a` and `b` with the `dot` of `ndarray`, on i32 arrays like the other algorithms
+#[cfg(feature = "ndarray")]
+pub(super) fn multiply_ndarray(a: &Matrix<i32>, b: &Matrix<i32>, shape: Shape) -> Matrix<i32> {
+    use ndarray::{ArrayView2, ShapeBuilder};
+
+    // the views skip the padding of the rows with their strides
+    let a = ArrayView2::from_shape((shape.m, shape.k).strides((a.stride(), 1)), a.as_slice())
+        .expect("A is m x k");
+    let b = ArrayView2::from_shape((shape.k, shape.n).strides((b.stride(), 1)), b.as_slice())
+        .expect("B is k x n");
+
+    Matrix::from_vec(shape.m, shape.n, a.dot(&b).into_iter().collect())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::matrix_multiplication::matrix_multiplication_sequential_ikj;
+
+    const SHAPE: Shape = Shape { m: 13, k: 7, n: 5 };
+
+    fn get_matrices(shape: Shape) -> (Matrix<i32>, Matrix<i32>) {
+        let a = Matrix::from_fn(shape.m, shape.k, |i, j| (i * 3 + j) as i32 % 7 - 3);
+        let b = Matrix::from_fn(shape.k, shape.n, |i, j| (i + j * 5) as i32 % 9 - 4);
+        (a, b)
+    }
+
+    #[test]
+    #[cfg(feature = "matrixmultiply")]
+    fn test_multiply_matrixmultiply() {
+        let (a, b) = get_matrices(SHAPE);
+
+        assert_eq!(
+            multiply_matrixmultiply(&a, &b, SHAPE),
+            matrix_multiplication_sequential_ikj(&a, &b, SHAPE).unwrap()
+        );
+    }
+
+    #[test]
+    #[cfg(feature = "ndarray")]
+    fn test_multiply_ndarray() {
+        let (a, b) = get_matrices(SHAPE);
+
+        assert_eq!(
+            multiply_ndarray(&a, &b, SHAPE),
+            matrix_multiplication_sequential_ikj(&a, &b, SHAPE).unwrap()
+        );
+    }
+
+    #[test]
+    #[cfg(feature = "faer")]
+    fn test_multiply_faer() {
+        let (a, b) = get_matrices(SHAPE);
+
+        assert_eq!(
+            multiply_faer(&a, &b, SHAPE),
+            matrix_multiplication_sequential_ikj(&a, &b, SHAPE).unwrap()
+        );
+    }
+
+    #[test]
+    #[cfg(feature = "blas")]
+    fn test_multiply_blas() {
+        let (a, b) = get_matrices(SHAPE);
+
+        assert_eq!(
+            multiply_blas(&a, &b, SHAPE),
+            matrix_multiplication_sequential_ikj(&a, &b, SHAPE).unwrap()
+        );
+    }
+}
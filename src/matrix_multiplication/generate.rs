@@ -1,4 +1,10 @@
-const MAX_ABS_VALUE_DEFAULT: i32 = 11; // 11 results in a matrix with values from -10 to 10
+use rand::{rngs::StdRng, Rng, SeedableRng};
+
+use crate::triangular::Triangle;
+
+use super::matrix::Matrix;
+
+pub const MAX_ABS_VALUE_DEFAULT: i32 = 11; // 11 results in a matrix with values from -10 to 10
 
 /// Generates a square matrix of size `size` filled with zeros or random values between -10 and 10
 ///
@@ -6,10 +12,12 @@ const MAX_ABS_VALUE_DEFAULT: i32 = 11; // 11 results in a matrix with values fro
 ///
 /// * `size` - The size of the matrix
 /// * `random_values` - If true, the matrix will be filled with random values between -10 and 10
+/// * `seed` - Seed of the random values, so that two runs generate the same matrix. If `None`,
+///   the values are seeded from the OS
 ///
 /// # Returns
 ///
-/// A square matrix of size `size` as a `Vec<Vec<i32>>`
+/// A square matrix of size `size`
 ///
 /// # Panics
 ///
@@ -18,36 +26,143 @@ pub fn generate_square_matrix_of_size(
     size: usize,
     random_values: bool,
     max_abs_value: Option<i32>,
-) -> Vec<Vec<i32>> {
-    let mut matrix = Vec::with_capacity(size);
-
+    seed: Option<u64>,
+) -> Matrix<i32> {
     let modulo = max_abs_value.unwrap_or(MAX_ABS_VALUE_DEFAULT);
 
     if modulo < 1 {
         panic!("max_abs_value must be greater than 1");
     }
 
-    for _ in 0..size {
-        let mut row = Vec::with_capacity(size);
-        for _ in 0..size {
-            if random_values {
-                // random between -10 and 10
-                row.push(rand::random::<i32>() % modulo);
+    if !random_values {
+        return Matrix::zeros(size, size);
+    }
+
+    // random between -10 and 10
+    let mut rng = seeded_rng(seed);
+    Matrix::from_fn(size, size, |_, _| rng.gen::<i32>() % modulo)
+}
+
+/// Returns a random number generator seeded with `seed`, or from the OS if `seed` is `None`
+pub fn seeded_rng(seed: Option<u64>) -> StdRng {
+    match seed {
+        Some(seed) => StdRng::seed_from_u64(seed),
+        None => StdRng::from_entropy(),
+    }
+}
+
+/// Generates a square matrix of size `size` filled with random values drawn from `rng`, between
+/// -`max_abs_value` + 1 and `max_abs_value` - 1
+///
+/// # Panics
+///
+/// Panics if `max_abs_value` is less than 1
+pub fn generate_random_square_matrix_with_rng<R: Rng>(
+    size: usize,
+    max_abs_value: i32,
+    rng: &mut R,
+) -> Matrix<i32> {
+    generate_random_matrix_with_rng(size, size, max_abs_value, rng)
+}
+
+/// Generates a `rows` x `cols` matrix filled with random values drawn from `rng`, between
+/// -`max_abs_value` + 1 and `max_abs_value` - 1
+///
+/// # Panics
+///
+/// Panics if `max_abs_value` is less than 1
+pub fn generate_random_matrix_with_rng<R: Rng>(
+    rows: usize,
+    cols: usize,
+    max_abs_value: i32,
+    rng: &mut R,
+) -> Matrix<i32> {
+    if max_abs_value < 1 {
+        panic!("max_abs_value must be greater than 1");
+    }
+
+    Matrix::from_fn(rows, cols, |_, _| {
+        rng.gen_range(-max_abs_value + 1..max_abs_value)
+    })
+}
+
+/// Generates a sparse square matrix of size `size` in which every element is, with probability
+/// `density`, a nonzero random value drawn from `rng` between -`max_abs_value` + 1 and
+/// `max_abs_value` - 1, and zero otherwise
+///
+/// # Panics
+///
+/// Panics if `max_abs_value` is less than 2, or if `density` is not between 0 and 1
+pub fn generate_random_sparse_square_matrix_with_rng<R: Rng>(
+    size: usize,
+    density: f64,
+    max_abs_value: i32,
+    rng: &mut R,
+) -> Matrix<i32> {
+    if max_abs_value < 2 {
+        panic!("max_abs_value must be greater than 1");
+    }
+
+    Matrix::from_fn(size, size, |_, _| match rng.gen_bool(density) {
+        true => {
+            let abs = rng.gen_range(1..max_abs_value);
+            if rng.gen() {
+                abs
             } else {
-                row.push(0);
+                -abs
             }
         }
-        matrix.push(row);
-    }
+        false => 0,
+    })
+}
 
+/// Generates a triangular square matrix of size `size` whose elements in `triangle` are random
+/// values drawn from `rng` between -`max_abs_value` + 1 and `max_abs_value` - 1, and the others
+/// zero
+///
+/// # Panics
+///
+/// Panics if `max_abs_value` is less than 2
+pub fn generate_random_triangular_square_matrix_with_rng<R: Rng>(
+    size: usize,
+    triangle: Triangle,
+    max_abs_value: i32,
+    rng: &mut R,
+) -> Matrix<i32> {
+    let mut matrix = generate_random_square_matrix_with_rng(size, max_abs_value, rng);
+    for (i, row) in matrix.iter_rows_mut().enumerate() {
+        for (j, value) in row.iter_mut().enumerate() {
+            if !triangle.contains(i, j) {
+                *value = 0;
+            }
+        }
+    }
     matrix
 }
 
+/// Generates a symmetric square matrix of size `size` whose elements on and above the diagonal
+/// are random values drawn from `rng` between -`max_abs_value` + 1 and `max_abs_value` - 1, and
+/// mirrored below it
+///
+/// # Panics
+///
+/// Panics if `max_abs_value` is less than 2
+pub fn generate_random_symmetric_square_matrix_with_rng<R: Rng>(
+    size: usize,
+    max_abs_value: i32,
+    rng: &mut R,
+) -> Matrix<i32> {
+    let upper = generate_random_square_matrix_with_rng(size, max_abs_value, rng);
+    Matrix::from_fn(size, size, |i, j| upper[i.min(j)][i.max(j)])
+}
+
 /// Generates a square matrix of size `size` filled with zeros
 #[macro_export]
 macro_rules! zero_filled_square_matrix_of_size {
     ($size: expr) => {
-        $crate::matrix_multiplication::generate::generate_square_matrix_of_size($size, false, None)
+        $crate::matrix_multiplication::generate::generate_square_matrix_of_size(
+            $size, false, None, None,
+        )
     };
 }
 
@@ -56,13 +171,16 @@ macro_rules! zero_filled_square_matrix_of_size {
 #[macro_export]
 macro_rules! random_filled_square_matrix_of_size {
     ($size: expr) => {
-        $crate::matrix_multiplication::generate::generate_square_matrix_of_size($size, true, None)
+        $crate::matrix_multiplication::generate::generate_square_matrix_of_size(
+            $size, true, None, None,
+        )
     };
     ($size: expr, $max_abs_value: expr) => {
         $crate::matrix_multiplication::generate::generate_square_matrix_of_size(
             $size,
             true,
             Some($max_abs_value),
+            None,
         )
     };
 }
@@ -74,20 +192,14 @@ mod tests {
     #[test]
     fn test_generate_square_matrix_of_size() {
         let matrix = zero_filled_square_matrix_of_size!(10);
-        assert_eq!(matrix.len(), 10);
-        assert_eq!(matrix[0].len(), 10);
-        for row in matrix {
-            for value in row {
-                assert_eq!(value, 0);
-            }
-        }
+        assert_eq!((matrix.rows(), matrix.cols()), (10, 10));
+        assert!(matrix.as_slice().iter().all(|value| *value == 0));
     }
 
     #[test]
     fn test_generate_square_matrix_of_size_random_default_abs() {
         let matrix = random_filled_square_matrix_of_size!(10);
-        assert_eq!(matrix.len(), 10);
-        assert_eq!(matrix[0].len(), 10);
+        assert_eq!((matrix.rows(), matrix.cols()), (10, 10));
         assert!(matrix[0][0] >= -MAX_ABS_VALUE_DEFAULT && matrix[0][0] <= MAX_ABS_VALUE_DEFAULT);
         assert!(matrix[9][9] >= -MAX_ABS_VALUE_DEFAULT && matrix[9][9] <= MAX_ABS_VALUE_DEFAULT);
     }
@@ -96,31 +208,118 @@ mod tests {
     fn test_generate_square_matrix_of_size_random_custom_abs() {
         let max_abs_value = 5;
         let matrix = random_filled_square_matrix_of_size!(10, max_abs_value);
-        assert_eq!(matrix.len(), 10);
-        assert_eq!(matrix[0].len(), 10);
+        assert_eq!((matrix.rows(), matrix.cols()), (10, 10));
         assert!(matrix[0][0] >= -max_abs_value && matrix[0][0] <= max_abs_value);
         assert!(matrix[9][9] >= -max_abs_value && matrix[9][9] <= max_abs_value);
     }
 
+    #[test]
+    fn test_generate_square_matrix_of_size_seeded() {
+        let matrix = generate_square_matrix_of_size(10, true, None, Some(7));
+        assert_eq!(
+            matrix,
+            generate_square_matrix_of_size(10, true, None, Some(7))
+        );
+        assert_ne!(
+            matrix,
+            generate_square_matrix_of_size(10, true, None, Some(8))
+        );
+    }
+
     #[test]
     fn test_generate_empty_matrix() {
         let matrix = zero_filled_square_matrix_of_size!(0);
-        assert_eq!(matrix.len(), 0);
+        assert!(matrix.is_empty());
     }
 
     #[test]
     fn test_generate_square_matrix_of_size_1() {
         let matrix = zero_filled_square_matrix_of_size!(1);
-        assert_eq!(matrix.len(), 1);
-        assert_eq!(matrix[0].len(), 1);
+        assert_eq!((matrix.rows(), matrix.cols()), (1, 1));
         assert_eq!(matrix[0][0], 0);
 
         let matrix = random_filled_square_matrix_of_size!(1);
-        assert_eq!(matrix.len(), 1);
-        assert_eq!(matrix[0].len(), 1);
+        assert_eq!((matrix.rows(), matrix.cols()), (1, 1));
         assert!(matrix[0][0] >= -MAX_ABS_VALUE_DEFAULT && matrix[0][0] <= MAX_ABS_VALUE_DEFAULT);
     }
 
+    #[test]
+    fn test_generate_random_square_matrix_with_rng() {
+        use rand::{rngs::StdRng, SeedableRng};
+
+        let matrix = generate_random_square_matrix_with_rng(10, 5, &mut StdRng::seed_from_u64(42));
+        assert_eq!(matrix.rows(), 10);
+        assert!(matrix
+            .as_slice()
+            .iter()
+            .all(|value| (-4..=4).contains(value)));
+
+        // the same seed generates the same matrix
+        let same = generate_random_square_matrix_with_rng(10, 5, &mut StdRng::seed_from_u64(42));
+        assert_eq!(matrix, same);
+    }
+
+    #[test]
+    fn test_generate_random_matrix_with_rng() {
+        use rand::{rngs::StdRng, SeedableRng};
+
+        let matrix = generate_random_matrix_with_rng(3, 7, 5, &mut StdRng::seed_from_u64(42));
+        assert_eq!((matrix.rows(), matrix.cols()), (3, 7));
+    }
+
+    #[test]
+    fn test_generate_random_sparse_square_matrix_with_rng() {
+        use rand::{rngs::StdRng, SeedableRng};
+
+        let mut rng = StdRng::seed_from_u64(42);
+        let matrix = generate_random_sparse_square_matrix_with_rng(100, 0.1, 5, &mut rng);
+        assert_eq!(matrix.rows(), 100);
+        assert!(matrix
+            .as_slice()
+            .iter()
+            .all(|value| (-4..=4).contains(value)));
+        let nnz = matrix
+            .as_slice()
+            .iter()
+            .filter(|value| **value != 0)
+            .count();
+        assert!((500..1500).contains(&nnz), "{} nonzeros", nnz);
+
+        let empty = generate_random_sparse_square_matrix_with_rng(10, 0.0, 5, &mut rng);
+        assert!(empty.as_slice().iter().all(|value| *value == 0));
+        let full = generate_random_sparse_square_matrix_with_rng(10, 1.0, 5, &mut rng);
+        assert!(full.as_slice().iter().all(|value| *value != 0));
+    }
+
+    #[test]
+    fn test_generate_random_triangular_square_matrix_with_rng() {
+        use rand::{rngs::StdRng, SeedableRng};
+
+        let mut rng = StdRng::seed_from_u64(42);
+        for triangle in [Triangle::Upper, Triangle::Lower] {
+            let matrix =
+                generate_random_triangular_square_matrix_with_rng(10, triangle, 5, &mut rng);
+            for (i, row) in matrix.iter_rows().enumerate() {
+                for (j, value) in row.iter().enumerate() {
+                    assert!(triangle.contains(i, j) || *value == 0);
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn test_generate_random_symmetric_square_matrix_with_rng() {
+        use rand::{rngs::StdRng, SeedableRng};
+
+        let matrix =
+            generate_random_symmetric_square_matrix_with_rng(10, 5, &mut StdRng::seed_from_u64(42));
+        for (i, row) in matrix.iter_rows().enumerate() {
+            for (j, value) in row.iter().enumerate() {
+                assert_eq!(*value, matrix[j][i]);
+            }
+        }
+    }
+
     #[test]
     fn test_generate_square_matrix_of_size_random_custom_abs_panic() {
         let max_abs_value = 1;
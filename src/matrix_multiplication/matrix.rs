@@ -0,0 +1,510 @@
+//! Dense matrices stored row by row in a single buffer
+
+use std::{
+    fmt::Debug,
+    hash::{Hash, Hasher},
+    iter, mem,
+    ops::{Index, IndexMut},
+};
+
+use num_traits::Zero;
+
+use super::{
+    sanitize::SanitizeError,
+    view::{MatrixView, MatrixViewMut},
+};
+
+/// Dense `rows` x `cols` matrix, stored row by row in a single buffer, `i32` unless stated
+/// otherwise. The kernels working on flat buffers use it without copying, and indexing it with a
+/// row index returns the row as a slice, so that `m[i][j]` is the element at row `i` and column
+/// `j`.
+///
+/// The rows start `stride` elements apart, which is the number of columns unless the matrix was
+/// created with [`Matrix::padded`] or [`Matrix::zeros_with_layout`]: every row is then followed by
+/// padding, e.g. to keep the rows of a power-of-two-sized tile from mapping to the same cache sets.
+///
+/// The first element is aligned to the alignment of `T`, or to a given number of bytes for the
+/// matrices created with [`Matrix::zeros_with_layout`] or [`Matrix::aligned`]. Clones keep both the
+/// stride and the alignment.
+pub struct Matrix<T = i32> {
+    /// The rows and their padding, from `offset` on
+    data: Vec<T>,
+    /// Number of elements of `data` before the first one, skipped to align it
+    offset: usize,
+    /// Alignment of the first element in bytes, 1 if it is the alignment of `T`
+    align: usize,
+    rows: usize,
+    cols: usize,
+    /// Number of elements from the start of a row to the start of the next one
+    stride: usize,
+}
+
+/// Returns a buffer holding `len` `elements` from the returned offset on, the first of which is
+/// aligned to `align` bytes, a power of two. The elements before the offset are copies of the
+/// first one. Elements whose size does not divide `align` are not aligned.
+fn aligned_buffer<T: Clone>(
+    mut elements: impl Iterator<Item = T>,
+    len: usize,
+    align: usize,
+) -> (Vec<T>, usize) {
+    let mut data = Vec::with_capacity(len + align / mem::size_of::<T>().max(1));
+    let Some(first) = elements.next() else {
+        return (data, 0);
+    };
+
+    let offset = match data.as_ptr().align_offset(align) {
+        offset if offset + len <= data.capacity() => offset,
+        _ => 0,
+    };
+    data.extend(iter::repeat_n(first.clone(), offset));
+    data.push(first);
+    data.extend(elements);
+    (data, offset)
+}
+
+impl<T> Matrix<T> {
+    /// Creates a `rows` x `cols` matrix from its elements, row by row
+    ///
+    /// # Panics
+    ///
+    /// Panics if `data` does not hold `rows` * `cols` elements
+    pub fn from_vec(rows: usize, cols: usize, data: Vec<T>) -> Matrix<T> {
+        Matrix::from_vec_strided(rows, cols, cols, data)
+    }
+
+    /// Creates a `rows` x `cols` matrix from its rows, each followed by `stride` - `cols` padding
+    /// elements
+    ///
+    /// # Panics
+    ///
+    /// Panics if `stride` is less than `cols`, or if `data` does not hold `rows` * `stride`
+    /// elements
+    pub fn from_vec_strided(rows: usize, cols: usize, stride: usize, data: Vec<T>) -> Matrix<T> {
+        assert!(
+            stride >= cols,
+            "the stride is less than the {} columns",
+            cols
+        );
+        assert_eq!(
+            data.len(),
+            rows * stride,
+            "a {}x{} matrix with a stride of {} has {} elements",
+            rows,
+            cols,
+            stride,
+            rows * stride
+        );
+        Matrix {
+            stride,
+            ..Matrix::natural(data, rows, cols)
+        }
+    }
+
+    /// Creates a `rows` x `cols` matrix whose element at row `i` and column `j` is `f(i, j)`
+    pub fn from_fn(rows: usize, cols: usize, mut f: impl FnMut(usize, usize) -> T) -> Matrix<T> {
+        let data = (0..rows)
+            .flat_map(|i| (0..cols).map(move |j| (i, j)))
+            .map(|(i, j)| f(i, j))
+            .collect();
+        Matrix::natural(data, rows, cols)
+    }
+
+    /// Creates a matrix from its rows, returning `SanitizeError::RaggedMatrix` with the given
+    /// name if they are not all the same length
+    pub fn from_rows(rows: Vec<Vec<T>>, matrix_name: &str) -> Result<Matrix<T>, SanitizeError> {
+        let cols = rows.first().map_or(0, |row| row.len());
+        if rows.iter().any(|row| row.len() != cols) {
+            return Err(SanitizeError::RaggedMatrix(matrix_name.to_string()));
+        }
+
+        let n_rows = rows.len();
+        Ok(Matrix::natural(
+            rows.into_iter().flatten().collect(),
+            n_rows,
+            cols,
+        ))
+    }
+
+    /// Creates a matrix from its elements, without padding and with the alignment of `T`
+    fn natural(data: Vec<T>, rows: usize, cols: usize) -> Matrix<T> {
+        Matrix {
+            data,
+            offset: 0,
+            align: 1,
+            rows,
+            cols,
+            stride: cols,
+        }
+    }
+
+    pub fn rows(&self) -> usize {
+        self.rows
+    }
+
+    pub fn cols(&self) -> usize {
+        self.cols
+    }
+
+    /// Returns the number of elements from the start of a row to the start of the next one, at
+    /// least the number of columns
+    pub fn stride(&self) -> usize {
+        self.stride
+    }
+
+    /// Returns whether the matrix has no elements
+    pub fn is_empty(&self) -> bool {
+        self.rows * self.cols == 0
+    }
+
+    /// Returns whether the first element is aligned to `align` bytes. The rows are all aligned if
+    /// the size of the stride is a multiple of `align` as well.
+    pub fn is_aligned(&self, align: usize) -> bool {
+        (self.as_slice().as_ptr() as usize).is_multiple_of(align)
+    }
+
+    /// Returns the elements, row by row, each row followed by its padding, see
+    /// [`Matrix::stride`]
+    pub fn as_slice(&self) -> &[T] {
+        &self.data[self.offset..]
+    }
+
+    /// Returns the elements, row by row, each row followed by its padding, see
+    /// [`Matrix::stride`]
+    pub fn as_mut_slice(&mut self) -> &mut [T] {
+        &mut self.data[self.offset..]
+    }
+
+    /// Returns a view of the whole matrix, whose blocks can be viewed in turn
+    pub fn view(&self) -> MatrixView<'_, T> {
+        MatrixView::from_slice(self.as_slice(), self.rows, self.cols, self.stride)
+    }
+
+    /// Returns a mutable view of the whole matrix, whose blocks can be viewed in turn
+    pub fn view_mut(&mut self) -> MatrixViewMut<'_, T> {
+        let (rows, cols, stride) = (self.rows, self.cols, self.stride);
+        MatrixViewMut::from_slice(self.as_mut_slice(), rows, cols, stride)
+    }
+
+    /// Returns an iterator over the rows, without their padding. A matrix without columns has no
+    /// rows to iterate over.
+    pub fn iter_rows(&self) -> impl DoubleEndedIterator<Item = &[T]> + ExactSizeIterator {
+        let cols = self.cols;
+        self.as_slice()
+            .chunks_exact(self.stride.max(1))
+            .map(move |row| &row[..cols])
+    }
+
+    /// Returns an iterator over the mutable rows, without their padding. A matrix without columns
+    /// has no rows to iterate over.
+    pub fn iter_rows_mut(
+        &mut self,
+    ) -> impl DoubleEndedIterator<Item = &mut [T]> + ExactSizeIterator {
+        let (cols, stride) = (self.cols, self.stride.max(1));
+        self.as_mut_slice()
+            .chunks_exact_mut(stride)
+            .map(move |row| &mut row[..cols])
+    }
+
+    /// Returns the matrix whose elements are `f` applied to the elements of this one, with the
+    /// same stride and alignment. `f` is applied to the padding as well.
+    pub fn map<U: Clone>(&self, f: impl FnMut(&T) -> U) -> Matrix<U> {
+        let elements = self.as_slice().iter().map(f);
+        Matrix::aligned_from(elements, self.rows, self.cols, self.stride, self.align)
+    }
+}
+
+impl<T: Clone> Matrix<T> {
+    /// Creates a `rows` x `cols` matrix whose elements are all `value`
+    pub fn filled(rows: usize, cols: usize, value: T) -> Matrix<T> {
+        Matrix::natural(vec![value; rows * cols], rows, cols)
+    }
+
+    /// Returns a copy of the matrix whose first element is aligned to `align` bytes, a power of
+    /// two, e.g. 64 to start at a cache line. The rows are all aligned if their size is a multiple
+    /// of `align` as well.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `align` is not a power of two
+    pub fn aligned(&self, align: usize) -> Matrix<T> {
+        let elements = self.as_slice().iter().cloned();
+        Matrix::aligned_from(elements, self.rows, self.cols, self.stride, align)
+    }
+
+    /// Creates a `rows` x `cols` matrix from its rows and their padding, `stride` elements apart,
+    /// aligning the first element to `align` bytes
+    fn aligned_from(
+        elements: impl Iterator<Item = T>,
+        rows: usize,
+        cols: usize,
+        stride: usize,
+        align: usize,
+    ) -> Matrix<T> {
+        if align <= 1 {
+            return Matrix::from_vec_strided(rows, cols, stride, elements.collect());
+        }
+
+        let (data, offset) = aligned_buffer(elements, rows * stride, align);
+        Matrix {
+            data,
+            offset,
+            align,
+            rows,
+            cols,
+            stride,
+        }
+    }
+}
+
+impl<T: Zero + Clone> Matrix<T> {
+    /// Creates a `rows` x `cols` matrix filled with zeros
+    pub fn zeros(rows: usize, cols: usize) -> Matrix<T> {
+        Matrix::filled(rows, cols, T::zero())
+    }
+
+    /// Creates a `rows` x `cols` matrix filled with zeros, whose rows start `stride` elements
+    /// apart and whose first element is aligned to `align` bytes, see [`Matrix::aligned`]
+    ///
+    /// # Panics
+    ///
+    /// Panics if `stride` is less than `cols`, or if `align` is not a power of two
+    pub fn zeros_with_layout(rows: usize, cols: usize, stride: usize, align: usize) -> Matrix<T> {
+        let elements = iter::repeat_n(T::zero(), rows * stride);
+        Matrix::aligned_from(elements, rows, cols, stride, align)
+    }
+
+    /// Returns a copy of the matrix whose rows start `stride` elements apart, with the same
+    /// alignment. Every row is followed by `stride` - `cols` zeros, so that e.g. the rows of a
+    /// matrix whose width is a power of two do not all map to the same cache sets.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `stride` is less than the number of columns
+    pub fn padded(&self, stride: usize) -> Matrix<T> {
+        assert!(
+            stride >= self.cols,
+            "the stride is less than the {} columns",
+            self.cols
+        );
+        let padding = stride - self.cols;
+        let elements = self.iter_rows().flat_map(|row| {
+            row.iter()
+                .cloned()
+                .chain(iter::repeat_n(T::zero(), padding))
+        });
+        Matrix::aligned_from(elements, self.rows, self.cols, stride, self.align)
+    }
+}
+
+impl<T: Clone> Clone for Matrix<T> {
+    /// Clones the matrix, keeping its alignment
+    fn clone(&self) -> Matrix<T> {
+        self.aligned(self.align)
+    }
+}
+
+impl<T: PartialEq> PartialEq for Matrix<T> {
+    /// Compares the dimensions and the elements, whatever their stride and alignment
+    fn eq(&self, other: &Matrix<T>) -> bool {
+        (self.rows, self.cols) == (other.rows, other.cols) && self.iter_rows().eq(other.iter_rows())
+    }
+}
+
+impl<T: Eq> Eq for Matrix<T> {}
+
+impl<T: Hash> Hash for Matrix<T> {
+    fn hash<H: Hasher>(&self, state: &mut H) {
+        (self.rows, self.cols).hash(state);
+        for row in self.iter_rows() {
+            row.hash(state);
+        }
+    }
+}
+
+impl<T: Debug> Debug for Matrix<T> {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("Matrix")
+            .field("rows", &self.rows)
+            .field("cols", &self.cols)
+            .field("stride", &self.stride)
+            .field("data", &self.iter_rows().collect::<Vec<_>>())
+            .finish()
+    }
+}
+
+impl<T> Default for Matrix<T> {
+    fn default() -> Matrix<T> {
+        Matrix::natural(Vec::new(), 0, 0)
+    }
+}
+
+impl<T> Index<usize> for Matrix<T> {
+    type Output = [T];
+
+    /// Returns row `i`
+    fn index(&self, i: usize) -> &[T] {
+        &self.as_slice()[i * self.stride..i * self.stride + self.cols]
+    }
+}
+
+impl<T> IndexMut<usize> for Matrix<T> {
+    /// Returns row `i`
+    fn index_mut(&mut self, i: usize) -> &mut [T] {
+        let (cols, stride) = (self.cols, self.stride);
+        &mut self.as_mut_slice()[i * stride..i * stride + cols]
+    }
+}
+
+impl<T> From<Vec<Vec<T>>> for Matrix<T> {
+    /// Creates a matrix from its rows
+    ///
+    /// # Panics
+    ///
+    /// Panics if the rows are not all the same length, see [`Matrix::from_rows`]
+    fn from(rows: Vec<Vec<T>>) -> Matrix<T> {
+        Matrix::from_rows(rows, "").expect("rows of different lengths")
+    }
+}
+
+/// Serialized form of a matrix: its dimensions and its elements row by row, without the padding
+/// and the alignment, which are properties of the storage rather than of the matrix
+#[cfg(feature = "serde")]
+#[derive(serde::Serialize, serde::Deserialize)]
+#[serde(rename = "Matrix")]
+struct SerializedMatrix<E> {
+    rows: usize,
+    cols: usize,
+    data: E,
+}
+
+/// Elements of a matrix, serialized as a sequence without being copied
+#[cfg(feature = "serde")]
+struct Elements<'a, T>(&'a Matrix<T>);
+
+#[cfg(feature = "serde")]
+impl<T: serde::Serialize> serde::Serialize for Elements<'_, T> {
+    fn serialize<S: serde::Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        serializer.collect_seq(self.0.iter_rows().flatten())
+    }
+}
+
+#[cfg(feature = "serde")]
+impl<T: serde::Serialize> serde::Serialize for Matrix<T> {
+    fn serialize<S: serde::Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        SerializedMatrix {
+            rows: self.rows,
+            cols: self.cols,
+            data: Elements(self),
+        }
+        .serialize(serializer)
+    }
+}
+
+#[cfg(feature = "serde")]
+impl<'de, T: serde::Deserialize<'de>> serde::Deserialize<'de> for Matrix<T> {
+    fn deserialize<D: serde::Deserializer<'de>>(deserializer: D) -> Result<Matrix<T>, D::Error> {
+        let m = SerializedMatrix::<Vec<T>>::deserialize(deserializer)?;
+        if Some(m.data.len()) != m.rows.checked_mul(m.cols) {
+            return Err(serde::de::Error::custom(format!(
+                "{} elements for a {}x{} matrix",
+                m.data.len(),
+                m.rows,
+                m.cols
+            )));
+        }
+        Ok(Matrix::from_vec(m.rows, m.cols, m.data))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_matrix() {
+        let mut m = Matrix::from(vec![vec![1, 2, 3], vec![4, 5, 6]]);
+        assert_eq!((m.rows(), m.cols()), (2, 3));
+        assert_eq!(m[1], [4, 5, 6]);
+        assert_eq!(m[0][2], 3);
+        m[1][0] = 7;
+        assert_eq!(m.as_slice(), [1, 2, 3, 7, 5, 6]);
+        assert_eq!(m.iter_rows().nth(1), Some(&[7, 5, 6][..]));
+        assert_eq!(m, Matrix::from_vec(2, 3, vec![1, 2, 3, 7, 5, 6]));
+        assert_eq!(
+            Matrix::from_fn(2, 2, |i, j| i * 2 + j),
+            Matrix::from_vec(2, 2, vec![0, 1, 2, 3])
+        );
+        assert_eq!(Matrix::<i32>::zeros(1, 2).as_slice(), [0, 0]);
+    }
+
+    #[test]
+    fn test_ragged_matrix() {
+        assert_eq!(
+            Matrix::from_rows(vec![vec![1, 2, 3], vec![4, 5], vec![6, 7, 8]], "C"),
+            Err(SanitizeError::RaggedMatrix("C".to_string()))
+        );
+        let empty = Matrix::<i32>::from(vec![]);
+        assert!(empty.is_empty());
+        assert_eq!(empty.iter_rows().count(), 0);
+    }
+
+    #[test]
+    fn test_aligned_matrix() {
+        let m = Matrix::from_fn(3, 5, |i, j| (i * 5 + j) as i32);
+        for align in [1, 4, 64, 256] {
+            let aligned = m.aligned(align);
+            assert!(aligned.is_aligned(align), "{} bytes", align);
+            assert_eq!(aligned, m);
+            assert_eq!(aligned[2], [10, 11, 12, 13, 14]);
+            assert!(aligned.clone().is_aligned(align));
+            assert!(aligned.map(|x| *x as i64).is_aligned(align));
+        }
+
+        let zeros = Matrix::<f64>::zeros_with_layout(7, 7, 7, 64);
+        assert!(zeros.is_aligned(64));
+        assert_eq!(zeros, Matrix::zeros(7, 7));
+        assert!(Matrix::<i32>::zeros_with_layout(0, 0, 0, 64).is_empty());
+    }
+
+    #[test]
+    fn test_padded_matrix() {
+        let m = Matrix::from_fn(3, 5, |i, j| (i * 5 + j) as i32);
+        let mut padded = m.padded(8);
+        assert_eq!(padded.stride(), 8);
+        assert_eq!(padded.as_slice().len(), 24);
+        assert_eq!(padded, m);
+        assert_eq!(padded[2], [10, 11, 12, 13, 14]);
+        assert_eq!(padded.iter_rows().nth(1), Some(&[5, 6, 7, 8, 9][..]));
+        assert_eq!(padded.as_slice()[5..8], [0, 0, 0]);
+
+        padded[1][4] = 20;
+        assert_eq!(padded.as_slice()[12], 20);
+        assert_eq!(padded.clone().stride(), 8);
+        assert_eq!(padded.map(|x| *x as i64).stride(), 8);
+        assert!(padded.padded(8).aligned(64).is_aligned(64));
+        assert_eq!(
+            Matrix::from_vec_strided(2, 1, 3, vec![1, 0, 0, 2, 0, 0]),
+            Matrix::from_vec(2, 1, vec![1, 2])
+        );
+        assert_eq!(
+            Matrix::<u8>::zeros_with_layout(4, 3, 16, 64)
+                .as_slice()
+                .len(),
+            64
+        );
+    }
+    #[cfg(feature = "serde")]
+    #[test]
+    fn test_serde_matrix() {
+        let m = Matrix::from_fn(2, 3, |i, j| (i * 3 + j) as i32).padded(4);
+        let json = serde_json::to_string(&m).unwrap();
+        assert_eq!(json, r#"{"rows":2,"cols":3,"data":[0,1,2,3,4,5]}"#);
+        assert_eq!(serde_json::from_str::<Matrix>(&json).unwrap(), m);
+
+        let err = serde_json::from_str::<Matrix>(r#"{"rows":2,"cols":3,"data":[0,1]}"#);
+        assert!(err
+            .unwrap_err()
+            .to_string()
+            .contains("2 elements for a 2x3 matrix"));
+    }
+}
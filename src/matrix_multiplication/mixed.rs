@@ -0,0 +1,61 @@
+//! Mixed-precision kernel, reading `f32` operands and accumulating in `f64`
+
+use super::{matrix::Matrix, types::Shape};
+
+/// Multiplies `a` and `b` with the ikj loop order, widening every operand to `f64` and
+/// accumulating the rows of C in an `f64` buffer, which is only rounded to `f32` once the row is
+/// complete. The rounding error is then that of a single conversion rather than of `k` additions.
+pub(super) fn multiply_f64_accumulation(
+    a: &Matrix<f32>,
+    b: &Matrix<f32>,
+    shape: Shape,
+) -> Matrix<f32> {
+    let mut c_i_wide = vec![0f64; shape.n];
+    let mut c = Matrix::zeros(shape.m, shape.n);
+
+    for (a_i, c_i) in a.iter_rows().zip(c.iter_rows_mut()) {
+        c_i_wide.fill(0.0);
+        for (a_ik, b_k) in a_i.iter().zip(b.iter_rows()) {
+            let a_ik = *a_ik as f64;
+            for (c_ij, b_kj) in c_i_wide.iter_mut().zip(b_k) {
+                *c_ij += a_ik * *b_kj as f64;
+            }
+        }
+        for (c_ij, wide) in c_i.iter_mut().zip(&c_i_wide) {
+            *c_ij = *wide as f32;
+        }
+    }
+
+    c
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_multiply_f64_accumulation() {
+        let (m, k, n) = (3, 4, 5);
+        let a = Matrix::from_fn(m, k, |i, p| (i + p) as f32 - 2.5);
+        let b = Matrix::from_fn(k, n, |p, j| (p * j) as f32 % 5.0 - 1.25);
+        let expected = Matrix::from_fn(m, n, |i, j| {
+            (0..k).map(|p| a[i][p] as f64 * b[p][j] as f64).sum::<f64>() as f32
+        });
+
+        assert_eq!(
+            multiply_f64_accumulation(&a, &b, Shape { m, k, n }),
+            expected
+        );
+    }
+
+    #[test]
+    fn test_accumulation_is_more_accurate() {
+        // 1 + 2^-24 + ... is rounded back to 1 at every step of an f32 accumulation
+        let k = 1024;
+        let a = Matrix::from_fn(1, k, |_, p| if p == 0 { 1.0 } else { f32::EPSILON / 2.0 });
+        let b = Matrix::from_fn(k, 1, |_, _| 1.0f32);
+
+        let c = multiply_f64_accumulation(&a, &b, Shape { m: 1, k, n: 1 });
+        assert!(c[0][0] > 1.0);
+    }
+}
@@ -0,0 +1,151 @@
+//! Multiplication on matrices stored in Morton (Z-order) layout: the matrices are split into a
+//! grid of leaf blocks whose side is a power of two, the blocks are stored one after the other
+//! along the Z-order curve, and the elements of every block are stored row-major. Every quadrant
+//! of a matrix at every level of the recursion is then a contiguous slice.
+
+use super::{element::Element, matrix::Matrix};
+
+/// Multiplies `a` and `b` converted to Morton layout, recursing on the quadrants down to leaf
+/// blocks at most `leaf_size` wide, which are multiplied with the ikj loop order.
+pub(super) fn multiply_morton<T: Element>(
+    a: &Matrix<T>,
+    b: &Matrix<T>,
+    size: usize,
+    leaf_size: usize,
+) -> Matrix<T> {
+    let (blocks, leaf) = leaf_grid(size, leaf_size);
+
+    let a = to_morton(a, blocks, leaf);
+    let b = to_morton(b, blocks, leaf);
+    let mut c = vec![T::zero(); a.len()];
+    multiply_recursive(&a, &b, &mut c, blocks, leaf);
+
+    from_morton(&c, size, leaf)
+}
+
+/// Returns the number of leaf blocks along a side, a power of two, and the side of the leaf
+/// blocks, the smallest that covers `size` without exceeding `leaf_size`.
+fn leaf_grid(size: usize, leaf_size: usize) -> (usize, usize) {
+    let mut blocks = 1;
+    while size.div_ceil(blocks) > leaf_size {
+        blocks *= 2;
+    }
+    (blocks, size.div_ceil(blocks))
+}
+
+/// Returns the position along the Z-order curve of the block at (`row`, `col`), interleaving the
+/// bits of the row (odd positions) and of the column (even positions).
+fn morton_index(row: usize, col: usize) -> usize {
+    let mut index = 0;
+    for bit in 0..usize::BITS as usize / 2 {
+        index |= ((col >> bit) & 1) << (2 * bit);
+        index |= ((row >> bit) & 1) << (2 * bit + 1);
+    }
+    index
+}
+
+/// Returns the offset of the element at (`i`, `j`) in Morton layout with `leaf` x `leaf` blocks
+fn morton_offset(i: usize, j: usize, leaf: usize) -> usize {
+    morton_index(i / leaf, j / leaf) * leaf * leaf + (i % leaf) * leaf + j % leaf
+}
+
+/// Converts `m` to Morton layout on a grid of `blocks` x `blocks` leaf blocks, filling the
+/// padding with zeros
+fn to_morton<T: Element>(m: &Matrix<T>, blocks: usize, leaf: usize) -> Vec<T> {
+    let padded = blocks * leaf;
+    let mut morton = vec![T::zero(); padded * padded];
+    for (i, row) in m.iter_rows().enumerate() {
+        for (j, m_ij) in row.iter().enumerate() {
+            morton[morton_offset(i, j, leaf)] = *m_ij;
+        }
+    }
+    morton
+}
+
+/// Converts the `size` x `size` top-left corner of `morton` back to row-major layout
+fn from_morton<T: Element>(morton: &[T], size: usize, leaf: usize) -> Matrix<T> {
+    Matrix::from_fn(size, size, |i, j| morton[morton_offset(i, j, leaf)])
+}
+
+/// Adds the product of `a` and `b` to `c`, all made of `blocks` x `blocks` leaf blocks in Morton
+/// layout
+fn multiply_recursive<T: Element>(a: &[T], b: &[T], c: &mut [T], blocks: usize, leaf: usize) {
+    if blocks == 1 {
+        multiply_leaf(a, b, c, leaf);
+        return;
+    }
+
+    // the quadrants are the four contiguous quarters, in the order 11, 12, 21, 22
+    let quarter = a.len() / 4;
+    let [a11, a12, a21, a22] = quadrants(a, quarter);
+    let [b11, b12, b21, b22] = quadrants(b, quarter);
+    let (c1, c2) = c.split_at_mut(2 * quarter);
+    let (c11, c12) = c1.split_at_mut(quarter);
+    let (c21, c22) = c2.split_at_mut(quarter);
+
+    let half = blocks / 2;
+    multiply_recursive(a11, b11, c11, half, leaf);
+    multiply_recursive(a12, b21, c11, half, leaf);
+    multiply_recursive(a11, b12, c12, half, leaf);
+    multiply_recursive(a12, b22, c12, half, leaf);
+    multiply_recursive(a21, b11, c21, half, leaf);
+    multiply_recursive(a22, b21, c21, half, leaf);
+    multiply_recursive(a21, b12, c22, half, leaf);
+    multiply_recursive(a22, b22, c22, half, leaf);
+}
+
+fn quadrants<T>(m: &[T], quarter: usize) -> [&[T]; 4] {
+    let (m1, m2) = m.split_at(2 * quarter);
+    let (m11, m12) = m1.split_at(quarter);
+    let (m21, m22) = m2.split_at(quarter);
+    [m11, m12, m21, m22]
+}
+
+/// Adds the product of the row-major `leaf` x `leaf` blocks `a` and `b` to `c`
+fn multiply_leaf<T: Element>(a: &[T], b: &[T], c: &mut [T], leaf: usize) {
+    for (a_i, c_i) in a.chunks(leaf).zip(c.chunks_mut(leaf)) {
+        for (a_ik, b_k) in a_i.iter().zip(b.chunks(leaf)) {
+            for (c_ij, b_kj) in c_i.iter_mut().zip(b_k) {
+                *c_ij += *a_ik * *b_kj;
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::matrix_multiplication::{matrix_multiplication_sequential_ikj, types::Shape};
+
+    #[test]
+    fn test_morton_index() {
+        assert_eq!(morton_index(0, 0), 0);
+        assert_eq!(morton_index(0, 1), 1);
+        assert_eq!(morton_index(1, 0), 2);
+        assert_eq!(morton_index(1, 1), 3);
+        assert_eq!(morton_index(2, 3), 13);
+    }
+
+    #[test]
+    fn test_leaf_grid() {
+        assert_eq!(leaf_grid(8, 8), (1, 8));
+        assert_eq!(leaf_grid(8, 3), (4, 2));
+        assert_eq!(leaf_grid(9, 4), (4, 3));
+    }
+
+    #[test]
+    fn test_multiply_morton() {
+        for (size, leaf_size) in [(1, 1), (5, 1), (7, 2), (12, 4), (13, 32)] {
+            let a = Matrix::from_fn(size, size, |i, j| (i * 3 + j) as i32 % 7 - 3);
+            let b = Matrix::from_fn(size, size, |i, j| (i + j * 5) as i32 % 9 - 4);
+
+            assert_eq!(
+                multiply_morton(&a, &b, size, leaf_size),
+                matrix_multiplication_sequential_ikj(&a, &b, Shape::square(size)).unwrap(),
+                "size {}, leaf size {}",
+                size,
+                leaf_size
+            );
+        }
+    }
+}
@@ -0,0 +1,112 @@
+//! Matrix multiplication on the first OpenCL device of the default platform
+
+use std::cell::RefCell;
+
+use ocl::{flags::MemFlags, Buffer, Context, Device, Kernel, Platform, Program, Queue};
+
+use super::matrix::Matrix;
+
+/// Naive kernel computing one element of C per work item, the rows of A and B being `lda` and
+/// `ldb` elements apart
+const KERNEL_SRC: &str = r#"
+__kernel void matrix_multiplication(
+    __global const int* a,
+    __global const int* b,
+    __global int* c,
+    const int size,
+    const int lda,
+    const int ldb
+) {
+    const int i = get_global_id(0);
+    const int j = get_global_id(1);
+
+    int sum = 0;
+    for (int k = 0; k < size; k++) {
+        sum += a[i * lda + k] * b[k * ldb + j];
+    }
+    c[i * size + j] = sum;
+}
+"#;
+
+thread_local! {
+    /// Queue and compiled program, kept alive for the whole run and set up by `initialize` before
+    /// the first run is timed, so that selecting the device and compiling the kernel are not part
+    /// of the measurements
+    static DEVICE: RefCell<Option<(Queue, Program)>> = const { RefCell::new(None) };
+}
+
+fn init_device() -> ocl::Result<(Queue, Program)> {
+    let platform = Platform::default();
+    let device = Device::first(platform)?;
+    let context = Context::builder()
+        .platform(platform)
+        .devices(device)
+        .build()?;
+    let program = Program::builder()
+        .devices(device)
+        .src(KERNEL_SRC)
+        .build(&context)?;
+    let queue = Queue::new(&context, device, None)?;
+
+    Ok((queue, program))
+}
+
+/// Selects the device and compiles the kernel for the calling thread, if not done yet
+pub(super) fn initialize() -> ocl::Result<()> {
+    DEVICE.with(|device| {
+        let mut device = device.borrow_mut();
+        if device.is_none() {
+            *device = Some(init_device()?);
+        }
+        Ok(())
+    })
+}
+
+/// Multiplies `a` and `b` on the OpenCL device. The measured time includes copying the matrices
+/// to and from the device, and setting up the device if it was not initialized beforehand.
+pub(super) fn multiply(a: &Matrix<i32>, b: &Matrix<i32>, size: usize) -> ocl::Result<Matrix<i32>> {
+    initialize()?;
+    DEVICE.with(|device| {
+        let device = device.borrow();
+        let (queue, program) = device.as_ref().unwrap();
+
+        let input_buffer = |host: &[i32]| {
+            Buffer::<i32>::builder()
+                .queue(queue.clone())
+                .flags(MemFlags::new().read_only())
+                .len(host.len())
+                .copy_host_slice(host)
+                .build()
+        };
+        // the matrices are copied with their padding, which the kernel skips
+        let a_buffer = input_buffer(a.as_slice())?;
+        let b_buffer = input_buffer(b.as_slice())?;
+        let c_buffer = Buffer::<i32>::builder()
+            .queue(queue.clone())
+            .flags(MemFlags::new().write_only())
+            .len(size * size)
+            .build()?;
+
+        let kernel = Kernel::builder()
+            .program(program)
+            .name("matrix_multiplication")
+            .queue(queue.clone())
+            .global_work_size([size, size])
+            .arg(&a_buffer)
+            .arg(&b_buffer)
+            .arg(&c_buffer)
+            .arg(size as i32)
+            .arg(a.stride() as i32)
+            .arg(b.stride() as i32)
+            .build()?;
+
+        // SAFETY: the kernel only accesses the `size` x `size` elements of the matrices in the
+        // buffers
+        unsafe { kernel.enq()? };
+
+        let mut c = vec![0; size * size];
+        c_buffer.read(&mut c).enq()?;
+
+        Ok(Matrix::from_vec(size, size, c))
+    })
+}
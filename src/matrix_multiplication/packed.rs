@@ -0,0 +1,133 @@
+//! BLIS/GotoBLAS-style multiplication: panels of A and B are packed into contiguous buffers sized
+//! to stay resident in cache, and a register-blocked micro-kernel computes `MR` x `NR` blocks of C.
+
+use super::{element::Element, matrix::Matrix};
+
+/// Rows of the block of C computed by the micro-kernel
+const MR: usize = 4;
+/// Columns of the block of C computed by the micro-kernel
+const NR: usize = 16;
+/// Depth of the packed panels, sized so that a panel of B stays in the L1 cache
+const KC: usize = 256;
+/// Rows of the packed block of A, sized so that it stays in the L2 cache
+const MC: usize = 64;
+/// Columns of the packed block of B, sized so that it stays in the L3 cache
+const NC: usize = 1024;
+
+pub(super) fn multiply_packed<T: Element>(a: &Matrix<T>, b: &Matrix<T>, size: usize) -> Matrix<T> {
+    let mut c = Matrix::zeros(size, size);
+    let mut a_packed = Vec::with_capacity(MC.next_multiple_of(MR) * KC);
+    let mut b_packed = Vec::with_capacity(NC.next_multiple_of(NR) * KC);
+
+    for jc in (0..size).step_by(NC) {
+        let nc = NC.min(size - jc);
+        for pc in (0..size).step_by(KC) {
+            let kc = KC.min(size - pc);
+            pack_b(b, pc, kc, jc, nc, &mut b_packed);
+
+            for ic in (0..size).step_by(MC) {
+                let mc = MC.min(size - ic);
+                pack_a(a, ic, mc, pc, kc, &mut a_packed);
+
+                for jr in (0..nc).step_by(NR) {
+                    let b_panel = &b_packed[jr * kc..(jr + NR) * kc];
+                    for ir in (0..mc).step_by(MR) {
+                        let a_panel = &a_packed[ir * kc..(ir + MR) * kc];
+                        let block = micro_kernel(kc, a_panel, b_panel);
+
+                        // the blocks on the edges of C are only partially inside the matrix
+                        let rows = MR.min(mc - ir);
+                        let cols = NR.min(nc - jr);
+                        for (i, block_row) in block.iter().enumerate().take(rows) {
+                            let c_row = &mut c[ic + ir + i][jc + jr..jc + jr + cols];
+                            for (c_ij, block_ij) in c_row.iter_mut().zip(block_row) {
+                                *c_ij += *block_ij;
+                            }
+                        }
+                    }
+                }
+            }
+        }
+    }
+
+    c
+}
+
+/// Packs the `mc` x `kc` block of A at (`ic`, `pc`) into panels of `MR` rows, each stored column
+/// by column, zero-padding the last panel.
+fn pack_a<T: Element>(
+    a: &Matrix<T>,
+    ic: usize,
+    mc: usize,
+    pc: usize,
+    kc: usize,
+    packed: &mut Vec<T>,
+) {
+    packed.clear();
+    for ir in (0..mc).step_by(MR) {
+        for p in 0..kc {
+            for i in ir..ir + MR {
+                packed.push(if i < mc { a[ic + i][pc + p] } else { T::zero() });
+            }
+        }
+    }
+}
+
+/// Packs the `kc` x `nc` block of B at (`pc`, `jc`) into panels of `NR` columns, each stored row
+/// by row, zero-padding the last panel.
+fn pack_b<T: Element>(
+    b: &Matrix<T>,
+    pc: usize,
+    kc: usize,
+    jc: usize,
+    nc: usize,
+    packed: &mut Vec<T>,
+) {
+    packed.clear();
+    for jr in (0..nc).step_by(NR) {
+        for b_p in b.iter_rows().skip(pc).take(kc) {
+            for j in jr..jr + NR {
+                packed.push(if j < nc { b_p[jc + j] } else { T::zero() });
+            }
+        }
+    }
+}
+
+/// Computes the `MR` x `NR` product of a packed panel of A and a packed panel of B of depth `kc`,
+/// keeping the accumulators in registers.
+#[inline(always)]
+fn micro_kernel<T: Element>(kc: usize, a_panel: &[T], b_panel: &[T]) -> [[T; NR]; MR] {
+    let mut acc = [[T::zero(); NR]; MR];
+    for p in 0..kc {
+        let a_p = &a_panel[p * MR..(p + 1) * MR];
+        let b_p = &b_panel[p * NR..(p + 1) * NR];
+        for (acc_i, a_ip) in acc.iter_mut().zip(a_p) {
+            for (acc_ij, b_pj) in acc_i.iter_mut().zip(b_p) {
+                *acc_ij += *a_ip * *b_pj;
+            }
+        }
+    }
+    acc
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::matrix_multiplication::{matrix_multiplication_sequential_ikj, types::Shape};
+
+    #[test]
+    fn test_multiply_packed() {
+        // sizes that are not multiples of the micro-kernel and span several KC panels
+        for size in [1, 5, 17, 300] {
+            let a = Matrix::from_fn(size, size, |i, j| ((i * 7 + j * 3) % 11) as i32 - 5);
+            let b = Matrix::from_fn(size, size, |i, j| ((i * 5 + j) % 13) as i32 - 6);
+
+            assert_eq!(
+                multiply_packed(&a, &b, size),
+                matrix_multiplication_sequential_ikj(&a, &b, Shape::square(size)).unwrap(),
+                "size {}",
+                size
+            );
+        }
+    }
+}
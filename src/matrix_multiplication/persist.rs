@@ -0,0 +1,104 @@
+use std::{
+    fs,
+    io::{self, BufRead, BufReader, BufWriter, Write},
+    path::{Path, PathBuf},
+};
+
+use super::matrix::Matrix;
+
+/// Returns the path of the file holding the given input matrix of the given iteration
+///
+/// # Arguments
+///
+/// * `dir` - The directory holding the inputs
+/// * `iteration` - The (zero-based) iteration the matrix belongs to
+/// * `matrix_name` - The name of the matrix (e.g. `a` or `b`)
+pub fn input_matrix_path(dir: &Path, iteration: usize, matrix_name: &str) -> PathBuf {
+    dir.join(format!("iteration_{}_{}.txt", iteration, matrix_name))
+}
+
+/// Writes a matrix to `path`, one row per line with space-separated values.
+/// Parent directories are created if missing.
+pub fn save_matrix(path: &Path, matrix: &Matrix<i32>) -> io::Result<()> {
+    if let Some(parent) = path.parent() {
+        fs::create_dir_all(parent)?;
+    }
+
+    let mut writer = BufWriter::new(fs::File::create(path)?);
+    for row in matrix.iter_rows() {
+        let line = row
+            .iter()
+            .map(|value| value.to_string())
+            .collect::<Vec<_>>()
+            .join(" ");
+        writeln!(writer, "{}", line)?;
+    }
+    writer.flush()
+}
+
+/// Reads a matrix previously written with `save_matrix` from `path`.
+///
+/// # Errors
+///
+/// Returns an `io::ErrorKind::InvalidData` error if a value cannot be parsed as an `i32`, or if the
+/// rows are not all the same length.
+pub fn load_matrix(path: &Path) -> io::Result<Matrix<i32>> {
+    let reader = BufReader::new(fs::File::open(path)?);
+
+    let mut rows = Vec::new();
+    for line in reader.lines() {
+        let line = line?;
+        if line.trim().is_empty() {
+            continue;
+        }
+        let row = line
+            .split_whitespace()
+            .map(|value| value.parse::<i32>())
+            .collect::<Result<Vec<_>, _>>()
+            .map_err(|err| {
+                io::Error::new(
+                    io::ErrorKind::InvalidData,
+                    format!("{}: {}", path.display(), err),
+                )
+            })?;
+        rows.push(row);
+    }
+
+    Matrix::from_rows(rows, &path.display().to_string())
+        .map_err(|err| io::Error::new(io::ErrorKind::InvalidData, err.to_string()))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_save_and_load_matrix() {
+        let dir = std::env::temp_dir().join(format!("mm_persist_{}", std::process::id()));
+        let path = input_matrix_path(&dir, 0, "a");
+        let matrix = Matrix::from(vec![vec![1, -2, 3], vec![4, 5, -6], vec![7, 8, 9]]);
+
+        save_matrix(&path, &matrix).unwrap();
+        assert_eq!(load_matrix(&path).unwrap(), matrix);
+
+        fs::remove_dir_all(&dir).unwrap();
+    }
+
+    #[test]
+    fn test_load_invalid_matrix() {
+        let dir = std::env::temp_dir().join(format!("mm_persist_invalid_{}", std::process::id()));
+        let path = input_matrix_path(&dir, 0, "a");
+        fs::create_dir_all(&dir).unwrap();
+        fs::write(&path, "1 2\n3 x\n").unwrap();
+
+        let err = load_matrix(&path).unwrap_err();
+        assert_eq!(err.kind(), io::ErrorKind::InvalidData);
+
+        fs::write(&path, "1 2\n3\n").unwrap();
+
+        let err = load_matrix(&path).unwrap_err();
+        assert_eq!(err.kind(), io::ErrorKind::InvalidData);
+
+        fs::remove_dir_all(&dir).unwrap();
+    }
+}
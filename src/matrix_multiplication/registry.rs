@@ -0,0 +1,154 @@
+//! Extension point of the benchmark: every kernel implementing `MatMulAlgorithm` can be added to a
+//! `Registry`, or registered with a `BenchmarkRunner`, which runs and reports it the same way as
+//! the built-in algorithms.
+
+use std::sync::Arc;
+
+use crate::thread_pool::ThreadPool;
+
+use super::{
+    algorithms::Algorithm, initialize, matrix::Matrix, matrix_multiplication,
+    sanitize::SanitizeError,
+};
+
+/// A matrix multiplication kernel that can be registered with the benchmark.
+/// Its `Display` implementation is the name shown in the results tables.
+pub trait MatMulAlgorithm: std::fmt::Display {
+    /// Returns the number of threads used by the kernel, or `None` if it is sequential.
+    /// Parallel kernels are given a thread pool of that many threads.
+    fn threads(&self) -> Option<usize>;
+
+    /// Returns the number of workers of the thread pool the kernel is given, or `None` if it is
+    /// given none, as the kernels running on threads of their own.
+    fn pool_threads(&self) -> Option<usize> {
+        self.threads()
+    }
+
+    /// Initializes the state the kernel keeps across its runs, before the first one is timed.
+    fn initialize(&self) {}
+
+    /// Returns the number the size of the square matrices the kernel multiplies must be a
+    /// multiple of, e.g. its tile size.
+    fn size_multiple(&self) -> usize {
+        1
+    }
+
+    /// Returns whether the kernel can multiply an `m` x `k` matrix by a `k` x `n` one, rather
+    /// than only square matrices of the same size.
+    fn supports_rectangular(&self) -> bool {
+        false
+    }
+
+    /// Multiplies `a` and `b`, submitting the jobs of parallel kernels to `pool` if one is given.
+    fn multiply(
+        &self,
+        a: &Matrix<i32>,
+        b: &Matrix<i32>,
+        pool: Option<&ThreadPool>,
+    ) -> Result<Matrix<i32>, SanitizeError>;
+}
+
+impl MatMulAlgorithm for Algorithm {
+    fn threads(&self) -> Option<usize> {
+        Algorithm::threads(self)
+    }
+
+    fn pool_threads(&self) -> Option<usize> {
+        Algorithm::pool_threads(self)
+    }
+
+    fn initialize(&self) {
+        initialize(*self)
+    }
+
+    fn size_multiple(&self) -> usize {
+        Algorithm::size_multiple(self)
+    }
+
+    fn supports_rectangular(&self) -> bool {
+        Algorithm::supports_rectangular(self)
+    }
+
+    fn multiply(
+        &self,
+        a: &Matrix<i32>,
+        b: &Matrix<i32>,
+        pool: Option<&ThreadPool>,
+    ) -> Result<Matrix<i32>, SanitizeError> {
+        matrix_multiplication(a, b, *self, pool)
+    }
+}
+
+/// Ordered collection of the kernels to benchmark
+#[derive(Default, Clone)]
+pub struct Registry {
+    algorithms: Vec<Arc<dyn MatMulAlgorithm>>,
+}
+
+impl Registry {
+    /// Adds `algorithm` after the kernels already registered
+    pub fn register<A: MatMulAlgorithm + 'static>(&mut self, algorithm: A) {
+        self.algorithms.push(Arc::new(algorithm));
+    }
+
+    /// Returns the registered kernels, in registration order
+    pub fn algorithms(&self) -> &[Arc<dyn MatMulAlgorithm>] {
+        &self.algorithms
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Kernel multiplying with the ijk loop order on plain indices
+    struct Naive;
+
+    impl std::fmt::Display for Naive {
+        fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+            write!(f, "Naive")
+        }
+    }
+
+    impl MatMulAlgorithm for Naive {
+        fn threads(&self) -> Option<usize> {
+            None
+        }
+
+        fn multiply(
+            &self,
+            a: &Matrix<i32>,
+            b: &Matrix<i32>,
+            _pool: Option<&ThreadPool>,
+        ) -> Result<Matrix<i32>, SanitizeError> {
+            Ok(Matrix::from_fn(a.rows(), b.cols(), |i, j| {
+                (0..b.rows()).map(|k| a[i][k] * b[k][j]).sum()
+            }))
+        }
+    }
+
+    #[test]
+    fn test_registry() {
+        let mut registry = Registry::default();
+        registry.register(Algorithm::SequentialIkj);
+        registry.register(Naive);
+
+        let names = registry
+            .algorithms()
+            .iter()
+            .map(|algorithm| algorithm.to_string())
+            .collect::<Vec<_>>();
+        assert_eq!(names, ["Sequential IKJ", "Naive"]);
+
+        let a = Matrix::from(vec![vec![1, 2], vec![3, 4]]);
+        let b = Matrix::from(vec![vec![5, 6], vec![7, 8]]);
+        for algorithm in registry.algorithms() {
+            assert_eq!(
+                algorithm.multiply(&a, &b, None),
+                Ok(Matrix::from(vec![vec![19, 22], vec![43, 50]]))
+            );
+        }
+        assert!(registry.algorithms()[0].supports_rectangular());
+        assert!(!registry.algorithms()[1].supports_rectangular());
+    }
+}
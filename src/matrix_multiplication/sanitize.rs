@@ -1,12 +1,43 @@
 use std::fmt;
 
-#[derive(Debug, PartialEq)]
+use super::{
+    algorithms::UNROLL_FACTORS,
+    matrix::Matrix,
+    types::{ElementType, Shape},
+};
+
+#[derive(Debug, Clone, PartialEq)]
 /// Enum to represent the errors that can occur during the sanitization of the matrices
 pub enum SanitizeError {
     EmptyMatrix(String),
     NotSquareMatrix(String),
+    NotTriangularMatrix(String),
     NotSameSize,
+    RaggedMatrix(String),
+    InnerDimensionMismatch,
+    /// The matrix C accumulating the product does not have the shape of the product
+    OutputShapeMismatch,
     SizeNotMultipleOfTileSize,
+    ZeroTileSize,
+    ZeroTilesPerJob,
+    OuterTileSizeNotMultipleOfInnerTileSize,
+    ZeroStrassenCutoff,
+    ZeroMortonLeafSize,
+    ZeroGridSize,
+    ZeroRowChunkSize,
+    UnsupportedUnrollFactor(usize),
+    SizeNotMultipleOfGridSize,
+    /// The algorithm is not implemented for the given element type
+    UnsupportedElementType(ElementType),
+    /// Elements of the named matrix do not fit in the given element type
+    ElementOutOfRange(String, ElementType),
+    /// The element of C at the given row and column overflows i32
+    Overflow(usize, usize),
+    /// The product may overflow i32, and the algorithm cannot compute it in i64 to check
+    MayOverflow,
+    /// The OpenCL runtime reported an error
+    #[cfg(feature = "opencl")]
+    OpenCl(String),
 }
 
 impl fmt::Display for SanitizeError {
@@ -16,38 +47,78 @@ impl fmt::Display for SanitizeError {
             SanitizeError::NotSquareMatrix(matrix_name) => {
                 write!(f, "Matrix {} is not square", matrix_name)
             }
+            SanitizeError::NotTriangularMatrix(matrix_name) => {
+                write!(f, "Matrix {} is not triangular", matrix_name)
+            }
             SanitizeError::NotSameSize => write!(f, "Matrices are not the same size"),
+            SanitizeError::RaggedMatrix(matrix_name) => {
+                write!(
+                    f,
+                    "Rows of matrix {} are not all the same length",
+                    matrix_name
+                )
+            }
+            SanitizeError::InnerDimensionMismatch => {
+                write!(f, "Number of columns of A differs from number of rows of B")
+            }
+            SanitizeError::OutputShapeMismatch => {
+                write!(
+                    f,
+                    "Matrix C does not have the shape of the product of A and B"
+                )
+            }
             SanitizeError::SizeNotMultipleOfTileSize => {
                 write!(f, "Matrix size is not a multiple of tile size")
             }
+            SanitizeError::ZeroTileSize => write!(f, "Tile size must be at least 1"),
+            SanitizeError::OuterTileSizeNotMultipleOfInnerTileSize => {
+                write!(f, "Outer tile size is not a multiple of inner tile size")
+            }
+            SanitizeError::ZeroTilesPerJob => write!(f, "Tiles per job must be at least 1"),
+            SanitizeError::ZeroStrassenCutoff => write!(f, "Strassen cutoff must be at least 1"),
+            SanitizeError::ZeroMortonLeafSize => write!(f, "Morton leaf size must be at least 1"),
+            SanitizeError::ZeroGridSize => write!(f, "Grid size must be at least 1"),
+            SanitizeError::UnsupportedUnrollFactor(unroll) => write!(
+                f,
+                "Unrolling factor {} is not one of {:?}",
+                unroll, UNROLL_FACTORS
+            ),
+            SanitizeError::ZeroRowChunkSize => write!(f, "Row chunk size must be at least 1"),
+            SanitizeError::SizeNotMultipleOfGridSize => {
+                write!(f, "Matrix size is not a multiple of grid size")
+            }
+            SanitizeError::UnsupportedElementType(element_type) => {
+                write!(f, "Algorithm not available for {} elements", element_type)
+            }
+            SanitizeError::ElementOutOfRange(matrix_name, element_type) => write!(
+                f,
+                "Elements of matrix {} do not fit in {}",
+                matrix_name, element_type
+            ),
+            SanitizeError::Overflow(i, j) => write!(f, "C[{}][{}] overflows i32", i, j),
+            SanitizeError::MayOverflow => write!(
+                f,
+                "The product may overflow i32, and cannot be computed in i64 to check it"
+            ),
+            #[cfg(feature = "opencl")]
+            SanitizeError::OpenCl(err) => write!(f, "OpenCL error: {}", err),
         }
     }
 }
 
-fn is_matrix_square(a: &Vec<Vec<i32>>, matrix_name: &str) -> Result<(), SanitizeError> {
-    if a.is_empty() {
+fn is_matrix_square<T>(a: &Matrix<T>, matrix_name: &str) -> Result<(), SanitizeError> {
+    if a.rows() == 0 {
         return Err(SanitizeError::EmptyMatrix(matrix_name.to_string()));
     }
 
-    let row_length = a.len();
-
-    let mut is_square = true;
-
-    for row in a {
-        if row.len() != row_length {
-            is_square = false;
-            break;
-        }
-    }
-
-    match is_square {
+    match a.cols() == a.rows() {
         true => Ok(()),
         false => Err(SanitizeError::NotSquareMatrix(matrix_name.to_string())),
     }
 }
 
-fn are_square_matrices_same_size(a: &Vec<Vec<i32>>, b: &Vec<Vec<i32>>) -> bool {
-    a.len() == b.len()
+fn are_square_matrices_same_size<T>(a: &Matrix<T>, b: &Matrix<T>) -> bool {
+    a.rows() == b.rows()
 }
 
 /// Sanitizes the matrices
@@ -60,7 +131,7 @@ fn are_square_matrices_same_size(a: &Vec<Vec<i32>>, b: &Vec<Vec<i32>>) -> bool {
 /// # Returns
 ///
 /// A `SanitizeResult` enum
-pub fn sanitize_matrices(a: &Vec<Vec<i32>>, b: &Vec<Vec<i32>>) -> Result<(), SanitizeError> {
+pub fn sanitize_matrices<T>(a: &Matrix<T>, b: &Matrix<T>) -> Result<(), SanitizeError> {
     is_matrix_square(a, "A")?;
 
     is_matrix_square(b, "B")?;
@@ -71,38 +142,131 @@ pub fn sanitize_matrices(a: &Vec<Vec<i32>>, b: &Vec<Vec<i32>>) -> Result<(), San
     }
 }
 
+/// Returns the number of rows and columns of `a`, checking that it is not empty
+fn matrix_dimensions<T>(a: &Matrix<T>, matrix_name: &str) -> Result<(usize, usize), SanitizeError> {
+    match a.is_empty() {
+        true => Err(SanitizeError::EmptyMatrix(matrix_name.to_string())),
+        false => Ok((a.rows(), a.cols())),
+    }
+}
+
+/// Sanitizes matrices that are not necessarily square, checking that `a` is `m` x `k` and `b` is
+/// `k` x `n`
+///
+/// # Returns
+///
+/// The shape of the product
+pub fn sanitize_rectangular_matrices<T>(
+    a: &Matrix<T>,
+    b: &Matrix<T>,
+) -> Result<Shape, SanitizeError> {
+    let (m, k) = matrix_dimensions(a, "A")?;
+    let (b_rows, n) = matrix_dimensions(b, "B")?;
+
+    match k == b_rows {
+        true => Ok(Shape { m, k, n }),
+        false => Err(SanitizeError::InnerDimensionMismatch),
+    }
+}
+
 pub fn extra_sanitization_steps_for_tiling_algorithm(
     size: usize,
     tile_size: usize,
+    tiles_per_job: usize,
 ) -> Result<(), SanitizeError> {
+    if tile_size == 0 {
+        return Err(SanitizeError::ZeroTileSize);
+    }
+    if tiles_per_job == 0 {
+        return Err(SanitizeError::ZeroTilesPerJob);
+    }
+
     match size % tile_size {
         0 => Ok(()),
         _ => Err(SanitizeError::SizeNotMultipleOfTileSize),
     }
 }
 
+pub fn extra_sanitization_steps_for_two_level_tiling_algorithm(
+    size: usize,
+    outer_tile_size: usize,
+    inner_tile_size: usize,
+) -> Result<(), SanitizeError> {
+    extra_sanitization_steps_for_tiling_algorithm(size, outer_tile_size, 1)?;
+    if inner_tile_size == 0 {
+        return Err(SanitizeError::ZeroTileSize);
+    }
+
+    match outer_tile_size % inner_tile_size {
+        0 => Ok(()),
+        _ => Err(SanitizeError::OuterTileSizeNotMultipleOfInnerTileSize),
+    }
+}
+
+pub fn extra_sanitization_steps_for_unrolled_algorithm(unroll: usize) -> Result<(), SanitizeError> {
+    match UNROLL_FACTORS.contains(&unroll) {
+        true => Ok(()),
+        false => Err(SanitizeError::UnsupportedUnrollFactor(unroll)),
+    }
+}
+
+pub fn extra_sanitization_steps_for_strassen_algorithm(cutoff: usize) -> Result<(), SanitizeError> {
+    match cutoff {
+        0 => Err(SanitizeError::ZeroStrassenCutoff),
+        _ => Ok(()),
+    }
+}
+
+pub fn extra_sanitization_steps_for_morton_algorithm(
+    leaf_size: usize,
+) -> Result<(), SanitizeError> {
+    match leaf_size {
+        0 => Err(SanitizeError::ZeroMortonLeafSize),
+        _ => Ok(()),
+    }
+}
+
+pub fn extra_sanitization_steps_for_row_chunks_algorithm(
+    chunk_size: usize,
+) -> Result<(), SanitizeError> {
+    match chunk_size {
+        0 => Err(SanitizeError::ZeroRowChunkSize),
+        _ => Ok(()),
+    }
+}
+
+pub fn extra_sanitization_steps_for_grid_algorithm(
+    size: usize,
+    grid: usize,
+) -> Result<(), SanitizeError> {
+    if grid == 0 {
+        return Err(SanitizeError::ZeroGridSize);
+    }
+
+    match size % grid {
+        0 => Ok(()),
+        _ => Err(SanitizeError::SizeNotMultipleOfGridSize),
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
 
-    fn get_3x3() -> Vec<Vec<i32>> {
-        vec![vec![1, 2, 3], vec![4, 5, 6], vec![7, 8, 9]]
-    }
-
-    fn get_2x2() -> Vec<Vec<i32>> {
-        vec![vec![1, 2], vec![3, 4]]
+    fn get_3x3() -> Matrix {
+        Matrix::from(vec![vec![1, 2, 3], vec![4, 5, 6], vec![7, 8, 9]])
     }
 
-    fn get_3x2() -> Vec<Vec<i32>> {
-        vec![vec![1, 2], vec![3, 4], vec![5, 6]]
+    fn get_2x2() -> Matrix {
+        Matrix::from(vec![vec![1, 2], vec![3, 4]])
     }
 
-    fn get_empty() -> Vec<Vec<i32>> {
-        vec![]
+    fn get_3x2() -> Matrix {
+        Matrix::from(vec![vec![1, 2], vec![3, 4], vec![5, 6]])
     }
 
-    fn get_row_of_different_length() -> Vec<Vec<i32>> {
-        vec![vec![1, 2, 3], vec![4, 5], vec![6, 7, 8]]
+    fn get_empty() -> Matrix {
+        Matrix::from(vec![])
     }
 
     #[test]
@@ -121,17 +285,12 @@ mod tests {
     fn test_is_matrix_square() {
         let a = get_3x3();
         let b = get_3x2();
-        let c = get_row_of_different_length();
 
         assert_eq!(is_matrix_square(&a, "A"), Ok(()));
         assert_eq!(
             is_matrix_square(&b, "B"),
             Err(SanitizeError::NotSquareMatrix("B".to_string()))
         );
-        assert_eq!(
-            is_matrix_square(&c, "C"),
-            Err(SanitizeError::NotSquareMatrix("C".to_string()))
-        );
     }
 
     #[test]
@@ -140,8 +299,8 @@ mod tests {
         let b = get_3x3();
         let c = get_2x2();
 
-        assert_eq!(are_square_matrices_same_size(&a, &b), true);
-        assert_eq!(are_square_matrices_same_size(&a, &c), false);
+        assert!(are_square_matrices_same_size(&a, &b));
+        assert!(!are_square_matrices_same_size(&a, &c));
     }
 
     #[test]
@@ -154,6 +313,33 @@ mod tests {
         assert_eq!(sanitize_matrices(&a, &c), Err(SanitizeError::NotSameSize));
     }
 
+    #[test]
+    fn test_sanitize_rectangular_matrices() {
+        let a = get_3x2();
+        let b = Matrix::from(vec![vec![1, 2, 3, 4], vec![5, 6, 7, 8]]);
+
+        assert_eq!(
+            sanitize_rectangular_matrices(&a, &b),
+            Ok(Shape { m: 3, k: 2, n: 4 })
+        );
+        assert_eq!(
+            sanitize_rectangular_matrices(&get_3x3(), &get_3x3()),
+            Ok(Shape::square(3))
+        );
+        assert_eq!(
+            sanitize_rectangular_matrices(&a, &get_3x3()),
+            Err(SanitizeError::InnerDimensionMismatch)
+        );
+        assert_eq!(
+            sanitize_rectangular_matrices(&a, &get_empty()),
+            Err(SanitizeError::EmptyMatrix("B".to_string()))
+        );
+        assert_eq!(
+            sanitize_rectangular_matrices(&Matrix::from(vec![vec![]]), &b),
+            Err(SanitizeError::EmptyMatrix("A".to_string()))
+        );
+    }
+
     #[test]
     fn test_size_multiple_of_tile_size() {
         let size = 4;
@@ -161,12 +347,97 @@ mod tests {
         let not_ok_tile_size = 3;
 
         assert_eq!(
-            extra_sanitization_steps_for_tiling_algorithm(size, ok_tile_size),
+            extra_sanitization_steps_for_tiling_algorithm(size, ok_tile_size, 1),
+            Ok(())
+        );
+        assert_eq!(
+            extra_sanitization_steps_for_tiling_algorithm(size, not_ok_tile_size, 1),
+            Err(SanitizeError::SizeNotMultipleOfTileSize)
+        );
+    }
+
+    #[test]
+    fn test_zero_tile_size() {
+        assert_eq!(
+            extra_sanitization_steps_for_tiling_algorithm(4, 0, 1),
+            Err(SanitizeError::ZeroTileSize)
+        );
+    }
+
+    #[test]
+    fn test_two_level_tiling() {
+        assert_eq!(
+            extra_sanitization_steps_for_two_level_tiling_algorithm(16, 8, 4),
             Ok(())
         );
         assert_eq!(
-            extra_sanitization_steps_for_tiling_algorithm(size, not_ok_tile_size),
+            extra_sanitization_steps_for_two_level_tiling_algorithm(16, 8, 0),
+            Err(SanitizeError::ZeroTileSize)
+        );
+        assert_eq!(
+            extra_sanitization_steps_for_two_level_tiling_algorithm(16, 8, 3),
+            Err(SanitizeError::OuterTileSizeNotMultipleOfInnerTileSize)
+        );
+        assert_eq!(
+            extra_sanitization_steps_for_two_level_tiling_algorithm(12, 8, 4),
             Err(SanitizeError::SizeNotMultipleOfTileSize)
         );
     }
+
+    #[test]
+    fn test_zero_morton_leaf_size() {
+        assert_eq!(
+            extra_sanitization_steps_for_morton_algorithm(0),
+            Err(SanitizeError::ZeroMortonLeafSize)
+        );
+        assert_eq!(extra_sanitization_steps_for_morton_algorithm(1), Ok(()));
+    }
+
+    #[test]
+    fn test_unsupported_unroll_factor() {
+        assert_eq!(extra_sanitization_steps_for_unrolled_algorithm(8), Ok(()));
+        assert_eq!(
+            extra_sanitization_steps_for_unrolled_algorithm(3),
+            Err(SanitizeError::UnsupportedUnrollFactor(3))
+        );
+    }
+
+    #[test]
+    fn test_zero_tiles_per_job() {
+        assert_eq!(
+            extra_sanitization_steps_for_tiling_algorithm(4, 2, 0),
+            Err(SanitizeError::ZeroTilesPerJob)
+        );
+    }
+
+    #[test]
+    fn test_zero_strassen_cutoff() {
+        assert_eq!(extra_sanitization_steps_for_strassen_algorithm(1), Ok(()));
+        assert_eq!(
+            extra_sanitization_steps_for_strassen_algorithm(0),
+            Err(SanitizeError::ZeroStrassenCutoff)
+        );
+    }
+
+    #[test]
+    fn test_grid_size() {
+        assert_eq!(extra_sanitization_steps_for_grid_algorithm(6, 3), Ok(()));
+        assert_eq!(
+            extra_sanitization_steps_for_grid_algorithm(6, 4),
+            Err(SanitizeError::SizeNotMultipleOfGridSize)
+        );
+        assert_eq!(
+            extra_sanitization_steps_for_grid_algorithm(6, 0),
+            Err(SanitizeError::ZeroGridSize)
+        );
+    }
+
+    #[test]
+    fn test_zero_row_chunk_size() {
+        assert_eq!(extra_sanitization_steps_for_row_chunks_algorithm(1), Ok(()));
+        assert_eq!(
+            extra_sanitization_steps_for_row_chunks_algorithm(0),
+            Err(SanitizeError::ZeroRowChunkSize)
+        );
+    }
 }
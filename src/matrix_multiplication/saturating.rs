@@ -0,0 +1,120 @@
+//! i32 numbers whose arithmetic saturates at the bounds of i32 instead of wrapping, multiplied by
+//! the generic kernels like any other element type
+
+use std::ops::{Add, AddAssign, Div, DivAssign, Mul, MulAssign, Rem, RemAssign, Sub, SubAssign};
+
+use num_traits::{Num, One, Zero};
+
+/// i32 number whose additions, subtractions and multiplications clamp their results to
+/// `i32::MIN..=i32::MAX`
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash, Default)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct Saturating(pub i32);
+
+impl std::fmt::Display for Saturating {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}", self.0)
+    }
+}
+
+impl Add for Saturating {
+    type Output = Saturating;
+
+    fn add(self, rhs: Saturating) -> Saturating {
+        Saturating(self.0.saturating_add(rhs.0))
+    }
+}
+
+impl Sub for Saturating {
+    type Output = Saturating;
+
+    fn sub(self, rhs: Saturating) -> Saturating {
+        Saturating(self.0.saturating_sub(rhs.0))
+    }
+}
+
+impl Mul for Saturating {
+    type Output = Saturating;
+
+    fn mul(self, rhs: Saturating) -> Saturating {
+        Saturating(self.0.saturating_mul(rhs.0))
+    }
+}
+
+impl Div for Saturating {
+    type Output = Saturating;
+
+    fn div(self, rhs: Saturating) -> Saturating {
+        Saturating(self.0.saturating_div(rhs.0))
+    }
+}
+
+impl Rem for Saturating {
+    type Output = Saturating;
+
+    /// The only overflowing remainder, `i32::MIN % -1`, is 0
+    fn rem(self, rhs: Saturating) -> Saturating {
+        Saturating(self.0.wrapping_rem(rhs.0))
+    }
+}
+
+macro_rules! impl_assign_op {
+    ($trait:ident, $method:ident, $op:tt) => {
+        impl $trait for Saturating {
+            fn $method(&mut self, rhs: Saturating) {
+                *self = *self $op rhs;
+            }
+        }
+    };
+}
+
+impl_assign_op!(AddAssign, add_assign, +);
+impl_assign_op!(SubAssign, sub_assign, -);
+impl_assign_op!(MulAssign, mul_assign, *);
+impl_assign_op!(DivAssign, div_assign, /);
+impl_assign_op!(RemAssign, rem_assign, %);
+
+impl Zero for Saturating {
+    fn zero() -> Saturating {
+        Saturating(0)
+    }
+
+    fn is_zero(&self) -> bool {
+        self.0 == 0
+    }
+}
+
+impl One for Saturating {
+    fn one() -> Saturating {
+        Saturating(1)
+    }
+}
+
+impl Num for Saturating {
+    type FromStrRadixErr = std::num::ParseIntError;
+
+    fn from_str_radix(s: &str, radix: u32) -> Result<Saturating, std::num::ParseIntError> {
+        i32::from_str_radix(s, radix).map(Saturating)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_saturating_arithmetic() {
+        let (max, min) = (Saturating(i32::MAX), Saturating(i32::MIN));
+        assert_eq!(max + Saturating(1), max);
+        assert_eq!(min - Saturating(1), min);
+        assert_eq!(max * Saturating(-2), min);
+        assert_eq!(min / Saturating(-1), max);
+        assert_eq!(min % Saturating(-1), Saturating(0));
+
+        let mut x = Saturating(7);
+        x *= Saturating(-3);
+        x += Saturating(1);
+        assert_eq!(x, Saturating(-20));
+        assert_eq!(Saturating::from_str_radix("-ff", 16), Ok(Saturating(-255)));
+    }
+}
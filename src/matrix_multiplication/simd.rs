@@ -0,0 +1,335 @@
+//! Explicitly vectorized kernels, selected at runtime depending on the features of the CPU
+
+use super::{matrix::Matrix, matrix_multiplication_sequential_ikj, types::Shape};
+
+/// Returns whether the CPU supports AVX2, so that the AVX2 kernel does not fall back to scalar code
+pub fn avx2_detected() -> bool {
+    #[cfg(target_arch = "x86_64")]
+    {
+        is_x86_feature_detected!("avx2")
+    }
+    #[cfg(not(target_arch = "x86_64"))]
+    {
+        false
+    }
+}
+
+/// Returns whether the CPU supports AVX-512, so that the AVX-512 kernel does not fall back to
+/// scalar code
+pub fn avx512_detected() -> bool {
+    #[cfg(target_arch = "x86_64")]
+    {
+        is_x86_feature_detected!("avx512f")
+    }
+    #[cfg(not(target_arch = "x86_64"))]
+    {
+        false
+    }
+}
+
+/// Returns whether the CPU supports NEON, so that the NEON kernel does not fall back to scalar
+/// code
+pub fn neon_detected() -> bool {
+    #[cfg(target_arch = "aarch64")]
+    {
+        std::arch::is_aarch64_feature_detected!("neon")
+    }
+    #[cfg(not(target_arch = "aarch64"))]
+    {
+        false
+    }
+}
+
+/// Hints the CPU to load the cache line holding `ptr` into all the cache levels. Does nothing on
+/// architectures without a prefetch instruction.
+#[inline(always)]
+pub(super) fn prefetch_read<T>(ptr: *const T) {
+    #[cfg(target_arch = "x86_64")]
+    // SAFETY: prefetching is only a hint, and never faults even if `ptr` is not valid; SSE is
+    // part of the x86_64 baseline
+    unsafe {
+        use std::arch::x86_64::{_mm_prefetch, _MM_HINT_T0};
+        _mm_prefetch::<_MM_HINT_T0>(ptr as *const i8);
+    }
+    #[cfg(target_arch = "aarch64")]
+    // SAFETY: prefetching is only a hint, and never faults even if `ptr` is not valid
+    unsafe {
+        std::arch::asm!("prfm pldl1keep, [{0}]", in(reg) ptr, options(nostack, readonly, preserves_flags));
+    }
+    #[cfg(not(any(target_arch = "x86_64", target_arch = "aarch64")))]
+    let _ = ptr;
+}
+
+/// Multiplies `a` and `b` with the ikj loop order, computing 8 elements of a row of C at a time
+/// with AVX2 instructions. Falls back to the scalar ikj kernel if AVX2 is not available. The rows
+/// of B and C are loaded with aligned instructions if B is aligned to 32 bytes and its stride is a
+/// multiple of 8 elements, C being given the same stride.
+pub(super) fn multiply_avx2(a: &Matrix<i32>, b: &Matrix<i32>, size: usize) -> Matrix<i32> {
+    #[cfg(target_arch = "x86_64")]
+    if avx2_detected() {
+        // SAFETY: AVX2 support was just checked
+        return unsafe { x86::multiply_avx2(a, b, size) };
+    }
+
+    matrix_multiplication_sequential_ikj(a, b, Shape::square(size)).unwrap()
+}
+
+/// Multiplies `a` and `b` with the ikj loop order, computing 16 elements of a row of C at a time
+/// with AVX-512 instructions. Falls back to the scalar ikj kernel if AVX-512 is not available. The
+/// rows of B and C are loaded with aligned instructions if B is aligned to 64 bytes and its stride
+/// is a multiple of 16 elements, C being given the same stride.
+pub(super) fn multiply_avx512(a: &Matrix<i32>, b: &Matrix<i32>, size: usize) -> Matrix<i32> {
+    #[cfg(target_arch = "x86_64")]
+    if avx512_detected() {
+        // SAFETY: AVX-512 support was just checked
+        return unsafe { x86::multiply_avx512(a, b, size) };
+    }
+
+    matrix_multiplication_sequential_ikj(a, b, Shape::square(size)).unwrap()
+}
+
+/// Multiplies `a` and `b` with the ikj loop order, computing 4 elements of a row of C at a time
+/// with NEON instructions. Falls back to the scalar ikj kernel if NEON is not available.
+pub(super) fn multiply_neon(a: &Matrix<i32>, b: &Matrix<i32>, size: usize) -> Matrix<i32> {
+    #[cfg(target_arch = "aarch64")]
+    if neon_detected() {
+        // SAFETY: NEON support was just checked
+        return unsafe { arm::multiply_neon(a, b, size) };
+    }
+
+    matrix_multiplication_sequential_ikj(a, b, Shape::square(size)).unwrap()
+}
+
+#[cfg(target_arch = "x86_64")]
+mod x86 {
+    use std::arch::x86_64::*;
+
+    use crate::matrix_multiplication::matrix::Matrix;
+
+    /// Number of i32 lanes of an AVX2 register
+    const AVX2_LANES: usize = 8;
+    /// Number of i32 lanes of an AVX-512 register
+    const AVX512_LANES: usize = 16;
+
+    /// Returns whether every row of `b` is aligned to the `lanes` i32 of a register
+    fn rows_aligned(b: &Matrix<i32>, lanes: usize) -> bool {
+        b.is_aligned(lanes * 4) && b.stride().is_multiple_of(lanes)
+    }
+
+    /// # Safety
+    ///
+    /// The CPU must support AVX2, and the matrices must be `size` x `size`.
+    #[target_feature(enable = "avx2")]
+    pub(super) unsafe fn multiply_avx2(
+        a: &Matrix<i32>,
+        b: &Matrix<i32>,
+        size: usize,
+    ) -> Matrix<i32> {
+        match rows_aligned(b, AVX2_LANES) {
+            true => multiply_avx2_loads::<true>(a, b, size),
+            false => multiply_avx2_loads::<false>(a, b, size),
+        }
+    }
+
+    /// # Safety
+    ///
+    /// The CPU must support AVX2, and the matrices must be `size` x `size`, with rows aligned to
+    /// 32 bytes if `ALIGNED`.
+    #[target_feature(enable = "avx2")]
+    unsafe fn multiply_avx2_loads<const ALIGNED: bool>(
+        a: &Matrix<i32>,
+        b: &Matrix<i32>,
+        size: usize,
+    ) -> Matrix<i32> {
+        // the rows of C are aligned like those of B by giving it the same stride
+        let stride = if ALIGNED { b.stride() } else { size };
+        let mut c = Matrix::<i32>::zeros_with_layout(size, size, stride, AVX2_LANES * 4);
+        let vectorized = size - size % AVX2_LANES;
+
+        for i in 0..size {
+            let c_i = c[i].as_mut_ptr();
+            for k in 0..size {
+                let a_ik = a[i][k];
+                let a_ik_v = _mm256_set1_epi32(a_ik);
+                let b_k = b[k].as_ptr();
+                for j in (0..vectorized).step_by(AVX2_LANES) {
+                    let (b_kj, c_ij) = (b_k.add(j) as *const __m256i, c_i.add(j) as *mut __m256i);
+                    let (b_v, c_v) = match ALIGNED {
+                        true => (_mm256_load_si256(b_kj), _mm256_load_si256(c_ij)),
+                        false => (_mm256_loadu_si256(b_kj), _mm256_loadu_si256(c_ij)),
+                    };
+                    let c_v = _mm256_add_epi32(c_v, _mm256_mullo_epi32(a_ik_v, b_v));
+                    match ALIGNED {
+                        true => _mm256_store_si256(c_ij, c_v),
+                        false => _mm256_storeu_si256(c_ij, c_v),
+                    }
+                }
+                for j in vectorized..size {
+                    *c_i.add(j) += a_ik * *b_k.add(j);
+                }
+            }
+        }
+
+        c
+    }
+
+    /// # Safety
+    ///
+    /// The CPU must support AVX-512F, and the matrices must be `size` x `size`.
+    #[target_feature(enable = "avx512f")]
+    pub(super) unsafe fn multiply_avx512(
+        a: &Matrix<i32>,
+        b: &Matrix<i32>,
+        size: usize,
+    ) -> Matrix<i32> {
+        match rows_aligned(b, AVX512_LANES) {
+            true => multiply_avx512_loads::<true>(a, b, size),
+            false => multiply_avx512_loads::<false>(a, b, size),
+        }
+    }
+
+    /// # Safety
+    ///
+    /// The CPU must support AVX-512F, and the matrices must be `size` x `size`, with rows aligned
+    /// to 64 bytes if `ALIGNED`.
+    #[target_feature(enable = "avx512f")]
+    unsafe fn multiply_avx512_loads<const ALIGNED: bool>(
+        a: &Matrix<i32>,
+        b: &Matrix<i32>,
+        size: usize,
+    ) -> Matrix<i32> {
+        // the rows of C are aligned like those of B by giving it the same stride
+        let stride = if ALIGNED { b.stride() } else { size };
+        let mut c = Matrix::<i32>::zeros_with_layout(size, size, stride, AVX512_LANES * 4);
+        let vectorized = size - size % AVX512_LANES;
+
+        for i in 0..size {
+            let c_i = c[i].as_mut_ptr();
+            for k in 0..size {
+                let a_ik = a[i][k];
+                let a_ik_v = _mm512_set1_epi32(a_ik);
+                let b_k = b[k].as_ptr();
+                for j in (0..vectorized).step_by(AVX512_LANES) {
+                    let (b_kj, c_ij) = (b_k.add(j) as *const __m512i, c_i.add(j) as *mut __m512i);
+                    let (b_v, c_v) = match ALIGNED {
+                        true => (_mm512_load_si512(b_kj), _mm512_load_si512(c_ij)),
+                        false => (_mm512_loadu_si512(b_kj), _mm512_loadu_si512(c_ij)),
+                    };
+                    let c_v = _mm512_add_epi32(c_v, _mm512_mullo_epi32(a_ik_v, b_v));
+                    match ALIGNED {
+                        true => _mm512_store_si512(c_ij, c_v),
+                        false => _mm512_storeu_si512(c_ij, c_v),
+                    }
+                }
+                for j in vectorized..size {
+                    *c_i.add(j) += a_ik * *b_k.add(j);
+                }
+            }
+        }
+
+        c
+    }
+}
+
+#[cfg(target_arch = "aarch64")]
+mod arm {
+    use std::arch::aarch64::*;
+
+    use crate::matrix_multiplication::matrix::Matrix;
+
+    /// Number of i32 lanes of a NEON register
+    const NEON_LANES: usize = 4;
+
+    /// # Safety
+    ///
+    /// The CPU must support NEON, and the matrices must be `size` x `size`.
+    #[target_feature(enable = "neon")]
+    pub(super) unsafe fn multiply_neon(
+        a: &Matrix<i32>,
+        b: &Matrix<i32>,
+        size: usize,
+    ) -> Matrix<i32> {
+        let mut c = Matrix::<i32>::zeros_with_layout(size, size, size, NEON_LANES * 4);
+        let vectorized = size - size % NEON_LANES;
+
+        for i in 0..size {
+            let c_i = c[i].as_mut_ptr();
+            for k in 0..size {
+                let a_ik = a[i][k];
+                let a_ik_v = vdupq_n_s32(a_ik);
+                let b_k = b[k].as_ptr();
+                for j in (0..vectorized).step_by(NEON_LANES) {
+                    let b_v = vld1q_s32(b_k.add(j));
+                    let c_v = vld1q_s32(c_i.add(j));
+                    vst1q_s32(c_i.add(j), vmlaq_s32(c_v, a_ik_v, b_v));
+                }
+                for j in vectorized..size {
+                    *c_i.add(j) += a_ik * *b_k.add(j);
+                }
+            }
+        }
+
+        c
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn get_matrices(size: usize) -> (Matrix<i32>, Matrix<i32>) {
+        let a = Matrix::from_fn(size, size, |i, j| (i * 3 + j) as i32 % 7 - 3);
+        let b = Matrix::from_fn(size, size, |i, j| (i + j * 5) as i32 % 9 - 4);
+        (a, b)
+    }
+
+    #[test]
+    fn test_multiply_avx2() {
+        // 11 columns exercise both the vectorized part and the scalar remainder
+        let (a, b) = get_matrices(11);
+
+        assert_eq!(
+            multiply_avx2(&a, &b, 11),
+            matrix_multiplication_sequential_ikj(&a, &b, Shape::square(11)).unwrap()
+        );
+    }
+
+    #[test]
+    fn test_multiply_avx512() {
+        let (a, b) = get_matrices(19);
+
+        assert_eq!(
+            multiply_avx512(&a, &b, 19),
+            matrix_multiplication_sequential_ikj(&a, &b, Shape::square(19)).unwrap()
+        );
+    }
+
+    #[test]
+    fn test_multiply_aligned() {
+        // rows of 32 elements are aligned to both vector widths
+        let (a, b) = get_matrices(32);
+        let b = b.aligned(64);
+        let expected = matrix_multiplication_sequential_ikj(&a, &b, Shape::square(32)).unwrap();
+
+        assert_eq!(multiply_avx2(&a, &b, 32), expected);
+        assert_eq!(multiply_avx512(&a, &b, 32), expected);
+
+        // rows of 19 elements are aligned once padded to 32
+        let (a, b) = get_matrices(19);
+        let b = b.padded(32).aligned(64);
+        let expected = matrix_multiplication_sequential_ikj(&a, &b, Shape::square(19)).unwrap();
+
+        assert_eq!(multiply_avx2(&a, &b, 19), expected);
+        assert_eq!(multiply_avx512(&a, &b, 19), expected);
+    }
+
+    #[test]
+    fn test_multiply_neon() {
+        let (a, b) = get_matrices(7);
+
+        assert_eq!(
+            multiply_neon(&a, &b, 7),
+            matrix_multiplication_sequential_ikj(&a, &b, Shape::square(7)).unwrap()
+        );
+    }
+}
@@ -0,0 +1,339 @@
+//! Strassen's sub-cubic matrix multiplication on square matrices, recursing on views of their
+//! quadrants
+
+use std::sync::mpsc;
+
+use crate::thread_pool::ThreadPool;
+
+use super::{
+    algorithms::TileScheduling, element::Element, matrix::Matrix,
+    matrix_multiplication_parallel_tiling, multiply_add, view::MatrixView,
+};
+
+/// Kernel multiplying two square matrices, used below the cutoff
+type BaseKernel<'a, T> = &'a dyn Fn(MatrixView<T>, MatrixView<T>) -> Matrix<T>;
+
+/// Multiplies `a` and `b` with Strassen's algorithm, recursing while the blocks are larger than
+/// `cutoff` and falling back to the classical ikj kernel below it.
+pub(super) fn strassen<T: Element>(
+    a: &Matrix<T>,
+    b: &Matrix<T>,
+    size: usize,
+    cutoff: usize,
+) -> Matrix<T> {
+    let (padded, base_size) = padded_size(size, cutoff, 1);
+    padded_multiplication(a, b, size, padded, |a, b| {
+        strassen_recursive(a, b, base_size, &multiply_ikj)
+    })
+}
+
+/// Multiplies `a` and `b` with the Winograd form of Strassen's algorithm, which needs 15 block
+/// additions per recursion step instead of 18, falling back to the classical ikj kernel at or
+/// below `cutoff`.
+pub(super) fn strassen_winograd<T: Element>(
+    a: &Matrix<T>,
+    b: &Matrix<T>,
+    size: usize,
+    cutoff: usize,
+) -> Matrix<T> {
+    let (padded, base_size) = padded_size(size, cutoff, 1);
+    padded_multiplication(a, b, size, padded, |a, b| {
+        winograd_recursive(a, b, base_size)
+    })
+}
+
+/// Multiplies `a` and `b` with Strassen's algorithm down to `cutoff`, and multiplies the blocks
+/// below it with the parallel tiling kernel on `pool`. The blocks are padded to a multiple of
+/// `tile_size`, so the recursion stops at the tile size if `cutoff` is smaller.
+pub(super) fn strassen_tiled<T: Element>(
+    a: &Matrix<T>,
+    b: &Matrix<T>,
+    size: usize,
+    cutoff: usize,
+    tile_size: usize,
+    pool: &ThreadPool,
+) -> Matrix<T> {
+    let (padded, base_size) = padded_size(size, cutoff.max(tile_size), tile_size);
+    let tiled = |a: MatrixView<T>, b: MatrixView<T>| {
+        let n = a.rows();
+        let c = matrix_multiplication_parallel_tiling(
+            a,
+            b,
+            n,
+            n,
+            tile_size,
+            1,
+            TileScheduling::Dynamic,
+            // only the static scheduling splits the tiles by the number of threads
+            pool.size(),
+            pool,
+        );
+        Matrix::from_vec(n, n, c)
+    };
+    padded_multiplication(a, b, size, padded, |a, b| {
+        strassen_recursive(a, b, base_size, &tiled)
+    })
+}
+
+/// Multiplies `a` and `b` with Strassen's algorithm, submitting the seven subproducts of the first
+/// recursion step to `pool`. Each job recurses sequentially, falling back to the classical ikj
+/// kernel at or below `cutoff`.
+pub(super) fn strassen_parallel<T: Element>(
+    a: &Matrix<T>,
+    b: &Matrix<T>,
+    size: usize,
+    cutoff: usize,
+    pool: &ThreadPool,
+) -> Matrix<T> {
+    let (padded, base_size) = padded_size(size, cutoff, 1);
+    padded_multiplication(a, b, size, padded, |a, b| {
+        if a.rows() <= base_size {
+            return multiply_ikj(a, b);
+        }
+
+        let [a11, a12, a21, a22] = a.quadrants();
+        let [b11, b12, b21, b22] = b.quadrants();
+
+        // the jobs must own their operands, so the quadrants used as they are get copied too
+        let operands = [
+            (add(a11, a22), add(b11, b22)),
+            (add(a21, a22), b11.to_matrix()),
+            (a11.to_matrix(), sub(b12, b22)),
+            (a22.to_matrix(), sub(b21, b11)),
+            (add(a11, a12), b22.to_matrix()),
+            (sub(a21, a11), add(b11, b12)),
+            (sub(a12, a22), add(b21, b22)),
+        ];
+
+        let (sender, receiver) = mpsc::channel();
+        for (index, (a, b)) in operands.into_iter().enumerate() {
+            let sender = sender.clone();
+            pool.execute(move || {
+                let m = strassen_recursive(a.view(), b.view(), base_size, &multiply_ikj);
+                sender.send((index, m)).unwrap();
+            });
+        }
+        drop(sender);
+
+        pool.wait();
+
+        let mut m: [Matrix<T>; 7] = Default::default();
+        for (index, product) in receiver {
+            m[index] = product;
+        }
+        combine(m)
+    })
+}
+
+/// Runs `recursive` on `a` and `b` zero-padded to `padded` x `padded`, and removes the padding
+/// from the result. Matrices that need no padding are passed as views, whatever their stride.
+fn padded_multiplication<T: Element>(
+    a: &Matrix<T>,
+    b: &Matrix<T>,
+    size: usize,
+    padded: usize,
+    recursive: impl FnOnce(MatrixView<T>, MatrixView<T>) -> Matrix<T>,
+) -> Matrix<T> {
+    if padded == size {
+        return recursive(a.view(), b.view());
+    }
+
+    let c = recursive(pad(a, padded).view(), pad(b, padded).view());
+    c.view().block(0, 0, size, size).to_matrix()
+}
+
+/// Returns the smallest size not less than `size` that can be halved repeatedly until the blocks
+/// are at most `cutoff` wide, and the size of those blocks. The blocks are rounded up to a
+/// multiple of `multiple`, which may make them wider than `cutoff`.
+fn padded_size(size: usize, cutoff: usize, multiple: usize) -> (usize, usize) {
+    let mut blocks = 1;
+    while size.div_ceil(blocks) > cutoff {
+        blocks *= 2;
+    }
+    let base_size = size.div_ceil(blocks).next_multiple_of(multiple);
+    (base_size * blocks, base_size)
+}
+
+/// Copies `m` into a `padded` x `padded` matrix, filling the extra rows and columns with zeros
+fn pad<T: Element>(m: &Matrix<T>, padded: usize) -> Matrix<T> {
+    let mut c = Matrix::zeros(padded, padded);
+    c.view_mut()
+        .block_mut(0, 0, m.rows(), m.cols())
+        .copy_from(m.view());
+    c
+}
+
+fn strassen_recursive<T: Element>(
+    a: MatrixView<T>,
+    b: MatrixView<T>,
+    base_size: usize,
+    base: BaseKernel<T>,
+) -> Matrix<T> {
+    if a.rows() <= base_size {
+        return base(a, b);
+    }
+
+    let [a11, a12, a21, a22] = a.quadrants();
+    let [b11, b12, b21, b22] = b.quadrants();
+
+    let m1 = strassen_recursive(add(a11, a22).view(), add(b11, b22).view(), base_size, base);
+    let m2 = strassen_recursive(add(a21, a22).view(), b11, base_size, base);
+    let m3 = strassen_recursive(a11, sub(b12, b22).view(), base_size, base);
+    let m4 = strassen_recursive(a22, sub(b21, b11).view(), base_size, base);
+    let m5 = strassen_recursive(add(a11, a12).view(), b22, base_size, base);
+    let m6 = strassen_recursive(sub(a21, a11).view(), add(b11, b12).view(), base_size, base);
+    let m7 = strassen_recursive(sub(a12, a22).view(), add(b21, b22).view(), base_size, base);
+
+    combine([m1, m2, m3, m4, m5, m6, m7])
+}
+
+/// Combines the seven subproducts of a Strassen recursion step into the product
+fn combine<T: Element>([m1, m2, m3, m4, m5, m6, m7]: [Matrix<T>; 7]) -> Matrix<T> {
+    let c11 = add(&sub(&add(&m1, &m4), &m5), &m7);
+    let c12 = add(&m3, &m5);
+    let c21 = add(&m2, &m4);
+    let c22 = add(&add(&sub(&m1, &m2), &m3), &m6);
+
+    join([&c11, &c12, &c21, &c22])
+}
+
+fn winograd_recursive<T: Element>(a: MatrixView<T>, b: MatrixView<T>, cutoff: usize) -> Matrix<T> {
+    if a.rows() <= cutoff {
+        return multiply_ikj(a, b);
+    }
+
+    let [a11, a12, a21, a22] = a.quadrants();
+    let [b11, b12, b21, b22] = b.quadrants();
+
+    let s1 = add(a21, a22);
+    let s2 = sub(&s1, a11);
+    let s3 = sub(a11, a21);
+    let s4 = sub(a12, &s2);
+    let t1 = sub(b12, b11);
+    let t2 = sub(b22, &t1);
+    let t3 = sub(b22, b12);
+    let t4 = sub(&t2, b21);
+
+    let m1 = winograd_recursive(a11, b11, cutoff);
+    let m2 = winograd_recursive(a12, b21, cutoff);
+    let m3 = winograd_recursive(s4.view(), b22, cutoff);
+    let m4 = winograd_recursive(a22, t4.view(), cutoff);
+    let m5 = winograd_recursive(s1.view(), t1.view(), cutoff);
+    let m6 = winograd_recursive(s2.view(), t2.view(), cutoff);
+    let m7 = winograd_recursive(s3.view(), t3.view(), cutoff);
+
+    let u2 = add(&m1, &m6);
+    let u3 = add(&u2, &m7);
+    let u4 = add(&u2, &m5);
+
+    let c11 = add(&m1, &m2);
+    let c12 = add(&u4, &m3);
+    let c21 = sub(&u3, &m4);
+    let c22 = add(&u3, &m5);
+
+    join([&c11, &c12, &c21, &c22])
+}
+
+/// Classical ikj multiplication of two square matrices
+fn multiply_ikj<T: Element>(a: MatrixView<T>, b: MatrixView<T>) -> Matrix<T> {
+    let mut c = Matrix::zeros(a.rows(), b.cols());
+    multiply_add(&a, &b, &mut c.view_mut());
+    c
+}
+
+/// Joins four equally sized quadrants `[top left, top right, bottom left, bottom right]` into a
+/// matrix twice as large
+fn join<T: Element>(quadrants: [&Matrix<T>; 4]) -> Matrix<T> {
+    let h = quadrants[0].rows();
+    let mut m = Matrix::zeros(2 * h, 2 * h);
+    for (mut target, quadrant) in m.view_mut().quadrants_mut().into_iter().zip(quadrants) {
+        target.copy_from(quadrant.view());
+    }
+    m
+}
+
+/// Applies `op` element-wise to two matrices of the same shape
+fn zip_with<'a, 'b, T: Element>(
+    a: impl Into<MatrixView<'a, T>>,
+    b: impl Into<MatrixView<'b, T>>,
+    op: impl Fn(T, T) -> T,
+) -> Matrix<T> {
+    let (a, b) = (a.into(), b.into());
+    let data = a
+        .iter_rows()
+        .zip(b.iter_rows())
+        .flat_map(|(x, y)| x.iter().zip(y).map(|(x, y)| op(*x, *y)))
+        .collect();
+    Matrix::from_vec(a.rows(), a.cols(), data)
+}
+
+fn add<'a, 'b, T: Element>(
+    a: impl Into<MatrixView<'a, T>>,
+    b: impl Into<MatrixView<'b, T>>,
+) -> Matrix<T> {
+    zip_with(a, b, |x, y| x + y)
+}
+
+fn sub<'a, 'b, T: Element>(
+    a: impl Into<MatrixView<'a, T>>,
+    b: impl Into<MatrixView<'b, T>>,
+) -> Matrix<T> {
+    zip_with(a, b, |x, y| x - y)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_padded_size() {
+        assert_eq!(padded_size(64, 64, 1), (64, 64));
+        assert_eq!(padded_size(128, 64, 1), (128, 64));
+        assert_eq!(padded_size(100, 64, 1), (100, 50));
+        assert_eq!(padded_size(101, 64, 1), (102, 51));
+        assert_eq!(padded_size(5, 1, 1), (8, 1));
+        // blocks rounded up to a multiple of the tile size
+        assert_eq!(padded_size(100, 64, 32), (128, 64));
+        assert_eq!(padded_size(10, 4, 3), (12, 3));
+    }
+
+    #[test]
+    fn test_join() {
+        let m = Matrix::from_fn(4, 4, |i, j| (i * 4 + j) as i32);
+        let [q11, q12, q21, q22] = m.view().quadrants().map(|q| q.to_matrix());
+
+        assert_eq!(q11, Matrix::from(vec![vec![0, 1], vec![4, 5]]));
+        assert_eq!(q22, Matrix::from(vec![vec![10, 11], vec![14, 15]]));
+        assert_eq!(join([&q11, &q12, &q21, &q22]), m);
+    }
+
+    #[test]
+    fn test_strassen() {
+        let a = Matrix::from_fn(7, 7, |i, j| (i * 7 + j) as i32 - 20);
+        let b = Matrix::from_fn(7, 7, |i, j| (i + 2 * j) as i32 % 5 - 2);
+
+        let expected = multiply_ikj(a.view(), b.view());
+        for cutoff in 1..=7 {
+            let c = strassen(&a, &b, 7, cutoff);
+            assert_eq!(c, expected, "cutoff {}", cutoff);
+            let c = strassen_winograd(&a, &b, 7, cutoff);
+            assert_eq!(c, expected, "Winograd, cutoff {}", cutoff);
+        }
+
+        let pool = ThreadPool::new(2);
+        for cutoff in 1..=7 {
+            let c = strassen_parallel(&a, &b, 7, cutoff, &pool);
+            assert_eq!(c, expected, "parallel, cutoff {}", cutoff);
+        }
+
+        for (cutoff, tile_size) in [(1, 1), (2, 1), (4, 2), (3, 4), (8, 8)] {
+            let c = strassen_tiled(&a, &b, 7, cutoff, tile_size, &pool);
+            assert_eq!(
+                c, expected,
+                "tiled, cutoff {}, tile size {}",
+                cutoff, tile_size
+            );
+        }
+        ThreadPool::terminate(pool);
+    }
+}
@@ -1,13 +1,149 @@
+use clap::ValueEnum;
+
+use super::matrix::Matrix;
+
+/// Dimensions of the product of an `m` x `k` matrix A by a `k` x `n` matrix B
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct Shape {
+    pub m: usize,
+    pub k: usize,
+    pub n: usize,
+}
+
+impl Shape {
+    /// Shape of the product of two `size` x `size` matrices
+    pub fn square(size: usize) -> Shape {
+        Shape {
+            m: size,
+            k: size,
+            n: size,
+        }
+    }
+
+    pub fn is_square(&self) -> bool {
+        self.m == self.k && self.k == self.n
+    }
+}
+
+impl std::fmt::Display for Shape {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}x{}x{}", self.m, self.k, self.n)
+    }
+}
+
+/// Policy applied to the i32 sums of products that overflow
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, ValueEnum)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "serde", serde(rename_all = "snake_case"))]
+pub enum Arithmetic {
+    /// Wrap around, as two's complement hardware does
+    Wrapping,
+    /// Clamp to the bounds of i32
+    Saturating,
+    /// Report the overflowing element of C as an error
+    Checked,
+}
+
+impl std::fmt::Display for Arithmetic {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Arithmetic::Wrapping => write!(f, "wrapping"),
+            Arithmetic::Saturating => write!(f, "saturating"),
+            Arithmetic::Checked => write!(f, "checked"),
+        }
+    }
+}
+
+/// Type of the elements of the matrices of the main benchmark
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, ValueEnum)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "serde", serde(rename_all = "snake_case"))]
+pub enum ElementType {
+    /// Only multiplied by the quantized kernels, which accumulate into i32
+    #[value(skip)]
+    I8,
+    I32,
+    I64,
+    U64,
+    F32,
+    F64,
+    /// Complex numbers with f32 parts
+    C32,
+    /// Complex numbers with f64 parts
+    C64,
+    /// Q16.16 fixed-point numbers
+    #[value(name = "q16.16")]
+    Fixed,
+    /// i32 with wrapping arithmetic, selected with `--arithmetic wrapping`
+    #[value(skip)]
+    WrappingI32,
+    /// i32 with saturating arithmetic, selected with `--arithmetic saturating`
+    #[value(skip)]
+    SaturatingI32,
+}
+
+impl ElementType {
+    /// Returns whether the type has negative values
+    pub fn is_signed(&self) -> bool {
+        *self != ElementType::U64
+    }
+
+    pub fn is_complex(&self) -> bool {
+        matches!(self, ElementType::C32 | ElementType::C64)
+    }
+
+    /// Returns the size of an element, in bytes
+    pub fn size(&self) -> usize {
+        match self {
+            ElementType::I8 => 1,
+            ElementType::I32
+            | ElementType::F32
+            | ElementType::Fixed
+            | ElementType::WrappingI32
+            | ElementType::SaturatingI32 => 4,
+            ElementType::I64 | ElementType::U64 | ElementType::F64 | ElementType::C32 => 8,
+            ElementType::C64 => 16,
+        }
+    }
+
+    /// Returns whether the arithmetic operations on the type are floating point operations
+    pub fn is_float(&self) -> bool {
+        matches!(
+            self,
+            ElementType::F32 | ElementType::F64 | ElementType::C32 | ElementType::C64
+        )
+    }
+}
+
+impl std::fmt::Display for ElementType {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            ElementType::I8 => write!(f, "i8"),
+            ElementType::I32 => write!(f, "i32"),
+            ElementType::I64 => write!(f, "i64"),
+            ElementType::U64 => write!(f, "u64"),
+            ElementType::F32 => write!(f, "f32"),
+            ElementType::F64 => write!(f, "f64"),
+            ElementType::C32 => write!(f, "c32"),
+            ElementType::C64 => write!(f, "c64"),
+            ElementType::Fixed => write!(f, "q16.16"),
+            ElementType::WrappingI32 => write!(f, "wrapping i32"),
+            ElementType::SaturatingI32 => write!(f, "saturating i32"),
+        }
+    }
+}
+
 /// Struct holding pointers to `MatrixRowPtr` type
 #[derive(Clone)]
-pub struct SquareMatrixPtr(pub Vec<MatrixRowPtr<*const i32>>);
+pub struct SquareMatrixPtr<T = i32>(pub Vec<MatrixRowPtr<*const T>>);
 
-impl SquareMatrixPtr {
-    /// Create new `SquareMatrixPtr` from `Vec<Vec<i32>>`
-    pub fn new(matrix: &Vec<Vec<i32>>) -> SquareMatrixPtr {
+impl<T> SquareMatrixPtr<T> {
+    /// Create new `SquareMatrixPtr` from a `Matrix<T>`
+    pub fn new(matrix: &Matrix<T>) -> SquareMatrixPtr<T> {
         let mut matrix_ptr = Vec::new();
 
-        for row in matrix {
+        for row in matrix.iter_rows() {
             matrix_ptr.push(MatrixRowPtr(row.as_ptr()));
         }
 
@@ -19,7 +155,7 @@ impl SquareMatrixPtr {
     /// # Panics
     ///
     /// Panics if `row` is out of bounds
-    pub fn get_row(&self, row: usize) -> &MatrixRowPtr<*const i32> {
+    pub fn get_row(&self, row: usize) -> &MatrixRowPtr<*const T> {
         let size = self.0.len();
         if row > size {
             panic!("Row index out of bounds");
@@ -29,14 +165,14 @@ impl SquareMatrixPtr {
     }
 }
 
-unsafe impl Send for SquareMatrixPtr {}
+unsafe impl<T> Send for SquareMatrixPtr<T> {}
 
-/// Struct holding mutable pointers to `i32` type.
+/// Struct holding pointers to the elements of a matrix, `i32` unless stated otherwise.
 /// It represents a row of a matrix that can be modified
 #[derive(Clone, Copy)]
 pub struct MatrixRowPtr<T>(pub T);
 
-impl MatrixRowPtr<*mut i32> {
+impl<T> MatrixRowPtr<*mut T> {
     /// Get value by index
     ///
     /// # Arguments
@@ -47,12 +183,12 @@ impl MatrixRowPtr<*mut i32> {
     ///
     /// This function is unsafe because it dereferences a raw pointer, and it
     /// is the caller's responsibility to ensure that the pointer is valid.
-    pub unsafe fn add_mut(&mut self, offset: usize) -> &mut i32 {
+    pub unsafe fn add_mut(&mut self, offset: usize) -> &mut T {
         &mut *self.0.add(offset)
     }
 }
 
-impl MatrixRowPtr<*const i32> {
+impl<T> MatrixRowPtr<*const T> {
     /// Get value by index
     ///
     /// # Arguments
@@ -63,11 +199,19 @@ impl MatrixRowPtr<*const i32> {
     ///
     /// This function is unsafe because it dereferences a raw pointer, and it
     /// is the caller's responsibility to ensure that the pointer is valid.
-    pub unsafe fn add(&self, offset: usize) -> &i32 {
+    pub unsafe fn add(&self, offset: usize) -> &T {
         &*self.0.add(offset)
     }
 }
 
+impl<T: Copy> MatrixRowPtr<T> {
+    /// Returns the wrapped pointer. Being a method, it makes the closures of the jobs capture the
+    /// whole `Send` wrapper rather than the bare pointer.
+    pub fn get(self) -> T {
+        self.0
+    }
+}
+
 unsafe impl<T> Send for MatrixRowPtr<T> {}
 
 #[cfg(test)]
@@ -76,7 +220,7 @@ mod tests {
 
     #[test]
     fn test_square_matrix_ptr() {
-        let a = vec![vec![1, 2, 3], vec![4, 5, 6], vec![7, 8, 9]];
+        let a = Matrix::from(vec![vec![1, 2, 3], vec![4, 5, 6], vec![7, 8, 9]]);
         let a_ptr = SquareMatrixPtr::new(&a);
 
         unsafe {
@@ -97,9 +241,17 @@ mod tests {
         }
     }
 
+    #[test]
+    fn test_shape() {
+        assert!(Shape::square(4).is_square());
+        let shape = Shape { m: 2, k: 3, n: 4 };
+        assert!(!shape.is_square());
+        assert_eq!(shape.to_string(), "2x3x4");
+    }
+
     #[test]
     fn test_get_row_out_of_bounds() {
-        let a = vec![vec![1, 2, 3], vec![4, 5, 6], vec![7, 8, 9]];
+        let a = Matrix::from(vec![vec![1, 2, 3], vec![4, 5, 6], vec![7, 8, 9]]);
         let a_ptr = SquareMatrixPtr::new(&a);
 
         assert!(std::panic::catch_unwind(|| {
@@ -110,7 +262,7 @@ mod tests {
 
     #[test]
     fn test_matrix_row_ptr_add() {
-        let a = vec![1, 2, 3];
+        let a = [1, 2, 3];
         let a_ptr = MatrixRowPtr(a.as_ptr());
 
         unsafe {
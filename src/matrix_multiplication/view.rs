@@ -0,0 +1,399 @@
+//! Views of blocks of matrices, sharing the storage of the matrix they were taken from, so that
+//! blocked and recursive algorithms work on sub-blocks without copying them
+
+use std::{
+    marker::PhantomData,
+    ops::{Index, IndexMut},
+    slice,
+};
+
+use super::matrix::Matrix;
+
+/// Read-only view of a `rows` x `cols` block of a matrix, whose rows start `stride` elements
+/// apart. Indexing it with a row index returns the row as a slice, like a [`Matrix`].
+pub struct MatrixView<'a, T = i32> {
+    /// The elements, from the first element of the first row to the last one of the last row
+    data: &'a [T],
+    rows: usize,
+    cols: usize,
+    stride: usize,
+}
+
+/// Number of elements spanned by `rows` rows of `cols` elements starting `stride` elements apart
+fn span(rows: usize, cols: usize, stride: usize) -> usize {
+    match rows == 0 || cols == 0 {
+        true => 0,
+        false => (rows - 1) * stride + cols,
+    }
+}
+
+impl<'a, T> MatrixView<'a, T> {
+    /// Creates a view of the `rows` x `cols` matrix whose rows start `stride` elements apart in
+    /// `data`
+    ///
+    /// # Panics
+    ///
+    /// Panics if `stride` is less than `cols`, or if `data` is too short to hold the rows
+    pub fn from_slice(data: &'a [T], rows: usize, cols: usize, stride: usize) -> MatrixView<'a, T> {
+        assert!(
+            stride >= cols,
+            "the stride is less than the {} columns",
+            cols
+        );
+        MatrixView {
+            data: &data[..span(rows, cols, stride)],
+            rows,
+            cols,
+            stride,
+        }
+    }
+
+    /// Creates a view of the `rows` x `cols` matrix whose rows start `stride` elements apart from
+    /// `ptr`, for the jobs of the thread pool, which cannot borrow the matrices they read
+    ///
+    /// # Safety
+    ///
+    /// `ptr` must be valid for reads of the rows for `'a`, and no thread may write to the elements
+    /// from the first one of the first row to the last one of the last row meanwhile.
+    pub unsafe fn from_raw_parts(
+        ptr: *const T,
+        rows: usize,
+        cols: usize,
+        stride: usize,
+    ) -> MatrixView<'a, T> {
+        MatrixView::from_slice(
+            slice::from_raw_parts(ptr, span(rows, cols, stride)),
+            rows,
+            cols,
+            stride,
+        )
+    }
+
+    pub fn rows(&self) -> usize {
+        self.rows
+    }
+
+    pub fn cols(&self) -> usize {
+        self.cols
+    }
+
+    /// Returns the number of elements from the start of a row to the start of the next one
+    pub fn stride(&self) -> usize {
+        self.stride
+    }
+
+    /// Returns a pointer to the first element
+    pub fn as_ptr(&self) -> *const T {
+        self.data.as_ptr()
+    }
+
+    /// Returns row `i`
+    pub fn row(&self, i: usize) -> &'a [T] {
+        assert!(i < self.rows, "row {} of a {}-row view", i, self.rows);
+        // the blocks without columns span no element
+        match self.cols {
+            0 => &[],
+            _ => &self.data[i * self.stride..i * self.stride + self.cols],
+        }
+    }
+
+    /// Returns an iterator over the rows
+    pub fn iter_rows(&self) -> impl DoubleEndedIterator<Item = &'a [T]> + ExactSizeIterator {
+        let view = *self;
+        (0..self.rows).map(move |i| view.row(i))
+    }
+
+    /// Returns the view of the `rows` x `cols` block whose first element is at (`row`, `col`)
+    ///
+    /// # Panics
+    ///
+    /// Panics if the block is not inside the view
+    pub fn block(&self, row: usize, col: usize, rows: usize, cols: usize) -> MatrixView<'a, T> {
+        assert!(
+            row + rows <= self.rows && col + cols <= self.cols,
+            "{}x{} block at ({}, {}) of a {}x{} view",
+            rows,
+            cols,
+            row,
+            col,
+            self.rows,
+            self.cols
+        );
+        let start = match rows == 0 || cols == 0 {
+            true => 0,
+            false => row * self.stride + col,
+        };
+        MatrixView::from_slice(&self.data[start..], rows, cols, self.stride)
+    }
+
+    /// Returns the four quadrants `[top left, top right, bottom left, bottom right]`, splitting
+    /// the rows and the columns in half
+    pub fn quadrants(&self) -> [MatrixView<'a, T>; 4] {
+        let (h, w) = (self.rows / 2, self.cols / 2);
+        let (rows, cols) = (self.rows - h, self.cols - w);
+        [
+            self.block(0, 0, h, w),
+            self.block(0, w, h, cols),
+            self.block(h, 0, rows, w),
+            self.block(h, w, rows, cols),
+        ]
+    }
+
+    /// Returns a copy of the viewed block
+    pub fn to_matrix(self) -> Matrix<T>
+    where
+        T: Clone,
+    {
+        Matrix::from_fn(self.rows, self.cols, |i, j| self[i][j].clone())
+    }
+}
+
+impl<T> Clone for MatrixView<'_, T> {
+    fn clone(&self) -> Self {
+        *self
+    }
+}
+
+impl<T> Copy for MatrixView<'_, T> {}
+
+impl<'a, T> From<&'a Matrix<T>> for MatrixView<'a, T> {
+    fn from(matrix: &'a Matrix<T>) -> MatrixView<'a, T> {
+        matrix.view()
+    }
+}
+
+impl<T> Index<usize> for MatrixView<'_, T> {
+    type Output = [T];
+
+    /// Returns row `i`
+    fn index(&self, i: usize) -> &[T] {
+        self.row(i)
+    }
+}
+
+/// Mutable view of a `rows` x `cols` block of a matrix, whose rows start `stride` elements apart.
+/// Only the rows of the block are ever borrowed, so views of blocks sharing no element, e.g. the
+/// tiles of C written by different jobs, can be used at the same time.
+pub struct MatrixViewMut<'a, T = i32> {
+    /// First element of the first row
+    ptr: *mut T,
+    rows: usize,
+    cols: usize,
+    stride: usize,
+    _marker: PhantomData<&'a mut T>,
+}
+
+// SAFETY: the view is a mutable borrow of its rows, which can be sent like a `&mut [T]`
+unsafe impl<T: Send> Send for MatrixViewMut<'_, T> {}
+
+impl<'a, T> MatrixViewMut<'a, T> {
+    /// Creates a view of the `rows` x `cols` matrix whose rows start `stride` elements apart in
+    /// `data`
+    ///
+    /// # Panics
+    ///
+    /// Panics if `stride` is less than `cols`, or if `data` is too short to hold the rows
+    pub fn from_slice(
+        data: &'a mut [T],
+        rows: usize,
+        cols: usize,
+        stride: usize,
+    ) -> MatrixViewMut<'a, T> {
+        assert!(
+            stride >= cols,
+            "the stride is less than the {} columns",
+            cols
+        );
+        let data = &mut data[..span(rows, cols, stride)];
+        // SAFETY: `data` holds the rows, and is borrowed for `'a`
+        unsafe { MatrixViewMut::from_raw_parts(data.as_mut_ptr(), rows, cols, stride) }
+    }
+
+    /// Creates a view of the `rows` x `cols` matrix whose rows start `stride` elements apart from
+    /// `ptr`, for the jobs of the thread pool, which cannot borrow the matrices they write to
+    ///
+    /// # Safety
+    ///
+    /// `ptr` must be valid for reads and writes of the rows for `'a`, and no other thread may
+    /// access the elements accessed through the view meanwhile.
+    pub unsafe fn from_raw_parts(
+        ptr: *mut T,
+        rows: usize,
+        cols: usize,
+        stride: usize,
+    ) -> MatrixViewMut<'a, T> {
+        MatrixViewMut {
+            ptr,
+            rows,
+            cols,
+            stride,
+            _marker: PhantomData,
+        }
+    }
+
+    /// Returns row `i`
+    pub fn row_mut(&mut self, i: usize) -> &mut [T] {
+        assert!(i < self.rows, "row {} of a {}-row view", i, self.rows);
+        if self.cols == 0 {
+            return &mut [];
+        }
+        // SAFETY: the row is inside the viewed block, borrowed mutably from the view
+        unsafe { slice::from_raw_parts_mut(self.ptr.add(i * self.stride), self.cols) }
+    }
+
+    /// Returns the view of the `rows` x `cols` block whose first element is at (`row`, `col`)
+    ///
+    /// # Panics
+    ///
+    /// Panics if the block is not inside the view
+    pub fn block_mut(
+        &mut self,
+        row: usize,
+        col: usize,
+        rows: usize,
+        cols: usize,
+    ) -> MatrixViewMut<'_, T> {
+        self.reborrow().into_block(row, col, rows, cols)
+    }
+
+    /// Returns the four quadrants `[top left, top right, bottom left, bottom right]`, splitting
+    /// the rows and the columns in half. They share no element, so they can be written to at the
+    /// same time.
+    pub fn quadrants_mut(self) -> [MatrixViewMut<'a, T>; 4] {
+        let (h, w) = (self.rows / 2, self.cols / 2);
+        let (rows, cols) = (self.rows - h, self.cols - w);
+        // SAFETY: the quadrants are disjoint blocks of the view, which is consumed
+        let quadrant = |row, col, rows, cols| unsafe {
+            MatrixViewMut::from_raw_parts(self.ptr, self.rows, self.cols, self.stride)
+                .into_block(row, col, rows, cols)
+        };
+        [
+            quadrant(0, 0, h, w),
+            quadrant(0, w, h, cols),
+            quadrant(h, 0, rows, w),
+            quadrant(h, w, rows, cols),
+        ]
+    }
+
+    /// Copies the elements of `source`, a block of the same dimensions
+    ///
+    /// # Panics
+    ///
+    /// Panics if the dimensions differ
+    pub fn copy_from(&mut self, source: MatrixView<T>)
+    where
+        T: Clone,
+    {
+        assert_eq!(
+            (self.rows, self.cols),
+            (source.rows(), source.cols()),
+            "copying a block of different dimensions"
+        );
+        for (i, source_row) in source.iter_rows().enumerate() {
+            self.row_mut(i).clone_from_slice(source_row);
+        }
+    }
+
+    /// Returns a view of the same block, borrowing this one
+    fn reborrow(&mut self) -> MatrixViewMut<'_, T> {
+        // SAFETY: the new view borrows this one mutably
+        unsafe { MatrixViewMut::from_raw_parts(self.ptr, self.rows, self.cols, self.stride) }
+    }
+
+    /// Turns the view into the one of the `rows` x `cols` block at (`row`, `col`)
+    fn into_block(self, row: usize, col: usize, rows: usize, cols: usize) -> MatrixViewMut<'a, T> {
+        assert!(
+            row + rows <= self.rows && col + cols <= self.cols,
+            "{}x{} block at ({}, {}) of a {}x{} view",
+            rows,
+            cols,
+            row,
+            col,
+            self.rows,
+            self.cols
+        );
+        let start = match rows == 0 || cols == 0 {
+            true => 0,
+            false => row * self.stride + col,
+        };
+        // SAFETY: the block is inside the view, which is consumed
+        unsafe { MatrixViewMut::from_raw_parts(self.ptr.add(start), rows, cols, self.stride) }
+    }
+}
+
+impl<T> Index<usize> for MatrixViewMut<'_, T> {
+    type Output = [T];
+
+    /// Returns row `i`
+    fn index(&self, i: usize) -> &[T] {
+        assert!(i < self.rows, "row {} of a {}-row view", i, self.rows);
+        // SAFETY: the row is inside the viewed block, borrowed from the view
+        unsafe { slice::from_raw_parts(self.ptr.add(i * self.stride), self.cols) }
+    }
+}
+
+impl<T> IndexMut<usize> for MatrixViewMut<'_, T> {
+    /// Returns row `i`
+    fn index_mut(&mut self, i: usize) -> &mut [T] {
+        self.row_mut(i)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_matrix_view() {
+        let m = Matrix::from_fn(4, 5, |i, j| (i * 5 + j) as i32).padded(7);
+        let view = m.view();
+        assert_eq!((view.rows(), view.cols(), view.stride()), (4, 5, 7));
+        assert_eq!(view[3], [15, 16, 17, 18, 19]);
+
+        let block = view.block(1, 2, 3, 2);
+        assert_eq!(
+            block.to_matrix(),
+            Matrix::from(vec![vec![7, 8], vec![12, 13], vec![17, 18]])
+        );
+        assert_eq!(block.iter_rows().next_back(), Some(&[17, 18][..]));
+
+        let [q11, q12, q21, q22] = view.quadrants();
+        assert_eq!(q11.to_matrix(), Matrix::from(vec![vec![0, 1], vec![5, 6]]));
+        assert_eq!((q12.rows(), q12.cols()), (2, 3));
+        assert_eq!(q21[1], [15, 16]);
+        assert_eq!(q22[0], [12, 13, 14]);
+        assert_eq!(view.block(4, 5, 0, 0).iter_rows().count(), 0);
+        assert_eq!(view.block(1, 5, 3, 0).row(2), [0; 0]);
+    }
+
+    #[test]
+    fn test_matrix_view_mut() {
+        let mut m = Matrix::<i32>::zeros(4, 4);
+        let [mut q11, _, _, mut q22] = m.view_mut().quadrants_mut();
+        q11[1][0] = 1;
+        q22.copy_from(Matrix::from(vec![vec![2, 3], vec![4, 5]]).view());
+
+        let mut view = m.view_mut();
+        view.block_mut(0, 2, 1, 2).row_mut(0).fill(6);
+        assert_eq!(view[0], [0, 0, 6, 6]);
+        assert_eq!(
+            m,
+            Matrix::from(vec![
+                vec![0, 0, 6, 6],
+                vec![1, 0, 0, 0],
+                vec![0, 0, 2, 3],
+                vec![0, 0, 4, 5]
+            ])
+        );
+    }
+
+    #[test]
+    fn test_block_out_of_bounds() {
+        let m = Matrix::<i32>::zeros(3, 3);
+
+        assert!(std::panic::catch_unwind(|| {
+            m.view().block(2, 0, 2, 1);
+        })
+        .is_err());
+    }
+}
@@ -0,0 +1,143 @@
+use std::{fs, str::FromStr, sync::OnceLock};
+
+use crate::cli::parse_cores;
+
+/// File listing the online NUMA nodes on Linux, e.g. `0-1`
+const ONLINE_NODES: &str = "/sys/devices/system/node/online";
+
+/// Policy set with `apply`, recorded in the reports
+static APPLIED: OnceLock<NumaPolicy> = OnceLock::new();
+
+/// Modes of `set_mempolicy`, as defined by the kernel
+const MPOL_BIND: i32 = 2;
+const MPOL_INTERLEAVE: i32 = 3;
+
+/// Placement of the memory allocated by the benchmark on the NUMA nodes, set with `--numa`
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum NumaPolicy {
+    /// Pages spread round-robin over every online node
+    Interleave,
+    /// Pages allocated on the given node only
+    Bind(usize),
+}
+
+impl std::fmt::Display for NumaPolicy {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            NumaPolicy::Interleave => write!(f, "interleave"),
+            NumaPolicy::Bind(node) => write!(f, "bind:{}", node),
+        }
+    }
+}
+
+impl FromStr for NumaPolicy {
+    type Err = String;
+
+    /// Parses `interleave` or `bind:NODE`
+    fn from_str(policy: &str) -> Result<Self, Self::Err> {
+        match policy.trim().split_once(':') {
+            None if policy.trim() == "interleave" => Ok(NumaPolicy::Interleave),
+            Some(("bind", node)) => node
+                .trim()
+                .parse()
+                .map(NumaPolicy::Bind)
+                .map_err(|_| format!("invalid NUMA node: {}", node)),
+            _ => Err(format!(
+                "the NUMA policy must be interleave or bind:NODE: {}",
+                policy
+            )),
+        }
+    }
+}
+
+/// Returns the online NUMA nodes, only node 0 on machines without NUMA support
+fn online_nodes() -> Vec<usize> {
+    fs::read_to_string(ONLINE_NODES)
+        .ok()
+        .and_then(|nodes| parse_cores(nodes.trim()).ok())
+        .unwrap_or(vec![0])
+}
+
+/// Returns the bit mask of `nodes`, as the words of a `nodemask_t` of the kernel
+fn node_mask(nodes: &[usize]) -> [u64; 16] {
+    let mut mask = [0; 16];
+    for node in nodes {
+        mask[node / 64] |= 1 << (node % 64);
+    }
+    mask
+}
+
+/// Sets the memory policy of the calling thread, inherited by the threads it spawns from now on,
+/// so that the matrices allocated afterwards, and the pages of the results first touched by the
+/// workers, are placed according to `policy`.
+/// Applying the same policy again only sets it for the calling thread.
+/// Fails if the node is not online, if the platform does not support NUMA policies, or if another
+/// policy is already set.
+pub fn apply(policy: NumaPolicy) -> Result<(), String> {
+    if let Some(applied) = applied().filter(|applied| *applied != policy) {
+        return Err(format!("the NUMA policy {} is already set", applied));
+    }
+    let online = online_nodes();
+    let (mode, nodes) = match policy {
+        NumaPolicy::Interleave => (MPOL_INTERLEAVE, online),
+        NumaPolicy::Bind(node) if online.contains(&node) => (MPOL_BIND, vec![node]),
+        NumaPolicy::Bind(node) => {
+            return Err(format!(
+                "node {} is not online, the online nodes are {:?}",
+                node, online
+            ))
+        }
+    };
+    if nodes.iter().any(|node| *node >= 16 * 64) {
+        return Err("the machine has too many NUMA nodes".to_string());
+    }
+    set_mempolicy(mode, &node_mask(&nodes))?;
+    APPLIED.get_or_init(|| policy);
+    Ok(())
+}
+
+/// Returns the policy set with `apply`, if any
+pub fn applied() -> Option<NumaPolicy> {
+    APPLIED.get().copied()
+}
+
+/// Calls `set_mempolicy`, the system call behind `numa_set_interleave_mask` and
+/// `numa_set_membind` of libnuma
+#[cfg(target_os = "linux")]
+fn set_mempolicy(mode: i32, mask: &[u64; 16]) -> Result<(), String> {
+    // the kernel reads one bit less than `maxnode`
+    let maxnode = mask.len() as libc::c_ulong * 64 + 1;
+    // SAFETY: the mask outlives the call and holds `maxnode - 1` bits
+    let result = unsafe { libc::syscall(libc::SYS_set_mempolicy, mode, mask.as_ptr(), maxnode) };
+    match result {
+        0 => Ok(()),
+        _ => Err(std::io::Error::last_os_error().to_string()),
+    }
+}
+
+#[cfg(not(target_os = "linux"))]
+fn set_mempolicy(_: i32, _: &[u64; 16]) -> Result<(), String> {
+    Err("NUMA policies are only supported on Linux".to_string())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_policy() {
+        assert_eq!("interleave".parse(), Ok(NumaPolicy::Interleave));
+        assert_eq!("bind:1".parse(), Ok(NumaPolicy::Bind(1)));
+        assert_eq!(NumaPolicy::Bind(1).to_string(), "bind:1");
+        assert!("bind:a".parse::<NumaPolicy>().is_err());
+        assert!("local".parse::<NumaPolicy>().is_err());
+    }
+
+    #[test]
+    fn test_node_mask() {
+        let mask = node_mask(&[0, 2, 65]);
+        assert_eq!(mask[0], 0b101);
+        assert_eq!(mask[1], 0b10);
+        assert!(mask[2..].iter().all(|word| *word == 0));
+    }
+}
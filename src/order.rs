@@ -0,0 +1,67 @@
+use clap::ValueEnum;
+use rand::{rngs::StdRng, seq::SliceRandom};
+
+/// Order in which the algorithms run within every iteration
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Hash, ValueEnum)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "serde", serde(rename_all = "snake_case"))]
+pub enum ExecutionOrder {
+    /// The order in which the algorithms are listed, in every iteration
+    #[default]
+    Fixed,
+    /// A new random order in every iteration, drawn from `--seed` if given
+    Shuffle,
+    /// The listed order rotated by one algorithm in every iteration, so that every algorithm runs
+    /// first, second and so on equally often over as many iterations as there are algorithms
+    Rotate,
+}
+
+impl std::fmt::Display for ExecutionOrder {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            ExecutionOrder::Fixed => write!(f, "fixed"),
+            ExecutionOrder::Shuffle => write!(f, "shuffle"),
+            ExecutionOrder::Rotate => write!(f, "rotate"),
+        }
+    }
+}
+
+impl ExecutionOrder {
+    /// Returns the indices of `count` algorithms in the order they run in the given iteration,
+    /// drawing the shuffled orders from `rng`
+    pub fn indices(&self, count: usize, iteration: usize, rng: &mut StdRng) -> Vec<usize> {
+        let mut indices = (0..count).collect::<Vec<_>>();
+        match self {
+            ExecutionOrder::Fixed => (),
+            ExecutionOrder::Shuffle => indices.shuffle(rng),
+            ExecutionOrder::Rotate if count > 0 => indices.rotate_left(iteration % count),
+            ExecutionOrder::Rotate => (),
+        }
+        indices
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::matrix_multiplication::generate::seeded_rng;
+
+    use super::*;
+
+    #[test]
+    fn test_indices() {
+        let mut rng = seeded_rng(Some(42));
+        assert_eq!(ExecutionOrder::Fixed.indices(3, 5, &mut rng), [0, 1, 2]);
+        assert_eq!(ExecutionOrder::Rotate.indices(3, 0, &mut rng), [0, 1, 2]);
+        assert_eq!(ExecutionOrder::Rotate.indices(3, 4, &mut rng), [1, 2, 0]);
+        assert!(ExecutionOrder::Rotate.indices(0, 4, &mut rng).is_empty());
+
+        let mut shuffled = ExecutionOrder::Shuffle.indices(8, 0, &mut rng);
+        shuffled.sort();
+        assert_eq!(shuffled, (0..8).collect::<Vec<_>>());
+        // the same seed draws the same orders
+        assert_eq!(
+            ExecutionOrder::Shuffle.indices(8, 0, &mut seeded_rng(Some(7))),
+            ExecutionOrder::Shuffle.indices(8, 0, &mut seeded_rng(Some(7)))
+        );
+    }
+}
@@ -0,0 +1,120 @@
+use std::time::Duration;
+
+use clap::ValueEnum;
+
+pub mod csv;
+pub mod html;
+#[cfg(feature = "json")]
+pub mod json;
+pub mod latex;
+pub mod markdown;
+#[cfg(feature = "plot")]
+pub mod plot;
+#[cfg(feature = "sqlite")]
+pub mod sqlite;
+
+/// Enum representing the formats the benchmark results can be printed in
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Hash, ValueEnum)]
+pub enum OutputFormat {
+    /// Tables drawn on the console
+    #[default]
+    Table,
+    /// A booktabs-style LaTeX table
+    Latex,
+    /// GitHub-flavored markdown tables, to paste into issues and READMEs
+    Markdown,
+    /// Comma-separated values with a line per algorithm, the times in nanoseconds, to read back
+    /// with the `compare` subcommand or a spreadsheet
+    Csv,
+    /// A JSON document with the parameters of the run, the timings of every iteration and their
+    /// summary statistics
+    #[cfg(feature = "json")]
+    Json,
+}
+
+impl OutputFormat {
+    /// Returns whether the results are printed as a document to read back or to paste, which the
+    /// standard output must hold alone
+    pub fn is_document(&self) -> bool {
+        match self {
+            OutputFormat::Table | OutputFormat::Markdown => false,
+            OutputFormat::Latex | OutputFormat::Csv => true,
+            #[cfg(feature = "json")]
+            OutputFormat::Json => true,
+        }
+    }
+}
+
+/// Aggregated results of a single algorithm, as presented in the exported formats
+#[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct ResultRow {
+    /// Name of the algorithm, including its parameters
+    pub algorithm: String,
+    /// Average execution time
+    pub time: Duration,
+    /// Throughput in billions of operations per second, `None` if the time is too short to be
+    /// measured
+    pub gops: Option<f64>,
+    /// Speedup relative to the fastest sequential algorithm, `None` if no sequential algorithm
+    /// was run
+    pub speedup: Option<f64>,
+}
+
+/// Table of results with a title, and one column per entry of `columns`, printed in the
+/// formats that are not specific to the benchmark results
+#[derive(Debug, Clone, PartialEq)]
+pub struct Table {
+    pub title: String,
+    pub columns: Vec<String>,
+    pub rows: Vec<Vec<String>>,
+}
+
+/// Formats a time in milliseconds with the unit, from nanoseconds to seconds, that keeps one to
+/// three digits before the decimal point, and three significant digits
+pub fn format_time(ms: f64) -> String {
+    let (value, unit) = match ms {
+        ms if ms >= 1e3 => (ms / 1e3, "s"),
+        ms if ms >= 1.0 => (ms, "ms"),
+        ms if ms >= 1e-3 => (ms * 1e3, "µs"),
+        // the clocks do not resolve fractions of a nanosecond
+        ms => return format!("{:.0} ns", ms * 1e6),
+    };
+    let decimals = match value {
+        value if value >= 100.0 => 0,
+        value if value >= 10.0 => 1,
+        _ => 2,
+    };
+    format!("{:.*} {}", decimals, value, unit)
+}
+
+/// Formats a duration like `format_time`
+pub fn format_duration(duration: Duration) -> String {
+    format_time(duration.as_secs_f64() * 1e3)
+}
+
+/// Formats an optional value with the given number of decimals, or a dash if it is missing
+pub fn format_optional(value: Option<f64>, decimals: usize) -> String {
+    match value {
+        Some(value) => format!("{:.*}", decimals, value),
+        None => "-".to_string(),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_format_time() {
+        assert_eq!(format_time(1234.5), "1.23 s");
+        assert_eq!(format_time(123.4), "123 ms");
+        assert_eq!(format_time(12.34), "12.3 ms");
+        assert_eq!(format_time(1.0), "1.00 ms");
+        assert_eq!(format_time(0.25), "250 µs");
+        assert_eq!(format_time(0.0015), "1.50 µs");
+        assert_eq!(format_time(0.000_42), "420 ns");
+        assert_eq!(format_time(0.0), "0 ns");
+        assert_eq!(format_duration(Duration::from_micros(2500)), "2.50 ms");
+    }
+}
@@ -0,0 +1,144 @@
+use std::time::Duration;
+
+use super::ResultRow;
+
+/// Columns of the exported results, the times in nanoseconds so that they can be read back
+/// without loss
+const HEADER: &str = "algorithm,time_ns,gops,speedup";
+
+/// Quotes a field if it contains a separator, a quote or a line break
+fn escape(field: &str) -> String {
+    match field.contains([',', '"', '\n', '\r']) {
+        true => format!("\"{}\"", field.replace('"', "\"\"")),
+        false => field.to_string(),
+    }
+}
+
+/// Splits a line into its fields, unquoting the quoted ones
+fn split(line: &str) -> Vec<String> {
+    let mut fields = vec![String::new()];
+    let mut quoted = false;
+    let mut chars = line.chars().peekable();
+    while let Some(c) = chars.next() {
+        match (c, quoted) {
+            ('"', true) if chars.peek() == Some(&'"') => {
+                chars.next();
+                fields.last_mut().unwrap().push('"');
+            }
+            ('"', _) => quoted = !quoted,
+            (',', false) => fields.push(String::new()),
+            (c, _) => fields.last_mut().unwrap().push(c),
+        }
+    }
+    fields
+}
+
+/// Returns the results as comma-separated values, with a header and a line per algorithm.
+/// Missing values are left empty.
+pub fn results_table(rows: &[ResultRow]) -> String {
+    let optional = |value: Option<f64>| value.map(|value| value.to_string()).unwrap_or_default();
+    let mut csv = format!("{}\n", HEADER);
+    for row in rows {
+        csv.push_str(&format!(
+            "{},{},{},{}\n",
+            escape(&row.algorithm),
+            row.time.as_nanos(),
+            optional(row.gops),
+            optional(row.speedup)
+        ));
+    }
+    csv
+}
+
+/// Parses results written by `results_table`. The columns are found by name in the header, so
+/// that columns can be reordered or added by other tools.
+pub fn parse_results(csv: &str) -> Result<Vec<ResultRow>, String> {
+    let mut lines = csv.lines().filter(|line| !line.trim().is_empty());
+    let header = split(lines.next().ok_or("the file is empty")?);
+    let column = |name: &str| {
+        header
+            .iter()
+            .position(|column| column.trim() == name)
+            .ok_or(format!("the header has no {} column", name))
+    };
+    let (algorithm, time) = (column("algorithm")?, column("time_ns")?);
+    let (gops, speedup) = (column("gops").ok(), column("speedup").ok());
+
+    lines
+        .enumerate()
+        .map(|(i, line)| {
+            let fields = split(line);
+            let field = |index: usize| fields.get(index).map(|field| field.trim());
+            let optional = |index: Option<usize>| {
+                index
+                    .and_then(field)
+                    .filter(|field| !field.is_empty())
+                    .map(str::parse::<f64>)
+                    .transpose()
+                    .map_err(|err| format!("line {}: {}", i + 2, err))
+            };
+            Ok(ResultRow {
+                algorithm: field(algorithm)
+                    .ok_or(format!("line {}: missing algorithm", i + 2))?
+                    .to_string(),
+                time: field(time)
+                    .ok_or(format!("line {}: missing time_ns", i + 2))?
+                    .parse()
+                    .map(Duration::from_nanos)
+                    .map_err(|err| format!("line {}: {}", i + 2, err))?,
+                gops: optional(gops)?,
+                speedup: optional(speedup)?,
+            })
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_escape() {
+        assert_eq!(escape("Sequential IKJ"), "Sequential IKJ");
+        assert_eq!(escape("Tiling (4, 8)"), "\"Tiling (4, 8)\"");
+        assert_eq!(escape("a \"b\""), "\"a \"\"b\"\"\"");
+        assert_eq!(
+            split("\"Tiling (4, 8)\",1,\"a \"\"b\"\"\","),
+            ["Tiling (4, 8)", "1", "a \"b\"", ""]
+        );
+    }
+
+    #[test]
+    fn test_round_trip() {
+        let rows = vec![
+            ResultRow {
+                algorithm: "Sequential IKJ".to_string(),
+                time: Duration::from_millis(20),
+                gops: Some(0.25),
+                speedup: Some(1.0),
+            },
+            ResultRow {
+                algorithm: "Parallel Tiling (4 threads, tile 8)".to_string(),
+                time: Duration::from_nanos(150),
+                gops: None,
+                speedup: None,
+            },
+        ];
+
+        let csv = results_table(&rows);
+
+        assert!(csv.starts_with("algorithm,time_ns,gops,speedup\nSequential IKJ,20000000,0.25,1\n"));
+        assert_eq!(parse_results(&csv).unwrap(), rows);
+    }
+
+    #[test]
+    fn test_parse_results_errors() {
+        assert!(parse_results("").is_err());
+        assert!(parse_results("algorithm,gops\nA,1\n").is_err());
+        assert!(parse_results("algorithm,time_ns\nA,fast\n").is_err());
+        // the columns are found by name
+        let rows = parse_results("time_ns,algorithm\n5,A\n").unwrap();
+        assert_eq!(rows[0].algorithm, "A");
+        assert_eq!(rows[0].time, Duration::from_nanos(5));
+    }
+}
@@ -0,0 +1,310 @@
+use std::fmt::Write;
+
+use super::{format_time, Table};
+
+/// Colors of the algorithms in the charts, reused cyclically
+const PALETTE: [&str; 10] = [
+    "#1f77b4", "#ff7f0e", "#2ca02c", "#d62728", "#9467bd", "#8c564b", "#e377c2", "#7f7f7f",
+    "#bcbd22", "#17becf",
+];
+
+/// Width of the charts, in pixels
+const CHART_WIDTH: f64 = 800.0;
+
+/// Height of the bars of the bar chart, in pixels
+const BAR_HEIGHT: f64 = 22.0;
+
+/// Height of the plot area of the line chart, in pixels
+const LINE_CHART_HEIGHT: f64 = 320.0;
+
+/// Kernel times of every iteration of an algorithm, in milliseconds
+#[derive(Debug, Clone, PartialEq)]
+pub struct Series {
+    pub algorithm: String,
+    pub times: Vec<f64>,
+}
+
+impl Series {
+    fn mean(&self) -> f64 {
+        match self.times.len() {
+            0 => 0.0,
+            len => self.times.iter().sum::<f64>() / len as f64,
+        }
+    }
+}
+
+/// Benchmark run, as written with `--report`
+#[derive(Debug, Clone, PartialEq)]
+pub struct Report {
+    /// Names and values of the parameters of the run
+    pub parameters: Vec<(String, String)>,
+    pub tables: Vec<Table>,
+    pub series: Vec<Series>,
+}
+
+/// Escapes the characters that have a special meaning in HTML
+fn escape(text: &str) -> String {
+    let mut escaped = String::with_capacity(text.len());
+    for c in text.chars() {
+        match c {
+            '&' => escaped.push_str("&amp;"),
+            '<' => escaped.push_str("&lt;"),
+            '>' => escaped.push_str("&gt;"),
+            '"' => escaped.push_str("&quot;"),
+            '\'' => escaped.push_str("&#39;"),
+            _ => escaped.push(c),
+        }
+    }
+    escaped
+}
+
+/// Returns an HTML table with the given column titles and rows
+fn table(columns: &[String], rows: &[Vec<String>]) -> String {
+    let mut html = String::from("<table>\n<tr>");
+    for column in columns {
+        write!(html, "<th>{}</th>", escape(column)).unwrap();
+    }
+    html.push_str("</tr>\n");
+    for row in rows {
+        html.push_str("<tr>");
+        for cell in row {
+            write!(html, "<td>{}</td>", escape(cell)).unwrap();
+        }
+        html.push_str("</tr>\n");
+    }
+    html.push_str("</table>\n");
+    html
+}
+
+/// Returns an SVG chart with a horizontal bar per algorithm, as long as its average time
+fn bar_chart(series: &[Series]) -> String {
+    let label_width = 0.45 * CHART_WIDTH;
+    let bars_width = CHART_WIDTH - label_width - 80.0;
+    let max = series.iter().map(Series::mean).fold(0.0, f64::max);
+    let height = BAR_HEIGHT * series.len() as f64;
+
+    let mut svg = format!(
+        "<svg xmlns=\"http://www.w3.org/2000/svg\" width=\"{}\" height=\"{}\">\n",
+        CHART_WIDTH, height
+    );
+    for (i, series) in series.iter().enumerate() {
+        let mean = series.mean();
+        let y = BAR_HEIGHT * i as f64;
+        let width = match max > 0.0 {
+            true => bars_width * mean / max,
+            false => 0.0,
+        };
+        writeln!(
+            svg,
+            "<text x=\"{:.1}\" y=\"{:.1}\" text-anchor=\"end\">{}</text>",
+            label_width - 6.0,
+            y + BAR_HEIGHT * 0.7,
+            escape(&series.algorithm)
+        )
+        .unwrap();
+        writeln!(
+            svg,
+            "<rect x=\"{:.1}\" y=\"{:.1}\" width=\"{:.1}\" height=\"{:.1}\" fill=\"{}\"/>",
+            label_width,
+            y + 2.0,
+            width,
+            BAR_HEIGHT - 4.0,
+            PALETTE[i % PALETTE.len()]
+        )
+        .unwrap();
+        writeln!(
+            svg,
+            "<text x=\"{:.1}\" y=\"{:.1}\">{}</text>",
+            label_width + width + 6.0,
+            y + BAR_HEIGHT * 0.7,
+            format_time(mean)
+        )
+        .unwrap();
+    }
+    svg.push_str("</svg>\n");
+    svg
+}
+
+/// Returns an SVG chart with a line per algorithm, joining its times of every iteration, followed
+/// by its legend
+fn line_chart(series: &[Series]) -> String {
+    let margin = 50.0;
+    let iterations = series.iter().map(|series| series.times.len()).max();
+    let iterations = iterations.unwrap_or_default().max(2);
+    let max = series
+        .iter()
+        .flat_map(|series| series.times.iter().copied())
+        .fold(0.0, f64::max);
+    let max = if max > 0.0 { max } else { 1.0 };
+    let x = |i: usize| margin + (CHART_WIDTH - 2.0 * margin) * i as f64 / (iterations - 1) as f64;
+    let y = |time: f64| margin + LINE_CHART_HEIGHT * (1.0 - time / max);
+
+    let mut svg = format!(
+        "<svg xmlns=\"http://www.w3.org/2000/svg\" width=\"{}\" height=\"{}\">\n",
+        CHART_WIDTH,
+        LINE_CHART_HEIGHT + 2.0 * margin
+    );
+    // axes, labelled with the iterations and the maximum time
+    writeln!(
+        svg,
+        "<polyline points=\"{m:.1},{m:.1} {m:.1},{b:.1} {r:.1},{b:.1}\" fill=\"none\" stroke=\"black\"/>",
+        m = margin,
+        b = y(0.0),
+        r = CHART_WIDTH - margin
+    )
+    .unwrap();
+    writeln!(
+        svg,
+        "<text x=\"{:.1}\" y=\"{:.1}\" text-anchor=\"end\">{}</text>",
+        margin - 4.0,
+        margin + 4.0,
+        format_time(max)
+    )
+    .unwrap();
+    for i in 0..iterations {
+        writeln!(
+            svg,
+            "<text x=\"{:.1}\" y=\"{:.1}\" text-anchor=\"middle\">{}</text>",
+            x(i),
+            y(0.0) + 20.0,
+            i + 1
+        )
+        .unwrap();
+    }
+    for (i, series) in series.iter().enumerate() {
+        let color = PALETTE[i % PALETTE.len()];
+        let points = series
+            .times
+            .iter()
+            .enumerate()
+            .map(|(iteration, time)| format!("{:.1},{:.1}", x(iteration), y(*time)))
+            .collect::<Vec<_>>();
+        writeln!(
+            svg,
+            "<polyline points=\"{}\" fill=\"none\" stroke=\"{}\"/>",
+            points.join(" "),
+            color
+        )
+        .unwrap();
+        for (iteration, time) in series.times.iter().enumerate() {
+            writeln!(
+                svg,
+                "<circle cx=\"{:.1}\" cy=\"{:.1}\" r=\"3\" fill=\"{}\"/>",
+                x(iteration),
+                y(*time),
+                color
+            )
+            .unwrap();
+        }
+    }
+    svg.push_str("</svg>\n<ul class=\"legend\">\n");
+    for (i, series) in series.iter().enumerate() {
+        writeln!(
+            svg,
+            "<li><span style=\"background: {}\"></span>{}</li>",
+            PALETTE[i % PALETTE.len()],
+            escape(&series.algorithm)
+        )
+        .unwrap();
+    }
+    svg.push_str("</ul>\n");
+    svg
+}
+
+/// Returns a self-contained HTML page with the parameters of the run, the tables of results, a
+/// bar chart of the average times and a line chart of the times of every iteration
+pub fn to_html(report: &Report) -> String {
+    let mut html = String::from(
+        "<!DOCTYPE html>\n\
+         <html>\n\
+         <head>\n\
+         <meta charset=\"utf-8\">\n\
+         <title>Matrix Multiplication Benchmark</title>\n\
+         <style>\n\
+         body { font-family: sans-serif; margin: 2em; }\n\
+         table { border-collapse: collapse; margin-bottom: 2em; }\n\
+         th, td { border: 1px solid #ccc; padding: 4px 8px; text-align: right; }\n\
+         th:first-child, td:first-child { text-align: left; }\n\
+         svg { font-size: 12px; }\n\
+         .legend { list-style: none; padding: 0; }\n\
+         .legend span { display: inline-block; width: 12px; height: 12px; margin-right: 6px; }\n\
+         </style>\n\
+         </head>\n\
+         <body>\n\
+         <h1>Matrix Multiplication Benchmark</h1>\n",
+    );
+
+    html.push_str("<h2>Parameters</h2>\n");
+    let parameters = report
+        .parameters
+        .iter()
+        .map(|(name, value)| vec![name.clone(), value.clone()])
+        .collect::<Vec<_>>();
+    html.push_str(&table(
+        &["Argument".to_string(), "Value".to_string()],
+        &parameters,
+    ));
+
+    for results in &report.tables {
+        writeln!(html, "<h2>{}</h2>", escape(&results.title)).unwrap();
+        html.push_str(&table(&results.columns, &results.rows));
+    }
+
+    html.push_str("<h2>Average time</h2>\n");
+    html.push_str(&bar_chart(&report.series));
+    html.push_str("<h2>Time of every iteration</h2>\n");
+    html.push_str(&line_chart(&report.series));
+
+    html.push_str("</body>\n</html>\n");
+    html
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_escape() {
+        assert_eq!(
+            escape("<a href=\"x\">A & B's</a>"),
+            "&lt;a href=&quot;x&quot;&gt;A &amp; B&#39;s&lt;/a&gt;"
+        );
+    }
+
+    #[test]
+    fn test_report() {
+        let report = Report {
+            parameters: vec![("Shape (MxKxN)".to_string(), "64x64x64".to_string())],
+            tables: vec![Table {
+                title: "Benchmark Results".to_string(),
+                columns: vec!["Algorithm".to_string(), "Average time (ms)".to_string()],
+                rows: vec![vec!["Sequential <IKJ>".to_string(), "20".to_string()]],
+            }],
+            series: vec![
+                Series {
+                    algorithm: "Sequential <IKJ>".to_string(),
+                    times: vec![19.0, 21.0],
+                },
+                Series {
+                    algorithm: "Parallel I Loop (4 threads)".to_string(),
+                    times: vec![6.0, 6.0],
+                },
+            ],
+        };
+
+        let html = to_html(&report);
+
+        assert!(html.starts_with("<!DOCTYPE html>\n"));
+        assert!(html.contains("<tr><td>Shape (MxKxN)</td><td>64x64x64</td></tr>\n"));
+        assert!(html.contains("<h2>Benchmark Results</h2>\n"));
+        assert!(html.contains("<tr><td>Sequential &lt;IKJ&gt;</td><td>20</td></tr>\n"));
+        // one bar per algorithm, the slowest one as long as the chart allows
+        assert_eq!(html.matches("<rect ").count(), 2);
+        assert!(html.contains(">20.0 ms</text>"));
+        assert!(html.contains(">6.00 ms</text>"));
+        // one line and one point per iteration per algorithm
+        assert_eq!(html.matches("fill=\"none\" stroke=\"#").count(), 2);
+        assert_eq!(html.matches("<circle ").count(), 4);
+        assert!(html.ends_with("</html>\n"));
+    }
+}
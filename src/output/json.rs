@@ -0,0 +1,133 @@
+use serde::{Deserialize, Serialize};
+
+use crate::{
+    benchmark::{InputDistribution, Timing},
+    clock::Clock,
+    matrix_multiplication::types::{ElementType, Shape},
+    order::ExecutionOrder,
+    stats::Summary,
+    system::SystemInfo,
+};
+
+use super::ResultRow;
+
+/// Whole benchmark run, as exported with `--format json`
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct Report {
+    pub parameters: Parameters,
+    /// Machine the benchmark ran on, at the end of the run
+    pub system: SystemInfo,
+    pub results: Vec<AlgorithmReport>,
+}
+
+/// Parameters shared by every algorithm of the run
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct Parameters {
+    pub shape: Shape,
+    pub iterations: usize,
+    pub distribution: InputDistribution,
+    pub clock: Clock,
+    pub align: Option<usize>,
+    pub pad: Option<usize>,
+    pub serialize_parallel: bool,
+    /// Seed the inputs were generated from, if given with `--seed`
+    pub seed: Option<u64>,
+    /// Whether every iteration reused the inputs of the first one
+    pub fixed_inputs: bool,
+    /// Order in which the algorithms ran within every iteration
+    #[serde(default)]
+    pub order: ExecutionOrder,
+    /// Number of untimed runs of every algorithm before its first timed run
+    #[serde(default)]
+    pub warmup: usize,
+    /// NUMA policy set with `--numa`, if any
+    #[cfg(feature = "numa")]
+    #[serde(default)]
+    pub numa: Option<String>,
+    /// Whether every run was timed in a child process of its own, with `--isolate`
+    #[cfg(feature = "isolate")]
+    #[serde(default)]
+    pub isolate: bool,
+}
+
+/// Results of a single algorithm
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct AlgorithmReport {
+    /// Aggregated results, as in the other formats
+    pub result: ResultRow,
+    pub element_type: ElementType,
+    /// Number of threads, `None` for sequential algorithms
+    pub threads: Option<usize>,
+    /// Summary statistics of the kernel times of the iterations
+    pub summary: Summary,
+    /// Timings of every iteration, in order
+    pub iterations: Vec<Timing>,
+}
+
+/// Returns the report as a pretty-printed JSON document
+pub fn to_json(report: &Report) -> String {
+    serde_json::to_string_pretty(report).expect("the report only has serializable fields")
+}
+
+/// Parses a report written by `to_json`
+pub fn from_json(json: &str) -> serde_json::Result<Report> {
+    serde_json::from_str(json)
+}
+
+#[cfg(test)]
+mod tests {
+    use std::time::Duration;
+
+    use super::*;
+
+    #[test]
+    fn test_to_json() {
+        let timing = Timing {
+            kernel: Duration::from_millis(12),
+            ..Timing::default()
+        };
+        let report = Report {
+            parameters: Parameters {
+                shape: Shape::square(64),
+                iterations: 2,
+                distribution: InputDistribution::Sparse(0.5, 0.25),
+                clock: Clock::Wall,
+                align: None,
+                pad: Some(16),
+                serialize_parallel: false,
+                seed: Some(42),
+                fixed_inputs: false,
+                order: ExecutionOrder::Shuffle,
+                warmup: 2,
+                #[cfg(feature = "numa")]
+                numa: Some("interleave".to_string()),
+                #[cfg(feature = "isolate")]
+                isolate: true,
+            },
+            system: SystemInfo::current(),
+            results: vec![AlgorithmReport {
+                result: ResultRow {
+                    algorithm: "Sequential IKJ".to_string(),
+                    time: Duration::from_millis(12),
+                    gops: Some(0.04),
+                    speedup: Some(1.0),
+                },
+                element_type: ElementType::I32,
+                threads: None,
+                summary: Summary::new(&[12.0, 12.0]),
+                iterations: vec![timing, timing],
+            }],
+        };
+
+        let json = to_json(&report);
+        let value: serde_json::Value = serde_json::from_str(&json).unwrap();
+        assert_eq!(value["parameters"]["clock"], "wall");
+        assert_eq!(value["results"][0]["result"]["algorithm"], "Sequential IKJ");
+        assert_eq!(value["results"][0]["element_type"], "i32");
+        assert_eq!(
+            value["results"][0]["iterations"][1]["kernel"]["nanos"],
+            12_000_000
+        );
+        assert_eq!(from_json(&json).unwrap(), report);
+    }
+}
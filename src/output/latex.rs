@@ -0,0 +1,129 @@
+use std::time::Duration;
+
+use super::{format_duration, format_optional, ResultRow};
+use crate::matrix_multiplication::types::Shape;
+
+/// Escapes the characters that have a special meaning in LaTeX
+fn escape(text: &str) -> String {
+    let mut escaped = String::with_capacity(text.len());
+    for c in text.chars() {
+        match c {
+            '&' | '%' | '$' | '#' | '_' | '{' | '}' => {
+                escaped.push('\\');
+                escaped.push(c);
+            }
+            '~' => escaped.push_str("\\textasciitilde{}"),
+            '^' => escaped.push_str("\\textasciicircum{}"),
+            '\\' => escaped.push_str("\\textbackslash{}"),
+            'µ' => escaped.push_str("\\textmu{}"),
+            _ => escaped.push(c),
+        }
+    }
+    escaped
+}
+
+/// Returns the caption of the results table of the products of `shape`, averaged over the given
+/// number of iterations, or over at least `iterations` and `min_time` with `--min-time`
+pub fn caption(shape: Shape, iterations: usize, min_time: Option<Duration>) -> String {
+    let matrices = match shape.is_square() {
+        true => format!("{n}x{n} matrices", n = shape.n),
+        false => format!(
+            "{}x{} by {}x{} matrices",
+            shape.m, shape.k, shape.k, shape.n
+        ),
+    };
+    match min_time {
+        Some(min_time) => format!(
+            "Benchmark results for {}, averaged over at least {} iterations and {:?} per \
+             algorithm.",
+            matrices, iterations, min_time
+        ),
+        None => format!(
+            "Benchmark results for {}, averaged over {} iterations.",
+            matrices, iterations
+        ),
+    }
+}
+
+/// Returns a booktabs-style LaTeX table of the results, with the given caption
+pub fn results_table(rows: &[ResultRow], caption: &str) -> String {
+    let mut latex = String::new();
+    latex.push_str("\\begin{table}[htbp]\n");
+    latex.push_str("  \\centering\n");
+    latex.push_str("  \\begin{tabular}{lrrr}\n");
+    latex.push_str("    \\toprule\n");
+    latex.push_str("    Algorithm & Time & GOP/s & Speedup \\\\\n");
+    latex.push_str("    \\midrule\n");
+    for row in rows {
+        latex.push_str(&format!(
+            "    {} & {} & {} & {} \\\\\n",
+            escape(&row.algorithm),
+            escape(&format_duration(row.time)),
+            format_optional(row.gops, 3),
+            format_optional(row.speedup, 2)
+        ));
+    }
+    latex.push_str("    \\bottomrule\n");
+    latex.push_str("  \\end{tabular}\n");
+    latex.push_str(&format!("  \\caption{{{}}}\n", escape(caption)));
+    latex.push_str("\\end{table}\n");
+    latex
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_escape() {
+        assert_eq!(escape("50% of A_1 & B"), "50\\% of A\\_1 \\& B");
+        assert_eq!(
+            escape("a~b^c\\d"),
+            "a\\textasciitilde{}b\\textasciicircum{}c\\textbackslash{}d"
+        );
+    }
+
+    #[test]
+    fn test_caption() {
+        assert_eq!(
+            caption(Shape::square(64), 5, None),
+            "Benchmark results for 64x64 matrices, averaged over 5 iterations."
+        );
+        assert_eq!(
+            caption(
+                Shape { m: 2, k: 3, n: 4 },
+                2,
+                Some(Duration::from_millis(100))
+            ),
+            "Benchmark results for 2x3 by 3x4 matrices, averaged over at least 2 iterations and \
+             100ms per algorithm."
+        );
+    }
+
+    #[test]
+    fn test_results_table() {
+        let rows = vec![
+            ResultRow {
+                algorithm: "Sequential IKJ".to_string(),
+                time: Duration::from_millis(20),
+                gops: Some(0.2097152),
+                speedup: Some(1.0),
+            },
+            ResultRow {
+                algorithm: "Parallel I Loop (4 threads)".to_string(),
+                time: Duration::from_micros(150),
+                gops: None,
+                speedup: None,
+            },
+        ];
+
+        let latex = results_table(&rows, "Results");
+
+        assert!(latex.starts_with("\\begin{table}[htbp]\n"));
+        assert!(latex.contains("    \\toprule\n"));
+        assert!(latex.contains("    Sequential IKJ & 20.0 ms & 0.210 & 1.00 \\\\\n"));
+        assert!(latex.contains("    Parallel I Loop (4 threads) & 150 \\textmu{}s & - & - \\\\\n"));
+        assert!(latex.contains("  \\caption{Results}\n"));
+        assert!(latex.ends_with("\\end{table}\n"));
+    }
+}
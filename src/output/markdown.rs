@@ -0,0 +1,53 @@
+/// Escapes the characters that would end a cell of a GitHub-flavored markdown table
+fn escape(text: &str) -> String {
+    text.replace('\\', "\\\\").replace('|', "\\|")
+}
+
+/// Returns a line of a markdown table with the given cells
+fn line<S: AsRef<str>>(cells: &[S]) -> String {
+    let cells = cells.iter().map(|cell| escape(cell.as_ref()));
+    format!("| {} |\n", cells.collect::<Vec<_>>().join(" | "))
+}
+
+/// Returns a GitHub-flavored markdown table, with one column per entry of `columns`, whose
+/// values are used as titles. Every column but the first one, holding the names of the
+/// algorithms, is right-aligned.
+pub fn table(columns: &[String], rows: &[Vec<String>]) -> String {
+    let alignments = (0..columns.len())
+        .map(|i| if i == 0 { "---" } else { "---:" })
+        .collect::<Vec<_>>();
+
+    let mut markdown = line(columns);
+    markdown.push_str(&line(&alignments));
+    for row in rows {
+        markdown.push_str(&line(row));
+    }
+    markdown
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_escape() {
+        assert_eq!(escape("a|b\\c"), "a\\|b\\\\c");
+    }
+
+    #[test]
+    fn test_table() {
+        let columns = ["Algorithm".to_string(), "Time (ms)".to_string()];
+        let rows = vec![
+            vec!["Sequential IKJ".to_string(), "20".to_string()],
+            vec!["Parallel I Loop (4 threads)".to_string(), "6".to_string()],
+        ];
+
+        assert_eq!(
+            table(&columns, &rows),
+            "| Algorithm | Time (ms) |\n\
+             | --- | ---: |\n\
+             | Sequential IKJ | 20 |\n\
+             | Parallel I Loop (4 threads) | 6 |\n"
+        );
+    }
+}
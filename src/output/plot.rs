@@ -0,0 +1,158 @@
+use std::{ops::Range, path::Path};
+
+use plotters::{coord::Shift, prelude::*};
+
+/// Size of the images, in pixels
+const SIZE: (u32, u32) = (1024, 640);
+
+/// Line of a chart, joining the values of an algorithm
+#[derive(Debug, Clone, PartialEq)]
+pub struct Line {
+    pub label: String,
+    pub points: Vec<(f64, f64)>,
+}
+
+/// Line chart drawn with `--plot`
+#[derive(Debug, Clone, PartialEq)]
+pub struct Chart {
+    pub title: String,
+    pub x_label: String,
+    pub y_label: String,
+    pub lines: Vec<Line>,
+}
+
+impl Chart {
+    /// Returns the range of the values on the x axis, and the one on the y axis starting from 0
+    /// with some room above the highest value for the points drawn on it
+    fn ranges(&self) -> (Range<f64>, Range<f64>) {
+        let points = self.lines.iter().flat_map(|line| line.points.iter());
+        let (x_min, x_max, y_max) = points.fold(
+            (f64::INFINITY, f64::NEG_INFINITY, 0.0f64),
+            |(x_min, x_max, y_max), (x, y)| (x_min.min(*x), x_max.max(*x), y_max.max(*y)),
+        );
+        let x = match x_min < x_max {
+            true => x_min..x_max,
+            // a single value on the x axis is drawn in the middle of the chart
+            false => x_min - 1.0..x_min + 1.0,
+        };
+        let y = match y_max > 0.0 {
+            true => 0.0..y_max * 1.05,
+            false => 0.0..1.0,
+        };
+        (x, y)
+    }
+}
+
+/// Draws the chart on the drawing area of any backend
+fn draw_on<DB: DrawingBackend>(
+    root: &DrawingArea<DB, Shift>,
+    chart: &Chart,
+) -> Result<(), DrawingAreaErrorKind<DB::ErrorType>> {
+    root.fill(&WHITE)?;
+    let (x, y) = chart.ranges();
+    let mut context = ChartBuilder::on(root)
+        .caption(&chart.title, ("sans-serif", 24))
+        .margin(16)
+        .x_label_area_size(48)
+        .y_label_area_size(72)
+        .build_cartesian_2d(x, y)?;
+    context
+        .configure_mesh()
+        .x_desc(&chart.x_label)
+        .y_desc(&chart.y_label)
+        .draw()?;
+
+    for (i, line) in chart.lines.iter().enumerate() {
+        let color = Palette99::pick(i).to_rgba();
+        context
+            .draw_series(LineSeries::new(
+                line.points.iter().copied(),
+                color.stroke_width(2),
+            ))?
+            .label(&line.label)
+            .legend(move |(x, y)| {
+                PathElement::new(vec![(x, y), (x + 20, y)], color.stroke_width(2))
+            });
+        context.draw_series(
+            line.points
+                .iter()
+                .map(|point| Circle::new(*point, 3, color.filled())),
+        )?;
+    }
+    context
+        .configure_series_labels()
+        .position(SeriesLabelPosition::UpperLeft)
+        .background_style(WHITE.mix(0.8))
+        .border_style(BLACK)
+        .draw()?;
+    root.present()
+}
+
+/// Draws the chart to `path`, as a PNG or an SVG image depending on its extension
+pub fn draw(path: &Path, chart: &Chart) -> Result<(), String> {
+    if chart.lines.iter().all(|line| line.points.is_empty()) {
+        return Err("there is nothing to plot".to_string());
+    }
+    match path.extension().and_then(|extension| extension.to_str()) {
+        Some("png") => draw_on(&BitMapBackend::new(path, SIZE).into_drawing_area(), chart)
+            .map_err(|err| err.to_string()),
+        Some("svg") => draw_on(&SVGBackend::new(path, SIZE).into_drawing_area(), chart)
+            .map_err(|err| err.to_string()),
+        _ => Err("the file must end in .png or .svg".to_string()),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::{env, fs};
+
+    use super::*;
+
+    fn chart() -> Chart {
+        Chart {
+            title: "Speedup".to_string(),
+            x_label: "Threads".to_string(),
+            y_label: "Speedup".to_string(),
+            lines: vec![
+                Line {
+                    label: "parallel-tiling".to_string(),
+                    points: vec![(1.0, 1.0), (2.0, 1.9), (4.0, 3.5)],
+                },
+                Line {
+                    label: "Ideal".to_string(),
+                    points: vec![(1.0, 1.0), (2.0, 2.0), (4.0, 4.0)],
+                },
+            ],
+        }
+    }
+
+    #[test]
+    fn test_ranges() {
+        let (x, y) = chart().ranges();
+        assert_eq!(x, 1.0..4.0);
+        assert_eq!(y, 0.0..4.2);
+
+        let single = Chart {
+            lines: vec![Line {
+                label: "parallel-tiling".to_string(),
+                points: vec![(8.0, 0.0)],
+            }],
+            ..chart()
+        };
+        assert_eq!(single.ranges(), (7.0..9.0, 0.0..1.0));
+    }
+
+    #[test]
+    fn test_draw_svg() {
+        let path = env::temp_dir().join(format!("plot-{}.svg", std::process::id()));
+
+        draw(&path, &chart()).unwrap();
+
+        let svg = fs::read_to_string(&path).unwrap();
+        fs::remove_file(&path).unwrap();
+        assert!(svg.starts_with("<svg"));
+        assert!(svg.contains("parallel-tiling"));
+        assert!(svg.contains("Ideal"));
+        assert!(draw(&path.with_extension("txt"), &chart()).is_err());
+    }
+}
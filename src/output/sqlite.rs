@@ -0,0 +1,224 @@
+use std::{
+    path::Path,
+    time::{Duration, SystemTime, UNIX_EPOCH},
+};
+
+use rusqlite::{params, Connection};
+
+use crate::{benchmark::Timing, matrix_multiplication::types::ElementType, system::SystemInfo};
+
+/// Tables of the history, created when missing so that every run appends to the same database
+const SCHEMA: &str = "
+CREATE TABLE IF NOT EXISTS runs (
+    id INTEGER PRIMARY KEY,
+    -- seconds since the Unix epoch
+    timestamp INTEGER NOT NULL,
+    version TEXT NOT NULL,
+    hostname TEXT,
+    os TEXT NOT NULL,
+    os_release TEXT,
+    kernel TEXT,
+    arch TEXT NOT NULL,
+    cpu_model TEXT,
+    logical_cpus INTEGER NOT NULL,
+    physical_cores INTEGER,
+    -- e.g. L1d 48 KiB, L1i 32 KiB, L2 2 MiB
+    caches TEXT NOT NULL,
+    total_memory_kib INTEGER,
+    rustc TEXT NOT NULL,
+    cpu_temperature REAL
+);
+CREATE TABLE IF NOT EXISTS parameters (
+    run_id INTEGER NOT NULL REFERENCES runs(id),
+    name TEXT NOT NULL,
+    value TEXT NOT NULL
+);
+CREATE TABLE IF NOT EXISTS results (
+    id INTEGER PRIMARY KEY,
+    run_id INTEGER NOT NULL REFERENCES runs(id),
+    algorithm TEXT NOT NULL,
+    element_type TEXT NOT NULL,
+    threads INTEGER,
+    average_ns INTEGER NOT NULL
+);
+CREATE TABLE IF NOT EXISTS iterations (
+    result_id INTEGER NOT NULL REFERENCES results(id),
+    iteration INTEGER NOT NULL,
+    kernel_ns INTEGER NOT NULL,
+    clock_ns INTEGER,
+    pool_ns INTEGER NOT NULL
+);
+";
+
+/// Timings of every iteration of a single algorithm
+#[derive(Debug, Clone, PartialEq)]
+pub struct AlgorithmRun {
+    pub algorithm: String,
+    pub element_type: ElementType,
+    /// Number of threads, `None` for sequential algorithms
+    pub threads: Option<usize>,
+    /// Average kernel time of the iterations
+    pub average: Duration,
+    pub iterations: Vec<Timing>,
+}
+
+/// Benchmark run, as appended to the database with `--db`
+#[derive(Debug, Clone, PartialEq)]
+pub struct Run {
+    /// Names and values of the parameters of the run
+    pub parameters: Vec<(String, String)>,
+    pub results: Vec<AlgorithmRun>,
+}
+
+/// Returns the duration in nanoseconds, as stored in the INTEGER columns of the database
+fn nanos(duration: Duration) -> i64 {
+    i64::try_from(duration.as_nanos()).unwrap_or(i64::MAX)
+}
+
+/// Creates the tables if missing and inserts the run in a single transaction, returning its id
+fn insert(
+    connection: &mut Connection,
+    run: &Run,
+    system: &SystemInfo,
+    timestamp: u64,
+) -> rusqlite::Result<i64> {
+    connection.execute_batch(SCHEMA)?;
+    let transaction = connection.transaction()?;
+    transaction.execute(
+        "INSERT INTO runs (timestamp, version, hostname, os, os_release, kernel, arch, cpu_model, \
+         logical_cpus, physical_cores, caches, total_memory_kib, rustc, cpu_temperature) \
+         VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8, ?9, ?10, ?11, ?12, ?13, ?14)",
+        params![
+            timestamp as i64,
+            env!("CARGO_PKG_VERSION"),
+            system.hostname,
+            system.os,
+            system.os_release,
+            system.kernel,
+            system.arch,
+            system.cpu_model,
+            system.logical_cpus as i64,
+            system.physical_cores.map(|cores| cores as i64),
+            system.cache_sizes(),
+            system.total_memory_kib.map(|kib| kib as i64),
+            system.rustc,
+            system.cpu_temperature,
+        ],
+    )?;
+    let run_id = transaction.last_insert_rowid();
+    for (name, value) in &run.parameters {
+        transaction.execute(
+            "INSERT INTO parameters (run_id, name, value) VALUES (?1, ?2, ?3)",
+            params![run_id, name, value],
+        )?;
+    }
+    for result in &run.results {
+        transaction.execute(
+            "INSERT INTO results (run_id, algorithm, element_type, threads, average_ns) \
+             VALUES (?1, ?2, ?3, ?4, ?5)",
+            params![
+                run_id,
+                result.algorithm,
+                result.element_type.to_string(),
+                result.threads.map(|threads| threads as i64),
+                nanos(result.average),
+            ],
+        )?;
+        let result_id = transaction.last_insert_rowid();
+        for (i, timing) in result.iterations.iter().enumerate() {
+            transaction.execute(
+                "INSERT INTO iterations (result_id, iteration, kernel_ns, clock_ns, pool_ns) \
+                 VALUES (?1, ?2, ?3, ?4, ?5)",
+                params![
+                    result_id,
+                    i as i64,
+                    nanos(timing.kernel),
+                    timing.clock.map(nanos),
+                    nanos(timing.pool),
+                ],
+            )?;
+        }
+    }
+    transaction.commit()?;
+    Ok(run_id)
+}
+
+/// Appends the run, the machine it ran on and the current time to the SQLite database at `path`,
+/// creating it if missing. Returns the id of the run in the `runs` table.
+pub fn append(path: &Path, run: &Run) -> rusqlite::Result<i64> {
+    let mut connection = Connection::open(path)?;
+    let timestamp = SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map_or(0, |elapsed| elapsed.as_secs());
+    insert(&mut connection, run, &SystemInfo::current(), timestamp)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_insert() {
+        let run = Run {
+            parameters: vec![("Shape (MxKxN)".to_string(), "64x64x64".to_string())],
+            results: vec![AlgorithmRun {
+                algorithm: "Parallel I Loop (4 threads)".to_string(),
+                element_type: ElementType::I32,
+                threads: Some(4),
+                average: Duration::from_millis(5),
+                iterations: vec![
+                    Timing {
+                        kernel: Duration::from_millis(4),
+                        ..Timing::default()
+                    },
+                    Timing {
+                        kernel: Duration::from_millis(6),
+                        clock: Some(Duration::from_millis(24)),
+                        ..Timing::default()
+                    },
+                ],
+            }],
+        };
+        let system = SystemInfo {
+            hostname: Some("bench".to_string()),
+            os: "linux".to_string(),
+            arch: "x86_64".to_string(),
+            logical_cpus: 8,
+            rustc: "rustc 1.80.0".to_string(),
+            ..SystemInfo::default()
+        };
+        let mut connection = Connection::open_in_memory().unwrap();
+
+        let first = insert(&mut connection, &run, &system, 1_700_000_000).unwrap();
+        let second = insert(&mut connection, &run, &system, 1_700_000_060).unwrap();
+
+        // runs are appended to the existing tables
+        assert_ne!(first, second);
+        let count = |table: &str| -> i64 {
+            connection
+                .query_row(&format!("SELECT COUNT(*) FROM {}", table), [], |row| {
+                    row.get(0)
+                })
+                .unwrap()
+        };
+        assert_eq!(count("runs"), 2);
+        assert_eq!(count("parameters"), 2);
+        assert_eq!(count("results"), 2);
+        assert_eq!(count("iterations"), 4);
+
+        let (algorithm, threads, kernel, clock): (String, Option<i64>, i64, Option<i64>) =
+            connection
+                .query_row(
+                    "SELECT algorithm, threads, kernel_ns, clock_ns FROM iterations \
+                     JOIN results ON results.id = iterations.result_id \
+                     WHERE run_id = ?1 AND iteration = 1",
+                    [second],
+                    |row| Ok((row.get(0)?, row.get(1)?, row.get(2)?, row.get(3)?)),
+                )
+                .unwrap();
+        assert_eq!(algorithm, "Parallel I Loop (4 threads)");
+        assert_eq!(threads, Some(4));
+        assert_eq!(kernel, 6_000_000);
+        assert_eq!(clock, Some(24_000_000));
+    }
+}
@@ -0,0 +1,205 @@
+/// Hardware events counted around a kernel, summed over the threads it ran on
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct Counters {
+    pub cycles: u64,
+    pub instructions: u64,
+    /// Misses of the last level cache
+    pub cache_misses: u64,
+}
+
+impl Counters {
+    /// Returns the element-wise mean of `counters`, or `None` if it is empty
+    pub fn mean(counters: &[Counters]) -> Option<Counters> {
+        let len = counters.len() as u64;
+        let sum = counters.iter().copied().reduce(|acc, counters| Counters {
+            cycles: acc.cycles + counters.cycles,
+            instructions: acc.instructions + counters.instructions,
+            cache_misses: acc.cache_misses + counters.cache_misses,
+        })?;
+        Some(Counters {
+            cycles: sum.cycles / len,
+            instructions: sum.instructions / len,
+            cache_misses: sum.cache_misses / len,
+        })
+    }
+
+    /// Returns the instructions retired per cycle
+    pub fn ipc(&self) -> Option<f64> {
+        (self.cycles > 0).then(|| self.instructions as f64 / self.cycles as f64)
+    }
+
+    /// Returns the cache misses per thousand instructions
+    pub fn mpki(&self) -> Option<f64> {
+        (self.instructions > 0)
+            .then(|| 1000.0 * self.cache_misses as f64 / self.instructions as f64)
+    }
+}
+
+#[cfg(target_os = "linux")]
+mod linux {
+    use std::{fs::File, io::Read, os::fd::FromRawFd};
+
+    use super::Counters;
+
+    /// `perf_event_attr::type` of the generalized hardware events
+    const PERF_TYPE_HARDWARE: u32 = 0;
+    const PERF_COUNT_HW_CPU_CYCLES: u64 = 0;
+    const PERF_COUNT_HW_INSTRUCTIONS: u64 = 1;
+    const PERF_COUNT_HW_CACHE_MISSES: u64 = 3;
+
+    /// `perf_event_attr::flags` bits
+    const FLAG_INHERIT: u64 = 1 << 1;
+    const FLAG_EXCLUDE_KERNEL: u64 = 1 << 5;
+    const FLAG_EXCLUDE_HV: u64 = 1 << 6;
+
+    /// `perf_event_open` flag closing the counter on exec
+    const PERF_FLAG_FD_CLOEXEC: libc::c_ulong = 1 << 3;
+
+    /// Prefix of `struct perf_event_attr` up to `config2`, the layout of `PERF_ATTR_SIZE_VER1`
+    /// which every kernel since 2.6.33 accepts
+    #[repr(C)]
+    #[derive(Default)]
+    struct PerfEventAttr {
+        type_: u32,
+        size: u32,
+        config: u64,
+        sample_period: u64,
+        sample_type: u64,
+        read_format: u64,
+        flags: u64,
+        wakeup_events: u32,
+        bp_type: u32,
+        config1: u64,
+        config2: u64,
+    }
+
+    /// Opens a counter of the hardware event `config` on the calling thread and on the threads it
+    /// spawns from now on, counting in user space only so that it works with the default
+    /// `perf_event_paranoid` setting.
+    fn open(config: u64) -> Option<File> {
+        let attr = PerfEventAttr {
+            type_: PERF_TYPE_HARDWARE,
+            size: std::mem::size_of::<PerfEventAttr>() as u32,
+            config,
+            flags: FLAG_INHERIT | FLAG_EXCLUDE_KERNEL | FLAG_EXCLUDE_HV,
+            ..PerfEventAttr::default()
+        };
+        // SAFETY: `attr` is a valid perf_event_attr of the size it declares, which outlives the
+        // call. pid 0 and cpu -1 count the calling thread on any CPU, with no group leader
+        let fd = unsafe {
+            libc::syscall(
+                libc::SYS_perf_event_open,
+                &attr as *const PerfEventAttr,
+                0 as libc::pid_t,
+                -1 as libc::c_int,
+                -1 as libc::c_int,
+                PERF_FLAG_FD_CLOEXEC,
+            )
+        };
+        // SAFETY: a non-negative return value is a newly opened file descriptor owned by nobody
+        // else
+        (fd >= 0).then(|| unsafe { File::from_raw_fd(fd as i32) })
+    }
+
+    /// Reads the value of a counter, including the counts of the threads that inherited it
+    fn read(mut counter: &File) -> Option<u64> {
+        let mut value = [0; 8];
+        counter.read_exact(&mut value).ok()?;
+        Some(u64::from_ne_bytes(value))
+    }
+
+    /// Counters of the hardware events, running since they were opened
+    pub struct CounterSet {
+        cycles: File,
+        instructions: File,
+        cache_misses: File,
+    }
+
+    impl CounterSet {
+        /// Starts counting, or returns `None` if the counters cannot be opened, e.g. in virtual
+        /// machines without a virtual PMU or when `perf_event_paranoid` forbids it
+        pub fn start() -> Option<CounterSet> {
+            Some(CounterSet {
+                cycles: open(PERF_COUNT_HW_CPU_CYCLES)?,
+                instructions: open(PERF_COUNT_HW_INSTRUCTIONS)?,
+                cache_misses: open(PERF_COUNT_HW_CACHE_MISSES)?,
+            })
+        }
+
+        /// Reads the counters and closes them
+        pub fn stop(self) -> Option<Counters> {
+            Some(Counters {
+                cycles: read(&self.cycles)?,
+                instructions: read(&self.instructions)?,
+                cache_misses: read(&self.cache_misses)?,
+            })
+        }
+    }
+}
+
+#[cfg(target_os = "linux")]
+pub use linux::CounterSet;
+
+/// Counters of the hardware events, not available on this platform
+#[cfg(not(target_os = "linux"))]
+pub struct CounterSet;
+
+#[cfg(not(target_os = "linux"))]
+impl CounterSet {
+    pub fn start() -> Option<CounterSet> {
+        None
+    }
+
+    pub fn stop(self) -> Option<Counters> {
+        None
+    }
+}
+
+/// Returns `true` if the hardware performance counters can be read on this machine
+pub fn is_available() -> bool {
+    CounterSet::start().and_then(CounterSet::stop).is_some()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_mean() {
+        let counters = [
+            Counters {
+                cycles: 100,
+                instructions: 300,
+                cache_misses: 3,
+            },
+            Counters {
+                cycles: 300,
+                instructions: 500,
+                cache_misses: 5,
+            },
+        ];
+        let mean = Counters::mean(&counters).unwrap();
+        assert_eq!(
+            mean,
+            Counters {
+                cycles: 200,
+                instructions: 400,
+                cache_misses: 4,
+            }
+        );
+        assert_eq!(mean.ipc(), Some(2.0));
+        assert_eq!(mean.mpki(), Some(10.0));
+
+        assert_eq!(Counters::mean(&[]), None);
+        assert_eq!(Counters::default().ipc(), None);
+    }
+
+    #[test]
+    fn test_counter_set() {
+        // the counters are not available everywhere, e.g. in most containers
+        if let Some(counters) = CounterSet::start().and_then(CounterSet::stop) {
+            assert!(counters.instructions > 0);
+        }
+    }
+}
@@ -0,0 +1,318 @@
+//! Quantized matrix multiplication, multiplying `i8` matrices and accumulating into `i32` as
+//! quantized neural network inference does.
+
+use crate::{
+    matrix_multiplication::{
+        in_pool,
+        matrix::Matrix,
+        sanitize::{sanitize_rectangular_matrices, SanitizeError},
+        simd,
+        types::{ElementType, MatrixRowPtr, Shape, SquareMatrixPtr},
+    },
+    thread_pool::ThreadPool,
+};
+
+/// Enum representing available quantized kernels, computing the `i32` product of two `i8`
+/// matrices
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum QuantizedKernel {
+    /// Sequential kernel with the ikj loop order, widening every element to i32
+    Sequential,
+    /// Sequential kernel computing every element of C as the dot product of a row of A and a row
+    /// of the transpose of B, with the widening multiply-add instructions of AVX2 or NEON. Falls
+    /// back to scalar dot products if neither is available.
+    SequentialDot,
+    /// Parallel version of the dot product kernel, splitting the rows of C into one stripe per
+    /// thread
+    ///
+    /// # Arguments
+    ///
+    /// * `usize` - number of threads to use
+    ParallelDot(usize),
+}
+
+impl std::fmt::Display for QuantizedKernel {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            QuantizedKernel::Sequential => write!(f, "Sequential Quantized"),
+            QuantizedKernel::SequentialDot => write!(f, "Sequential Quantized Dot"),
+            QuantizedKernel::ParallelDot(threads) => {
+                write!(f, "Parallel Quantized Dot ({} threads)", threads)
+            }
+        }
+    }
+}
+
+impl QuantizedKernel {
+    /// Returns the number of threads used by the kernel, or `None` if it is sequential
+    pub fn threads(&self) -> Option<usize> {
+        match self {
+            QuantizedKernel::Sequential | QuantizedKernel::SequentialDot => None,
+            QuantizedKernel::ParallelDot(threads) => Some(*threads),
+        }
+    }
+}
+
+/// Converts the elements of `m` to `i8`, failing if any of them is out of its range rather than
+/// wrapping it, e.g. elements of inputs loaded with `--load-inputs`
+pub fn to_i8(m: &Matrix<i32>, matrix_name: &str) -> Result<Matrix<i8>, SanitizeError> {
+    match m.iter_rows().flatten().all(|x| i8::try_from(*x).is_ok()) {
+        true => Ok(m.map(|x| *x as i8)),
+        false => Err(SanitizeError::ElementOutOfRange(
+            matrix_name.to_string(),
+            ElementType::I8,
+        )),
+    }
+}
+
+/// Multiplies the `i8` matrices `a` and `b` with the given kernel, accumulating into `i32`.
+/// The parallel kernel runs on `pool`, see `in_pool`.
+pub fn quantized_multiplication(
+    a: &Matrix<i8>,
+    b: &Matrix<i8>,
+    kernel: QuantizedKernel,
+    pool: Option<&ThreadPool>,
+) -> Result<Matrix<i32>, SanitizeError> {
+    let shape = sanitize_rectangular_matrices(a, b)?;
+
+    match kernel {
+        QuantizedKernel::Sequential => Ok(multiply_sequential(a, b, shape)),
+        QuantizedKernel::SequentialDot => {
+            let b_t = transpose(b, shape);
+            Ok(Matrix::from_fn(shape.m, shape.n, |i, j| {
+                dot(&a[i], &b_t[j])
+            }))
+        }
+        QuantizedKernel::ParallelDot(threads) => in_pool(pool, threads, |pool| {
+            Ok(multiply_parallel_dot(a, b, shape, threads, pool))
+        }),
+    }
+}
+
+fn multiply_sequential(a: &Matrix<i8>, b: &Matrix<i8>, shape: Shape) -> Matrix<i32> {
+    let mut c = Matrix::zeros(shape.m, shape.n);
+
+    for (a_i, c_i) in a.iter_rows().zip(c.iter_rows_mut()) {
+        for (a_ik, b_k) in a_i.iter().zip(b.iter_rows()) {
+            let a_ik = *a_ik as i32;
+            for (c_ij, b_kj) in c_i.iter_mut().zip(b_k) {
+                *c_ij += a_ik * *b_kj as i32;
+            }
+        }
+    }
+
+    c
+}
+
+/// Returns the `n` x `k` transpose of the `k` x `n` matrix `b`, whose rows are the columns of B
+fn transpose(b: &Matrix<i8>, shape: Shape) -> Matrix<i8> {
+    Matrix::from_fn(shape.n, shape.k, |j, k| b[k][j])
+}
+
+/// Dot product of `x` and `y`, which have the same length, accumulated into `i32`
+fn dot(x: &[i8], y: &[i8]) -> i32 {
+    #[cfg(target_arch = "x86_64")]
+    if simd::avx2_detected() {
+        // SAFETY: AVX2 support was just checked
+        return unsafe { x86::dot_avx2(x, y) };
+    }
+    #[cfg(target_arch = "aarch64")]
+    if simd::neon_detected() {
+        // SAFETY: NEON support was just checked
+        return unsafe { arm::dot_neon(x, y) };
+    }
+
+    dot_scalar(x, y)
+}
+
+fn dot_scalar(x: &[i8], y: &[i8]) -> i32 {
+    x.iter().zip(y).map(|(x, y)| *x as i32 * *y as i32).sum()
+}
+
+fn multiply_parallel_dot(
+    a: &Matrix<i8>,
+    b: &Matrix<i8>,
+    shape: Shape,
+    threads: usize,
+    pool: &ThreadPool,
+) -> Matrix<i32> {
+    let threads = threads.max(1);
+    let b_t = transpose(b, shape);
+    let mut c = Matrix::zeros(shape.m, shape.n);
+
+    let a = SquareMatrixPtr::new(a);
+    let b_t_ptr = SquareMatrixPtr::new(&b_t);
+    let c_ptrs = c
+        .iter_rows_mut()
+        .map(|row| MatrixRowPtr(row.as_mut_ptr()))
+        .collect::<Vec<_>>();
+    for t in 0..threads {
+        let rows = t * shape.m / threads..(t + 1) * shape.m / threads;
+        let a = a.clone();
+        let b_t = b_t_ptr.clone();
+        let mut c_rows = c_ptrs[rows.clone()].to_vec();
+
+        pool.execute(move || {
+            // SAFETY: the inputs outlive the jobs, which are waited for below, and every job
+            // writes to its own stripe of rows of C
+            for (i, c_i) in rows.zip(c_rows.iter_mut()) {
+                let a_i = unsafe { std::slice::from_raw_parts(a.get_row(i).0, shape.k) };
+                for j in 0..shape.n {
+                    let b_t_j = unsafe { std::slice::from_raw_parts(b_t.get_row(j).0, shape.k) };
+                    unsafe {
+                        *c_i.add_mut(j) = dot(a_i, b_t_j);
+                    }
+                }
+            }
+        });
+    }
+
+    pool.wait();
+
+    c
+}
+
+#[cfg(target_arch = "x86_64")]
+mod x86 {
+    use std::arch::x86_64::*;
+
+    /// Number of i8 elements widened to i16 and multiplied by a single AVX2 instruction
+    const AVX2_I8_LANES: usize = 16;
+
+    /// # Safety
+    ///
+    /// The CPU must support AVX2, and `x` and `y` must have the same length.
+    #[target_feature(enable = "avx2")]
+    pub(super) unsafe fn dot_avx2(x: &[i8], y: &[i8]) -> i32 {
+        let len = x.len();
+        let vectorized = len - len % AVX2_I8_LANES;
+        let mut acc = _mm256_setzero_si256();
+
+        for p in (0..vectorized).step_by(AVX2_I8_LANES) {
+            let x_v = _mm256_cvtepi8_epi16(_mm_loadu_si128(x.as_ptr().add(p) as *const __m128i));
+            let y_v = _mm256_cvtepi8_epi16(_mm_loadu_si128(y.as_ptr().add(p) as *const __m128i));
+            // multiplies the i16 pairs and adds adjacent products into i32, which cannot overflow
+            acc = _mm256_add_epi32(acc, _mm256_madd_epi16(x_v, y_v));
+        }
+
+        let sum = _mm_add_epi32(
+            _mm256_castsi256_si128(acc),
+            _mm256_extracti128_si256::<1>(acc),
+        );
+        let sum = _mm_add_epi32(sum, _mm_shuffle_epi32::<0b01_00_11_10>(sum));
+        let sum = _mm_add_epi32(sum, _mm_shuffle_epi32::<0b10_11_00_01>(sum));
+        let mut dot = _mm_cvtsi128_si32(sum);
+        for p in vectorized..len {
+            dot += x[p] as i32 * y[p] as i32;
+        }
+
+        dot
+    }
+}
+
+#[cfg(target_arch = "aarch64")]
+mod arm {
+    use std::arch::aarch64::*;
+
+    /// Number of i8 lanes of a NEON register
+    const NEON_I8_LANES: usize = 16;
+
+    /// # Safety
+    ///
+    /// The CPU must support NEON, and `x` and `y` must have the same length.
+    #[target_feature(enable = "neon")]
+    pub(super) unsafe fn dot_neon(x: &[i8], y: &[i8]) -> i32 {
+        let len = x.len();
+        let vectorized = len - len % NEON_I8_LANES;
+        let mut acc = vdupq_n_s32(0);
+
+        for p in (0..vectorized).step_by(NEON_I8_LANES) {
+            let x_v = vld1q_s8(x.as_ptr().add(p));
+            let y_v = vld1q_s8(y.as_ptr().add(p));
+            // widening multiplies into i16, then pairwise additions into the i32 accumulators
+            acc = vpadalq_s16(acc, vmull_s8(vget_low_s8(x_v), vget_low_s8(y_v)));
+            acc = vpadalq_s16(acc, vmull_high_s8(x_v, y_v));
+        }
+
+        let mut dot = vaddvq_s32(acc);
+        for p in vectorized..len {
+            dot += x[p] as i32 * y[p] as i32;
+        }
+
+        dot
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn get_matrix(rows: usize, cols: usize, seed: usize) -> Matrix<i8> {
+        Matrix::from_fn(rows, cols, |i, j| {
+            ((i * 37 + j * 11 + seed) % 256) as u8 as i8
+        })
+    }
+
+    #[test]
+    fn test_quantized_multiplication() {
+        // covers the extreme values of i8, and lengths around the vector widths
+        for (m, k, n) in [(1, 1, 1), (3, 16, 5), (7, 33, 4), (17, 40, 19)] {
+            let a = get_matrix(m, k, 0);
+            let b = get_matrix(k, n, 5);
+            let expected = Matrix::from_fn(m, n, |i, j| {
+                (0..k).map(|p| a[i][p] as i32 * b[p][j] as i32).sum()
+            });
+
+            for kernel in [
+                QuantizedKernel::Sequential,
+                QuantizedKernel::SequentialDot,
+                QuantizedKernel::ParallelDot(3),
+            ] {
+                assert_eq!(
+                    quantized_multiplication(&a, &b, kernel, None).unwrap(),
+                    expected,
+                    "{} on {}x{}x{}",
+                    kernel,
+                    m,
+                    k,
+                    n
+                );
+            }
+        }
+    }
+
+    #[test]
+    fn test_to_i8() {
+        let m = Matrix::from_fn(2, 2, |i, j| [-128, 127][(i + j) % 2]);
+        assert_eq!(to_i8(&m, "A").unwrap(), m.map(|x| *x as i8));
+
+        let m = Matrix::from_fn(2, 2, |i, j| (i * 2 + j) as i32 * 100);
+        assert_eq!(
+            to_i8(&m, "B"),
+            Err(SanitizeError::ElementOutOfRange(
+                "B".to_string(),
+                ElementType::I8
+            ))
+        );
+    }
+
+    #[test]
+    fn test_dot_extreme_values() {
+        let x = vec![i8::MIN; 50];
+        assert_eq!(dot(&x, &x), 50 * 128 * 128);
+        assert_eq!(dot(&x, &x), dot_scalar(&x, &x));
+    }
+
+    #[test]
+    fn test_quantized_inner_dimension_mismatch() {
+        assert_eq!(
+            quantized_multiplication(
+                &get_matrix(2, 3, 0),
+                &get_matrix(2, 3, 0),
+                QuantizedKernel::Sequential,
+                None
+            ),
+            Err(SanitizeError::InnerDimensionMismatch)
+        );
+    }
+}
@@ -0,0 +1,571 @@
+use std::{
+    sync::Arc,
+    thread,
+    time::{Duration, Instant},
+};
+
+use log::{debug, error, info, warn};
+
+#[cfg(feature = "isolate")]
+use crate::isolate::{self, ChildRun};
+#[cfg(feature = "perf")]
+use crate::perf::CounterSet;
+use crate::{
+    benchmark::{
+        apply_placement, pool_size, Benchmarkable, InputDistribution, InputPair, Timing,
+        TimingOptions,
+    },
+    cli::RunArgs,
+    clock::{CpuUsage, Stopwatch},
+    matrix_multiplication::{
+        algorithms::Algorithm,
+        generate::seeded_rng,
+        matrix::Matrix,
+        matrix_multiplication,
+        persist::{input_matrix_path, load_matrix, save_matrix},
+        registry::{MatMulAlgorithm, Registry},
+        types::Shape,
+    },
+    output::format_duration,
+    system::{PeakMemoryTracker, ThermalSample},
+    thread_pool::ThreadPool,
+    tsc,
+};
+
+/// Timings of every iteration of every algorithm of a benchmark suite, in the order in which the
+/// algorithms were given
+pub type Runs<K> = Vec<(K, Vec<Timing>)>;
+
+/// Builder of the benchmark suite of a set of algorithms, the engine of the benchmark
+/// subprograms, which other crates can use to embed the benchmark instead of running the binary.
+/// The options not set with the builder methods have the defaults of the command line options.
+#[derive(Clone)]
+pub struct BenchmarkRunner<K> {
+    algorithms: Vec<K>,
+    shapes: Vec<Shape>,
+    iterations: usize,
+    distribution: InputDistribution,
+    run: RunArgs,
+}
+
+impl<K: Benchmarkable> BenchmarkRunner<K> {
+    /// Returns a runner of `algorithms`, running 5 iterations on dense inputs without warm-up
+    /// runs nor verification. At least one size must be set before running it.
+    pub fn new(algorithms: impl IntoIterator<Item = K>) -> Self {
+        BenchmarkRunner {
+            algorithms: algorithms.into_iter().collect(),
+            shapes: Vec::new(),
+            iterations: 5,
+            distribution: InputDistribution::Dense,
+            run: RunArgs::default(),
+        }
+    }
+
+    /// Sets the sizes of the square matrices to multiply, replacing the sizes and shapes set
+    /// before
+    pub fn sizes(self, sizes: impl IntoIterator<Item = usize>) -> Self {
+        self.shapes(sizes.into_iter().map(Shape::square))
+    }
+
+    /// Sets the shapes of the products to compute, replacing the sizes and shapes set before
+    pub fn shapes(mut self, shapes: impl IntoIterator<Item = Shape>) -> Self {
+        self.shapes = shapes.into_iter().collect();
+        self
+    }
+
+    /// Sets the number of timed iterations of every algorithm on every size
+    pub fn iterations(mut self, iterations: usize) -> Self {
+        self.iterations = iterations;
+        self
+    }
+
+    /// Sets the number of untimed runs of every algorithm before its first timed run
+    pub fn warmup(mut self, runs: usize) -> Self {
+        self.run.warmup = runs;
+        self
+    }
+
+    /// Sets whether the result of every run is compared with the product computed by the
+    /// sequential ikj algorithm, as with `--check`
+    pub fn verify(mut self, verify: bool) -> Self {
+        self.run.check = verify;
+        self
+    }
+
+    /// Sets the seed the input matrices are generated from
+    pub fn seed(mut self, seed: u64) -> Self {
+        self.run.seed = Some(seed);
+        self
+    }
+
+    /// Sets the distribution of the generated input matrices
+    pub fn distribution(mut self, distribution: InputDistribution) -> Self {
+        self.distribution = distribution;
+        self
+    }
+
+    /// Sets every option shared by the benchmark subprograms at once, as parsed from the command
+    /// line, replacing the warm-up runs, the verification and the seed set before. The pinning to
+    /// cores and the NUMA policy are applied by `run`.
+    pub fn options(mut self, run: RunArgs) -> Self {
+        self.run = run;
+        self
+    }
+
+    /// Runs the benchmark suite on every size in turn, returning the timings of every size.
+    /// The pinning to cores and the NUMA policy of the options are applied first, and the run
+    /// fails if they cannot be, e.g. if the process is already pinned to other cores.
+    pub fn run(&self) -> Result<Vec<(Shape, Runs<K>)>, String> {
+        if self.shapes.is_empty() {
+            return Err("No size to benchmark".to_string());
+        }
+        apply_placement(&self.run)?;
+        self.shapes
+            .iter()
+            .map(|shape| Ok((*shape, self.suite(*shape)?)))
+            .collect()
+    }
+
+    /// Runs the benchmark suite for a given number of iterations. With `--min-time`, every
+    /// algorithm then keeps running on the inputs of the last iteration until it has run for the
+    /// time budget, unless one of its runs failed. With `--warmup`, every algorithm first runs
+    /// untimed on the inputs of the first iteration. With `--check`, the result of every algorithm
+    /// is compared with the product of the inputs computed by the sequential ikj algorithm. With
+    /// `--isolate`, every run is timed in a child process of its own.
+    pub(crate) fn suite(&self, shape: Shape) -> Result<Runs<K>, String> {
+        let run = &self.run;
+        #[cfg(feature = "isolate")]
+        let suite = isolate::next_suite();
+        #[cfg(feature = "isolate")]
+        if let Some(child) = isolate::child_run() {
+            return Ok(serve_child_run(
+                &self.algorithms,
+                self.iterations,
+                suite,
+                child,
+                run,
+            ));
+        }
+
+        let mut results: Runs<K> = Vec::with_capacity(self.algorithms.len());
+
+        for algorithm in &self.algorithms {
+            results.push((algorithm.clone(), Vec::with_capacity(self.iterations)));
+        }
+
+        // time spent running every algorithm, including the preparation of its inputs
+        let mut spent = vec![Duration::ZERO; self.algorithms.len()];
+        let below_budget = |spent: Duration| run.min_time.is_some_and(|min_time| spent < min_time);
+        // the algorithms that failed are not run to fill the time budget
+        let mut failed = vec![false; self.algorithms.len()];
+        let mut inputs = None;
+        let mut reference = None;
+        // the thermal state is sampled after every run, to annotate the runs during which the CPUs
+        // throttled
+        let mut thermal = ThermalSample::current();
+        let mut order_rng = seeded_rng(run.seed);
+        for i in 0.. {
+            let filling = |(spent, failed): (&Duration, &bool)| !failed && below_budget(*spent);
+            if i >= self.iterations && !spent.iter().zip(&failed).any(filling) {
+                break;
+            }
+            // with --fixed-inputs, the inputs of the first iteration are reused by the others
+            if (i < self.iterations && !run.fixed_inputs) || inputs.is_none() {
+                let (a, b) = iteration_inputs(run, i, shape, self.distribution)?;
+                if run.check {
+                    let product = matrix_multiplication(&a, &b, Algorithm::SequentialIkj, None);
+                    reference =
+                        Some(product.map_err(|err| format!("Cannot check the results: {}", err))?);
+                }
+                inputs = Some((a, b));
+                info!("Running iteration {}/{}", i + 1, self.iterations);
+            } else if i < self.iterations {
+                info!(
+                    "Running iteration {}/{} on the same inputs",
+                    i + 1,
+                    self.iterations
+                );
+            } else {
+                debug!("Running iteration {} to fill the time budget", i + 1);
+            }
+            let (a, b) = inputs.as_ref().unwrap();
+            let order = run.order.indices(self.algorithms.len(), i, &mut order_rng);
+            for (position, index) in order.into_iter().enumerate() {
+                let (algorithm, times) = &mut results[index];
+                let spent = &mut spent[index];
+                if i >= self.iterations && (failed[index] || !below_budget(*spent)) {
+                    continue;
+                }
+                // the warm-up runs of --isolate happen in the child processes
+                #[cfg(feature = "isolate")]
+                let warm_up_here = i == 0 && !run.isolate;
+                #[cfg(not(feature = "isolate"))]
+                let warm_up_here = i == 0;
+                if warm_up_here {
+                    warm_up(algorithm, a, b, run);
+                }
+                let start = Instant::now();
+                #[cfg(feature = "isolate")]
+                let time = match run.isolate {
+                    true => {
+                        let child = ChildRun {
+                            suite,
+                            algorithm: algorithm.to_string(),
+                        };
+                        isolate::time_in_child(&child, a, b, reference.as_ref()).unwrap_or_else(
+                            |err| {
+                                error!("In algorithm: {}. {}", algorithm, err);
+                                None
+                            },
+                        )
+                    }
+                    false => time_algorithm(algorithm, a, b, reference.as_ref(), run.into()),
+                };
+                #[cfg(not(feature = "isolate"))]
+                let time = time_algorithm(algorithm, a, b, reference.as_ref(), run.into());
+                failed[index] |= time.is_none();
+                let mut time = time.unwrap_or_default();
+                *spent += start.elapsed();
+                time.position = position;
+                let sample = ThermalSample::current();
+                let throttling = sample.throttling(&thermal);
+                thermal = sample;
+                if !throttling.is_empty() {
+                    warn!(
+                        "The CPUs throttled while running {}: {}",
+                        algorithm,
+                        throttling.join(", ")
+                    );
+                    time.throttled = 1;
+                    if let Some(pause) = run.throttle_pause {
+                        info!("Pausing for {:?} to let the CPUs cool down", pause);
+                        thread::sleep(pause);
+                        thermal = ThermalSample::current();
+                    }
+                }
+                times.push(time);
+                info!(
+                    "Finished {} in {} ({} spawning the pool)",
+                    algorithm,
+                    format_duration(time.kernel),
+                    format_duration(time.pool)
+                );
+            }
+        }
+        Ok(results)
+    }
+}
+
+impl BenchmarkRunner<Arc<dyn MatMulAlgorithm>> {
+    /// Returns a runner of the kernels of `registry`, in registration order
+    pub fn from_registry(registry: &Registry) -> Self {
+        Self::new(registry.algorithms().iter().cloned())
+    }
+
+    /// Adds `algorithm` after the kernels to run: any kernel implementing `MatMulAlgorithm`, e.g.
+    /// one of another crate, is run and reported the same way as the built-in algorithms
+    pub fn register<A: MatMulAlgorithm + 'static>(mut self, algorithm: A) -> Self {
+        self.algorithms.push(Arc::new(algorithm));
+        self
+    }
+}
+
+/// Benchmarks the execution time of a given kernel.
+/// The thread pool of parallel kernels is spawned before starting the timer and torn down after
+/// stopping it, and its cost is reported separately in `Timing::pool`.
+/// If a `reference` product is given, the result is compared with it after stopping the timer.
+/// Returns `None` if an error occurred, in which case the error is logged and printed to the
+/// console.
+pub fn time_algorithm<K: Benchmarkable>(
+    algorithm: &K,
+    a: &Matrix<i32>,
+    b: &Matrix<i32>,
+    reference: Option<&Matrix<i32>>,
+    options: TimingOptions,
+) -> Option<Timing> {
+    let inputs = algorithm.prepare(a, b);
+
+    // the counters are inherited by the threads spawned from now on, the workers of the pool
+    #[cfg(feature = "perf")]
+    let counters = options.perf_counters.then(CounterSet::start).flatten();
+
+    let spawn_start = Instant::now();
+    let pool = algorithm
+        .pool_threads()
+        .map(|threads| ThreadPool::new(pool_size(threads, options.serialize_parallel)));
+    let spawn = spawn_start.elapsed();
+
+    let memory = options.peak_memory.then(PeakMemoryTracker::start).flatten();
+    let cpu_start = options.cpu_time.then(CpuUsage::now).flatten();
+    let stopwatch = Stopwatch::start(options.clock);
+    // the fences of the reads would otherwise serialize every measurement
+    let tsc_start = options.tsc.then(tsc::start);
+    let res = algorithm.run(&inputs, pool.as_ref());
+    let tsc_stop = options.tsc.then(tsc::stop);
+    let (kernel, clock_time) = stopwatch.stop();
+    // the workers of the pool are still alive, their time is accounted for
+    let cpu_usage = cpu_start
+        .zip(CpuUsage::now())
+        .map(|(start, end)| end.since(&start));
+    let peak_memory = memory.and_then(PeakMemoryTracker::stop);
+    let pool_metrics = pool.as_ref().map(ThreadPool::metrics);
+
+    let teardown_start = Instant::now();
+    if let Some(pool) = pool {
+        ThreadPool::terminate(pool);
+    }
+    let teardown = teardown_start.elapsed();
+
+    // the counts of the workers are added up once they have exited
+    #[cfg(feature = "perf")]
+    let counters = counters.and_then(CounterSet::stop);
+
+    match res {
+        Ok(output) => {
+            let correct = reference.and_then(|reference| algorithm.check(&output, reference));
+            if correct == Some(false) {
+                error!(
+                    "In algorithm: {}. The result differs from the sequential ikj reference",
+                    algorithm
+                );
+            }
+            Some(Timing {
+                kernel,
+                clock: clock_time,
+                cycles: tsc_stop
+                    .zip(tsc_start)
+                    .map(|(stop, start)| stop.wrapping_sub(start)),
+                pool: spawn + teardown,
+                pool_metrics,
+                correct,
+                #[cfg(feature = "perf")]
+                counters,
+                peak_memory,
+                cpu_usage,
+                // detected by the caller, which samples the thermal state between the runs
+                throttled: 0,
+                position: 0,
+            })
+        }
+        Err(err) => {
+            error!("In algorithm: {}. {}", algorithm, err);
+            None
+        }
+    }
+}
+
+/// Runs `algorithm` untimed the number of times given with `--warmup`, discarding the results
+fn warm_up<K: Benchmarkable>(algorithm: &K, a: &Matrix<i32>, b: &Matrix<i32>, run: &RunArgs) {
+    for _ in 0..run.warmup {
+        time_algorithm(algorithm, a, b, None, run.into());
+    }
+}
+
+/// Returns the input matrices of the given iteration, padded and aligned as requested with `--pad`
+/// and `--align`.
+fn iteration_inputs(
+    run: &RunArgs,
+    iteration: usize,
+    shape: Shape,
+    distribution: InputDistribution,
+) -> Result<InputPair, String> {
+    let (a, b) = load_or_generate_inputs(run, iteration, shape, distribution)?;
+    Ok(layout_inputs(run, a, b))
+}
+
+/// Pads and aligns the input matrices as requested with `--pad` and `--align`
+fn layout_inputs(run: &RunArgs, mut a: Matrix<i32>, mut b: Matrix<i32>) -> InputPair {
+    if let Some(pad) = run.pad {
+        (a, b) = (a.padded(a.cols() + pad), b.padded(b.cols() + pad));
+    }
+    match run.align {
+        Some(align) => {
+            let (a, b) = (a.aligned(align), b.aligned(align));
+            debug_assert!(a.is_aligned(align) && b.is_aligned(align));
+            (a, b)
+        }
+        None => (a, b),
+    }
+}
+
+/// Returns the input matrices of the given iteration, either loaded from the directory given
+/// with `--load-inputs` or freshly generated from `distribution`. With `--seed`, every iteration
+/// is generated from its own seed derived from it, so that two runs generate the same inputs.
+/// Generated matrices are saved to the directory given with `--save-inputs`, if any.
+fn load_or_generate_inputs(
+    run: &RunArgs,
+    iteration: usize,
+    shape: Shape,
+    distribution: InputDistribution,
+) -> Result<InputPair, String> {
+    if let Some(dir) = &run.load_inputs {
+        let load = |name| {
+            let path = input_matrix_path(dir, iteration, name);
+            load_matrix(&path).map_err(|err| format!("Cannot load {}: {}", path.display(), err))
+        };
+        let (a, b) = (load("a")?, load("b")?);
+        if (a.rows(), a.cols()) != (shape.m, shape.k) || (b.rows(), b.cols()) != (shape.k, shape.n)
+        {
+            return Err(format!(
+                "Loaded inputs of iteration {} do not match the shape {}",
+                iteration + 1,
+                shape
+            ));
+        }
+        return Ok((a, b));
+    }
+
+    let seed = run.seed.map(|seed| seed.wrapping_add(iteration as u64));
+    let (a, b) = distribution.generate(shape, &mut seeded_rng(seed));
+
+    if let Some(dir) = &run.save_inputs {
+        for (name, matrix) in [("a", &a), ("b", &b)] {
+            let path = input_matrix_path(dir, iteration, name);
+            save_matrix(&path, matrix)
+                .map_err(|err| format!("Cannot save {}: {}", path.display(), err))?;
+        }
+    }
+
+    Ok((a, b))
+}
+
+/// Times the run a child process of `--isolate` was spawned for, once the subprogram reaches its
+/// suite, on the inputs sent by the parent, then exits. The suites before it are skipped, with
+/// empty timings standing for their runs, and never written anywhere since the child exits
+/// before the end of the subprogram.
+#[cfg(feature = "isolate")]
+fn serve_child_run<K: Benchmarkable>(
+    algorithms: &[K],
+    iterations: usize,
+    suite: usize,
+    child: ChildRun,
+    run: &RunArgs,
+) -> Runs<K> {
+    if child.suite > suite {
+        return algorithms
+            .iter()
+            .map(|algorithm| {
+                (
+                    algorithm.clone(),
+                    vec![Timing::default(); iterations.max(1)],
+                )
+            })
+            .collect();
+    }
+    let algorithm = algorithms
+        .iter()
+        .find(|algorithm| algorithm.to_string() == child.algorithm)
+        .filter(|_| child.suite == suite);
+    let served = match algorithm {
+        Some(algorithm) => isolate::read_inputs().and_then(|(a, b, reference)| {
+            let (a, b) = layout_inputs(run, a, b);
+            warm_up(algorithm, &a, &b, run);
+            isolate::write_timing(time_algorithm(
+                algorithm,
+                &a,
+                &b,
+                reference.as_ref(),
+                run.into(),
+            ))
+        }),
+        None => Err(format!(
+            "{} is not in the benchmark suite {} of the child process",
+            child.algorithm, child.suite
+        )),
+    };
+    match served {
+        Ok(()) => std::process::exit(0),
+        Err(err) => {
+            error!("{}", err);
+            std::process::exit(1);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::matrix_multiplication::checked::Checked;
+
+    #[test]
+    fn test_runner() {
+        let runs = BenchmarkRunner::new([Algorithm::SequentialIkj, Algorithm::ParallelILoop(2)])
+            .sizes([8, 16])
+            .iterations(2)
+            .warmup(1)
+            .verify(true)
+            .seed(42)
+            .run()
+            .unwrap();
+        assert_eq!(runs.len(), 2);
+        assert_eq!(runs[1].0, Shape::square(16));
+        for (_, suite) in &runs {
+            assert_eq!(suite[1].0, Algorithm::ParallelILoop(2));
+            for (_, times) in suite {
+                assert_eq!(times.len(), 2);
+                assert!(times.iter().all(|time| time.correct == Some(true)));
+            }
+        }
+
+        assert!(BenchmarkRunner::new([Algorithm::SequentialIkj])
+            .run()
+            .is_err());
+    }
+
+    #[test]
+    fn test_runner_min_time_failure() {
+        let run = RunArgs {
+            min_time: Some(Duration::from_millis(50)),
+            ..RunArgs::default()
+        };
+        // a tile size of 3 does not divide 8
+        let runs = BenchmarkRunner::new([Algorithm::SequentialIkj, Algorithm::SequentialTiling(3)])
+            .sizes([8])
+            .iterations(2)
+            .options(run)
+            .run()
+            .unwrap();
+        let suite = &runs[0].1;
+        assert!(suite[0].1.len() >= 2);
+        assert_eq!(suite[1].1.len(), 2);
+    }
+
+    #[test]
+    fn test_runner_registry() {
+        let mut registry = Registry::default();
+        registry.register(Algorithm::SequentialIkj);
+        let runs = BenchmarkRunner::from_registry(&registry)
+            .register(Checked(Algorithm::ParallelILoop(2)))
+            .sizes([8])
+            .iterations(1)
+            .verify(true)
+            .run()
+            .unwrap();
+        let names = runs[0]
+            .1
+            .iter()
+            .map(|(algorithm, _)| algorithm.to_string())
+            .collect::<Vec<_>>();
+        assert_eq!(
+            names,
+            ["Sequential IKJ", "Parallel I Loop (2 threads) (checked)"]
+        );
+        for (_, times) in &runs[0].1 {
+            assert_eq!(times[0].correct, Some(true));
+        }
+    }
+
+    #[test]
+    fn test_runner_placement() {
+        let run = RunArgs {
+            pin_cores: Some(vec![usize::MAX]),
+            ..RunArgs::default()
+        };
+        let err = BenchmarkRunner::new([Algorithm::SequentialIkj])
+            .sizes([8])
+            .options(run)
+            .run()
+            .unwrap_err();
+        assert!(err.starts_with("Cannot pin the benchmark to the cores"));
+    }
+}
@@ -0,0 +1,237 @@
+use std::panic::{self, AssertUnwindSafe};
+
+use clap::ValueEnum;
+use cli_table::Cell;
+use log::{debug, error, info};
+use rand::{rngs::StdRng, SeedableRng};
+
+use crate::{
+    cli::{AlgorithmName, SelfTest},
+    cli_tables::{print_args_table, print_benchmark_results_table, print_title},
+    matrix_multiplication::{
+        algorithms::Algorithm,
+        generate::{generate_random_square_matrix_with_rng, MAX_ABS_VALUE_DEFAULT},
+        matrix::Matrix,
+        matrix_multiplication,
+    },
+};
+
+/// Outcomes of the configurations of an algorithm on matrices of a given size
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+struct Outcomes {
+    passed: usize,
+    /// Configurations whose output differs from the reference, or that panicked
+    failed: usize,
+    /// Configurations rejected by the sanitization of the inputs, e.g. tile sizes that do not
+    /// divide the matrix size
+    skipped: usize,
+}
+
+impl Outcomes {
+    /// Returns the cell of the pass/fail matrix summarizing the outcomes
+    fn cell(&self) -> String {
+        match (self.failed, self.passed) {
+            (0, 0) => "skip".to_string(),
+            (0, _) => "ok".to_string(),
+            (failed, passed) => format!("FAIL {}/{}", failed, failed + passed),
+        }
+    }
+}
+
+/// Returns the distinct algorithms `name` stands for with the given thread counts and tile sizes.
+/// Sequential algorithms ignore the thread count, and most algorithms the tile size.
+fn configurations(name: AlgorithmName, threads: &[usize], tile_sizes: &[usize]) -> Vec<Algorithm> {
+    let mut algorithms: Vec<Algorithm> = Vec::new();
+    for threads in threads {
+        for tile_size in tile_sizes {
+            let algorithm = name.to_algorithm(*threads, *tile_size);
+            if !algorithms.contains(&algorithm) {
+                algorithms.push(algorithm);
+            }
+        }
+    }
+    algorithms
+}
+
+/// Runs every configuration on `a` and `b`, comparing the outputs with `reference`.
+/// Panics of the kernels are caught and count as failures.
+fn test_configurations(
+    algorithms: &[Algorithm],
+    a: &Matrix<i32>,
+    b: &Matrix<i32>,
+    reference: &Matrix<i32>,
+) -> Outcomes {
+    let mut outcomes = Outcomes::default();
+    for algorithm in algorithms {
+        let res = panic::catch_unwind(AssertUnwindSafe(|| {
+            matrix_multiplication(a, b, *algorithm, None)
+        }));
+        match res {
+            Ok(Ok(c)) if c == *reference => outcomes.passed += 1,
+            Ok(Ok(_)) => {
+                error!(
+                    "{} differs from the reference on {}x{} matrices",
+                    algorithm,
+                    a.rows(),
+                    a.rows()
+                );
+                outcomes.failed += 1;
+            }
+            Ok(Err(err)) => {
+                debug!(
+                    "Skipping {} on {}x{} matrices: {}",
+                    algorithm,
+                    a.rows(),
+                    a.rows(),
+                    err
+                );
+                outcomes.skipped += 1;
+            }
+            Err(_) => {
+                error!(
+                    "{} panicked on {}x{} matrices",
+                    algorithm,
+                    a.rows(),
+                    a.rows()
+                );
+                outcomes.failed += 1;
+            }
+        }
+    }
+    outcomes
+}
+
+/// Subprogram running every algorithm across a grid of small sizes, tile sizes and thread
+/// counts, comparing every output with the sequential reference, and printing a pass/fail matrix
+/// with a row per algorithm and a column per size. Returns `false` if any configuration failed.
+pub fn selftest(cli: &SelfTest) -> bool {
+    print_title("Welcome to Self Test!");
+
+    let join = |values: &[usize]| {
+        values
+            .iter()
+            .map(ToString::to_string)
+            .collect::<Vec<_>>()
+            .join(", ")
+    };
+    let table = vec![
+        vec!["Sizes".cell(), join(&cli.sizes).cell()],
+        vec!["Tile sizes".cell(), join(&cli.tile_sizes).cell()],
+        vec!["Threads".cell(), join(&cli.threads).cell()],
+        vec!["Seed".cell(), cli.seed.to_string().cell()],
+    ];
+    print_args_table(table);
+
+    let inputs = cli
+        .sizes
+        .iter()
+        .map(|size| {
+            let mut rng = StdRng::seed_from_u64(cli.seed.wrapping_add(*size as u64));
+            let a = generate_random_square_matrix_with_rng(*size, MAX_ABS_VALUE_DEFAULT, &mut rng);
+            let b = generate_random_square_matrix_with_rng(*size, MAX_ABS_VALUE_DEFAULT, &mut rng);
+            let reference = matrix_multiplication(&a, &b, Algorithm::SequentialIkj, None)
+                .expect("self test cases generate valid square matrices");
+            (a, b, reference)
+        })
+        .collect::<Vec<_>>();
+
+    let mut columns = vec!["Algorithm".to_string()];
+    columns.extend(cli.sizes.iter().map(|size| format!("{n}x{n}", n = size)));
+    let mut failed = false;
+    let mut rows = Vec::new();
+    for name in AlgorithmName::value_variants() {
+        let algorithms = configurations(*name, &cli.threads, &cli.tile_sizes);
+        let label = name.to_possible_value().unwrap().get_name().to_string();
+        info!("Testing {} in {} configurations", label, algorithms.len());
+        let mut row = vec![label.cell()];
+        for (a, b, reference) in &inputs {
+            let outcomes = test_configurations(&algorithms, a, b, reference);
+            failed |= outcomes.failed > 0;
+            row.push(outcomes.cell().cell());
+        }
+        rows.push(row);
+    }
+
+    print_title("Self Test Results");
+    print_benchmark_results_table(&columns, rows);
+
+    match failed {
+        true => error!("Some configurations disagree with the sequential reference"),
+        false => info!("Every configuration agrees with the sequential reference"),
+    }
+    !failed
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_configurations_are_distinct() {
+        let sequential = configurations(AlgorithmName::SequentialIkj, &[1, 2, 4], &[2, 4]);
+        assert_eq!(sequential, vec![Algorithm::SequentialIkj]);
+
+        let tiled = configurations(AlgorithmName::SequentialTiling, &[1, 2, 4], &[2, 4, 2]);
+        assert_eq!(
+            tiled,
+            vec![
+                Algorithm::SequentialTiling(2),
+                Algorithm::SequentialTiling(4)
+            ]
+        );
+    }
+
+    #[test]
+    fn test_outcomes_cell() {
+        let outcomes = |passed, failed, skipped| Outcomes {
+            passed,
+            failed,
+            skipped,
+        };
+        assert_eq!(outcomes(3, 0, 1).cell(), "ok");
+        assert_eq!(outcomes(0, 0, 2).cell(), "skip");
+        assert_eq!(outcomes(2, 1, 0).cell(), "FAIL 1/3");
+    }
+
+    #[test]
+    fn test_test_configurations() {
+        let mut rng = StdRng::seed_from_u64(3);
+        let a = generate_random_square_matrix_with_rng(6, MAX_ABS_VALUE_DEFAULT, &mut rng);
+        let b = generate_random_square_matrix_with_rng(6, MAX_ABS_VALUE_DEFAULT, &mut rng);
+        let reference = matrix_multiplication(&a, &b, Algorithm::SequentialIkj, None).unwrap();
+
+        let algorithms = configurations(AlgorithmName::ParallelTiling, &[1, 3], &[2, 4]);
+        let outcomes = test_configurations(&algorithms, &a, &b, &reference);
+
+        // 4 does not divide 6
+        assert_eq!(
+            outcomes,
+            Outcomes {
+                passed: 2,
+                failed: 0,
+                skipped: 2
+            }
+        );
+    }
+
+    #[test]
+    #[cfg(debug_assertions)]
+    fn test_test_configurations_parallel_panic() {
+        // the products overflow i32, which panics inside the jobs of the pools in debug builds
+        let a = Matrix::from_fn(4, 4, |_, _| i32::MAX);
+        let reference = Matrix::from_fn(4, 4, |_, _| 0);
+
+        let mut algorithms = configurations(AlgorithmName::ParallelILoop, &[2], &[2]);
+        algorithms.extend(configurations(AlgorithmName::ParallelTiling, &[2], &[2]));
+        let outcomes = test_configurations(&algorithms, &a, &a, &reference);
+
+        assert_eq!(
+            outcomes,
+            Outcomes {
+                passed: 0,
+                failed: 2,
+                skipped: 0
+            }
+        );
+    }
+}
@@ -0,0 +1,338 @@
+use std::sync::{Arc, Mutex};
+
+use crate::{
+    matrix_multiplication::{in_pool, matrix::Matrix, sanitize::SanitizeError},
+    thread_pool::ThreadPool,
+};
+
+/// Matrix in compressed sparse row (CSR) format, storing only the nonzero elements
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct CsrMatrix {
+    pub rows: usize,
+    pub cols: usize,
+    /// Offset in `col_indices` and `values` of the first nonzero of every row, followed by the
+    /// number of nonzeros
+    pub row_offsets: Vec<usize>,
+    /// Column of every nonzero, increasing within each row
+    pub col_indices: Vec<usize>,
+    pub values: Vec<i32>,
+}
+
+impl CsrMatrix {
+    /// Converts the dense matrix `m` to CSR, dropping its zeros
+    pub fn from_dense(m: &Matrix<i32>) -> CsrMatrix {
+        let mut csr = CsrMatrix {
+            rows: m.rows(),
+            cols: m.cols(),
+            row_offsets: Vec::with_capacity(m.rows() + 1),
+            col_indices: Vec::new(),
+            values: Vec::new(),
+        };
+        csr.row_offsets.push(0);
+        for row in m.iter_rows() {
+            for (j, m_ij) in row.iter().enumerate().filter(|(_, m_ij)| **m_ij != 0) {
+                csr.col_indices.push(j);
+                csr.values.push(*m_ij);
+            }
+            csr.row_offsets.push(csr.values.len());
+        }
+        csr
+    }
+
+    /// Returns the number of nonzero elements
+    pub fn nnz(&self) -> usize {
+        self.values.len()
+    }
+
+    /// Returns the (column, value) pairs of the nonzeros of row `i`
+    fn row(&self, i: usize) -> impl Iterator<Item = (usize, i32)> + '_ {
+        let range = self.row_offsets[i]..self.row_offsets[i + 1];
+        self.col_indices[range.clone()]
+            .iter()
+            .copied()
+            .zip(self.values[range].iter().copied())
+    }
+}
+
+/// Enum representing available sparse matrix multiplication kernels, both implementing
+/// Gustavson's row-by-row algorithm
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum SparseKernel {
+    /// Sequential kernel
+    ///
+    /// # Arguments
+    ///
+    /// * `f64` - expected density of A, used to estimate the number of operations
+    /// * `f64` - expected density of B, used to estimate the number of operations
+    Sequential(f64, f64),
+    /// Parallel kernel splitting the rows of C into one stripe per thread
+    ///
+    /// # Arguments
+    ///
+    /// * `f64` - expected density of A, used to estimate the number of operations
+    /// * `f64` - expected density of B, used to estimate the number of operations
+    /// * `usize` - number of threads to use
+    Parallel(f64, f64, usize),
+}
+
+impl std::fmt::Display for SparseKernel {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            SparseKernel::Sequential(_, _) => write!(f, "Sequential Gustavson"),
+            SparseKernel::Parallel(_, _, threads) => {
+                write!(f, "Parallel Gustavson ({} threads)", threads)
+            }
+        }
+    }
+}
+
+impl SparseKernel {
+    /// Returns the number of threads used by the kernel, or `None` if it is sequential
+    pub fn threads(&self) -> Option<usize> {
+        match self {
+            SparseKernel::Sequential(_, _) => None,
+            SparseKernel::Parallel(_, _, threads) => Some(*threads),
+        }
+    }
+
+    /// Returns the expected densities of A and B
+    pub fn densities(&self) -> (f64, f64) {
+        match self {
+            SparseKernel::Sequential(density_a, density_b)
+            | SparseKernel::Parallel(density_a, density_b, _) => (*density_a, *density_b),
+        }
+    }
+}
+
+/// Multiplies the sparse matrices `a` and `b` with the given kernel.
+/// The parallel kernel runs on `pool`, see `in_pool`.
+pub fn spgemm(
+    a: &CsrMatrix,
+    b: &CsrMatrix,
+    kernel: SparseKernel,
+    pool: Option<&ThreadPool>,
+) -> Result<CsrMatrix, SanitizeError> {
+    if a.cols != b.rows {
+        return Err(SanitizeError::InnerDimensionMismatch);
+    }
+
+    match kernel {
+        SparseKernel::Sequential(_, _) => Ok(spgemm_sequential(a, b)),
+        SparseKernel::Parallel(_, _, threads) => Ok(in_pool(pool, threads, |pool| {
+            spgemm_parallel(a, b, threads, pool)
+        })),
+    }
+}
+
+/// Nonzeros of a stripe of consecutive rows of C, in CSR order
+#[derive(Default)]
+struct Stripe {
+    /// Number of nonzeros of every row
+    row_lengths: Vec<usize>,
+    col_indices: Vec<usize>,
+    values: Vec<i32>,
+}
+
+/// Dense accumulator of a row of C, tracking which of its columns were touched
+struct Accumulator {
+    values: Vec<i32>,
+    touched: Vec<bool>,
+    columns: Vec<usize>,
+}
+
+impl Accumulator {
+    fn new(cols: usize) -> Accumulator {
+        Accumulator {
+            values: vec![0; cols],
+            touched: vec![false; cols],
+            columns: Vec::new(),
+        }
+    }
+
+    /// Computes row `i` of `a` times `b` and appends its nonzeros to `stripe`, resetting the
+    /// accumulator for the next row
+    fn multiply_row(&mut self, a: &CsrMatrix, b: &CsrMatrix, i: usize, stripe: &mut Stripe) {
+        for (k, a_ik) in a.row(i) {
+            for (j, b_kj) in b.row(k) {
+                if !self.touched[j] {
+                    self.touched[j] = true;
+                    self.columns.push(j);
+                }
+                self.values[j] += a_ik * b_kj;
+            }
+        }
+
+        self.columns.sort_unstable();
+        let start = stripe.values.len();
+        for &j in &self.columns {
+            // products may cancel out, leaving an explicit zero out of the result
+            if self.values[j] != 0 {
+                stripe.col_indices.push(j);
+                stripe.values.push(self.values[j]);
+            }
+            self.values[j] = 0;
+            self.touched[j] = false;
+        }
+        stripe.row_lengths.push(stripe.values.len() - start);
+        self.columns.clear();
+    }
+}
+
+/// Assembles the stripes of C, in row order, into a matrix
+fn from_stripes(rows: usize, cols: usize, stripes: Vec<Stripe>) -> CsrMatrix {
+    let mut c = CsrMatrix {
+        rows,
+        cols,
+        row_offsets: Vec::with_capacity(rows + 1),
+        col_indices: Vec::with_capacity(stripes.iter().map(|s| s.values.len()).sum()),
+        values: Vec::new(),
+    };
+    c.row_offsets.push(0);
+    for stripe in stripes {
+        for length in stripe.row_lengths {
+            c.row_offsets.push(c.row_offsets.last().unwrap() + length);
+        }
+        c.col_indices.extend(stripe.col_indices);
+        c.values.extend(stripe.values);
+    }
+    c
+}
+
+fn spgemm_sequential(a: &CsrMatrix, b: &CsrMatrix) -> CsrMatrix {
+    let mut accumulator = Accumulator::new(b.cols);
+    let mut stripe = Stripe::default();
+    for i in 0..a.rows {
+        accumulator.multiply_row(a, b, i, &mut stripe);
+    }
+
+    from_stripes(a.rows, b.cols, vec![stripe])
+}
+
+/// Pointer to a matrix borrowed by the jobs of the pool, which are waited for before it goes out
+/// of scope
+#[derive(Clone, Copy)]
+struct CsrMatrixPtr(*const CsrMatrix);
+
+unsafe impl Send for CsrMatrixPtr {}
+
+impl CsrMatrixPtr {
+    /// # Safety
+    ///
+    /// The matrix must still be alive
+    unsafe fn get<'a>(self) -> &'a CsrMatrix {
+        &*self.0
+    }
+}
+
+fn spgemm_parallel(a: &CsrMatrix, b: &CsrMatrix, threads: usize, pool: &ThreadPool) -> CsrMatrix {
+    let threads = threads.max(1);
+    let stripes: Arc<Mutex<Vec<Stripe>>> = Arc::new(Mutex::new(
+        (0..threads).map(|_| Stripe::default()).collect(),
+    ));
+
+    let (a_ptr, b_ptr) = (CsrMatrixPtr(a), CsrMatrixPtr(b));
+    for n in 0..threads {
+        let rows = n * a.rows / threads..(n + 1) * a.rows / threads;
+        let stripes = Arc::clone(&stripes);
+        pool.execute(move || {
+            // SAFETY: the matrices outlive the jobs, which are waited for below
+            let (a, b) = unsafe { (a_ptr.get(), b_ptr.get()) };
+            let mut accumulator = Accumulator::new(b.cols);
+            let mut stripe = Stripe::default();
+            for i in rows {
+                accumulator.multiply_row(a, b, i, &mut stripe);
+            }
+            stripes.lock().unwrap()[n] = stripe;
+        });
+    }
+
+    pool.wait();
+
+    let stripes = std::mem::take(&mut *stripes.lock().unwrap());
+    from_stripes(a.rows, b.cols, stripes)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::matrix_multiplication::{algorithms::Algorithm, matrix_multiplication};
+
+    fn to_dense(m: &CsrMatrix) -> Matrix<i32> {
+        let mut dense = Matrix::zeros(m.rows, m.cols);
+        for i in 0..m.rows {
+            for (j, m_ij) in m.row(i) {
+                dense[i][j] = m_ij;
+            }
+        }
+        dense
+    }
+
+    /// Matrix with a nonzero on roughly one element out of `period`
+    fn get_sparse(size: usize, period: usize, seed: usize) -> Matrix<i32> {
+        Matrix::from_fn(size, size, |i, j| match (i * 7 + j * 13 + seed) % period {
+            0 => ((i + j) % 5) as i32 - 2,
+            _ => 0,
+        })
+    }
+
+    #[test]
+    fn test_csr_round_trip() {
+        let m = get_sparse(9, 4, 0);
+        let csr = CsrMatrix::from_dense(&m);
+
+        assert_eq!(csr.nnz(), m.as_slice().iter().filter(|x| **x != 0).count());
+        assert_eq!(to_dense(&csr), m);
+    }
+
+    #[test]
+    fn test_spgemm_cancellation() {
+        // C[0][0] = 1 * 1 + 1 * -1 is touched but zero, and is not stored
+        let a =
+            CsrMatrix::from_dense(&Matrix::from_rows(vec![vec![1, 1], vec![0, 2]], "A").unwrap());
+        let b =
+            CsrMatrix::from_dense(&Matrix::from_rows(vec![vec![1, 0], vec![-1, 3]], "B").unwrap());
+
+        for kernel in [
+            SparseKernel::Sequential(0.5, 0.5),
+            SparseKernel::Parallel(0.5, 0.5, 2),
+        ] {
+            let c = spgemm(&a, &b, kernel, None).unwrap();
+            assert_eq!(c.row_offsets, vec![0, 1, 3], "{}", kernel);
+            assert_eq!(c.col_indices, vec![1, 0, 1], "{}", kernel);
+            assert_eq!(c.values, vec![3, -2, 6], "{}", kernel);
+        }
+    }
+
+    #[test]
+    fn test_spgemm_empty_rows_and_stripes() {
+        // a rectangular A whose rows 1 and 3 are empty, split among more threads than rows, so
+        // that some stripes of C hold no row at all
+        let a = Matrix::from_fn(4, 6, |i, j| match i % 2 {
+            0 => (i * 7 + j * 13) as i32 % 5 - 2,
+            _ => 0,
+        });
+        let b = get_sparse(6, 3, 1);
+        let expected = matrix_multiplication(&a, &b, Algorithm::SequentialIkj, None).unwrap();
+        let (a, b) = (CsrMatrix::from_dense(&a), CsrMatrix::from_dense(&b));
+
+        for kernel in [
+            SparseKernel::Sequential(0.5, 0.5),
+            SparseKernel::Parallel(0.5, 0.5, 7),
+        ] {
+            let c = spgemm(&a, &b, kernel, None).unwrap();
+            assert_eq!((c.rows, c.cols), (4, 6), "{}", kernel);
+            assert_eq!(to_dense(&c), expected, "{}", kernel);
+        }
+    }
+
+    #[test]
+    fn test_spgemm_inner_dimension_mismatch() {
+        let a = CsrMatrix::from_dense(&get_sparse(3, 2, 0));
+        let b = CsrMatrix::from_dense(&get_sparse(4, 2, 0));
+
+        assert_eq!(
+            spgemm(&a, &b, SparseKernel::Sequential(1.0, 1.0), None),
+            Err(SanitizeError::InnerDimensionMismatch)
+        );
+    }
+}
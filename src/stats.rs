@@ -0,0 +1,150 @@
+//! Summary statistics of the execution times of the iterations of a benchmark
+
+/// Two-sided 95% critical values of Student's t distribution, for 1 to 30 degrees of freedom
+const T_975: [f64; 30] = [
+    12.706, 4.303, 3.182, 2.776, 2.571, 2.447, 2.365, 2.306, 2.262, 2.228, 2.201, 2.179, 2.160,
+    2.145, 2.131, 2.120, 2.110, 2.101, 2.093, 2.086, 2.080, 2.074, 2.069, 2.064, 2.060, 2.056,
+    2.052, 2.048, 2.045, 2.042,
+];
+
+/// Critical value of the normal distribution, used past 30 degrees of freedom
+const Z_975: f64 = 1.96;
+
+/// Summary statistics of the execution times of an algorithm over all the iterations, in
+/// milliseconds
+#[derive(Debug, Clone, Copy, Default, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct Summary {
+    /// Number of samples
+    pub count: usize,
+    pub min: f64,
+    pub max: f64,
+    pub median: f64,
+    /// Sample standard deviation, zero for a single iteration
+    pub stddev: f64,
+    /// Half-width of the 95% confidence interval of the mean, from Student's t distribution,
+    /// `None` for a single iteration
+    pub ci95: Option<f64>,
+    /// 95th percentile, with the nearest-rank method
+    pub p95: f64,
+    /// Number of samples outside of Tukey's fences, more than 1.5 interquartile ranges below the
+    /// first quartile or above the third one
+    pub outliers: usize,
+}
+
+impl Summary {
+    /// Returns the summary statistics of the given samples, or the default summary if there are
+    /// none.
+    pub fn new(samples: &[f64]) -> Summary {
+        if samples.is_empty() {
+            return Summary::default();
+        }
+        let mut sorted = samples.to_vec();
+        sorted.sort_by(f64::total_cmp);
+        let len = sorted.len();
+
+        let median = match len % 2 {
+            0 => (sorted[len / 2 - 1] + sorted[len / 2]) / 2.0,
+            _ => sorted[len / 2],
+        };
+        let mean = sorted.iter().sum::<f64>() / len as f64;
+        let stddev = match len {
+            1 => 0.0,
+            _ => {
+                let squares = sorted.iter().map(|x| (x - mean).powi(2)).sum::<f64>();
+                (squares / (len - 1) as f64).sqrt()
+            }
+        };
+        let ci95 = (len > 1).then(|| {
+            let t = T_975.get(len - 2).copied().unwrap_or(Z_975);
+            t * stddev / (len as f64).sqrt()
+        });
+        let p95_rank = (0.95 * len as f64).ceil() as usize;
+        let (q1, q3) = (quantile(&sorted, 0.25), quantile(&sorted, 0.75));
+        let (low, high) = (q1 - 1.5 * (q3 - q1), q3 + 1.5 * (q3 - q1));
+        let outliers = sorted.iter().filter(|x| **x < low || **x > high).count();
+
+        Summary {
+            count: len,
+            min: sorted[0],
+            max: sorted[len - 1],
+            median,
+            stddev,
+            ci95,
+            p95: sorted[p95_rank.max(1) - 1],
+            outliers,
+        }
+    }
+}
+
+/// Returns the `q` quantile of the sorted samples, interpolating linearly between the closest
+/// ranks.
+fn quantile(sorted: &[f64], q: f64) -> f64 {
+    let rank = q * (sorted.len() - 1) as f64;
+    let (below, above) = (rank.floor() as usize, rank.ceil() as usize);
+    sorted[below] + (sorted[above] - sorted[below]) * (rank - below as f64)
+}
+
+/// Returns the mean of the samples without the given fraction of the lowest and of the highest
+/// ones, rounded down to a whole number of samples, or zero if there are no samples.
+pub fn trimmed_mean(samples: &[f64], fraction: f64) -> f64 {
+    let mut sorted = samples.to_vec();
+    sorted.sort_by(f64::total_cmp);
+    let trimmed = (fraction * sorted.len() as f64) as usize;
+    let kept = &sorted[trimmed..sorted.len() - trimmed];
+    match kept.len() {
+        0 => 0.0,
+        len => kept.iter().sum::<f64>() / len as f64,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_summary() {
+        let summary = Summary::new(&[4.0, 1.0, 3.0, 2.0]);
+        assert_eq!(summary.count, 4);
+        assert_eq!(summary.min, 1.0);
+        assert_eq!(summary.max, 4.0);
+        assert_eq!(summary.median, 2.5);
+        assert!((summary.stddev - (5.0f64 / 3.0).sqrt()).abs() < 1e-12);
+        assert_eq!(summary.p95, 4.0);
+        assert_eq!(summary.outliers, 0);
+        // t = 3.182 for 3 degrees of freedom
+        let ci95 = summary.ci95.unwrap();
+        assert!((ci95 - 3.182 * (5.0f64 / 3.0).sqrt() / 2.0).abs() < 1e-12);
+
+        let samples = (1..=100).map(|x| x as f64).collect::<Vec<_>>();
+        let summary = Summary::new(&samples);
+        assert_eq!(summary.median, 50.5);
+        assert_eq!(summary.p95, 95.0);
+
+        let summary = Summary::new(&[7.0]);
+        assert_eq!(summary.ci95, None);
+        assert_eq!(
+            (summary.median, summary.stddev, summary.p95),
+            (7.0, 0.0, 7.0)
+        );
+        assert_eq!(Summary::new(&[]), Summary::default());
+    }
+
+    #[test]
+    fn test_outliers() {
+        // a scheduler hiccup in the third iteration
+        let summary = Summary::new(&[10.0, 11.0, 48.0, 10.0, 12.0]);
+        assert_eq!(summary.outliers, 1);
+
+        assert_eq!(Summary::new(&[10.0, 11.0, 12.0, 10.0, 12.0]).outliers, 0);
+    }
+
+    #[test]
+    fn test_trimmed_mean() {
+        let samples = [10.0, 11.0, 48.0, 10.0, 12.0];
+        assert_eq!(trimmed_mean(&samples, 0.0), 18.2);
+        assert_eq!(trimmed_mean(&samples, 0.1), 18.2);
+        assert_eq!(trimmed_mean(&samples, 0.2), 11.0);
+        assert_eq!(trimmed_mean(&[], 0.2), 0.0);
+    }
+}
@@ -0,0 +1,178 @@
+use std::{
+    thread,
+    time::{Duration, Instant},
+};
+
+use cli_table::Cell;
+use log::{error, info, warn};
+
+use crate::{
+    benchmark::{gops, pool_size, Benchmarkable},
+    cli::Stress,
+    cli_tables::{print_args_table, print_title},
+    matrix_multiplication::{
+        algorithms::Algorithm, generate::generate_square_matrix_of_size, initialize,
+        matrix_multiplication, types::Shape,
+    },
+    system,
+    thread_pool::ThreadPool,
+};
+
+/// Progress of the stress test since the last report
+struct Interval {
+    start: Instant,
+    runs: u64,
+}
+
+/// Returns the throughput of `runs` multiplications of `size` x `size` matrices done in
+/// `elapsed`, in GOP/s
+fn runs_gops(algorithm: Algorithm, size: usize, runs: u64, elapsed: Duration) -> f64 {
+    gops(
+        algorithm.operations(Shape::square(size)) * runs as f64,
+        elapsed,
+    )
+}
+
+fn format_temperature(temperature: Option<f64>) -> String {
+    match temperature {
+        Some(temperature) => format!("{:.1} °C", temperature),
+        None => "n/a".to_string(),
+    }
+}
+
+/// Subprogram continuously running an algorithm for a given duration, verifying every result
+/// against the sequential reference and periodically reporting throughput and temperature.
+/// Returns `false` if a run produced a wrong result or failed.
+pub fn stress_test(cli: &Stress) -> bool {
+    let n = cli.size;
+    let available_threads = thread::available_parallelism().unwrap().get();
+    let threads = cli.threads.unwrap_or(available_threads);
+    let algorithm = cli.algorithm.to_algorithm(threads, cli.tile_size);
+
+    print_title("Welcome to Stress Test!");
+
+    let mut table = vec![
+        vec!["Matrix size".cell(), n.to_string().cell()],
+        vec!["Algorithm".cell(), algorithm.to_string().cell()],
+        vec!["Duration".cell(), format!("{:?}", cli.duration).cell()],
+        vec![
+            "Report interval".cell(),
+            format!("{:?}", cli.report_interval).cell(),
+        ],
+        vec![
+            "Serialize parallel".cell(),
+            cli.serialize_parallel.to_string().cell(),
+        ],
+    ];
+    if let Some(seed) = cli.seed {
+        table.push(vec!["Seed".cell(), seed.cell()]);
+    }
+    print_args_table(table);
+
+    let a = generate_square_matrix_of_size(n, true, None, cli.seed);
+    let b =
+        generate_square_matrix_of_size(n, true, None, cli.seed.map(|seed| seed.wrapping_add(1)));
+    let reference = match matrix_multiplication(&a, &b, Algorithm::SequentialIkj, None) {
+        Ok(reference) => reference,
+        Err(err) => {
+            error!("{}", err);
+            return false;
+        }
+    };
+
+    print_title("Stressing!");
+
+    // the pool is kept alive for the whole test, as a sustained load would
+    initialize(algorithm);
+    let pool = algorithm
+        .pool_threads()
+        .map(|threads| ThreadPool::new(pool_size(threads, cli.serialize_parallel)));
+
+    let start = Instant::now();
+    let mut total_runs: u64 = 0;
+    let mut failures: u64 = 0;
+    let mut max_temperature: Option<f64> = None;
+    let mut interval = Interval {
+        start: Instant::now(),
+        runs: 0,
+    };
+
+    while start.elapsed() < cli.duration {
+        match matrix_multiplication(&a, &b, algorithm, pool.as_ref()) {
+            Ok(c) if c == reference => (),
+            Ok(_) => {
+                failures += 1;
+                warn!("Run {} produced a wrong result", total_runs + 1);
+            }
+            Err(err) => {
+                error!("In algorithm: {}. {}", algorithm, err);
+                return false;
+            }
+        }
+        total_runs += 1;
+        interval.runs += 1;
+
+        let elapsed = interval.start.elapsed();
+        if elapsed >= cli.report_interval {
+            let temperature = system::cpu_temperature();
+            if let Some(temperature) = temperature {
+                max_temperature =
+                    Some(max_temperature.map_or(temperature, |max| max.max(temperature)));
+            }
+            info!(
+                "[{:>6.0}s] {:.2} runs/s, {:.3} GOP/s, temperature {}, {} failures so far",
+                start.elapsed().as_secs_f64(),
+                interval.runs as f64 / elapsed.as_secs_f64(),
+                runs_gops(algorithm, n, interval.runs, elapsed),
+                format_temperature(temperature),
+                failures
+            );
+            interval = Interval {
+                start: Instant::now(),
+                runs: 0,
+            };
+        }
+    }
+
+    let elapsed = start.elapsed();
+    if let Some(pool) = pool {
+        ThreadPool::terminate(pool);
+    }
+
+    print_title("Stress Test Results");
+
+    let table = vec![
+        vec!["Runs".cell(), total_runs.to_string().cell()],
+        vec!["Failures".cell(), failures.to_string().cell()],
+        vec![
+            "Average throughput (GOP/s)".cell(),
+            format!("{:.3}", runs_gops(algorithm, n, total_runs, elapsed)).cell(),
+        ],
+        vec![
+            "Max temperature".cell(),
+            format_temperature(max_temperature).cell(),
+        ],
+    ];
+    print_args_table(table);
+
+    if failures > 0 {
+        error!(
+            "{} of {} runs produced a wrong result",
+            failures, total_runs
+        );
+    }
+    failures == 0
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_gops() {
+        // 2 * 1000^3 operations in one second
+        let algorithm = Algorithm::SequentialIkj;
+        assert_eq!(runs_gops(algorithm, 1000, 1, Duration::from_secs(1)), 2.0);
+        assert_eq!(runs_gops(algorithm, 1000, 2, Duration::from_secs(4)), 1.0);
+    }
+}
@@ -0,0 +1,260 @@
+use crate::{
+    matrix_multiplication::{
+        in_pool,
+        matrix::Matrix,
+        sanitize::{sanitize_matrices, SanitizeError},
+        types::MatrixRowPtr,
+    },
+    thread_pool::ThreadPool,
+    zero_filled_square_matrix_of_size,
+};
+
+/// Symmetric matrix of which only the upper triangle is stored, packed row after row
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct PackedSymmetricMatrix {
+    pub size: usize,
+    /// The `size` - `i` elements on and above the diagonal of every row `i`
+    pub values: Vec<i32>,
+}
+
+impl PackedSymmetricMatrix {
+    /// Packs the upper triangle of `m`, ignoring the lower one
+    pub fn from_upper(m: &Matrix<i32>) -> PackedSymmetricMatrix {
+        let size = m.rows();
+        let mut values = Vec::with_capacity(size * (size + 1) / 2);
+        for (i, row) in m.iter_rows().enumerate() {
+            values.extend_from_slice(&row[i..]);
+        }
+        PackedSymmetricMatrix { size, values }
+    }
+
+    /// Returns the offset in `values` of the element at (`i`, `j`), with `i` <= `j`
+    fn offset(&self, i: usize, j: usize) -> usize {
+        // rows 0..i hold size + (size - 1) + ... + (size - i + 1) elements
+        i * self.size - i * i.saturating_sub(1) / 2 + j - i
+    }
+
+    /// Returns the element at (`i`, `j`), reading it from the upper triangle
+    fn get(&self, i: usize, j: usize) -> i32 {
+        match i <= j {
+            true => self.values[self.offset(i, j)],
+            false => self.values[self.offset(j, i)],
+        }
+    }
+
+    /// Returns the number of bytes of the stored elements
+    pub fn bytes(&self) -> usize {
+        self.values.len() * std::mem::size_of::<i32>()
+    }
+}
+
+/// Enum representing available symmetric matrix multiplication (SYMM) kernels, computing
+/// `C = A * B` with a symmetric A of which only the upper triangle is read and a dense B
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum SymmetricKernel {
+    /// Sequential kernel with the ikj loop order
+    Sequential,
+    /// Parallel kernel submitting one job per row to the thread pool
+    ///
+    /// # Arguments
+    ///
+    /// * `usize` - number of threads to use
+    Parallel(usize),
+}
+
+impl std::fmt::Display for SymmetricKernel {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            SymmetricKernel::Sequential => write!(f, "Sequential SYMM"),
+            SymmetricKernel::Parallel(threads) => {
+                write!(f, "Parallel SYMM ({} threads)", threads)
+            }
+        }
+    }
+}
+
+impl SymmetricKernel {
+    /// Returns the number of threads used by the kernel, or `None` if it is sequential
+    pub fn threads(&self) -> Option<usize> {
+        match self {
+            SymmetricKernel::Sequential => None,
+            SymmetricKernel::Parallel(threads) => Some(*threads),
+        }
+    }
+}
+
+/// Multiplies the symmetric matrix `a` by the dense matrix `b` with the given kernel.
+/// The parallel kernel runs on `pool`, see `in_pool`.
+pub fn symm(
+    a: &PackedSymmetricMatrix,
+    b: &Matrix<i32>,
+    kernel: SymmetricKernel,
+    pool: Option<&ThreadPool>,
+) -> Result<Matrix<i32>, SanitizeError> {
+    sanitize_matrices(b, b)?;
+    if a.size != b.rows() {
+        return Err(SanitizeError::NotSameSize);
+    }
+
+    let size = a.size;
+
+    match kernel {
+        SymmetricKernel::Sequential => Ok(symm_sequential(a, b, size)),
+        SymmetricKernel::Parallel(threads) => {
+            in_pool(pool, threads, |pool| Ok(symm_parallel(a, b, size, pool)))
+        }
+    }
+}
+
+/// Adds row `i` of `a` times `b` to `c_i`
+///
+/// # Safety
+///
+/// `c_i` must point to a row of `size` elements that nothing else accesses
+unsafe fn multiply_row(
+    a: &PackedSymmetricMatrix,
+    b: &Matrix<i32>,
+    i: usize,
+    size: usize,
+    mut c_i: MatrixRowPtr<*mut i32>,
+) {
+    for (k, b_k) in b.iter_rows().enumerate() {
+        let a_ik = a.get(i, k);
+        for (j, b_kj) in b_k.iter().enumerate().take(size) {
+            *c_i.add_mut(j) += a_ik * b_kj;
+        }
+    }
+}
+
+fn symm_sequential(a: &PackedSymmetricMatrix, b: &Matrix<i32>, size: usize) -> Matrix<i32> {
+    let mut c = zero_filled_square_matrix_of_size!(size);
+
+    for (i, c_i) in c.iter_rows_mut().enumerate() {
+        // SAFETY: `c_i` is borrowed mutably for the whole call
+        unsafe { multiply_row(a, b, i, size, MatrixRowPtr(c_i.as_mut_ptr())) };
+    }
+
+    c
+}
+
+/// Pointer to the inputs borrowed by the jobs of the pool, which are waited for before they go
+/// out of scope
+#[derive(Clone, Copy)]
+struct InputsPtr(*const PackedSymmetricMatrix, *const Matrix<i32>);
+
+unsafe impl Send for InputsPtr {}
+
+impl InputsPtr {
+    /// # Safety
+    ///
+    /// The inputs must still be alive
+    unsafe fn get<'a>(self) -> (&'a PackedSymmetricMatrix, &'a Matrix<i32>) {
+        (&*self.0, &*self.1)
+    }
+}
+
+fn symm_parallel(
+    a: &PackedSymmetricMatrix,
+    b: &Matrix<i32>,
+    size: usize,
+    pool: &ThreadPool,
+) -> Matrix<i32> {
+    let mut c = zero_filled_square_matrix_of_size!(size);
+    let inputs = InputsPtr(a, b);
+
+    for (i, c_i) in c.iter_rows_mut().enumerate() {
+        let c_i = MatrixRowPtr(c_i.as_mut_ptr());
+
+        pool.execute(move || {
+            // SAFETY: the inputs outlive the jobs, which are waited for below, and every job
+            // writes to its own row of C
+            unsafe {
+                let (a, b) = inputs.get();
+                multiply_row(a, b, i, size, c_i);
+            }
+        });
+    }
+
+    pool.wait();
+
+    c
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::matrix_multiplication::{
+        algorithms::Algorithm,
+        generate::{generate_random_square_matrix_with_rng, seeded_rng, MAX_ABS_VALUE_DEFAULT},
+        matrix_multiplication,
+    };
+
+    fn get_symmetric(size: usize) -> Matrix<i32> {
+        Matrix::from_fn(size, size, |i, j| (i * j + i + j) as i32 % 7 - 3)
+    }
+
+    #[test]
+    fn test_packed_symmetric_matrix() {
+        let m = get_symmetric(5);
+        let packed = PackedSymmetricMatrix::from_upper(&m);
+
+        assert_eq!(packed.values.len(), 15);
+        for (i, row) in m.iter_rows().enumerate() {
+            for (j, m_ij) in row.iter().enumerate() {
+                assert_eq!(packed.get(i, j), *m_ij, "({}, {})", i, j);
+            }
+        }
+    }
+
+    #[test]
+    fn test_symm_ignores_lower_triangle() {
+        // the lower triangle of the matrix the kernels are given is not its mirror image
+        let m = Matrix::from_fn(6, 6, |i, j| match i <= j {
+            true => (i * j + i + j) as i32 % 7 - 3,
+            false => 100,
+        });
+        let a = Matrix::from_fn(6, 6, |i, j| m[i.min(j)][i.max(j)]);
+        let b = generate_random_square_matrix_with_rng(
+            6,
+            MAX_ABS_VALUE_DEFAULT,
+            &mut seeded_rng(Some(7)),
+        );
+        let expected = matrix_multiplication(&a, &b, Algorithm::SequentialIkj, None).unwrap();
+        let packed = PackedSymmetricMatrix::from_upper(&m);
+
+        for kernel in [SymmetricKernel::Sequential, SymmetricKernel::Parallel(2)] {
+            assert_eq!(
+                symm(&packed, &b, kernel, None).unwrap(),
+                expected,
+                "{}",
+                kernel
+            );
+        }
+    }
+
+    #[test]
+    fn test_symm_single_element() {
+        // the packed matrix is its diagonal alone
+        let a = PackedSymmetricMatrix::from_upper(&Matrix::filled(1, 1, -3));
+        let b = Matrix::filled(1, 1, 4);
+
+        for kernel in [SymmetricKernel::Sequential, SymmetricKernel::Parallel(2)] {
+            assert_eq!(
+                symm(&a, &b, kernel, None).unwrap(),
+                Matrix::filled(1, 1, -12),
+                "{}",
+                kernel
+            );
+        }
+    }
+
+    #[test]
+    fn test_symm_size_mismatch() {
+        let a = PackedSymmetricMatrix::from_upper(&get_symmetric(3));
+
+        assert_eq!(
+            symm(&a, &get_symmetric(4), SymmetricKernel::Sequential, None),
+            Err(SanitizeError::NotSameSize)
+        );
+    }
+}
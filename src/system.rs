@@ -0,0 +1,688 @@
+use std::{collections::HashSet, env, fs, path::Path, thread};
+
+/// Directory exposing the thermal zones of the machine on Linux
+const THERMAL_ZONES_DIR: &str = "/sys/class/thermal";
+
+/// Directory exposing the CPUs of the machine and their frequency scaling on Linux
+const CPU_DIR: &str = "/sys/devices/system/cpu";
+
+/// File exposing the memory usage of the process on Linux
+const PROC_STATUS: &str = "/proc/self/status";
+
+/// Files describing the processors, the memory, the kernel and the distribution on Linux
+const PROC_CPUINFO: &str = "/proc/cpuinfo";
+const PROC_MEMINFO: &str = "/proc/meminfo";
+const KERNEL_RELEASE: &str = "/proc/sys/kernel/osrelease";
+const HOSTNAME: &str = "/proc/sys/kernel/hostname";
+const OS_RELEASE: &str = "/etc/os-release";
+
+/// File resetting the peak resident set size of the process on Linux 4.0+ when 5 is written to it
+const PROC_CLEAR_REFS: &str = "/proc/self/clear_refs";
+
+/// Peak resident memory of the process while a kernel ran, in KiB
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct PeakMemory {
+    /// Peak resident set size of the whole process, inputs included
+    pub peak_kib: u64,
+    /// Increase of the peak over the resident set size before the kernel started, the footprint
+    /// of the output and of the buffers of the kernel
+    pub increase_kib: u64,
+}
+
+/// Tracks the peak resident memory of the process since its creation
+pub struct PeakMemoryTracker {
+    baseline_kib: u64,
+}
+
+impl PeakMemoryTracker {
+    /// Resets the peak resident set size of the process and starts tracking it, or returns `None`
+    /// if it cannot be reset or read on this platform
+    pub fn start() -> Option<PeakMemoryTracker> {
+        fs::write(PROC_CLEAR_REFS, "5").ok()?;
+        let status = fs::read_to_string(PROC_STATUS).ok()?;
+        Some(PeakMemoryTracker {
+            baseline_kib: status_field_kib(&status, "VmRSS")?,
+        })
+    }
+
+    /// Returns the peak resident memory since the tracker started
+    pub fn stop(self) -> Option<PeakMemory> {
+        let status = fs::read_to_string(PROC_STATUS).ok()?;
+        let peak_kib = status_field_kib(&status, "VmHWM")?;
+        Some(PeakMemory {
+            peak_kib,
+            increase_kib: peak_kib.saturating_sub(self.baseline_kib),
+        })
+    }
+}
+
+/// Returns the value in KiB of a field of `/proc/self/status`, such as `VmRSS:   1024 kB`
+fn status_field_kib(status: &str, field: &str) -> Option<u64> {
+    status
+        .lines()
+        .find_map(|line| line.strip_prefix(field)?.strip_prefix(':'))?
+        .trim()
+        .strip_suffix("kB")?
+        .trim()
+        .parse()
+        .ok()
+}
+
+/// Cache of the first CPU
+#[derive(Debug, Clone, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct Cache {
+    pub level: u32,
+    /// Data, Instruction or Unified
+    pub kind: String,
+    pub size_kib: u64,
+}
+
+impl Cache {
+    /// Returns the usual name of the cache, e.g. L1d or L2
+    pub fn name(&self) -> String {
+        match self.kind.as_str() {
+            "Data" => format!("L{}d", self.level),
+            "Instruction" => format!("L{}i", self.level),
+            _ => format!("L{}", self.level),
+        }
+    }
+}
+
+/// Description of the machine and of the toolchain, reported with every run so that saved
+/// results can be interpreted later. The fields that cannot be read on this platform are missing.
+#[derive(Debug, Clone, Default, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "serde", serde(default))]
+pub struct SystemInfo {
+    pub hostname: Option<String>,
+    pub os: String,
+    /// Name and version of the distribution
+    pub os_release: Option<String>,
+    pub kernel: Option<String>,
+    pub arch: String,
+    pub cpu_model: Option<String>,
+    /// Number of threads the machine can run in parallel
+    pub logical_cpus: usize,
+    pub physical_cores: Option<usize>,
+    pub caches: Vec<Cache>,
+    pub total_memory_kib: Option<u64>,
+    /// Version of the compiler the benchmark was built with
+    pub rustc: String,
+    /// Highest temperature of the thermal zones when the information was collected, in degrees
+    /// Celsius
+    pub cpu_temperature: Option<f64>,
+}
+
+impl SystemInfo {
+    /// Returns the description of the machine the benchmark is running on
+    pub fn current() -> SystemInfo {
+        let read = |path: &str| fs::read_to_string(path).ok();
+        let cpu_dir = Path::new(CPU_DIR);
+        SystemInfo {
+            hostname: read(HOSTNAME).map(|hostname| hostname.trim().to_string()),
+            os: env::consts::OS.to_string(),
+            os_release: read(OS_RELEASE).and_then(|text| pretty_name(&text)),
+            kernel: read(KERNEL_RELEASE).map(|release| release.trim().to_string()),
+            arch: env::consts::ARCH.to_string(),
+            cpu_model: read(PROC_CPUINFO).and_then(|cpuinfo| cpu_model(&cpuinfo)),
+            logical_cpus: thread::available_parallelism().map_or(1, |threads| threads.get()),
+            physical_cores: physical_cores(cpu_dir),
+            caches: caches(&cpu_dir.join("cpu0/cache")),
+            total_memory_kib: read(PROC_MEMINFO)
+                .and_then(|meminfo| status_field_kib(&meminfo, "MemTotal")),
+            rustc: env!("RUSTC_VERSION").to_string(),
+            cpu_temperature: cpu_temperature(),
+        }
+    }
+
+    /// Returns the sizes of the caches, e.g. `L1d 48 KiB, L1i 32 KiB, L2 2 MiB`
+    pub fn cache_sizes(&self) -> String {
+        let caches = self
+            .caches
+            .iter()
+            .map(|cache| format!("{} {}", cache.name(), format_kib(cache.size_kib)))
+            .collect::<Vec<_>>();
+        caches.join(", ")
+    }
+
+    /// Returns the names and values of the information, as listed in the tables of arguments
+    /// and in the reports
+    pub fn rows(&self) -> Vec<(String, String)> {
+        let mut rows = Vec::new();
+        if let Some(hostname) = &self.hostname {
+            rows.push(("Host".to_string(), hostname.clone()));
+        }
+        let os = match (&self.os_release, &self.kernel) {
+            (Some(release), Some(kernel)) => format!("{}, kernel {}", release, kernel),
+            (Some(release), None) => release.clone(),
+            (None, Some(kernel)) => format!("{}, kernel {}", self.os, kernel),
+            (None, None) => self.os.clone(),
+        };
+        rows.push(("Operating system".to_string(), os));
+        let cpu = self.cpu_model.as_deref().unwrap_or("unknown");
+        rows.push(("CPU".to_string(), format!("{} ({})", cpu, self.arch)));
+        let cores = match self.physical_cores {
+            Some(physical) => format!(
+                "{} physical, {} logical ({} per core)",
+                physical,
+                self.logical_cpus,
+                self.logical_cpus / physical.max(1)
+            ),
+            None => format!("{} logical", self.logical_cpus),
+        };
+        rows.push(("Cores".to_string(), cores));
+        if !self.caches.is_empty() {
+            rows.push(("Caches".to_string(), self.cache_sizes()));
+        }
+        if let Some(memory) = self.total_memory_kib {
+            rows.push(("Memory".to_string(), format_kib(memory)));
+        }
+        rows.push(("Compiler".to_string(), self.rustc.clone()));
+        rows
+    }
+}
+
+/// Returns the size of the L1 data cache of the first CPU in KiB, or `None` if it cannot be read
+/// on this platform
+pub fn l1_data_cache_kib() -> Option<u64> {
+    caches(&Path::new(CPU_DIR).join("cpu0/cache"))
+        .into_iter()
+        .find(|cache| cache.level == 1 && cache.kind != "Instruction")
+        .map(|cache| cache.size_kib)
+}
+
+/// Returns the largest power of two tile size whose tiles of A, B and C, with elements of
+/// `element_size` bytes, fit together in a cache of `cache_kib` KiB, so that the innermost loops
+/// of the tiling algorithms never evict the tiles they work on
+pub fn tile_size_for_cache(cache_kib: u64, element_size: usize) -> usize {
+    let fits = |tile: usize| 3 * tile * tile * element_size <= cache_kib as usize * 1024;
+    let mut tile = 1;
+    while fits(tile * 2) {
+        tile *= 2;
+    }
+    tile
+}
+
+/// Formats a size in KiB with the largest binary unit it has at least one of
+pub fn format_kib(kib: u64) -> String {
+    let mut value = kib as f64;
+    let mut unit = 0;
+    let units = ["KiB", "MiB", "GiB", "TiB"];
+    while value >= 1024.0 && unit < units.len() - 1 {
+        value /= 1024.0;
+        unit += 1;
+    }
+    match value.fract() == 0.0 {
+        true => format!("{:.0} {}", value, units[unit]),
+        false => format!("{:.1} {}", value, units[unit]),
+    }
+}
+
+/// Returns the `PRETTY_NAME` of `/etc/os-release`, e.g. `Debian GNU/Linux 12 (bookworm)`
+fn pretty_name(os_release: &str) -> Option<String> {
+    os_release
+        .lines()
+        .find_map(|line| line.strip_prefix("PRETTY_NAME="))
+        .map(|name| name.trim().trim_matches('"').to_string())
+}
+
+/// Returns the model of the first CPU in `/proc/cpuinfo`
+fn cpu_model(cpuinfo: &str) -> Option<String> {
+    cpuinfo
+        .lines()
+        .find_map(|line| {
+            let (key, value) = line.split_once(':')?;
+            (key.trim() == "model name").then(|| value.trim().to_string())
+        })
+        .filter(|model| !model.is_empty())
+}
+
+/// Returns the number of distinct cores of the `cpu*` directories in `dir`, counting the SMT
+/// siblings of a core once
+fn physical_cores(dir: &Path) -> Option<usize> {
+    let read = |path: &Path| fs::read_to_string(path).ok().map(|s| s.trim().to_string());
+    let cores = fs::read_dir(dir)
+        .ok()?
+        .filter_map(|entry| entry.ok())
+        .filter(|entry| {
+            let name = entry.file_name();
+            let name = name.to_string_lossy();
+            name.strip_prefix("cpu")
+                .is_some_and(|id| !id.is_empty() && id.chars().all(|c| c.is_ascii_digit()))
+        })
+        .filter_map(|entry| {
+            let topology = entry.path().join("topology");
+            Some((
+                read(&topology.join("physical_package_id"))?,
+                read(&topology.join("core_id"))?,
+            ))
+        })
+        .collect::<HashSet<_>>();
+    (!cores.is_empty()).then_some(cores.len())
+}
+
+/// Returns the caches of the `index*` directories in `dir`, ordered by level and kind.
+/// Their `size` file holds sizes such as `48K` or `2M`.
+fn caches(dir: &Path) -> Vec<Cache> {
+    let read = |path: &Path| fs::read_to_string(path).ok().map(|s| s.trim().to_string());
+    let mut caches = fs::read_dir(dir)
+        .into_iter()
+        .flatten()
+        .filter_map(|entry| entry.ok())
+        .filter(|entry| entry.file_name().to_string_lossy().starts_with("index"))
+        .filter_map(|entry| {
+            let path = entry.path();
+            let size = read(&path.join("size"))?;
+            let size_kib = match size.strip_suffix('K') {
+                Some(kib) => kib.parse::<u64>().ok()?,
+                None => size.strip_suffix('M')?.parse::<u64>().ok()? * 1024,
+            };
+            Some(Cache {
+                level: read(&path.join("level"))?.parse().ok()?,
+                kind: read(&path.join("type"))?,
+                size_kib,
+            })
+        })
+        .collect::<Vec<_>>();
+    caches.sort_by(|a, b| (a.level, &a.kind).cmp(&(b.level, &b.kind)));
+    caches
+}
+
+/// Returns the highest temperature reported by the thermal zones of the machine, in degrees
+/// Celsius, or `None` if it cannot be read on this platform.
+pub fn cpu_temperature() -> Option<f64> {
+    max_thermal_zone_temperature(Path::new(THERMAL_ZONES_DIR))
+}
+
+/// Temperature from which the CPUs are considered throttled, in degrees Celsius, when the kernel
+/// does not count the throttling events
+const THROTTLE_TEMPERATURE: f64 = 90.0;
+
+/// Thermal state of the machine sampled between the runs of the kernels, to detect the thermal
+/// throttling of the CPUs, as far as it can be read on this platform
+#[derive(Debug, Clone, Copy, Default, PartialEq)]
+pub struct ThermalSample {
+    /// Highest temperature of the thermal zones, in degrees Celsius
+    pub temperature: Option<f64>,
+    /// Average current frequency of the CPUs, in MHz
+    pub frequency_mhz: Option<f64>,
+    /// Number of times the cores and the packages were throttled since boot, counted by the
+    /// kernel on Intel CPUs
+    pub throttle_events: Option<u64>,
+}
+
+impl ThermalSample {
+    /// Returns the current thermal state of the machine
+    pub fn current() -> ThermalSample {
+        ThermalSample {
+            temperature: cpu_temperature(),
+            ..thermal_sample(Path::new(CPU_DIR))
+        }
+    }
+
+    /// Returns the signs that the CPUs throttled since the `previous` sample: new throttling
+    /// events counted by the kernel or, where they are not counted, a temperature reaching
+    /// `THROTTLE_TEMPERATURE`
+    pub fn throttling(&self, previous: &ThermalSample) -> Vec<String> {
+        let mut signs = Vec::new();
+        match self.throttle_events.zip(previous.throttle_events) {
+            Some((events, previous)) if events > previous => {
+                signs.push(format!("{} throttling events", events - previous))
+            }
+            Some(_) => (),
+            None => {
+                if let Some(temperature) = self
+                    .temperature
+                    .filter(|temperature| *temperature >= THROTTLE_TEMPERATURE)
+                {
+                    signs.push(format!("the CPU reached {:.0} °C", temperature));
+                }
+            }
+        }
+        if let (false, Some(frequency), Some(previous)) =
+            (signs.is_empty(), self.frequency_mhz, previous.frequency_mhz)
+        {
+            signs.push(format!(
+                "the frequency went from {:.0} MHz to {:.0} MHz",
+                previous, frequency
+            ));
+        }
+        signs
+    }
+}
+
+/// Frequency scaling configuration of the CPUs, as far as it can be read on this platform
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct FrequencyScaling {
+    /// Distinct frequency governors of the CPUs, in alphabetical order
+    pub governors: Vec<String>,
+    /// Whether the CPUs can boost their frequency above the base one, `None` if unknown
+    pub turbo: Option<bool>,
+}
+
+impl FrequencyScaling {
+    /// Returns the frequency scaling configuration of the machine
+    pub fn current() -> FrequencyScaling {
+        frequency_scaling(Path::new(CPU_DIR))
+    }
+
+    /// Returns the reasons why the configuration makes the timings unreliable, if any
+    pub fn warnings(&self) -> Vec<String> {
+        let mut warnings = Vec::new();
+        let other_governors = self
+            .governors
+            .iter()
+            .filter(|governor| *governor != "performance")
+            .cloned()
+            .collect::<Vec<_>>();
+        if !other_governors.is_empty() {
+            warnings.push(format!(
+                "The CPU frequency governor is {} instead of performance, the frequency varies with the load",
+                other_governors.join(", ")
+            ));
+        }
+        if self.turbo == Some(true) {
+            warnings.push(
+                "Turbo boost is enabled, the frequency depends on the temperature and on the load of the other cores"
+                    .to_string(),
+            );
+        }
+        warnings
+    }
+}
+
+/// Returns the frequency scaling configuration of the `cpu*` directories in `dir`.
+/// Turbo boost is reported by `intel_pstate/no_turbo` with the Intel P-state driver, and by
+/// `cpufreq/boost` with the other drivers.
+fn frequency_scaling(dir: &Path) -> FrequencyScaling {
+    let read = |path: &Path| fs::read_to_string(path).ok().map(|s| s.trim().to_string());
+
+    let mut governors = fs::read_dir(dir)
+        .into_iter()
+        .flatten()
+        .filter_map(|entry| entry.ok())
+        .filter(|entry| {
+            let name = entry.file_name();
+            let name = name.to_string_lossy();
+            name.strip_prefix("cpu")
+                .is_some_and(|id| !id.is_empty() && id.chars().all(|c| c.is_ascii_digit()))
+        })
+        .filter_map(|entry| read(&entry.path().join("cpufreq/scaling_governor")))
+        .collect::<Vec<_>>();
+    governors.sort();
+    governors.dedup();
+
+    let turbo = match read(&dir.join("intel_pstate/no_turbo")) {
+        Some(no_turbo) => Some(no_turbo == "0"),
+        None => read(&dir.join("cpufreq/boost")).map(|boost| boost == "1"),
+    };
+
+    FrequencyScaling { governors, turbo }
+}
+
+/// Returns the average current frequency and the throttling events of the `cpu*` directories in
+/// `dir`. The frequencies are reported in kHz by `cpufreq/scaling_cur_freq`, the events by the
+/// `thermal_throttle` directory of every CPU.
+fn thermal_sample(dir: &Path) -> ThermalSample {
+    let read = |path: &Path| fs::read_to_string(path).ok()?.trim().parse::<u64>().ok();
+    let cpus = fs::read_dir(dir)
+        .into_iter()
+        .flatten()
+        .filter_map(|entry| entry.ok())
+        .filter(|entry| {
+            let name = entry.file_name();
+            let name = name.to_string_lossy();
+            name.strip_prefix("cpu")
+                .is_some_and(|id| !id.is_empty() && id.chars().all(|c| c.is_ascii_digit()))
+        })
+        .map(|entry| entry.path())
+        .collect::<Vec<_>>();
+
+    let frequencies = cpus
+        .iter()
+        .filter_map(|cpu| read(&cpu.join("cpufreq/scaling_cur_freq")))
+        .collect::<Vec<_>>();
+    let frequency_mhz = (!frequencies.is_empty())
+        .then(|| frequencies.iter().sum::<u64>() as f64 / frequencies.len() as f64 / 1000.0);
+    let events = cpus
+        .iter()
+        .flat_map(|cpu| {
+            ["core_throttle_count", "package_throttle_count"]
+                .map(|file| read(&cpu.join("thermal_throttle").join(file)))
+        })
+        .flatten()
+        .collect::<Vec<_>>();
+    ThermalSample {
+        temperature: None,
+        frequency_mhz,
+        throttle_events: (!events.is_empty()).then(|| events.iter().sum()),
+    }
+}
+
+/// Returns the highest temperature of the `thermal_zone*` directories in `dir`.
+/// The zones report millidegrees Celsius in their `temp` file.
+fn max_thermal_zone_temperature(dir: &Path) -> Option<f64> {
+    fs::read_dir(dir)
+        .ok()?
+        .filter_map(|entry| entry.ok())
+        .filter(|entry| {
+            entry
+                .file_name()
+                .to_string_lossy()
+                .starts_with("thermal_zone")
+        })
+        .filter_map(|entry| fs::read_to_string(entry.path().join("temp")).ok())
+        .filter_map(|temp| temp.trim().parse::<f64>().ok())
+        .map(|millidegrees| millidegrees / 1000.0)
+        .reduce(f64::max)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_max_thermal_zone_temperature() {
+        let dir = std::env::temp_dir().join(format!("mm_thermal_{}", std::process::id()));
+        for (zone, temp) in [("thermal_zone0", "42000\n"), ("thermal_zone1", "55500\n")] {
+            fs::create_dir_all(dir.join(zone)).unwrap();
+            fs::write(dir.join(zone).join("temp"), temp).unwrap();
+        }
+        fs::create_dir_all(dir.join("cooling_device0")).unwrap();
+
+        assert_eq!(max_thermal_zone_temperature(&dir), Some(55.5));
+
+        fs::remove_dir_all(&dir).unwrap();
+    }
+
+    #[test]
+    fn test_thermal_sample() {
+        let dir = std::env::temp_dir().join(format!("mm_throttle_{}", std::process::id()));
+        for (cpu, frequency, events) in [("cpu0", "2400000\n", "3\n"), ("cpu1", "1800000\n", "4\n")]
+        {
+            fs::create_dir_all(dir.join(cpu).join("cpufreq")).unwrap();
+            fs::create_dir_all(dir.join(cpu).join("thermal_throttle")).unwrap();
+            fs::write(dir.join(cpu).join("cpufreq/scaling_cur_freq"), frequency).unwrap();
+            fs::write(
+                dir.join(cpu).join("thermal_throttle/core_throttle_count"),
+                events,
+            )
+            .unwrap();
+        }
+
+        let sample = thermal_sample(&dir);
+        fs::remove_dir_all(&dir).unwrap();
+        assert_eq!(sample.frequency_mhz, Some(2100.0));
+        assert_eq!(sample.throttle_events, Some(7));
+    }
+
+    #[test]
+    fn test_throttling() {
+        let cool = ThermalSample {
+            temperature: Some(60.0),
+            frequency_mhz: Some(3000.0),
+            throttle_events: None,
+        };
+        let hot = ThermalSample {
+            temperature: Some(95.0),
+            frequency_mhz: Some(2000.0),
+            ..cool
+        };
+        assert!(cool.throttling(&cool).is_empty());
+        assert_eq!(
+            hot.throttling(&cool),
+            [
+                "the CPU reached 95 °C",
+                "the frequency went from 3000 MHz to 2000 MHz"
+            ]
+        );
+
+        // the events counted by the kernel take precedence over the temperature
+        let counted = |events| ThermalSample {
+            throttle_events: Some(events),
+            ..hot
+        };
+        assert!(counted(5).throttling(&counted(5)).is_empty());
+        assert_eq!(counted(7).throttling(&counted(5)).len(), 2);
+    }
+
+    #[test]
+    fn test_frequency_scaling() {
+        let dir = std::env::temp_dir().join(format!("mm_cpufreq_{}", std::process::id()));
+        for (cpu, governor) in [
+            ("cpu0", "powersave\n"),
+            ("cpu1", "performance\n"),
+            ("cpu2", "powersave\n"),
+        ] {
+            fs::create_dir_all(dir.join(cpu).join("cpufreq")).unwrap();
+            fs::write(dir.join(cpu).join("cpufreq/scaling_governor"), governor).unwrap();
+        }
+        fs::create_dir_all(dir.join("cpuidle")).unwrap();
+        fs::create_dir_all(dir.join("intel_pstate")).unwrap();
+        fs::write(dir.join("intel_pstate/no_turbo"), "0\n").unwrap();
+
+        let scaling = frequency_scaling(&dir);
+        assert_eq!(scaling.governors, vec!["performance", "powersave"]);
+        assert_eq!(scaling.turbo, Some(true));
+        assert_eq!(scaling.warnings().len(), 2);
+        assert!(scaling.warnings()[0].contains("powersave instead of performance"));
+
+        fs::remove_dir_all(&dir).unwrap();
+    }
+
+    #[test]
+    fn test_frequency_scaling_reliable() {
+        let scaling = FrequencyScaling {
+            governors: vec!["performance".to_string()],
+            turbo: Some(false),
+        };
+        assert!(scaling.warnings().is_empty());
+        // nothing to warn about when the configuration cannot be read
+        assert!(FrequencyScaling::default().warnings().is_empty());
+    }
+
+    #[test]
+    fn test_status_field_kib() {
+        let status = "Name:\tmatrix_multipl\nVmHWM:\t   20480 kB\nVmRSS:\t   10240 kB\n";
+        assert_eq!(status_field_kib(status, "VmHWM"), Some(20480));
+        assert_eq!(status_field_kib(status, "VmRSS"), Some(10240));
+        assert_eq!(status_field_kib(status, "VmSwap"), None);
+        assert_eq!(status_field_kib(status, "Name"), None);
+    }
+
+    #[test]
+    fn test_caches_and_cores() {
+        let dir = std::env::temp_dir().join(format!("mm_topology_{}", std::process::id()));
+        for (index, level, kind, size) in [
+            ("index0", "1", "Data", "48K\n"),
+            ("index1", "1", "Instruction", "32K\n"),
+            ("index3", "3", "Unified", "32M\n"),
+            ("index2", "2", "Unified", "2048K\n"),
+        ] {
+            let index = dir.join("cpu0/cache").join(index);
+            fs::create_dir_all(&index).unwrap();
+            fs::write(index.join("level"), level).unwrap();
+            fs::write(index.join("type"), kind).unwrap();
+            fs::write(index.join("size"), size).unwrap();
+        }
+        // two cores with two SMT siblings each
+        for (cpu, core) in [("cpu0", "0"), ("cpu1", "1"), ("cpu2", "0"), ("cpu3", "1")] {
+            let topology = dir.join(cpu).join("topology");
+            fs::create_dir_all(&topology).unwrap();
+            fs::write(topology.join("physical_package_id"), "0\n").unwrap();
+            fs::write(topology.join("core_id"), core).unwrap();
+        }
+
+        let caches = caches(&dir.join("cpu0/cache"));
+        let names = caches.iter().map(Cache::name).collect::<Vec<_>>();
+        assert_eq!(names, ["L1d", "L1i", "L2", "L3"]);
+        assert_eq!(caches[2].size_kib, 2048);
+        assert_eq!(caches[3].size_kib, 32 * 1024);
+        assert_eq!(physical_cores(&dir), Some(2));
+
+        fs::remove_dir_all(&dir).unwrap();
+    }
+
+    #[test]
+    fn test_system_info_rows() {
+        let cpuinfo = "processor\t: 0\nmodel name\t: Intel(R) Xeon(R) Processor\n";
+        let os_release =
+            "NAME=\"Debian GNU/Linux\"\nPRETTY_NAME=\"Debian GNU/Linux 12 (bookworm)\"\n";
+        let info = SystemInfo {
+            os: "linux".to_string(),
+            os_release: pretty_name(os_release),
+            kernel: Some("6.1.0".to_string()),
+            arch: "x86_64".to_string(),
+            cpu_model: cpu_model(cpuinfo),
+            logical_cpus: 8,
+            physical_cores: Some(4),
+            caches: vec![Cache {
+                level: 1,
+                kind: "Data".to_string(),
+                size_kib: 48,
+            }],
+            total_memory_kib: Some(16 * 1024 * 1024),
+            rustc: "rustc 1.80.0".to_string(),
+            ..SystemInfo::default()
+        };
+
+        let rows = info.rows();
+        let value = |name: &str| rows.iter().find(|(row, _)| row == name).unwrap().1.clone();
+        assert_eq!(
+            value("Operating system"),
+            "Debian GNU/Linux 12 (bookworm), kernel 6.1.0"
+        );
+        assert_eq!(value("CPU"), "Intel(R) Xeon(R) Processor (x86_64)");
+        assert_eq!(value("Cores"), "4 physical, 8 logical (2 per core)");
+        assert_eq!(value("Caches"), "L1d 48 KiB");
+        assert_eq!(value("Memory"), "16 GiB");
+        assert!(rows.iter().all(|(name, _)| name != "Host"));
+    }
+
+    #[test]
+    fn test_tile_size_for_cache() {
+        // 3 tiles of 64x64 i32 take exactly 48 KiB
+        assert_eq!(tile_size_for_cache(48, 4), 64);
+        assert_eq!(tile_size_for_cache(32, 4), 32);
+        assert_eq!(tile_size_for_cache(32, 8), 32);
+        assert_eq!(tile_size_for_cache(32, 16), 16);
+        assert_eq!(tile_size_for_cache(0, 4), 1);
+    }
+
+    #[test]
+    fn test_format_kib() {
+        assert_eq!(format_kib(48), "48 KiB");
+        assert_eq!(format_kib(2048), "2 MiB");
+        assert_eq!(format_kib(6158152), "5.9 GiB");
+    }
+
+    #[test]
+    fn test_missing_thermal_zones() {
+        assert_eq!(
+            max_thermal_zone_temperature(Path::new("/nonexistent/thermal")),
+            None
+        );
+    }
+}
@@ -0,0 +1,117 @@
+use std::sync::atomic::{AtomicU64, AtomicUsize, Ordering};
+use std::time::{Duration, Instant};
+
+/// Aggregated scheduling metrics of a `ThreadPool`.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct PoolMetrics {
+    /// Number of jobs picked up by the workers
+    pub jobs: u64,
+    /// Sum of the times the jobs waited in the queue before being picked up by a worker
+    pub total_wait: Duration,
+    /// Longest time a job waited in the queue before being picked up by a worker
+    pub max_wait: Duration,
+    /// Largest number of jobs waiting in the queue at the same time
+    pub max_queue_depth: usize,
+}
+
+impl PoolMetrics {
+    /// Returns the average time a job waited in the queue, or zero if no job was run
+    pub fn mean_wait(&self) -> Duration {
+        match self.jobs {
+            0 => Duration::ZERO,
+            jobs => self.total_wait / jobs as u32,
+        }
+    }
+
+    /// Combines the metrics of two runs, as if the jobs of both were submitted to the same pool
+    pub fn merge(&self, other: &PoolMetrics) -> PoolMetrics {
+        PoolMetrics {
+            jobs: self.jobs + other.jobs,
+            total_wait: self.total_wait + other.total_wait,
+            max_wait: self.max_wait.max(other.max_wait),
+            max_queue_depth: self.max_queue_depth.max(other.max_queue_depth),
+        }
+    }
+}
+
+/// Lock-free counters shared between a `ThreadPool` and its workers
+#[derive(Default)]
+pub(super) struct MetricsCollector {
+    queue_depth: AtomicUsize,
+    max_queue_depth: AtomicUsize,
+    jobs: AtomicU64,
+    total_wait_nanos: AtomicU64,
+    max_wait_nanos: AtomicU64,
+}
+
+impl MetricsCollector {
+    /// Records that a job was pushed on the queue
+    pub(super) fn job_enqueued(&self) {
+        let depth = self.queue_depth.fetch_add(1, Ordering::Relaxed) + 1;
+        self.max_queue_depth.fetch_max(depth, Ordering::Relaxed);
+    }
+
+    /// Records that a job enqueued at `enqueued_at` was picked up by a worker
+    pub(super) fn job_dequeued(&self, enqueued_at: Instant) {
+        let wait = enqueued_at.elapsed().as_nanos() as u64;
+        self.queue_depth.fetch_sub(1, Ordering::Relaxed);
+        self.jobs.fetch_add(1, Ordering::Relaxed);
+        self.total_wait_nanos.fetch_add(wait, Ordering::Relaxed);
+        self.max_wait_nanos.fetch_max(wait, Ordering::Relaxed);
+    }
+
+    /// Returns a snapshot of the metrics collected so far
+    pub(super) fn snapshot(&self) -> PoolMetrics {
+        PoolMetrics {
+            jobs: self.jobs.load(Ordering::Relaxed),
+            total_wait: Duration::from_nanos(self.total_wait_nanos.load(Ordering::Relaxed)),
+            max_wait: Duration::from_nanos(self.max_wait_nanos.load(Ordering::Relaxed)),
+            max_queue_depth: self.max_queue_depth.load(Ordering::Relaxed),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_collect_metrics() {
+        let collector = MetricsCollector::default();
+        let enqueued_at = Instant::now();
+        collector.job_enqueued();
+        collector.job_enqueued();
+        collector.job_dequeued(enqueued_at);
+        collector.job_enqueued();
+        collector.job_dequeued(enqueued_at);
+        collector.job_dequeued(enqueued_at);
+
+        let metrics = collector.snapshot();
+        assert_eq!(metrics.jobs, 3);
+        assert_eq!(metrics.max_queue_depth, 2);
+        assert!(metrics.max_wait <= metrics.total_wait);
+    }
+
+    #[test]
+    fn test_merge_metrics() {
+        let a = PoolMetrics {
+            jobs: 2,
+            total_wait: Duration::from_micros(30),
+            max_wait: Duration::from_micros(20),
+            max_queue_depth: 2,
+        };
+        let b = PoolMetrics {
+            jobs: 1,
+            total_wait: Duration::from_micros(30),
+            max_wait: Duration::from_micros(30),
+            max_queue_depth: 1,
+        };
+
+        let merged = a.merge(&b);
+        assert_eq!(merged.jobs, 3);
+        assert_eq!(merged.mean_wait(), Duration::from_micros(20));
+        assert_eq!(merged.max_wait, Duration::from_micros(30));
+        assert_eq!(merged.max_queue_depth, 2);
+    }
+}
@@ -1,9 +1,18 @@
 use log::debug;
 use std::cmp::min_by;
 use std::num::NonZeroUsize;
+use std::panic::{self, AssertUnwindSafe};
+use std::sync::atomic::{AtomicBool, Ordering};
 use std::sync::mpsc;
-use std::sync::{Arc, Mutex};
+use std::sync::{Arc, Condvar, Mutex};
 use std::thread::{self, JoinHandle};
+use std::time::Instant;
+
+use crate::affinity;
+use metrics::MetricsCollector;
+pub use metrics::PoolMetrics;
+
+mod metrics;
 
 /// A ThreadPool that manages a variable number of threads.
 /// The maximum number of threads however cannot exceed the number of available threads on the system.
@@ -16,6 +25,10 @@ pub struct ThreadPool {
     workers: Vec<Worker>,
     /// Channel to send jobs to the threads
     sender: mpsc::Sender<Message>,
+    /// Number of submitted jobs that have not completed yet
+    pending: Arc<PendingJobs>,
+    /// Scheduling metrics of the submitted jobs
+    metrics: Arc<MetricsCollector>,
 }
 
 impl ThreadPool {
@@ -37,14 +50,26 @@ impl ThreadPool {
 
         let (sender, receiver) = mpsc::channel::<Message>();
         let receiver = Arc::new(Mutex::new(receiver));
+        let pending = Arc::new(PendingJobs::default());
+        let metrics = Arc::new(MetricsCollector::default());
         let mut workers = Vec::with_capacity(size);
 
         for id in 0..number_of_threads_to_use {
             // create some threads and store them in the vector
-            workers.push(Worker::new(id, Arc::clone(&receiver)));
+            workers.push(Worker::new(
+                id,
+                Arc::clone(&receiver),
+                Arc::clone(&pending),
+                Arc::clone(&metrics),
+            ));
         }
 
-        ThreadPool { workers, sender }
+        ThreadPool {
+            workers,
+            sender,
+            pending,
+            metrics,
+        }
     }
 
     /// Execute a function in the thread pool.
@@ -53,11 +78,34 @@ impl ThreadPool {
     where
         F: FnOnce() + Send + 'static,
     {
-        let job = Message::NewJob(Box::new(f));
+        let job = Message::NewJob(Box::new(f), Instant::now());
 
+        self.pending.increment();
+        self.metrics.job_enqueued();
         self.sender.send(job).unwrap();
     }
 
+    /// Returns the number of threads in the pool.
+    pub fn size(&self) -> usize {
+        self.workers.len()
+    }
+
+    /// Block until every job submitted so far has completed.
+    /// Unlike `ThreadPool::terminate`, the pool can be reused afterwards.
+    ///
+    /// # Panics
+    ///
+    /// Panics if any of the jobs submitted since the previous call panicked. The workers survive the
+    /// panicking jobs, so the pool can still be reused afterwards.
+    pub fn wait(&self) {
+        self.pending.wait_until_empty();
+    }
+
+    /// Returns the scheduling metrics of the jobs picked up since the pool was created.
+    pub fn metrics(&self) -> PoolMetrics {
+        self.metrics.snapshot()
+    }
+
     /// Terminate the thread pool.
     /// By calling this method, the thread pool will be dropped.
     pub fn terminate(_: Self) {}
@@ -70,7 +118,8 @@ impl Drop for ThreadPool {
         }
         for w in &mut self.workers {
             if let Some(thread) = w.thread.take() {
-                thread.join().unwrap();
+                // the pool may be dropped while unwinding, where a second panic would abort
+                let _ = thread.join();
             }
         }
     }
@@ -82,18 +131,31 @@ struct Worker {
 }
 
 impl Worker {
-    fn new(id: usize, receiver: Arc<Mutex<mpsc::Receiver<Message>>>) -> Worker {
-        let thread = thread::spawn(move || loop {
-            let message = receiver.lock().unwrap().recv().unwrap();
-
-            match message {
-                Message::NewJob(job) => {
-                    debug!("Worker {} got a job; executing.", id);
-                    job();
-                }
-                Message::Terminate => {
-                    debug!("Worker {} was told to terminate.", id);
-                    break;
+    fn new(
+        id: usize,
+        receiver: Arc<Mutex<mpsc::Receiver<Message>>>,
+        pending: Arc<PendingJobs>,
+        metrics: Arc<MetricsCollector>,
+    ) -> Worker {
+        let thread = thread::spawn(move || {
+            affinity::pin_worker(id);
+            loop {
+                let message = receiver.lock().unwrap().recv().unwrap();
+
+                match message {
+                    Message::NewJob(job, enqueued_at) => {
+                        metrics.job_dequeued(enqueued_at);
+                        debug!("Worker {} got a job; executing.", id);
+                        // a panicking job must neither kill the worker nor leave the job pending
+                        if panic::catch_unwind(AssertUnwindSafe(job)).is_err() {
+                            pending.panicked.store(true, Ordering::SeqCst);
+                        }
+                        pending.decrement();
+                    }
+                    Message::Terminate => {
+                        debug!("Worker {} was told to terminate.", id);
+                        break;
+                    }
                 }
             }
         });
@@ -107,6 +169,40 @@ impl Worker {
 
 type Job = Box<dyn FnOnce() + Send + 'static>;
 
+/// Counter of the jobs submitted to a pool that have not completed yet
+#[derive(Default)]
+struct PendingJobs {
+    count: Mutex<usize>,
+    all_done: Condvar,
+    panicked: AtomicBool,
+}
+
+impl PendingJobs {
+    fn increment(&self) {
+        *self.count.lock().unwrap() += 1;
+    }
+
+    fn decrement(&self) {
+        let mut count = self.count.lock().unwrap();
+        *count -= 1;
+        if *count == 0 {
+            self.all_done.notify_all();
+        }
+    }
+
+    fn wait_until_empty(&self) {
+        let mut count = self.count.lock().unwrap();
+        while *count > 0 {
+            count = self.all_done.wait(count).unwrap();
+        }
+        // releases the lock first, so that panicking does not poison it
+        drop(count);
+        if self.panicked.swap(false, Ordering::SeqCst) {
+            panic!("A job submitted to the thread pool panicked");
+        }
+    }
+}
+
 /// Returns the number of threads to use, based on the desired size and the number of available threads.
 fn number_of_threads_to_use(desired_size: usize) -> usize {
     min_by(
@@ -119,7 +215,8 @@ fn number_of_threads_to_use(desired_size: usize) -> usize {
 
 enum Message {
     Terminate,
-    NewJob(Job),
+    /// A job together with the instant it was submitted
+    NewJob(Job, Instant),
 }
 
 #[cfg(test)]
@@ -151,6 +248,85 @@ mod tests {
         ThreadPool::terminate(pool);
     }
 
+    #[test]
+    fn test_wait_for_jobs() {
+        let pool = ThreadPool::new(2);
+        let counter = Arc::new(Mutex::new(0));
+
+        for round in 1..=2 {
+            for _ in 0..8 {
+                let counter = Arc::clone(&counter);
+                pool.execute(move || {
+                    thread::sleep(Duration::from_millis(10));
+                    *counter.lock().unwrap() += 1;
+                });
+            }
+            pool.wait();
+            assert_eq!(*counter.lock().unwrap(), 8 * round);
+        }
+
+        ThreadPool::terminate(pool);
+    }
+
+    #[test]
+    fn test_single_worker_runs_jobs_in_submission_order() {
+        let pool = ThreadPool::new(1);
+        let order = Arc::new(Mutex::new(Vec::new()));
+
+        for i in 0..16 {
+            let order = Arc::clone(&order);
+            pool.execute(move || order.lock().unwrap().push(i));
+        }
+        pool.wait();
+
+        assert_eq!(*order.lock().unwrap(), (0..16).collect::<Vec<_>>());
+        ThreadPool::terminate(pool);
+    }
+
+    #[test]
+    fn test_panicking_job() {
+        let pool = ThreadPool::new(1);
+
+        pool.execute(|| panic!("job panicked"));
+        assert!(panic::catch_unwind(AssertUnwindSafe(|| pool.wait())).is_err());
+
+        // the single worker survived and the panic was reported only once
+        let (tx, rx) = mpsc::channel();
+        pool.execute(move || tx.send(1).unwrap());
+        pool.wait();
+        assert_eq!(rx.recv().unwrap(), 1);
+        ThreadPool::terminate(pool);
+    }
+
+    #[test]
+    fn test_drop_while_unwinding() {
+        // dropping the pool while `wait` unwinds must not abort the process
+        let res = panic::catch_unwind(|| {
+            let pool = ThreadPool::new(2);
+            pool.execute(|| panic!("job panicked"));
+            pool.wait();
+        });
+
+        assert!(res.is_err());
+    }
+
+    #[test]
+    fn test_pool_metrics() {
+        let pool = ThreadPool::new(1);
+
+        for _ in 0..4 {
+            pool.execute(move || thread::sleep(Duration::from_millis(10)));
+        }
+        pool.wait();
+
+        let metrics = pool.metrics();
+        assert_eq!(metrics.jobs, 4);
+        assert!(metrics.max_queue_depth >= 1);
+        // the last job waits for the three before it on the single worker
+        assert!(metrics.max_wait >= Duration::from_millis(20));
+        ThreadPool::terminate(pool);
+    }
+
     #[test]
     fn test_request_more_threads_than_available() {
         let available_threads = thread::available_parallelism().unwrap().get();
@@ -163,6 +339,10 @@ mod tests {
     #[test]
     fn test_request_less_threads_than_available() {
         let available_threads = thread::available_parallelism().unwrap().get();
+        if available_threads < 2 {
+            // cannot request fewer threads than available on a single-threaded machine
+            return;
+        }
         let pool = ThreadPool::new(available_threads - 1);
 
         assert_eq!(pool.workers.len(), available_threads - 1);
@@ -0,0 +1,277 @@
+use clap::ValueEnum;
+
+use crate::{
+    matrix_multiplication::{
+        in_pool,
+        matrix::Matrix,
+        sanitize::{sanitize_matrices, SanitizeError},
+        types::MatrixRowPtr,
+    },
+    thread_pool::ThreadPool,
+    zero_filled_square_matrix_of_size,
+};
+
+/// Enum representing the half of a triangular matrix holding its nonzeros
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, ValueEnum)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "serde", serde(rename_all = "snake_case"))]
+pub enum Triangle {
+    /// Nonzeros on and above the diagonal
+    Upper,
+    /// Nonzeros on and below the diagonal
+    Lower,
+}
+
+impl Triangle {
+    /// Returns whether the element at (`i`, `j`) belongs to the triangle
+    pub fn contains(&self, i: usize, j: usize) -> bool {
+        match self {
+            Triangle::Upper => i <= j,
+            Triangle::Lower => i >= j,
+        }
+    }
+
+    /// Returns the range of columns of row `i` belonging to the triangle of a `size` x `size`
+    /// matrix
+    fn columns(&self, i: usize, size: usize) -> std::ops::Range<usize> {
+        match self {
+            Triangle::Upper => i..size,
+            Triangle::Lower => 0..i + 1,
+        }
+    }
+}
+
+impl std::fmt::Display for Triangle {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Triangle::Upper => write!(f, "upper"),
+            Triangle::Lower => write!(f, "lower"),
+        }
+    }
+}
+
+/// Enum representing available triangular matrix multiplication (TRMM) kernels, computing
+/// `C = A * B` with a triangular A and a dense B
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum TriangularKernel {
+    /// Sequential kernel with the ikj loop order, skipping the zero half of A
+    ///
+    /// # Arguments
+    ///
+    /// * `Triangle` - the triangle of A holding its nonzeros
+    Sequential(Triangle),
+    /// Parallel kernel submitting one job per row to the thread pool. Rows do different amounts
+    /// of work, which the shared queue of the pool balances among the threads.
+    ///
+    /// # Arguments
+    ///
+    /// * `Triangle` - the triangle of A holding its nonzeros
+    /// * `usize` - number of threads to use
+    Parallel(Triangle, usize),
+}
+
+impl std::fmt::Display for TriangularKernel {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            TriangularKernel::Sequential(triangle) => {
+                write!(f, "Sequential TRMM ({} triangular)", triangle)
+            }
+            TriangularKernel::Parallel(triangle, threads) => {
+                write!(
+                    f,
+                    "Parallel TRMM ({} triangular, {} threads)",
+                    triangle, threads
+                )
+            }
+        }
+    }
+}
+
+impl TriangularKernel {
+    /// Returns the number of threads used by the kernel, or `None` if it is sequential
+    pub fn threads(&self) -> Option<usize> {
+        match self {
+            TriangularKernel::Sequential(_) => None,
+            TriangularKernel::Parallel(_, threads) => Some(*threads),
+        }
+    }
+
+    /// Returns the triangle of A holding its nonzeros
+    pub fn triangle(&self) -> Triangle {
+        match self {
+            TriangularKernel::Sequential(triangle) | TriangularKernel::Parallel(triangle, _) => {
+                *triangle
+            }
+        }
+    }
+}
+
+/// Multiplies the triangular matrix `a` by the dense matrix `b` with the given kernel.
+/// The parallel kernel runs on `pool`, see `in_pool`.
+pub fn trmm(
+    a: &Matrix<i32>,
+    b: &Matrix<i32>,
+    kernel: TriangularKernel,
+    pool: Option<&ThreadPool>,
+) -> Result<Matrix<i32>, SanitizeError> {
+    sanitize_matrices(a, b)?;
+    is_matrix_triangular(a, kernel.triangle(), "A")?;
+
+    let size = a.rows();
+
+    match kernel {
+        TriangularKernel::Sequential(triangle) => Ok(trmm_sequential(a, b, size, triangle)),
+        TriangularKernel::Parallel(triangle, threads) => in_pool(pool, threads, |pool| {
+            Ok(trmm_parallel(a, b, size, triangle, pool))
+        }),
+    }
+}
+
+fn is_matrix_triangular(
+    a: &Matrix<i32>,
+    triangle: Triangle,
+    matrix_name: &str,
+) -> Result<(), SanitizeError> {
+    let outside = a.iter_rows().enumerate().any(|(i, row)| {
+        row.iter()
+            .enumerate()
+            .any(|(j, a_ij)| *a_ij != 0 && !triangle.contains(i, j))
+    });
+
+    match outside {
+        true => Err(SanitizeError::NotTriangularMatrix(matrix_name.to_string())),
+        false => Ok(()),
+    }
+}
+
+fn trmm_sequential(
+    a: &Matrix<i32>,
+    b: &Matrix<i32>,
+    size: usize,
+    triangle: Triangle,
+) -> Matrix<i32> {
+    let mut c = zero_filled_square_matrix_of_size!(size);
+
+    for (i, (a_i, c_i)) in a.iter_rows().zip(c.iter_rows_mut()).enumerate() {
+        for k in triangle.columns(i, size) {
+            let a_ik = a_i[k];
+            for (c_ij, b_kj) in c_i.iter_mut().zip(&b[k]) {
+                *c_ij += a_ik * b_kj;
+            }
+        }
+    }
+
+    c
+}
+
+fn trmm_parallel(
+    a: &Matrix<i32>,
+    b: &Matrix<i32>,
+    size: usize,
+    triangle: Triangle,
+    pool: &ThreadPool,
+) -> Matrix<i32> {
+    let mut c = zero_filled_square_matrix_of_size!(size);
+    let b_rows = b
+        .iter_rows()
+        .map(|row| MatrixRowPtr(row.as_ptr()))
+        .collect::<Vec<_>>();
+
+    for i in 0..size {
+        let a_i = MatrixRowPtr(a[i].as_ptr());
+        let mut c_i = MatrixRowPtr(c[i].as_mut_ptr());
+        let b_rows = b_rows.clone();
+
+        pool.execute(move || {
+            for k in triangle.columns(i, size) {
+                let b_k = b_rows[k];
+                unsafe {
+                    let a_ik = *a_i.add(k);
+                    for j in 0..size {
+                        *c_i.add_mut(j) += a_ik * *b_k.add(j);
+                    }
+                }
+            }
+        });
+    }
+
+    pool.wait();
+
+    c
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::matrix_multiplication::{
+        algorithms::Algorithm,
+        generate::{generate_random_square_matrix_with_rng, seeded_rng, MAX_ABS_VALUE_DEFAULT},
+        matrix_multiplication,
+    };
+
+    /// Matrix whose elements outside `triangle` are zero
+    fn get_triangular(size: usize, triangle: Triangle) -> Matrix<i32> {
+        Matrix::from_fn(size, size, |i, j| match triangle.contains(i, j) {
+            true => (i * 3 + j) as i32 % 7 - 3,
+            false => 0,
+        })
+    }
+
+    fn get_b(size: usize) -> Matrix<i32> {
+        generate_random_square_matrix_with_rng(
+            size,
+            MAX_ABS_VALUE_DEFAULT,
+            &mut seeded_rng(Some(7)),
+        )
+    }
+
+    #[test]
+    fn test_trmm_unbalanced_rows() {
+        // the first row of an upper triangle and the last of a lower one are full, while the
+        // opposite rows hold a single element
+        let b = get_b(7);
+        for triangle in [Triangle::Upper, Triangle::Lower] {
+            let a = get_triangular(7, triangle);
+            let expected = matrix_multiplication(&a, &b, Algorithm::SequentialIkj, None).unwrap();
+
+            for kernel in [
+                TriangularKernel::Sequential(triangle),
+                TriangularKernel::Parallel(triangle, 3),
+            ] {
+                assert_eq!(trmm(&a, &b, kernel, None).unwrap(), expected, "{}", kernel);
+            }
+        }
+    }
+
+    #[test]
+    fn test_trmm_diagonal() {
+        // a diagonal matrix is both upper and lower triangular, and scales the rows of B
+        let a = Matrix::from_fn(4, 4, |i, j| match i == j {
+            true => i as i32 - 2,
+            false => 0,
+        });
+        let b = get_b(4);
+        let expected = Matrix::from_fn(4, 4, |i, j| (i as i32 - 2) * b[i][j]);
+
+        for triangle in [Triangle::Upper, Triangle::Lower] {
+            for kernel in [
+                TriangularKernel::Sequential(triangle),
+                TriangularKernel::Parallel(triangle, 3),
+            ] {
+                assert_eq!(trmm(&a, &b, kernel, None).unwrap(), expected, "{}", kernel);
+            }
+        }
+    }
+
+    #[test]
+    fn test_trmm_rejects_wrong_triangle() {
+        let a = get_triangular(4, Triangle::Upper);
+        let b = get_triangular(4, Triangle::Lower);
+
+        assert_eq!(
+            trmm(&a, &b, TriangularKernel::Sequential(Triangle::Lower), None),
+            Err(SanitizeError::NotTriangularMatrix("A".to_string()))
+        );
+        assert!(trmm(&a, &b, TriangularKernel::Sequential(Triangle::Upper), None).is_ok());
+    }
+}
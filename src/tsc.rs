@@ -0,0 +1,126 @@
+use std::{
+    thread,
+    time::{Duration, Instant},
+};
+
+/// Duration of the sleep used to calibrate the frequency of the time-stamp counter
+const CALIBRATION_DURATION: Duration = Duration::from_millis(100);
+
+/// Returns `true` if the time-stamp counter can be used on this machine
+pub fn is_available() -> bool {
+    cfg!(target_arch = "x86_64")
+}
+
+/// Returns `true` if the time-stamp counter ticks at a constant rate regardless of the frequency
+/// and power state of the core, so that cycle counts are comparable across runs
+#[cfg(target_arch = "x86_64")]
+pub fn is_invariant() -> bool {
+    use std::arch::x86_64::__cpuid;
+
+    let max_extended_leaf = __cpuid(0x8000_0000).eax;
+    if max_extended_leaf < 0x8000_0007 {
+        return false;
+    }
+    let power_management = __cpuid(0x8000_0007);
+    power_management.edx & (1 << 8) != 0
+}
+
+#[cfg(not(target_arch = "x86_64"))]
+pub fn is_invariant() -> bool {
+    false
+}
+
+/// Returns `true` if the CPU supports `rdtscp`, which `is_x86_feature_detected!` does not know.
+/// The answer of CPUID is cached by the first call, made by `start` before the measured region.
+#[cfg(target_arch = "x86_64")]
+fn has_rdtscp() -> bool {
+    use std::{arch::x86_64::__cpuid, sync::OnceLock};
+
+    static RDTSCP: OnceLock<bool> = OnceLock::new();
+    *RDTSCP.get_or_init(|| {
+        let max_extended_leaf = __cpuid(0x8000_0000).eax;
+        max_extended_leaf >= 0x8000_0001 && __cpuid(0x8000_0001).edx & (1 << 27) != 0
+    })
+}
+
+/// Reads the time-stamp counter at the start of a measured region.
+/// The fences prevent the read from being reordered with the instructions around it.
+#[cfg(target_arch = "x86_64")]
+pub fn start() -> u64 {
+    use std::arch::x86_64::{_mm_lfence, _rdtsc};
+
+    // detects rdtscp for `stop` before the measured region
+    has_rdtscp();
+    // SAFETY: lfence and rdtsc are always available on x86_64
+    unsafe {
+        _mm_lfence();
+        let tsc = _rdtsc();
+        _mm_lfence();
+        tsc
+    }
+}
+
+#[cfg(not(target_arch = "x86_64"))]
+pub fn start() -> u64 {
+    0
+}
+
+/// Reads the time-stamp counter at the end of a measured region.
+/// `rdtscp` waits for the preceding instructions to complete, and the trailing fence prevents the
+/// following ones from starting before the read. CPUs without `rdtscp` fence an `rdtsc` on both
+/// sides instead, as `start` does.
+#[cfg(target_arch = "x86_64")]
+pub fn stop() -> u64 {
+    use std::arch::x86_64::{__rdtscp, _mm_lfence};
+
+    if !has_rdtscp() {
+        return start();
+    }
+    let mut aux = 0;
+    // SAFETY: rdtscp is supported, as checked above, and `aux` is a valid, writable u32
+    unsafe {
+        let tsc = __rdtscp(&mut aux);
+        _mm_lfence();
+        tsc
+    }
+}
+
+#[cfg(not(target_arch = "x86_64"))]
+pub fn stop() -> u64 {
+    0
+}
+
+/// Estimates the frequency of the time-stamp counter in GHz (i.e. ticks per nanosecond) by
+/// comparing it against the wall clock over a short sleep.
+/// Returns `None` if the time-stamp counter is not available.
+pub fn calibrate() -> Option<f64> {
+    if !is_available() {
+        return None;
+    }
+
+    let wall_start = Instant::now();
+    let tsc_start = start();
+    thread::sleep(CALIBRATION_DURATION);
+    let tsc_end = stop();
+    let elapsed = wall_start.elapsed();
+
+    Some(tsc_end.wrapping_sub(tsc_start) as f64 / elapsed.as_nanos() as f64)
+}
+
+#[cfg(all(test, target_arch = "x86_64"))]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_tsc_is_monotonic() {
+        let start = start();
+        let stop = stop();
+        assert!(stop >= start);
+    }
+
+    #[test]
+    fn test_calibrate() {
+        let frequency = calibrate().unwrap();
+        assert!(frequency > 0.0);
+    }
+}